@@ -0,0 +1,348 @@
+//! Standalone address decoding for Cardano addresses.
+
+use crate::error::{Error, Result};
+use crate::network::NetworkName;
+use cml_chain::address::{Address, BaseAddress, EnterpriseAddress, RewardAddress};
+use cml_chain::byron::ByronAddress;
+use cml_chain::certs::Credential;
+use cml_core::serialization::Deserialize;
+use cml_crypto::{Ed25519KeyHash, RawBytesEncoding};
+use serde_json::Value as JsonValue;
+
+/// Decoded address with all components.
+pub struct DecodedAddress {
+    /// The address as text: bech32, or base58 for Byron addresses.
+    pub bech32: String,
+    /// The raw address bytes (header and payload) in hex.
+    pub hex: String,
+    /// The address type.
+    pub address_type: AddressType,
+    /// Network (mainnet or testnet, named if the testnet is known).
+    pub network: Network,
+    /// Payment credential (if applicable).
+    pub payment_credential: Option<DecodedCredential>,
+    /// Stake credential (if applicable).
+    pub stake_credential: Option<DecodedCredential>,
+    /// Pointer info for pointer addresses.
+    pub pointer: Option<Pointer>,
+    /// The reward address sharing a base address's stake credential.
+    pub stake_address: Option<String>,
+}
+
+/// Address type enumeration.
+#[derive(Debug, Clone, Copy)]
+pub enum AddressType {
+    Base,
+    Enterprise,
+    Reward,
+    Pointer,
+    Byron,
+}
+
+impl AddressType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressType::Base => "base",
+            AddressType::Enterprise => "enterprise",
+            AddressType::Reward => "reward",
+            AddressType::Pointer => "pointer",
+            AddressType::Byron => "byron",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            AddressType::Base => "Base (Shelley)",
+            AddressType::Enterprise => "Enterprise (Shelley, no staking)",
+            AddressType::Reward => "Reward/Stake",
+            AddressType::Pointer => "Pointer (Shelley)",
+            AddressType::Byron => "Byron (Legacy)",
+        }
+    }
+}
+
+/// Network enumeration.
+#[derive(Debug, Clone, Copy)]
+pub enum Network {
+    Mainnet,
+    /// A testnet, and which one if the caller said so.
+    Testnet(Option<NetworkName>),
+    Unknown,
+}
+
+impl Network {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet(Some(network)) => network.as_str(),
+            Network::Testnet(None) => "testnet",
+            Network::Unknown => "unknown",
+        }
+    }
+}
+
+/// Decoded credential.
+pub struct DecodedCredential {
+    /// Type of credential (keyhash or scripthash).
+    pub cred_type: CredentialType,
+    /// Hash in hex.
+    pub hash: String,
+}
+
+/// Credential type.
+#[derive(Debug, Clone, Copy)]
+pub enum CredentialType {
+    KeyHash,
+    ScriptHash,
+}
+
+impl CredentialType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CredentialType::KeyHash => "keyhash",
+            CredentialType::ScriptHash => "scripthash",
+        }
+    }
+}
+
+/// Pointer info for pointer addresses.
+pub struct Pointer {
+    pub slot: u64,
+    pub tx_index: u64,
+    pub cert_index: u64,
+}
+
+/// Decode a Cardano address: bech32, hex address bytes (as in CBOR dumps
+/// and db-sync), or a base58 Byron address.
+///
+/// A testnet address is labeled with `network`'s name if that is a testnet.
+pub fn decode_address(addr_str: &str, network: Option<NetworkName>) -> Result<DecodedAddress> {
+    let addr_str = addr_str.trim();
+    let addr = parse_address(addr_str)?;
+
+    // Hex input is shown in its usual text form
+    let bech32 = match &addr {
+        Address::Byron(byron_addr) => byron_addr.to_base58(),
+        _ if Address::from_bech32(addr_str).is_ok() => addr_str.to_string(),
+        _ => addr
+            .to_bech32(None)
+            .map_err(|e| Error::DecodeFailed(format!("Invalid address: {}", e)))?,
+    };
+
+    let hex = hex::encode(addr.to_raw_bytes());
+
+    // Network ID (CIP-19): 0 = testnet (covers preprod, preview, and all
+    // other testnets), 1 = mainnet. Testnets can't be told apart from the
+    // address alone.
+    let network = match addr.network_id() {
+        Ok(1) => Network::Mainnet,
+        Ok(0) => Network::Testnet(network.filter(|network| network.network_id() == 0)),
+        _ => Network::Unknown,
+    };
+
+    match addr {
+        Address::Base(base_addr) => Ok(DecodedAddress {
+            bech32,
+            hex,
+            address_type: AddressType::Base,
+            network,
+            payment_credential: Some(decode_credential(&base_addr.payment)),
+            stake_credential: Some(decode_credential(&base_addr.stake)),
+            pointer: None,
+            stake_address: RewardAddress::new(base_addr.network, base_addr.stake)
+                .to_address()
+                .to_bech32(None)
+                .ok(),
+        }),
+        Address::Enterprise(enterprise_addr) => Ok(DecodedAddress {
+            bech32,
+            hex,
+            address_type: AddressType::Enterprise,
+            network,
+            payment_credential: Some(decode_credential(&enterprise_addr.payment)),
+            stake_credential: None,
+            pointer: None,
+            stake_address: None,
+        }),
+        Address::Ptr(ptr_addr) => Ok(DecodedAddress {
+            bech32,
+            hex,
+            address_type: AddressType::Pointer,
+            network,
+            payment_credential: Some(decode_credential(&ptr_addr.payment)),
+            stake_credential: None,
+            pointer: Some(Pointer {
+                slot: ptr_addr.stake.slot(),
+                tx_index: ptr_addr.stake.tx_index(),
+                cert_index: ptr_addr.stake.cert_index(),
+            }),
+            stake_address: None,
+        }),
+        Address::Reward(reward_addr) => Ok(DecodedAddress {
+            bech32,
+            hex,
+            address_type: AddressType::Reward,
+            network,
+            payment_credential: None,
+            stake_credential: Some(decode_credential(&reward_addr.payment)),
+            pointer: None,
+            stake_address: None,
+        }),
+        Address::Byron(_) => Ok(DecodedAddress {
+            bech32,
+            hex,
+            address_type: AddressType::Byron,
+            network,
+            payment_credential: None,
+            stake_credential: None,
+            pointer: None,
+            stake_address: None,
+        }),
+    }
+}
+
+/// Build an address from a payment key hash and a stake key hash or stake
+/// address: a base address from both, an enterprise address from the
+/// payment key hash alone, or a reward address from the stake alone.
+///
+/// Key hashes are hex; `stake` may be either form.
+pub fn build_address(
+    payment_keyhash: Option<&str>,
+    stake: Option<&str>,
+    network: NetworkName,
+) -> Result<DecodedAddress> {
+    let network_id = network.network_id();
+    let payment = payment_keyhash.map(key_hash_credential).transpose()?;
+    let stake = stake.map(stake_credential).transpose()?;
+
+    let addr = match (payment, stake) {
+        (Some(payment), Some(stake)) => BaseAddress::new(network_id, payment, stake).to_address(),
+        (Some(payment), None) => EnterpriseAddress::new(network_id, payment).to_address(),
+        (None, Some(stake)) => RewardAddress::new(network_id, stake).to_address(),
+        (None, None) => {
+            return Err(Error::DecodeFailed(
+                "a payment or stake key hash is required".to_string(),
+            ));
+        }
+    };
+    let bech32 = addr
+        .to_bech32(None)
+        .map_err(|e| Error::DecodeFailed(format!("Invalid address: {}", e)))?;
+    decode_address(&bech32, Some(network))
+}
+
+/// A key hash credential from 28 bytes of hex.
+fn key_hash_credential(hash: &str) -> Result<Credential> {
+    let bytes = hex::decode(hash.trim())?;
+    let hash = Ed25519KeyHash::from_raw_bytes(&bytes).map_err(|_| {
+        Error::DecodeFailed(format!(
+            "key hash must be 28 bytes, got {} bytes",
+            bytes.len()
+        ))
+    })?;
+    Ok(Credential::new_pub_key(hash))
+}
+
+/// A stake credential from a key hash or a stake (reward) address.
+fn stake_credential(stake: &str) -> Result<Credential> {
+    if hex::decode(stake.trim()).is_ok() {
+        return key_hash_credential(stake);
+    }
+    match parse_address(stake.trim())? {
+        Address::Reward(reward_addr) => Ok(reward_addr.payment),
+        _ => Err(Error::DecodeFailed(format!(
+            "{} is not a stake address",
+            stake.trim()
+        ))),
+    }
+}
+
+/// Parse an address from bech32, hex bytes or base58 (Byron).
+fn parse_address(addr_str: &str) -> Result<Address> {
+    let bech32_error = match Address::from_bech32(addr_str) {
+        Ok(addr) => return Ok(addr),
+        Err(e) => e,
+    };
+
+    let hex_candidate = addr_str.strip_prefix("0x").unwrap_or(addr_str);
+    if let Ok(bytes) = hex::decode(hex_candidate) {
+        // Raw address bytes, or the same wrapped in a CBOR byte string
+        return Address::from_raw_bytes(&bytes)
+            .or_else(|e| Address::from_cbor_bytes(&bytes).map_err(|_| e))
+            .map_err(|e| Error::DecodeFailed(format!("Invalid address bytes: {}", e)));
+    }
+
+    if let Ok(byron_addr) = ByronAddress::from_base58(addr_str) {
+        return Ok(byron_addr.to_address());
+    }
+
+    let reason = super::bech32::diagnose(addr_str).unwrap_or_else(|| bech32_error.to_string());
+    Err(Error::DecodeFailed(format!("Invalid address: {}", reason)))
+}
+
+/// Decode a credential to our format.
+fn decode_credential(cred: &Credential) -> DecodedCredential {
+    match cred {
+        Credential::PubKey { hash, .. } => DecodedCredential {
+            cred_type: CredentialType::KeyHash,
+            hash: hex::encode(hash.to_raw_bytes()),
+        },
+        Credential::Script { hash, .. } => DecodedCredential {
+            cred_type: CredentialType::ScriptHash,
+            hash: hex::encode(hash.to_raw_bytes()),
+        },
+    }
+}
+
+impl DecodedAddress {
+    /// Convert to JSON.
+    pub fn to_json(&self) -> JsonValue {
+        let mut json = serde_json::json!({
+            "address": self.bech32,
+            "hex": self.hex,
+            "type": self.address_type.as_str(),
+            "network": self.network.as_str()
+        });
+
+        if let Some(ref payment) = self.payment_credential {
+            json["payment_credential"] = serde_json::json!({
+                "type": payment.cred_type.as_str(),
+                "hash": payment.hash
+            });
+        }
+
+        if let Some(ref stake) = self.stake_credential {
+            json["stake_credential"] = serde_json::json!({
+                "type": stake.cred_type.as_str(),
+                "hash": stake.hash
+            });
+        }
+
+        if let Some(ref stake_address) = self.stake_address {
+            json["stake_address"] = JsonValue::from(stake_address.as_str());
+        }
+
+        if let Some(ref ptr) = self.pointer {
+            json["pointer"] = serde_json::json!({
+                "slot": ptr.slot,
+                "tx_index": ptr.tx_index,
+                "cert_index": ptr.cert_index
+            });
+        }
+
+        json
+    }
+
+    /// The stake (reward) address for this address: derived for base
+    /// addresses, the address itself for reward addresses.
+    pub fn stake_address(&self) -> Result<&str> {
+        match (&self.address_type, &self.stake_address) {
+            (AddressType::Reward, _) => Ok(&self.bech32),
+            (_, Some(stake_address)) => Ok(stake_address),
+            _ => Err(Error::DecodeFailed(format!(
+                "{} address has no stake credential to derive a stake address from",
+                self.address_type.as_str()
+            ))),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Diagnosing why a string is not a valid bech32 Cardano address, and
+//! encoding identifiers CML has no bech32 form for.
+//!
+//! CML reports the first failure it meets in its own terms ("invalid
+//! checksum"); these checks say what is wrong and, for typos, where.
+
+/// The bech32 alphabet, indexed by 5-bit value.
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Prefixes of Cardano payment and stake addresses (CIP-5).
+const ADDRESS_PREFIXES: [&str; 4] = ["addr", "addr_test", "stake", "stake_test"];
+
+/// Length of the bech32 checksum, in characters.
+const CHECKSUM_LEN: usize = 6;
+
+/// Why `s` is not a valid bech32 address, or `None` if no problem is found.
+///
+/// Positions are 1-based character positions in `s`.
+pub(crate) fn diagnose(s: &str) -> Option<String> {
+    let has_lower = s.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = s.chars().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Some("mixed case (bech32 is all lowercase or all uppercase)".to_string());
+    }
+
+    let s = s.to_ascii_lowercase();
+    let Some(separator) = s.rfind('1') else {
+        return Some("missing the '1' separating the prefix from the data".to_string());
+    };
+    let (hrp, data) = (&s[..separator], &s[separator + 1..]);
+    if !ADDRESS_PREFIXES.contains(&hrp) {
+        return Some(format!(
+            "unknown prefix '{}' (expected addr, addr_test, stake or stake_test)",
+            hrp
+        ));
+    }
+
+    let mut values = Vec::with_capacity(data.len());
+    for (idx, c) in data.chars().enumerate() {
+        match CHARSET.iter().position(|&b| char::from(b) == c) {
+            Some(value) => values.push(value as u8),
+            None => {
+                return Some(format!(
+                    "invalid character '{}' at position {} (bech32 excludes 1, b, i and o)",
+                    c,
+                    separator + 2 + idx
+                ));
+            }
+        }
+    }
+    if values.len() < CHECKSUM_LEN {
+        return Some("too short to hold a checksum".to_string());
+    }
+
+    if !checksum_valid(hrp, &values) {
+        return Some(checksum_diagnosis(hrp, &mut values, separator + 2));
+    }
+
+    let payload = convert_bits(&values[..values.len() - CHECKSUM_LEN])?;
+    payload_diagnosis(hrp, &payload)
+}
+
+/// Describe a checksum failure, pointing at the character to fix if
+/// exactly one single-character change makes the checksum valid.
+fn checksum_diagnosis(hrp: &str, values: &mut [u8], data_start: usize) -> String {
+    let mut fixes = Vec::new();
+    for idx in 0..values.len() {
+        let original = values[idx];
+        for candidate in 0..32u8 {
+            if candidate == original {
+                continue;
+            }
+            values[idx] = candidate;
+            if checksum_valid(hrp, values) {
+                fixes.push((idx, original, candidate));
+            }
+        }
+        values[idx] = original;
+    }
+
+    match fixes.as_slice() {
+        [(idx, original, candidate)] => format!(
+            "checksum mismatch: the character at position {} ('{}') is likely wrong, perhaps '{}'",
+            data_start + idx,
+            char::from(CHARSET[*original as usize]),
+            char::from(CHARSET[*candidate as usize])
+        ),
+        _ => "checksum mismatch (a mistyped, missing or extra character)".to_string(),
+    }
+}
+
+/// Check the decoded bytes against the header's address type and network.
+fn payload_diagnosis(hrp: &str, payload: &[u8]) -> Option<String> {
+    let Some(&header) = payload.first() else {
+        return Some("no address bytes after the prefix".to_string());
+    };
+    let (kind, expected_len) = match header >> 4 {
+        0..=3 => ("base", Some(57)),
+        4 | 5 => ("pointer", None),
+        6 | 7 => ("enterprise", Some(29)),
+        14 | 15 => ("reward", Some(29)),
+        other => return Some(format!("unknown address type {} in the header", other)),
+    };
+
+    if let Some(expected) = expected_len {
+        if payload.len() != expected {
+            return Some(format!(
+                "invalid length: {} bytes, a {} address has {}",
+                payload.len(),
+                kind,
+                expected
+            ));
+        }
+    }
+
+    let is_stake_prefix = hrp.starts_with("stake");
+    if is_stake_prefix != (kind == "reward") {
+        return Some(format!(
+            "prefix '{}' does not match the {} address in the header",
+            hrp, kind
+        ));
+    }
+
+    let is_test_prefix = hrp.ends_with("_test");
+    let network_id = header & 0x0f;
+    if is_test_prefix != (network_id == 0) {
+        return Some(format!(
+            "prefix '{}' does not match the header's network id {}",
+            hrp, network_id
+        ));
+    }
+    None
+}
+
+/// Whether the checksum over `hrp` and the 5-bit `values` is valid (bech32,
+/// not bech32m).
+fn checksum_valid(hrp: &str, values: &[u8]) -> bool {
+    polymod(hrp_expand(hrp).chain(values.iter().copied())) == 1
+}
+
+/// The prefix as checksummed: high bits, a zero, then low bits.
+fn hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes()
+        .map(|b| b >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.bytes().map(|b| b & 31))
+}
+
+/// Encode bytes as bech32 with a prefix, e.g. CIP-129 governance IDs
+/// (`drep1...`), whose header byte no CML type carries.
+pub fn encode(hrp: &str, bytes: &[u8]) -> String {
+    let mut values = Vec::with_capacity(bytes.len() * 8 / 5 + 1 + CHECKSUM_LEN);
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for &byte in bytes {
+        acc = ((acc << 8) | u32::from(byte)) & 0xffff;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            values.push(((acc >> bits) & 31) as u8);
+        }
+    }
+    if bits > 0 {
+        values.push(((acc << (5 - bits)) & 31) as u8);
+    }
+
+    let checksum = polymod(
+        hrp_expand(hrp)
+            .chain(values.iter().copied())
+            .chain([0; CHECKSUM_LEN]),
+    ) ^ 1;
+    values.extend((0..CHECKSUM_LEN).map(|i| ((checksum >> (5 * (5 - i))) & 31) as u8));
+    let data: String = values
+        .iter()
+        .map(|&value| char::from(CHARSET[usize::from(value)]))
+        .collect();
+    format!("{}1{}", hrp, data)
+}
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    values.fold(1, |checksum, value| {
+        let top = checksum >> 25;
+        let checksum = ((checksum & 0x1ffffff) << 5) ^ u32::from(value);
+        GENERATOR
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| (top >> bit) & 1 == 1)
+            .fold(checksum, |checksum, (_, g)| checksum ^ g)
+    })
+}
+
+/// Regroup 5-bit values into bytes, or `None` if the padding is invalid.
+fn convert_bits(values: &[u8]) -> Option<Vec<u8>> {
+    let (mut acc, mut bits) = (0u32, 0u32);
+    let mut bytes = Vec::with_capacity(values.len() * 5 / 8);
+    for &value in values {
+        acc = (acc << 5) | u32::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+        }
+    }
+    (bits < 5 && acc & ((1 << bits) - 1) == 0).then_some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "addr1vx46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46h2c4u8cnp";
+
+    #[test]
+    fn test_valid_address() {
+        assert_eq!(diagnose(ADDRESS), None);
+        assert_eq!(diagnose(&ADDRESS.to_uppercase()), None);
+    }
+
+    #[test]
+    fn test_typo_position() {
+        // Change the character at position 10
+        let mut typo = ADDRESS.to_string();
+        typo.replace_range(9..10, "q");
+        let diagnosis = diagnose(&typo).unwrap();
+        assert!(diagnosis.contains("position 10"), "{}", diagnosis);
+        assert!(diagnosis.contains("perhaps 'h'"), "{}", diagnosis);
+    }
+
+    #[test]
+    fn test_diagnoses() {
+        let diagnosis = |s: &str| diagnose(s).unwrap();
+        assert!(diagnosis("Addr1vx46").contains("mixed case"));
+        assert!(diagnosis("addrvx46").contains("missing the '1'"));
+        assert!(diagnosis(&ADDRESS.replacen("addr", "adr", 1)).contains("unknown prefix 'adr'"));
+        assert!(diagnosis("addr1vx4bh2").contains("invalid character 'b' at position 9"));
+        assert!(diagnosis("addr1vx4").contains("too short"));
+        assert!(diagnosis(&ADDRESS.replacen("addr", "addr_test", 1)).contains("checksum mismatch"));
+    }
+
+    #[test]
+    fn test_encode() {
+        use cml_crypto::{Ed25519KeyHash, RawBytesEncoding};
+
+        let hash = Ed25519KeyHash::from([0x5a; 28]);
+        assert_eq!(
+            encode("pool", hash.to_raw_bytes()),
+            hash.to_bech32("pool").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_payload_diagnoses() {
+        use cml_chain::address::Address;
+
+        let address = Address::from_bech32(ADDRESS).unwrap();
+        let with_prefix = |hrp: &str| address.to_bech32(Some(hrp.to_string())).unwrap();
+        assert!(
+            diagnose(&with_prefix("stake"))
+                .unwrap()
+                .contains("enterprise address")
+        );
+        assert!(
+            diagnose(&with_prefix("addr_test"))
+                .unwrap()
+                .contains("network id 1")
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Blocks and the transactions in them.
+
+use crate::decode::cbor::{array_items, read_uint};
+use crate::decode::transaction::DecodedTransaction;
+use crate::error::{Error, Result};
+use cml_chain::block::Block;
+use cml_chain::transaction::Transaction;
+use cml_core::serialization::{Deserialize, Serialize};
+use cml_crypto::BlockHeaderHash;
+
+/// A decoded block with its header hash.
+#[derive(Debug)]
+pub struct DecodedBlock {
+    /// The parsed CML block.
+    pub block: Block,
+    /// blake2b-256 of the original header bytes.
+    pub hash: BlockHeaderHash,
+}
+
+impl DecodedBlock {
+    /// Slot the block was minted in.
+    pub fn slot(&self) -> u64 {
+        self.block.header.header_body.slot
+    }
+
+    /// Height of the block in the chain.
+    pub fn block_number(&self) -> u64 {
+        self.block.header.header_body.block_number
+    }
+
+    /// The block's transactions, in order.
+    pub fn transactions(&self) -> Vec<DecodedTransaction<'static>> {
+        let block = &self.block;
+        block
+            .transaction_bodies
+            .iter()
+            .zip(&block.transaction_witness_sets)
+            .enumerate()
+            .map(|(idx, (body, witness_set))| {
+                let idx = idx as u16;
+                let tx = Transaction::new(
+                    body.clone(),
+                    witness_set.clone(),
+                    !block.invalid_transactions.contains(&idx),
+                    block.auxiliary_data_set.get(&idx).cloned(),
+                );
+                DecodedTransaction {
+                    original_bytes: tx.to_cbor_bytes().into(),
+                    hash: body.hash(),
+                    tx,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Decode a Babbage or Conway block, optionally wrapped as `[era, block]`
+/// as nodes send them.
+pub fn decode_block(bytes: &[u8]) -> Result<DecodedBlock> {
+    let bytes = match array_items(bytes, 0)?.as_slice() {
+        [era, block] if read_uint(bytes, era.start).is_ok() => block.slice(bytes),
+        _ => bytes,
+    };
+    let block = Block::from_cbor_bytes(bytes).map_err(|e| Error::DecodeFailed(e.to_string()))?;
+    let header = array_items(bytes, 0)?[0];
+    let hash = BlockHeaderHash::from(cml_crypto::blake2b256(header.slice(bytes)));
+    Ok(DecodedBlock { block, hash })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cml_chain::block::{Header, HeaderBody, OperationalCert, ProtocolVersion};
+    use cml_chain::crypto::{KESSignature, VRFCert};
+    use cml_chain::transaction::{TransactionBody, TransactionWitnessSet};
+    use cml_crypto::{
+        BlockBodyHash, Ed25519Signature, KESVkey, PublicKey, RawBytesEncoding, VRFVkey,
+    };
+
+    #[test]
+    fn test_decode_block() {
+        let header_body = HeaderBody::new(
+            7,
+            1234,
+            None,
+            PublicKey::from_raw_bytes(&[1; 32]).unwrap(),
+            VRFVkey::from([2; 32]),
+            VRFCert::new(vec![3; 64], vec![4; 80]).unwrap(),
+            0,
+            BlockBodyHash::from([5; 32]),
+            OperationalCert::new(
+                KESVkey::from([6; 32]),
+                0,
+                0,
+                Ed25519Signature::from_raw_bytes(&[7; 64]).unwrap(),
+            ),
+            ProtocolVersion::new(10, 0),
+        );
+        let header = Header::new(header_body, KESSignature::new(vec![8; 448]).unwrap());
+        let body = TransactionBody::new(Vec::new().into(), Vec::new(), 200_000);
+        let block = Block::new(
+            header.clone(),
+            vec![body.clone()],
+            vec![TransactionWitnessSet::new()],
+            Default::default(),
+            vec![0],
+        );
+        // As nodes send it: [era, block]
+        let bytes = [&[0x82, 0x07][..], &block.to_cbor_bytes()].concat();
+
+        let decoded = decode_block(&bytes).unwrap();
+        assert_eq!((decoded.slot(), decoded.block_number()), (1234, 7));
+        assert_eq!(
+            decoded.hash.to_raw_bytes(),
+            cml_crypto::blake2b256(&header.to_cbor_bytes())
+        );
+        let transactions = decoded.transactions();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].hash, body.hash());
+        assert!(!transactions[0].is_valid());
+    }
+}
@@ -0,0 +1,389 @@
+//! Span-aware walking of raw CBOR bytes.
+//!
+//! CML re-encodes values on serialization, so byte-level views (sizes,
+//! offsets, sub-slices) must be computed from the original input bytes.
+
+use crate::error::{Error, Result};
+use cbor_event::de::Deserializer;
+use cml_core::DeserializeError;
+use cml_core::serialization::Deserialize;
+use std::io::Cursor;
+
+/// Deepest nesting of arrays, maps and tags walked, as in ciborium, so
+/// malformed input cannot exhaust the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Byte range of a single CBOR data item within a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CborSpan {
+    /// Offset of the first byte (including any leading tags).
+    pub start: usize,
+    /// Offset one past the last byte.
+    pub end: usize,
+}
+
+impl CborSpan {
+    /// Number of bytes covered by this span.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Check if the span is empty.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Borrow the bytes covered by this span.
+    pub fn slice<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
+        &bytes[self.start..self.end]
+    }
+}
+
+/// Decoded initial byte(s) of a CBOR data item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CborHeader {
+    /// Major type (0-7).
+    pub major: u8,
+    /// Additional information (low 5 bits of the initial byte).
+    pub info: u8,
+    /// Argument value, or `None` for indefinite-length items and breaks.
+    pub arg: Option<u64>,
+    /// Number of bytes used by the header.
+    pub len: usize,
+}
+
+/// Read the header of the CBOR item at `offset`.
+pub fn read_header(bytes: &[u8], offset: usize) -> Result<CborHeader> {
+    let initial = *bytes.get(offset).ok_or_else(|| malformed(offset))?;
+    let major = initial >> 5;
+    let info = initial & 0x1f;
+
+    let (arg, len) = match info {
+        0..=23 => (Some(info as u64), 1),
+        24..=27 => {
+            let size = 1usize << (info - 24);
+            let raw = bytes
+                .get(offset + 1..offset + 1 + size)
+                .ok_or_else(|| malformed(offset))?;
+            let value = raw.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+            (Some(value), 1 + size)
+        }
+        31 if major >= 2 => (None, 1),
+        _ => return Err(malformed(offset)),
+    };
+
+    Ok(CborHeader {
+        major,
+        info,
+        arg,
+        len,
+    })
+}
+
+/// Decode a CML value from the start of `bytes`, with the number of bytes
+/// it takes. CML stops after the value and ignores anything following it.
+pub fn decode_prefix<T: Deserialize>(
+    bytes: &[u8],
+) -> std::result::Result<(T, usize), DeserializeError> {
+    let mut raw = Deserializer::from(Cursor::new(bytes));
+    let value = T::deserialize(&mut raw)?;
+    Ok((value, raw.as_ref().position() as usize))
+}
+
+/// Compute the span of the complete CBOR item starting at `offset`.
+pub fn item_span(bytes: &[u8], offset: usize) -> Result<CborSpan> {
+    let end = item_end(bytes, offset, 0)?;
+    Ok(CborSpan { start: offset, end })
+}
+
+/// Skip any leading tags, returning the offset of the tagged content.
+pub fn skip_tags(bytes: &[u8], mut offset: usize) -> Result<usize> {
+    loop {
+        let header = read_header(bytes, offset)?;
+        if header.major != 6 {
+            return Ok(offset);
+        }
+        offset += header.len;
+    }
+}
+
+/// List the element spans of the array at `offset` (leading tags are skipped).
+pub fn array_items(bytes: &[u8], offset: usize) -> Result<Vec<CborSpan>> {
+    let offset = skip_tags(bytes, offset)?;
+    let header = read_header(bytes, offset)?;
+    if header.major != 4 {
+        return Err(Error::DecodeFailed(format!(
+            "Expected CBOR array at offset {}",
+            offset
+        )));
+    }
+
+    let mut items = Vec::new();
+    let mut pos = offset + header.len;
+    match header.arg {
+        Some(count) => {
+            for _ in 0..entry_count(bytes, pos, count, 1)? {
+                let span = item_span(bytes, pos)?;
+                pos = span.end;
+                items.push(span);
+            }
+        }
+        None => {
+            while !is_break(bytes, pos)? {
+                let span = item_span(bytes, pos)?;
+                pos = span.end;
+                items.push(span);
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// List the key/value spans of the map at `offset` (leading tags are skipped).
+pub fn map_entries(bytes: &[u8], offset: usize) -> Result<Vec<(CborSpan, CborSpan)>> {
+    let offset = skip_tags(bytes, offset)?;
+    let header = read_header(bytes, offset)?;
+    if header.major != 5 {
+        return Err(Error::DecodeFailed(format!(
+            "Expected CBOR map at offset {}",
+            offset
+        )));
+    }
+
+    let mut entries = Vec::new();
+    let mut pos = offset + header.len;
+    let mut read_entry = |pos: &mut usize| -> Result<()> {
+        let key = item_span(bytes, *pos)?;
+        let value = item_span(bytes, key.end)?;
+        *pos = value.end;
+        entries.push((key, value));
+        Ok(())
+    };
+    match header.arg {
+        Some(count) => {
+            for _ in 0..entry_count(bytes, pos, count, 2)? {
+                read_entry(&mut pos)?;
+            }
+        }
+        None => {
+            while !is_break(bytes, pos)? {
+                read_entry(&mut pos)?;
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Read an unsigned integer item at `offset`.
+pub fn read_uint(bytes: &[u8], offset: usize) -> Result<u64> {
+    let header = read_header(bytes, offset)?;
+    match (header.major, header.arg) {
+        (0, Some(value)) => Ok(value),
+        _ => Err(Error::DecodeFailed(format!(
+            "Expected unsigned integer at offset {}",
+            offset
+        ))),
+    }
+}
+
+/// Find the end offset of the item at `offset`, nested `depth` levels deep.
+fn item_end(bytes: &[u8], offset: usize, depth: usize) -> Result<usize> {
+    if depth > MAX_DEPTH {
+        return Err(Error::DecodeFailed(format!(
+            "CBOR nested too deeply at offset {}",
+            offset
+        )));
+    }
+    let header = read_header(bytes, offset)?;
+    let mut pos = offset + header.len;
+
+    match (header.major, header.arg) {
+        // Integers and simple values carry no content beyond the header
+        (0 | 1 | 7, Some(_)) => Ok(pos),
+        // Definite-length byte/text strings
+        (2 | 3, Some(len)) => {
+            let end = pos
+                .checked_add(len as usize)
+                .filter(|end| *end <= bytes.len())
+                .ok_or_else(|| malformed(offset))?;
+            Ok(end)
+        }
+        (4, Some(count)) => {
+            for _ in 0..entry_count(bytes, pos, count, 1)? {
+                pos = item_end(bytes, pos, depth + 1)?;
+            }
+            Ok(pos)
+        }
+        (5, Some(count)) => {
+            for _ in 0..entry_count(bytes, pos, count, 2)? {
+                pos = item_end(bytes, pos, depth + 1)?;
+                pos = item_end(bytes, pos, depth + 1)?;
+            }
+            Ok(pos)
+        }
+        (6, Some(_)) => item_end(bytes, pos, depth + 1),
+        // Indefinite-length strings, arrays and maps end with a break byte
+        (2..=5, None) => {
+            while !is_break(bytes, pos)? {
+                pos = item_end(bytes, pos, depth + 1)?;
+            }
+            Ok(pos + 1)
+        }
+        _ => Err(malformed(offset)),
+    }
+}
+
+/// Check a container's entry count against the bytes left at `offset`.
+/// Each entry holds `items` items of at least a byte, so more entries than
+/// fit in the rest of the input are malformed.
+fn entry_count(bytes: &[u8], offset: usize, count: u64, items: u64) -> Result<u64> {
+    count
+        .checked_mul(items)
+        .filter(|total| *total <= bytes.len().saturating_sub(offset) as u64)
+        .map(|_| count)
+        .ok_or_else(|| malformed(offset))
+}
+
+/// Check whether the byte at `offset` is a break (0xff).
+fn is_break(bytes: &[u8], offset: usize) -> Result<bool> {
+    bytes
+        .get(offset)
+        .map(|b| *b == 0xff)
+        .ok_or_else(|| malformed(offset))
+}
+
+fn malformed(offset: usize) -> Error {
+    Error::DecodeFailed(format!("Malformed CBOR at offset {}", offset))
+}
+
+/// Field name for a transaction body map key.
+pub fn body_field_name(key: u64) -> Option<&'static str> {
+    match key {
+        0 => Some("inputs"),
+        1 => Some("outputs"),
+        2 => Some("fee"),
+        3 => Some("ttl"),
+        4 => Some("certs"),
+        5 => Some("withdrawals"),
+        7 => Some("auxiliary_data_hash"),
+        8 => Some("validity_interval_start"),
+        9 => Some("mint"),
+        11 => Some("script_data_hash"),
+        13 => Some("collateral_inputs"),
+        14 => Some("required_signers"),
+        15 => Some("network_id"),
+        16 => Some("collateral_return"),
+        17 => Some("total_collateral"),
+        18 => Some("reference_inputs"),
+        19 => Some("voting_procedures"),
+        20 => Some("proposal_procedures"),
+        21 => Some("current_treasury_value"),
+        22 => Some("donation"),
+        _ => None,
+    }
+}
+
+/// Field name for a witness set map key.
+pub fn witness_field_name(key: u64) -> Option<&'static str> {
+    match key {
+        0 => Some("vkeywitnesses"),
+        1 => Some("native_scripts"),
+        2 => Some("bootstrap_witnesses"),
+        3 => Some("plutus_v1_scripts"),
+        4 => Some("plutus_datums"),
+        5 => Some("redeemers"),
+        6 => Some("plutus_v2_scripts"),
+        7 => Some("plutus_v3_scripts"),
+        _ => None,
+    }
+}
+
+/// Field name for a post-Alonzo transaction output map key.
+pub fn output_field_name(key: u64) -> Option<&'static str> {
+    match key {
+        0 => Some("address"),
+        1 => Some("value"),
+        2 => Some("datum"),
+        3 => Some("script_ref"),
+        _ => None,
+    }
+}
+
+/// Field names for the map at a transaction path (e.g. `["body"]`).
+///
+/// Legacy (pre-Babbage) outputs are arrays whose indices follow the same
+/// names, so callers may also use this for output positions.
+pub fn map_field_names(path: &[&str]) -> Option<fn(u64) -> Option<&'static str>> {
+    match path {
+        ["body"] => Some(body_field_name),
+        ["witness_set"] => Some(witness_field_name),
+        ["body", "outputs", _] | ["body", "collateral_return"] => Some(output_field_name),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_span_nested() {
+        // [1, [2, 3], h'aabb']
+        let bytes = [0x83, 0x01, 0x82, 0x02, 0x03, 0x42, 0xaa, 0xbb];
+        let span = item_span(&bytes, 0).unwrap();
+        assert_eq!(span.len(), 8);
+
+        let items = array_items(&bytes, 0).unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1], CborSpan { start: 2, end: 5 });
+        assert_eq!(items[2].slice(&bytes), &[0x42, 0xaa, 0xbb]);
+    }
+
+    #[test]
+    fn test_indefinite_and_tagged() {
+        // 258([_ 1, 2])
+        let bytes = [0xd9, 0x01, 0x02, 0x9f, 0x01, 0x02, 0xff];
+        assert_eq!(item_span(&bytes, 0).unwrap().len(), 7);
+        assert_eq!(array_items(&bytes, 0).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_map_entries() {
+        // {0: 1, 2: 24(h'')}
+        let bytes = [0xa2, 0x00, 0x01, 0x02, 0xd8, 0x18, 0x40];
+        let entries = map_entries(&bytes, 0).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(read_uint(&bytes, entries[1].0.start).unwrap(), 2);
+        assert_eq!(entries[1].1.len(), 3);
+    }
+
+    #[test]
+    fn test_map_field_names() {
+        assert_eq!(map_field_names(&["body"]).and_then(|f| f(2)), Some("fee"));
+        assert_eq!(
+            map_field_names(&["body", "outputs", "1"]).and_then(|f| f(1)),
+            Some("value")
+        );
+        assert!(map_field_names(&["body", "outputs", "1", "value"]).is_none());
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        let bytes = [0x82, 0x01];
+        assert!(item_span(&bytes, 0).is_err());
+    }
+
+    #[test]
+    fn test_hostile_input() {
+        // 500k nested one-element arrays
+        let mut deep = vec![0x81; 500_000];
+        deep.push(0x00);
+        assert!(item_span(&deep, 0).is_err());
+
+        // A map claiming 2^64 - 1 entries
+        let huge = [0xbb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+        assert!(item_span(&huge, 0).is_err());
+        assert!(map_entries(&huge, 0).is_err());
+        assert!(array_items(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff], 0).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+//! COSE_Sign1 decoding and verification for CIP-8 / CIP-30 `signData`.
+
+use crate::error::{Error, Result};
+use ciborium::Value as CborValue;
+use cml_chain::address::Address;
+use cml_chain::certs::Credential;
+use cml_crypto::{Ed25519Signature, PublicKey, RawBytesEncoding};
+use serde_json::Value as JsonValue;
+
+/// COSE tag for COSE_Sign1.
+const COSE_SIGN1_TAG: u64 = 18;
+/// COSE header label for the algorithm.
+const ALG: i64 = 1;
+/// COSE header label for the key id.
+const KID: i64 = 4;
+/// COSE algorithm id for EdDSA.
+const EDDSA: i64 = -8;
+/// COSE_Key label for the OKP public key (`x`).
+const KEY_X: i64 = -2;
+
+/// A decoded COSE_Sign1 structure.
+pub struct DecodedCoseSign1 {
+    /// Serialized protected header (signed over as-is).
+    pub protected: Vec<u8>,
+    /// Algorithm from the protected header.
+    pub algorithm: Option<i64>,
+    /// Signing address from the protected `address` header.
+    pub address: Option<Vec<u8>>,
+    /// Key id from the protected header.
+    pub key_id: Option<Vec<u8>>,
+    /// Whether the payload is a hash of the message (unprotected `hashed`).
+    pub hashed: bool,
+    /// Payload, or `None` if detached.
+    pub payload: Option<Vec<u8>>,
+    /// Ed25519 signature.
+    pub signature: Vec<u8>,
+    /// Verification result, once checked against a key.
+    pub verification: Option<Verification>,
+}
+
+/// Result of checking a signature against a public key.
+pub struct Verification {
+    /// Public key used.
+    pub public_key: Vec<u8>,
+    /// Whether the signature is valid for the key.
+    pub valid: bool,
+    /// Whether the key hash matches the address's payment credential.
+    pub key_matches_address: Option<bool>,
+}
+
+/// Decode a COSE_Sign1 (optionally tagged 18).
+pub fn decode_cose_sign1(bytes: &[u8]) -> Result<DecodedCoseSign1> {
+    let value = parse(bytes)?;
+    let value = match value {
+        CborValue::Tag(COSE_SIGN1_TAG, inner) => *inner,
+        other => other,
+    };
+    let CborValue::Array(items) = value else {
+        return Err(invalid("expected a 4-element array"));
+    };
+    let [protected, unprotected, payload, signature] =
+        <[CborValue; 4]>::try_from(items).map_err(|_| invalid("expected a 4-element array"))?;
+
+    let CborValue::Bytes(protected) = protected else {
+        return Err(invalid("protected header must be a byte string"));
+    };
+    // An empty protected header is encoded as a zero-length byte string
+    let headers = if protected.is_empty() {
+        Vec::new()
+    } else {
+        match parse(&protected)? {
+            CborValue::Map(entries) => entries,
+            _ => return Err(invalid("protected header must encode a map")),
+        }
+    };
+    let CborValue::Map(unprotected) = unprotected else {
+        return Err(invalid("unprotected header must be a map"));
+    };
+    let payload = match payload {
+        CborValue::Bytes(payload) => Some(payload),
+        CborValue::Null => None,
+        _ => return Err(invalid("payload must be a byte string or null")),
+    };
+    let CborValue::Bytes(signature) = signature else {
+        return Err(invalid("signature must be a byte string"));
+    };
+
+    Ok(DecodedCoseSign1 {
+        algorithm: header(&headers, ALG).and_then(as_i64),
+        address: text_header(&headers, "address").and_then(as_bytes),
+        key_id: header(&headers, KID).and_then(as_bytes),
+        hashed: text_header(&unprotected, "hashed") == Some(&CborValue::Bool(true)),
+        protected,
+        payload,
+        signature,
+        verification: None,
+    })
+}
+
+/// Extract the Ed25519 public key from a COSE_Key, or accept a raw 32-byte key.
+pub fn cose_key_public_key(bytes: &[u8]) -> Result<Vec<u8>> {
+    if bytes.len() == 32 {
+        return Ok(bytes.to_vec());
+    }
+    match parse(bytes)? {
+        CborValue::Map(entries) => header(&entries, KEY_X)
+            .and_then(as_bytes)
+            .ok_or_else(|| invalid("COSE_Key has no public key (-2)")),
+        _ => Err(invalid(
+            "key must be a COSE_Key map or a 32-byte public key",
+        )),
+    }
+}
+
+impl DecodedCoseSign1 {
+    /// The `Sig_structure` the signature is computed over.
+    pub fn sig_structure(&self) -> Result<Vec<u8>> {
+        let payload = self
+            .payload
+            .clone()
+            .ok_or_else(|| invalid("payload is detached; cannot verify"))?;
+        let structure = CborValue::Array(vec![
+            CborValue::Text("Signature1".to_string()),
+            CborValue::Bytes(self.protected.clone()),
+            CborValue::Bytes(Vec::new()),
+            CborValue::Bytes(payload),
+        ]);
+        let mut out = Vec::new();
+        ciborium::into_writer(&structure, &mut out)
+            .map_err(|e| Error::FormatError(format!("CBOR error: {}", e)))?;
+        Ok(out)
+    }
+
+    /// Check the signature against an Ed25519 public key.
+    pub fn verify(&mut self, public_key: &[u8]) -> Result<bool> {
+        let key = PublicKey::from_raw_bytes(public_key)
+            .map_err(|e| invalid(&format!("invalid public key: {}", e)))?;
+        let signature = Ed25519Signature::from_raw_bytes(&self.signature)
+            .map_err(|e| invalid(&format!("invalid signature: {}", e)))?;
+        let valid = key.verify(&self.sig_structure()?, &signature);
+
+        let key_hash = key.hash();
+        let key_matches_address = self
+            .address
+            .as_deref()
+            .and_then(|bytes| Address::from_raw_bytes(bytes).ok())
+            .and_then(|address| address.payment_cred().cloned())
+            .map(|cred| matches!(cred, Credential::PubKey { hash, .. } if hash == key_hash));
+
+        self.verification = Some(Verification {
+            public_key: public_key.to_vec(),
+            valid,
+            key_matches_address,
+        });
+        Ok(valid)
+    }
+
+    /// Signing address as bech32 (hex if it does not parse).
+    pub fn address_display(&self) -> Option<String> {
+        self.address.as_deref().map(|bytes| {
+            Address::from_raw_bytes(bytes)
+                .ok()
+                .and_then(|address| address.to_bech32(None).ok())
+                .unwrap_or_else(|| hex::encode(bytes))
+        })
+    }
+
+    /// Signature algorithm name (the COSE number if it is not EdDSA).
+    pub fn algorithm_name(&self) -> Option<String> {
+        self.algorithm.map(algorithm_name)
+    }
+
+    /// Payload as text, if it is printable UTF-8.
+    pub fn payload_text(&self) -> Option<&str> {
+        self.payload
+            .as_deref()
+            .and_then(|p| std::str::from_utf8(p).ok())
+            .filter(|s| !s.chars().any(|c| c.is_control() && !c.is_whitespace()))
+    }
+
+    /// Convert to JSON.
+    pub fn to_json(&self) -> JsonValue {
+        let mut json = serde_json::json!({
+            "algorithm": self.algorithm_name(),
+            "address": self.address_display(),
+            "key_id": self.key_id.as_ref().map(hex::encode),
+            "hashed": self.hashed,
+            "payload": self.payload.as_ref().map(hex::encode),
+            "payload_text": self.payload_text(),
+            "signature": hex::encode(&self.signature)
+        });
+        if let Some(v) = &self.verification {
+            json["verification"] = serde_json::json!({
+                "public_key": hex::encode(&v.public_key),
+                "valid": v.valid,
+                "key_matches_address": v.key_matches_address
+            });
+        }
+        json
+    }
+}
+
+fn algorithm_name(alg: i64) -> String {
+    match alg {
+        EDDSA => "EdDSA".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn parse(bytes: &[u8]) -> Result<CborValue> {
+    ciborium::from_reader(bytes).map_err(|e| invalid(&e.to_string()))
+}
+
+/// Look up an integer-labelled header.
+fn header(entries: &[(CborValue, CborValue)], label: i64) -> Option<&CborValue> {
+    entries
+        .iter()
+        .find(|(k, _)| as_i64(k) == Some(label))
+        .map(|(_, v)| v)
+}
+
+/// Look up a text-labelled header.
+fn text_header<'a>(entries: &'a [(CborValue, CborValue)], label: &str) -> Option<&'a CborValue> {
+    entries
+        .iter()
+        .find(|(k, _)| k.as_text() == Some(label))
+        .map(|(_, v)| v)
+}
+
+fn as_i64(value: &CborValue) -> Option<i64> {
+    value.as_integer().and_then(|i| i64::try_from(i).ok())
+}
+
+fn as_bytes(value: &CborValue) -> Option<Vec<u8>> {
+    value.as_bytes().cloned()
+}
+
+fn invalid(message: &str) -> Error {
+    Error::DecodeFailed(format!("Invalid COSE_Sign1: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cml_crypto::PrivateKey;
+
+    /// Build a COSE_Sign1 over `payload` signed with a fixed test key.
+    fn signed(payload: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let key = PrivateKey::from_normal_bytes(&[7; 32]).unwrap();
+        // {1: -8, "address": h'60' + 28 zero bytes}
+        let mut protected = vec![0xa2, 0x01, 0x27, 0x67];
+        protected.extend_from_slice(b"address");
+        protected.extend_from_slice(&[0x58, 0x1d, 0x60]);
+        protected.extend_from_slice(&[0; 28]);
+
+        let mut cose = DecodedCoseSign1 {
+            protected: protected.clone(),
+            algorithm: None,
+            address: None,
+            key_id: None,
+            hashed: false,
+            payload: Some(payload.to_vec()),
+            signature: Vec::new(),
+            verification: None,
+        };
+        let signature = key.sign(&cose.sig_structure().unwrap());
+        cose.signature = signature.to_raw_bytes().to_vec();
+
+        let value = CborValue::Array(vec![
+            CborValue::Bytes(protected),
+            CborValue::Map(vec![(
+                CborValue::Text("hashed".into()),
+                CborValue::Bool(false),
+            )]),
+            CborValue::Bytes(payload.to_vec()),
+            CborValue::Bytes(cose.signature),
+        ]);
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&value, &mut bytes).unwrap();
+        (bytes, key.to_public().to_raw_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_decode_and_verify() {
+        let (bytes, public_key) = signed(b"hello");
+        let mut cose = decode_cose_sign1(&bytes).unwrap();
+        assert_eq!(cose.algorithm, Some(EDDSA));
+        assert_eq!(cose.payload_text(), Some("hello"));
+        assert!(cose.address_display().unwrap().starts_with("addr_test1"));
+
+        assert!(cose.verify(&public_key).unwrap());
+        // The zero-hash address is not derived from the test key
+        let verification = cose.verification.as_ref().unwrap();
+        assert_eq!(verification.key_matches_address, Some(false));
+
+        let mut other = decode_cose_sign1(&signed(b"other").0).unwrap();
+        other.signature = cose.signature.clone();
+        assert!(!other.verify(&public_key).unwrap());
+    }
+
+    #[test]
+    fn test_cose_key_public_key() {
+        let x = [9u8; 32];
+        // {1: 1, 3: -8, -1: 6, -2: x}
+        let mut key = vec![0xa4, 0x01, 0x01, 0x03, 0x27, 0x20, 0x06, 0x21, 0x58, 0x20];
+        key.extend_from_slice(&x);
+        assert_eq!(cose_key_public_key(&key).unwrap(), x);
+        assert_eq!(cose_key_public_key(&x).unwrap(), x);
+        assert!(cose_key_public_key(&[0xa0]).is_err());
+    }
+}
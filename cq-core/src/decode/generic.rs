@@ -0,0 +1,103 @@
+//! Decoding of arbitrary CBOR into JSON for querying (`--generic`).
+//!
+//! - Integers and floats become numbers (integers beyond 64 bits, and
+//!   floats JSON cannot hold, become strings)
+//! - Byte strings become hex strings
+//! - Maps become objects; non-text keys are written in their JSON form
+//!   (`{0: h'01'}` becomes `{"0": "01"}`)
+//! - Tagged items become `{"tag": N, "value": ...}`
+
+use crate::error::{Error, Result};
+use ciborium::Value as CborValue;
+use serde_json::Value as JsonValue;
+
+/// Decode a single CBOR data item into JSON.
+pub fn cbor_to_json(bytes: &[u8]) -> Result<JsonValue> {
+    let mut reader = bytes;
+    let value: CborValue = ciborium::from_reader(&mut reader)
+        .map_err(|e| Error::DecodeFailed(format!("invalid CBOR: {}", e)))?;
+    if !reader.is_empty() {
+        return Err(Error::DecodeFailed(format!(
+            "{} trailing bytes after the CBOR item",
+            reader.len()
+        )));
+    }
+    Ok(value_to_json(&value))
+}
+
+/// Convert a CBOR value to JSON.
+fn value_to_json(value: &CborValue) -> JsonValue {
+    match value {
+        CborValue::Integer(n) => {
+            let n = i128::from(*n);
+            match (u64::try_from(n), i64::try_from(n)) {
+                (Ok(n), _) => JsonValue::from(n),
+                (_, Ok(n)) => JsonValue::from(n),
+                _ => JsonValue::String(n.to_string()),
+            }
+        }
+        CborValue::Bytes(bytes) => JsonValue::String(hex::encode(bytes)),
+        CborValue::Float(f) => serde_json::Number::from_f64(*f)
+            .map(JsonValue::Number)
+            .unwrap_or_else(|| JsonValue::String(f.to_string())),
+        CborValue::Text(text) => JsonValue::String(text.clone()),
+        CborValue::Bool(b) => JsonValue::Bool(*b),
+        CborValue::Null => JsonValue::Null,
+        CborValue::Tag(tag, inner) => serde_json::json!({
+            "tag": tag,
+            "value": value_to_json(inner)
+        }),
+        CborValue::Array(items) => JsonValue::Array(items.iter().map(value_to_json).collect()),
+        CborValue::Map(entries) => JsonValue::Object(
+            entries
+                .iter()
+                .map(|(k, v)| (key_to_string(k), value_to_json(v)))
+                .collect(),
+        ),
+        // Undefined and any other simple values have no JSON equivalent
+        _ => JsonValue::Null,
+    }
+}
+
+/// Render a map key as an object key.
+fn key_to_string(key: &CborValue) -> String {
+    match key {
+        CborValue::Text(text) => text.clone(),
+        other => match value_to_json(other) {
+            JsonValue::String(s) => s,
+            json => json.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cbor_to_json() {
+        // {0: h'0102', "name": [1, -2, true, null], 1: 6(3.5)}
+        let bytes = hex::decode("a300420102646e616d65840121f5f601c6f94300").unwrap();
+        let json = cbor_to_json(&bytes).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "0": "0102",
+                "name": [1, -2, true, null],
+                "1": { "tag": 6, "value": 3.5 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_cbor() {
+        assert!(matches!(
+            cbor_to_json(&[0x82, 0x01]),
+            Err(Error::DecodeFailed(_))
+        ));
+        assert!(matches!(
+            cbor_to_json(&[0x01, 0x02]),
+            Err(Error::DecodeFailed(_))
+        ));
+    }
+}
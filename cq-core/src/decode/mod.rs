@@ -0,0 +1,33 @@
+//! CBOR decoding module with CML integration.
+
+mod address;
+mod bech32;
+mod block;
+mod cbor;
+mod cose;
+mod generic;
+mod param_update;
+mod transaction;
+mod uplc;
+mod utxo;
+
+pub use address::{DecodedAddress, Network, build_address, decode_address};
+pub use bech32::encode as encode_bech32;
+pub use block::{DecodedBlock, decode_block};
+pub use cbor::{
+    CborHeader, CborSpan, array_items, body_field_name, item_span, map_entries, map_field_names,
+    output_field_name, read_header, read_uint, skip_tags, witness_field_name,
+};
+pub use cose::{DecodedCoseSign1, Verification, cose_key_public_key, decode_cose_sign1};
+pub use generic::cbor_to_json;
+pub use param_update::{decode_param_updates, param_update_to_json};
+pub use transaction::{
+    DecodedTransaction, Era, decode_transaction, decode_transaction_batch, decode_transaction_body,
+};
+pub use uplc::{
+    Constant, Program, ProgramSummary, Term, Type, builtin_name, decode_program,
+    double_wrapped_hash, double_wrapped_script, unwrap_script_bytes,
+};
+pub use utxo::{
+    UtxoSet, decode_utxo_set, find_utxo, parse_outpoint, parse_utxo_cbor, parse_utxo_json,
+};
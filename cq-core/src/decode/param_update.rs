@@ -0,0 +1,229 @@
+//! Decoding of protocol parameter updates (`cq pparams`).
+//!
+//! An update is a map of the parameters it changes, as proposed by a
+//! Conway parameter-change governance action. Ratios are written as
+//! `"numerator/denominator"`; cost models are summarized per language with
+//! their parameter count, keeping the values.
+
+use crate::decode::decode_transaction;
+use crate::error::{Error, Result};
+use cml_chain::governance::GovAction;
+use cml_chain::plutus::{CostModels, ExUnits};
+use cml_chain::{ProtocolParamUpdate, Rational, UnitInterval};
+use cml_core::serialization::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+
+/// Decode a protocol parameter update, or the parameter-change proposals in
+/// a transaction.
+///
+/// A bare update becomes an object of the parameters it sets; a
+/// transaction becomes an array of `{"proposal", "update"}` objects, one
+/// per parameter-change action.
+pub fn decode_param_updates(bytes: &[u8]) -> Result<JsonValue> {
+    if let Ok(update) = ProtocolParamUpdate::from_cbor_bytes(bytes) {
+        return Ok(param_update_to_json(&update));
+    }
+    let tx = decode_transaction(bytes).map_err(|_| {
+        Error::DecodeFailed(
+            "not a protocol parameter update or a transaction proposing one".to_string(),
+        )
+    })?;
+    let updates: Vec<JsonValue> = tx
+        .tx
+        .body
+        .proposal_procedures
+        .iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(idx, proposal)| match &proposal.gov_action {
+            GovAction::ParameterChangeAction(action) => Some(serde_json::json!({
+                "proposal": idx,
+                "update": param_update_to_json(&action.update)
+            })),
+            _ => None,
+        })
+        .collect();
+    if updates.is_empty() {
+        return Err(Error::DecodeFailed(
+            "the transaction proposes no protocol parameter changes".to_string(),
+        ));
+    }
+    Ok(JsonValue::Array(updates))
+}
+
+/// Convert a protocol parameter update to JSON, with only the parameters
+/// it sets.
+pub fn param_update_to_json(update: &ProtocolParamUpdate) -> JsonValue {
+    let mut json = Map::new();
+    let mut set = |name: &str, value: Option<JsonValue>| {
+        if let Some(value) = value {
+            json.insert(name.to_string(), value);
+        }
+    };
+    let number = |value: Option<u64>| value.map(JsonValue::from);
+
+    set("min_fee_a", number(update.minfee_a));
+    set("min_fee_b", number(update.minfee_b));
+    set("max_block_body_size", number(update.max_block_body_size));
+    set("max_tx_size", number(update.max_transaction_size));
+    set(
+        "max_block_header_size",
+        number(update.max_block_header_size),
+    );
+    set("key_deposit", number(update.key_deposit));
+    set("pool_deposit", number(update.pool_deposit));
+    set("max_epoch", number(update.maximum_epoch));
+    set("n_opt", number(update.n_opt));
+    set(
+        "pool_pledge_influence",
+        update.pool_pledge_influence.as_ref().map(rational),
+    );
+    set(
+        "expansion_rate",
+        update.expansion_rate.as_ref().map(interval),
+    );
+    set(
+        "treasury_growth_rate",
+        update.treasury_growth_rate.as_ref().map(interval),
+    );
+    set("min_pool_cost", number(update.min_pool_cost));
+    set("coins_per_utxo_byte", number(update.ada_per_utxo_byte));
+    set(
+        "cost_models",
+        update
+            .cost_models_for_script_languages
+            .as_ref()
+            .map(cost_models),
+    );
+    set(
+        "execution_prices",
+        update.execution_costs.as_ref().map(|prices| {
+            serde_json::json!({
+                "mem": rational(&prices.mem_price),
+                "steps": rational(&prices.step_price)
+            })
+        }),
+    );
+    set(
+        "max_tx_ex_units",
+        update.max_tx_ex_units.as_ref().map(ex_units),
+    );
+    set(
+        "max_block_ex_units",
+        update.max_block_ex_units.as_ref().map(ex_units),
+    );
+    set("max_value_size", number(update.max_value_size));
+    set(
+        "collateral_percentage",
+        number(update.collateral_percentage),
+    );
+    set(
+        "max_collateral_inputs",
+        number(update.max_collateral_inputs),
+    );
+    set(
+        "pool_voting_thresholds",
+        update.pool_voting_thresholds.as_ref().map(|t| {
+            serde_json::json!({
+                "motion_no_confidence": interval(&t.motion_no_confidence),
+                "committee_normal": interval(&t.committee_normal),
+                "committee_no_confidence": interval(&t.committee_no_confidence),
+                "hard_fork_initiation": interval(&t.hard_fork_initiation),
+                "security_relevant_parameter": interval(&t.security_relevant_parameter_voting_threshold)
+            })
+        }),
+    );
+    set(
+        "drep_voting_thresholds",
+        update.d_rep_voting_thresholds.as_ref().map(|t| {
+            serde_json::json!({
+                "motion_no_confidence": interval(&t.motion_no_confidence),
+                "committee_normal": interval(&t.committee_normal),
+                "committee_no_confidence": interval(&t.committee_no_confidence),
+                "update_constitution": interval(&t.update_constitution),
+                "hard_fork_initiation": interval(&t.hard_fork_initiation),
+                "pp_network_group": interval(&t.pp_network_group),
+                "pp_economic_group": interval(&t.pp_economic_group),
+                "pp_technical_group": interval(&t.pp_technical_group),
+                "pp_governance_group": interval(&t.pp_governance_group),
+                "treasury_withdrawal": interval(&t.treasury_withdrawal)
+            })
+        }),
+    );
+    set("min_committee_size", number(update.min_committee_size));
+    set("committee_term_limit", number(update.committee_term_limit));
+    set(
+        "gov_action_lifetime",
+        number(update.governance_action_validity_period),
+    );
+    set(
+        "gov_action_deposit",
+        number(update.governance_action_deposit),
+    );
+    set("drep_deposit", number(update.d_rep_deposit));
+    set("drep_activity", number(update.d_rep_inactivity_period));
+    set(
+        "min_fee_ref_script_cost_per_byte",
+        update
+            .min_fee_ref_script_cost_per_byte
+            .as_ref()
+            .map(rational),
+    );
+
+    JsonValue::Object(json)
+}
+
+fn rational(r: &Rational) -> JsonValue {
+    format!("{}/{}", r.numerator, r.denominator).into()
+}
+
+fn interval(r: &UnitInterval) -> JsonValue {
+    format!("{}/{}", r.start, r.end).into()
+}
+
+fn ex_units(units: &ExUnits) -> JsonValue {
+    serde_json::json!({ "mem": units.mem, "steps": units.steps })
+}
+
+/// Cost models keyed by language, each with its parameter count and values.
+fn cost_models(models: &CostModels) -> JsonValue {
+    let languages = models.inner.iter().map(|(language, values)| {
+        let name = match language {
+            0 => "plutus_v1".to_string(),
+            1 => "plutus_v2".to_string(),
+            2 => "plutus_v3".to_string(),
+            other => other.to_string(),
+        };
+        let model = serde_json::json!({ "parameters": values.len(), "values": values });
+        (name, model)
+    });
+    JsonValue::Object(languages.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cml_core::serialization::Serialize;
+
+    #[test]
+    fn test_param_update_to_json() {
+        let mut update = ProtocolParamUpdate::new();
+        update.max_transaction_size = Some(16_384);
+        update.execution_costs = Some(cml_chain::plutus::ExUnitPrices::new(
+            Rational::new(577, 10_000),
+            Rational::new(721, 10_000_000),
+        ));
+        update.cost_models_for_script_languages =
+            Some(CostModels::new([(1, vec![1, 2, 3])].into_iter().collect()));
+
+        let json = decode_param_updates(&update.to_cbor_bytes()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "max_tx_size": 16384,
+                "cost_models": { "plutus_v2": { "parameters": 3, "values": [1, 2, 3] } },
+                "execution_prices": { "mem": "577/10000", "steps": "721/10000000" }
+            })
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! Transaction decoding with CML.
+
+use crate::decode::cbor::{
+    CborSpan, array_items, decode_prefix, item_span, read_header, skip_tags,
+};
+use crate::error::{Error, Result};
+use cml_chain::auxdata::AuxiliaryData;
+use cml_chain::transaction::{Transaction, TransactionBody, TransactionWitnessSet};
+use cml_crypto::TransactionHash;
+use std::borrow::Cow;
+
+/// A decoded Cardano transaction with preserved original bytes.
+///
+/// The original bytes are borrowed from the input when they can be, so a
+/// memory-mapped file is not copied.
+#[derive(Debug)]
+pub struct DecodedTransaction<'a> {
+    /// The parsed CML transaction.
+    pub tx: Transaction,
+    /// Original CBOR bytes (preserved for hash computation).
+    pub original_bytes: Cow<'a, [u8]>,
+    /// Computed transaction hash.
+    pub hash: TransactionHash,
+}
+
+impl DecodedTransaction<'_> {
+    /// The transaction with its own copy of the original bytes, no longer
+    /// borrowing the input.
+    pub fn into_owned(self) -> DecodedTransaction<'static> {
+        DecodedTransaction {
+            tx: self.tx,
+            original_bytes: Cow::Owned(self.original_bytes.into_owned()),
+            hash: self.hash,
+        }
+    }
+
+    /// Access the transaction body.
+    pub fn body(&self) -> &TransactionBody {
+        &self.tx.body
+    }
+
+    /// Access the witness set.
+    pub fn witness_set(&self) -> &TransactionWitnessSet {
+        &self.tx.witness_set
+    }
+
+    /// Access auxiliary data (metadata).
+    pub fn auxiliary_data(&self) -> Option<&AuxiliaryData> {
+        self.tx.auxiliary_data.as_ref()
+    }
+
+    /// Check if the transaction is marked as valid.
+    pub fn is_valid(&self) -> bool {
+        self.tx.is_valid
+    }
+
+    /// The network the transaction is for: its `network_id` field, or the
+    /// network of its first output address.
+    pub fn network_id(&self) -> Option<u8> {
+        let body = self.body();
+        body.network_id
+            .as_ref()
+            .map(|n| n.network as u8)
+            .or_else(|| {
+                body.outputs
+                    .iter()
+                    .find_map(|o| o.address().network_id().ok())
+            })
+    }
+
+    /// Best-effort era detection from the features used by the transaction.
+    ///
+    /// CBOR does not carry an explicit era tag, so the era reported is the
+    /// latest one whose features the transaction uses: Conway for
+    /// governance, treasury, Conway certificates, Plutus V3 scripts or
+    /// tag-258 sets; Babbage for reference inputs, collateral return,
+    /// inline datums, reference scripts, map-format outputs or Plutus V2
+    /// scripts; Alonzo for Plutus V1 scripts, datums, redeemers, collateral,
+    /// required signers, a script data hash or a network ID. A transaction
+    /// using none of them fits every era from Alonzo on and is
+    /// [`Era::Unknown`]. Earlier eras have no validity flag, so their
+    /// transactions do not decode.
+    pub fn era(&self) -> Era {
+        use cml_chain::certs::Certificate;
+        use cml_chain::transaction::TransactionOutput;
+        use cml_core::serialization::Serialize;
+
+        let body = self.body();
+        let witnesses = self.witness_set();
+
+        let has_governance = body.voting_procedures.is_some()
+            || body.proposal_procedures.is_some()
+            || body.current_treasury_value.is_some()
+            || body.donation.is_some();
+
+        let has_conway_certs = body.certs.as_ref().is_some_and(|certs| {
+            certs.iter().any(|cert| {
+                !matches!(
+                    cert,
+                    Certificate::StakeRegistration(_)
+                        | Certificate::StakeDeregistration(_)
+                        | Certificate::StakeDelegation(_)
+                        | Certificate::PoolRegistration(_)
+                        | Certificate::PoolRetirement(_)
+                )
+            })
+        });
+
+        // Conway sets may be tagged 258; CML keeps the tag when re-encoding
+        let has_set_tags = body.inputs.to_cbor_bytes().starts_with(&[0xd9, 0x01, 0x02]);
+
+        let has_babbage_outputs = body
+            .outputs
+            .iter()
+            .chain(body.collateral_return.as_ref())
+            .any(|output| matches!(output, TransactionOutput::ConwayFormatTxOut(_)));
+        let has_babbage_fields = body.reference_inputs.is_some()
+            || body.collateral_return.is_some()
+            || body.total_collateral.is_some()
+            || witnesses.plutus_v2_scripts.is_some();
+
+        let has_datum_hashes = body
+            .outputs
+            .iter()
+            .any(|output| output.datum_hash().is_some());
+        let has_alonzo_fields = body.script_data_hash.is_some()
+            || body.collateral_inputs.is_some()
+            || body.required_signers.is_some()
+            || body.network_id.is_some()
+            || witnesses.plutus_v1_scripts.is_some()
+            || witnesses.plutus_datums.is_some()
+            || witnesses.redeemers.is_some();
+
+        let conway = has_governance
+            || has_conway_certs
+            || witnesses.plutus_v3_scripts.is_some()
+            || has_set_tags;
+        let babbage = has_babbage_outputs || has_babbage_fields;
+        let alonzo = has_datum_hashes || has_alonzo_fields;
+        log::trace!(
+            "era detection: Conway features {}, Babbage features {}, Alonzo features {}",
+            conway,
+            babbage,
+            alonzo
+        );
+
+        match (conway, babbage, alonzo) {
+            (true, _, _) => Era::Conway,
+            (false, true, _) => Era::Babbage,
+            (false, false, true) => Era::Alonzo,
+            (false, false, false) => Era::Unknown,
+        }
+    }
+}
+
+/// Ledger era of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Era {
+    Alonzo,
+    Babbage,
+    Conway,
+    /// No era-specific features: any era from Alonzo on.
+    Unknown,
+}
+
+impl Era {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Era::Alonzo => "alonzo",
+            Era::Babbage => "babbage",
+            Era::Conway => "conway",
+            Era::Unknown => "unknown",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Era::Alonzo => "Alonzo",
+            Era::Babbage => "Babbage",
+            Era::Conway => "Conway",
+            Era::Unknown => "unknown",
+        }
+    }
+}
+
+/// Decode a transaction from CBOR bytes.
+///
+/// Only the transaction's own bytes are kept as its original bytes: input
+/// continuing after it, such as a CBOR sequence of transactions, is left
+/// for [`decode_transaction_batch`].
+pub fn decode_transaction(bytes: &[u8]) -> Result<DecodedTransaction<'_>> {
+    // Use CML to deserialize the transaction
+    let (tx, len) =
+        decode_prefix::<Transaction>(bytes).map_err(|e| Error::DecodeFailed(e.to_string()))?;
+
+    // Compute transaction hash from body
+    // CML's TransactionBody::hash() computes blake2b_256 of the body bytes
+    let hash = tx.body.hash();
+
+    let decoded = DecodedTransaction {
+        tx,
+        original_bytes: Cow::Borrowed(&bytes[..len]),
+        hash,
+    };
+    log::debug!(
+        "decoded {}-byte transaction {} ({} era)",
+        len,
+        decoded.hash.to_hex(),
+        decoded.era().description()
+    );
+    Ok(decoded)
+}
+
+/// Decode a bare transaction body, as builders emit before witnessing, as
+/// a transaction without witnesses or auxiliary data.
+///
+/// The body keeps its original bytes, so the hash is the id the witnessed
+/// transaction will have.
+pub fn decode_transaction_body(bytes: &[u8]) -> Result<DecodedTransaction<'static>> {
+    let (_, len) =
+        decode_prefix::<TransactionBody>(bytes).map_err(|e| Error::DecodeFailed(e.to_string()))?;
+    if len != bytes.len() {
+        return Err(Error::DecodeFailed(format!(
+            "{} trailing bytes after the transaction body",
+            bytes.len() - len
+        )));
+    }
+
+    // [body, {}, true, null]
+    let wrapped = [&[0x84], bytes, &[0xa0, 0xf5, 0xf6]].concat();
+    let DecodedTransaction { tx, hash, .. } = decode_transaction(&wrapped)?;
+    Ok(DecodedTransaction {
+        tx,
+        original_bytes: Cow::Owned(wrapped),
+        hash,
+    })
+}
+
+/// Decode input holding several transactions: a CBOR sequence of them
+/// (concatenated items), or a CBOR array of them.
+///
+/// Returns `None` for input that is a single item other than an array of
+/// transactions, such as one transaction. A transaction that fails to
+/// decode fails the batch, naming its position.
+///
+/// This walks the input item by item, so try [`decode_transaction`] first
+/// and only fall back to a batch when it fails or stops short of the end.
+pub fn decode_transaction_batch(bytes: &[u8]) -> Option<Result<Vec<DecodedTransaction<'_>>>> {
+    let items = batch_items(bytes)?;
+    let decoded = items
+        .iter()
+        .enumerate()
+        .map(|(index, span)| {
+            decode_transaction(span.slice(bytes)).map_err(|e| match e {
+                Error::DecodeFailed(msg) => {
+                    Error::DecodeFailed(format!("transaction {}: {}", index, msg))
+                }
+                other => other,
+            })
+        })
+        .collect();
+    Some(decoded)
+}
+
+/// Spans of the transactions in a batch input.
+fn batch_items(bytes: &[u8]) -> Option<Vec<CborSpan>> {
+    let is_array = |span: &CborSpan| {
+        skip_tags(bytes, span.start)
+            .and_then(|offset| read_header(bytes, offset))
+            .is_ok_and(|header| header.major == 4)
+    };
+
+    // CML ignores bytes after the first item, so a sequence must be
+    // recognized before decoding. Every item must be an array: a stray
+    // trailing byte such as a newline is not a transaction.
+    let mut items = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let span = item_span(bytes, offset).ok()?;
+        offset = span.end;
+        items.push(span);
+    }
+    if items.len() > 1 {
+        return items.iter().all(is_array).then_some(items);
+    }
+
+    // A transaction is an array starting with its body map; an array of
+    // transactions starts with an array
+    let items = array_items(bytes, 0).ok()?;
+    is_array(items.first()?).then_some(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cml_core::serialization::{Deserialize, Serialize};
+
+    #[test]
+    fn test_decode_transaction_body() {
+        let body = TransactionBody::new(Vec::new().into(), Vec::new(), 170_000);
+        let decoded = decode_transaction_body(&body.to_cbor_bytes()).unwrap();
+        assert_eq!(decoded.hash, body.hash());
+        assert_eq!(decoded.body().fee, 170_000);
+        assert!(decoded.witness_set().vkeywitnesses.is_none());
+
+        let tx = Transaction::new(body, TransactionWitnessSet::new(), true, None);
+        assert!(decode_transaction_body(&tx.to_cbor_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_era() {
+        use cml_chain::transaction::{AlonzoFormatTxOut, ConwayFormatTxOut};
+        use cml_chain::{Value, address::Address};
+
+        let address =
+            Address::from_bech32("addr_test1vp9s80tz7l3dxmg4wcsd6fwnjcxuqul6wy6x5pwt98hmhjg52l8g8")
+                .unwrap();
+        let era = |body: TransactionBody| {
+            let tx = Transaction::new(body, TransactionWitnessSet::new(), true, None);
+            decode_transaction(&tx.to_cbor_bytes()).unwrap().era()
+        };
+        // An untagged input array: CML tags sets it builds itself
+        let inputs = cml_chain::SetTransactionInput::from_cbor_bytes(&[0x80]).unwrap();
+        let body = |output| TransactionBody::new(inputs.clone(), vec![output], 170_000);
+        let legacy = AlonzoFormatTxOut::new(address.clone(), Value::from(2_000_000));
+
+        assert_eq!(era(body(legacy.clone().into())), Era::Unknown);
+
+        let mut with_datum_hash = legacy.clone();
+        with_datum_hash.datum_hash = Some(cml_crypto::DatumHash::from([0; 32]));
+        assert_eq!(era(body(with_datum_hash.into())), Era::Alonzo);
+
+        let map_format = ConwayFormatTxOut::new(address, Value::from(2_000_000));
+        assert_eq!(era(body(map_format.into())), Era::Babbage);
+
+        let mut donating = body(legacy.clone().into());
+        donating.donation = Some(1_000_000);
+        assert_eq!(era(donating), Era::Conway);
+
+        let tagged = TransactionBody::new(Vec::new().into(), vec![legacy.into()], 170_000);
+        assert_eq!(era(tagged), Era::Conway);
+    }
+
+    #[test]
+    fn test_decode_transaction_batch() {
+        let body = |fee| TransactionBody::new(Vec::new().into(), Vec::new(), fee);
+        let tx = |fee| {
+            Transaction::new(body(fee), TransactionWitnessSet::new(), true, None).to_cbor_bytes()
+        };
+        let (first, second) = (tx(1), tx(2));
+        assert!(decode_transaction_batch(&first).is_none());
+
+        let sequence = [first.as_slice(), &second].concat();
+        assert_eq!(decode_transaction(&sequence).unwrap().original_bytes, first);
+        let batch = decode_transaction_batch(&sequence).unwrap().unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[1].original_bytes, second);
+        assert!(matches!(batch[1].original_bytes, Cow::Borrowed(_)));
+
+        let array = [&[0x82], first.as_slice(), &second].concat();
+        assert_eq!(decode_transaction_batch(&array).unwrap().unwrap().len(), 2);
+
+        let trailing_newline = [first.as_slice(), b"\n"].concat();
+        assert!(decode_transaction_batch(&trailing_newline).is_none());
+
+        let broken = [first.as_slice(), &[0x80]].concat();
+        let err = decode_transaction_batch(&broken).unwrap().unwrap_err();
+        assert!(err.to_string().contains("transaction 1:"));
+    }
+}
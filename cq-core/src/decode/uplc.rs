@@ -0,0 +1,908 @@
+//! Untyped Plutus Core: the programs inside Plutus scripts.
+//!
+//! On chain, a Plutus script is a byte string holding the CBOR byte string
+//! of a flat-encoded program. Flat is a bit-level encoding: terms are 4-bit
+//! tags followed by their fields, numbers are 7-bit groups, and byte strings
+//! are aligned to a byte boundary and split into chunks.
+//!
+//! The decoder is our own rather than the `uplc` crate's: that crate comes
+//! with the Plutus evaluator, whose native crypto (blst, secp256k1) and
+//! second ledger library (pallas) are far more than reading a program needs.
+
+use crate::decode::cbor::read_header;
+use crate::error::{Error, Result};
+use cml_chain::Script;
+use cml_chain::plutus::{PlutusV1Script, PlutusV2Script, PlutusV3Script};
+use cml_crypto::{RawBytesEncoding, ScriptHash};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Deepest term nesting decoded, so malformed input cannot exhaust the stack.
+const MAX_DEPTH: usize = 10_000;
+
+/// Deepest constant type nesting decoded. Real types nest a few levels;
+/// a constant's value, printing and cloning recurse as deep as its type.
+const MAX_TYPE_DEPTH: usize = 100;
+
+/// Width pretty-printed programs are wrapped to.
+const WIDTH: usize = 80;
+
+/// Deepest indentation of pretty-printed programs; deeper terms are written
+/// at this level, keeping a line's room for terms and the output linear.
+const MAX_INDENT: usize = 20;
+
+/// A decoded program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// Plutus Core language version (`1.0.0`, or `1.1.0` with Plutus V3).
+    pub version: (u64, u64, u64),
+    pub term: Term,
+    /// Size of the flat encoding in bytes.
+    pub size: usize,
+}
+
+/// A term, with variables as de Bruijn indices (1 is the nearest lambda).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Var(u64),
+    Delay(Box<Term>),
+    Lambda(Box<Term>),
+    Apply(Box<Term>, Box<Term>),
+    Constant(Constant),
+    Force(Box<Term>),
+    Error,
+    Builtin(u8),
+    Constr(u64, Vec<Term>),
+    Case(Box<Term>, Vec<Term>),
+}
+
+/// A constant value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constant {
+    /// An integer of any size, in decimal.
+    Integer(String),
+    ByteString(Vec<u8>),
+    String(String),
+    Unit,
+    Bool(bool),
+    /// A list and the type of its elements.
+    List(Type, Vec<Constant>),
+    Pair(Box<Constant>, Box<Constant>),
+    /// Plutus data, as CBOR.
+    Data(Vec<u8>),
+}
+
+/// The type of a constant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    Integer,
+    ByteString,
+    String,
+    Unit,
+    Bool,
+    List(Box<Type>),
+    Pair(Box<Type>, Box<Type>),
+    Data,
+}
+
+/// Counts of a program's terms, by kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProgramSummary {
+    pub terms: usize,
+    pub variables: usize,
+    pub lambdas: usize,
+    pub applications: usize,
+    pub constants: usize,
+    pub delays: usize,
+    pub forces: usize,
+    pub errors: usize,
+    pub constrs: usize,
+    pub cases: usize,
+    /// How often each builtin is referenced.
+    pub builtins: BTreeMap<String, usize>,
+}
+
+/// Strip the CBOR byte string layers around a flat program: one on chain,
+/// two in a cardano-cli script file's `cborHex`.
+pub fn unwrap_script_bytes(mut bytes: &[u8]) -> &[u8] {
+    while let Some(inner) = strip_byte_string(bytes) {
+        bytes = inner;
+    }
+    bytes
+}
+
+/// The script bytes without their extra CBOR byte string layer, when a
+/// Plutus script's bytes wrap the flat program twice rather than once.
+///
+/// The ledger hashes script bytes as they are, so such a script gets a
+/// hash other than the one of the intended script. A flat program starts
+/// with its version, never with a byte string header, so a second layer
+/// is always an extra one.
+pub fn double_wrapped_script(script_bytes: &[u8]) -> Option<&[u8]> {
+    let inner = strip_byte_string(script_bytes)?;
+    strip_byte_string(inner).map(|_| inner)
+}
+
+/// Hash the script would have without its extra CBOR layer, for a Plutus
+/// script double wrapped as [`double_wrapped_script`] describes.
+pub fn double_wrapped_hash(script: &Script) -> Option<ScriptHash> {
+    match script {
+        Script::Native { .. } => None,
+        Script::PlutusV1 { script, .. } => double_wrapped_script(script.to_raw_bytes())
+            .map(|inner| PlutusV1Script::new(inner.to_vec()).hash()),
+        Script::PlutusV2 { script, .. } => double_wrapped_script(script.to_raw_bytes())
+            .map(|inner| PlutusV2Script::new(inner.to_vec()).hash()),
+        Script::PlutusV3 { script, .. } => double_wrapped_script(script.to_raw_bytes())
+            .map(|inner| PlutusV3Script::new(inner.to_vec()).hash()),
+    }
+}
+
+/// The content of a definite-length byte string spanning all of `bytes`.
+fn strip_byte_string(bytes: &[u8]) -> Option<&[u8]> {
+    let header = read_header(bytes, 0).ok()?;
+    let len = header.arg?;
+    (header.major == 2 && header.len as u64 + len == bytes.len() as u64)
+        .then(|| &bytes[header.len..])
+}
+
+/// Decode a Plutus script's program, from flat bytes or script bytes
+/// wrapped in CBOR byte strings.
+pub fn decode_program(bytes: &[u8]) -> Result<Program> {
+    let flat = unwrap_script_bytes(bytes);
+    let mut reader = Reader {
+        bytes: flat,
+        pos: 0,
+    };
+    let version = (reader.natural()?, reader.natural()?, reader.natural()?);
+    let term = reader.term(0)?;
+    Ok(Program {
+        version,
+        term,
+        size: flat.len(),
+    })
+}
+
+/// Name of a builtin function by its flat tag.
+pub fn builtin_name(tag: u8) -> Option<&'static str> {
+    BUILTINS.get(tag as usize).copied()
+}
+
+const BUILTINS: &[&str] = &[
+    "addInteger",
+    "subtractInteger",
+    "multiplyInteger",
+    "divideInteger",
+    "quotientInteger",
+    "remainderInteger",
+    "modInteger",
+    "equalsInteger",
+    "lessThanInteger",
+    "lessThanEqualsInteger",
+    "appendByteString",
+    "consByteString",
+    "sliceByteString",
+    "lengthOfByteString",
+    "indexByteString",
+    "equalsByteString",
+    "lessThanByteString",
+    "lessThanEqualsByteString",
+    "sha2_256",
+    "sha3_256",
+    "blake2b_256",
+    "verifyEd25519Signature",
+    "appendString",
+    "equalsString",
+    "encodeUtf8",
+    "decodeUtf8",
+    "ifThenElse",
+    "chooseUnit",
+    "trace",
+    "fstPair",
+    "sndPair",
+    "chooseList",
+    "mkCons",
+    "headList",
+    "tailList",
+    "nullList",
+    "chooseData",
+    "constrData",
+    "mapData",
+    "listData",
+    "iData",
+    "bData",
+    "unConstrData",
+    "unMapData",
+    "unListData",
+    "unIData",
+    "unBData",
+    "equalsData",
+    "mkPairData",
+    "mkNilData",
+    "mkNilPairData",
+    "serialiseData",
+    "verifyEcdsaSecp256k1Signature",
+    "verifySchnorrSecp256k1Signature",
+    "bls12_381_G1_add",
+    "bls12_381_G1_neg",
+    "bls12_381_G1_scalarMul",
+    "bls12_381_G1_equal",
+    "bls12_381_G1_compress",
+    "bls12_381_G1_uncompress",
+    "bls12_381_G1_hashToGroup",
+    "bls12_381_G2_add",
+    "bls12_381_G2_neg",
+    "bls12_381_G2_scalarMul",
+    "bls12_381_G2_equal",
+    "bls12_381_G2_compress",
+    "bls12_381_G2_uncompress",
+    "bls12_381_G2_hashToGroup",
+    "bls12_381_millerLoop",
+    "bls12_381_mulMlResult",
+    "bls12_381_finalVerify",
+    "keccak_256",
+    "blake2b_224",
+    "integerToByteString",
+    "byteStringToInteger",
+    "andByteString",
+    "orByteString",
+    "xorByteString",
+    "complementByteString",
+    "readBit",
+    "writeBits",
+    "replicateByte",
+    "shiftByteString",
+    "rotateByteString",
+    "countSetBits",
+    "findFirstSetBit",
+    "ripemd_160",
+    "expModInteger",
+];
+
+impl Program {
+    /// Count the program's terms.
+    pub fn summary(&self) -> ProgramSummary {
+        let mut summary = ProgramSummary::default();
+        let mut pending = vec![&self.term];
+        while let Some(term) = pending.pop() {
+            summary.terms += 1;
+            match term {
+                Term::Var(_) => summary.variables += 1,
+                Term::Delay(t) => {
+                    summary.delays += 1;
+                    pending.push(t);
+                }
+                Term::Lambda(t) => {
+                    summary.lambdas += 1;
+                    pending.push(t);
+                }
+                Term::Apply(f, a) => {
+                    summary.applications += 1;
+                    pending.extend([&**f, &**a]);
+                }
+                Term::Constant(_) => summary.constants += 1,
+                Term::Force(t) => {
+                    summary.forces += 1;
+                    pending.push(t);
+                }
+                Term::Error => summary.errors += 1,
+                Term::Builtin(tag) => {
+                    *summary.builtins.entry(builtin_label(*tag)).or_default() += 1
+                }
+                Term::Constr(_, fields) => {
+                    summary.constrs += 1;
+                    pending.extend(fields);
+                }
+                Term::Case(scrutinee, branches) => {
+                    summary.cases += 1;
+                    pending.push(scrutinee);
+                    pending.extend(branches);
+                }
+            }
+        }
+        summary
+    }
+
+    /// The program in the textual UPLC syntax, with terms wider than a
+    /// line broken over indented lines.
+    pub fn pretty(&self) -> String {
+        let mut out = format!("(program {}\n", self.version_string());
+        write_pretty(&self.term, 0, 1, &mut out);
+        out.push_str("\n)");
+        out
+    }
+
+    /// The version, flat size, term counts and pretty-printed program.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::json!({
+            "version": self.version_string(),
+            "size": self.size,
+        });
+        if let Ok(serde_json::Value::Object(summary)) = serde_json::to_value(self.summary()) {
+            json.as_object_mut().unwrap().extend(summary);
+        }
+        json["program"] = self.pretty().into();
+        json
+    }
+
+    /// The version as `major.minor.patch`.
+    pub fn version_string(&self) -> String {
+        let (major, minor, patch) = self.version;
+        format!("{}.{}.{}", major, minor, patch)
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut term = String::new();
+        write_inline(&self.term, 0, &mut term, usize::MAX);
+        write!(f, "(program {} {})", self.version_string(), term)
+    }
+}
+
+fn builtin_label(tag: u8) -> String {
+    builtin_name(tag).map_or_else(|| format!("builtin_{}", tag), str::to_string)
+}
+
+/// Name of a variable: lambdas are named by how many enclose them.
+fn var_name(index: u64, lambdas: usize) -> String {
+    match usize::try_from(index) {
+        Ok(index @ 1..) if index <= lambdas => format!("i_{}", lambdas - index),
+        _ => format!("free_{}", index),
+    }
+}
+
+/// Write a term on one line, giving up once `out` is longer than `limit`.
+fn write_inline(term: &Term, lambdas: usize, out: &mut String, limit: usize) -> bool {
+    if out.len() > limit {
+        return false;
+    }
+    match term {
+        Term::Var(index) => out.push_str(&var_name(*index, lambdas)),
+        Term::Lambda(body) => {
+            out.push_str(&format!("(lam i_{} ", lambdas));
+            if !write_inline(body, lambdas + 1, out, limit) {
+                return false;
+            }
+            out.push(')');
+        }
+        Term::Apply(..) => {
+            out.push('[');
+            for (idx, part) in apply_parts(term).into_iter().enumerate() {
+                if idx > 0 {
+                    out.push(' ');
+                }
+                if !write_inline(part, lambdas, out, limit) {
+                    return false;
+                }
+            }
+            out.push(']');
+        }
+        _ => {
+            let (head, children) = node_parts(term);
+            out.push('(');
+            out.push_str(&head);
+            for child in children {
+                out.push(' ');
+                if !write_inline(child, lambdas, out, limit) {
+                    return false;
+                }
+            }
+            out.push(')');
+        }
+    }
+    out.len() <= limit
+}
+
+/// Write a term, on one line if it fits and over indented lines otherwise.
+fn write_pretty(term: &Term, lambdas: usize, indent: usize, out: &mut String) {
+    let pad = "  ".repeat(indent.min(MAX_INDENT));
+    // Leaves cannot be broken up, so they go on their line however long
+    let limit = match term {
+        Term::Var(_) | Term::Constant(_) | Term::Builtin(_) | Term::Error => usize::MAX,
+        _ => WIDTH - pad.len(),
+    };
+    let mut line = String::new();
+    if write_inline(term, lambdas, &mut line, limit) {
+        out.push_str(&pad);
+        out.push_str(&line);
+        return;
+    }
+
+    let (open, children, inner, close) = match term {
+        Term::Lambda(body) => (
+            format!("(lam i_{}", lambdas),
+            vec![&**body],
+            lambdas + 1,
+            ')',
+        ),
+        Term::Apply(..) => ("[".to_string(), apply_parts(term), lambdas, ']'),
+        _ => {
+            let (head, children) = node_parts(term);
+            (format!("({}", head), children, lambdas, ')')
+        }
+    };
+    out.push_str(&pad);
+    out.push_str(&open);
+    for child in children {
+        out.push('\n');
+        write_pretty(child, inner, indent + 1, out);
+    }
+    out.push('\n');
+    out.push_str(&pad);
+    out.push(close);
+}
+
+/// The function and arguments of nested applications: `[f a b]`.
+fn apply_parts(term: &Term) -> Vec<&Term> {
+    let mut parts = Vec::new();
+    let mut head = term;
+    while let Term::Apply(f, a) = head {
+        parts.push(&**a);
+        head = f;
+    }
+    parts.push(head);
+    parts.reverse();
+    parts
+}
+
+/// The keyword (with any inline fields) and subterms of other terms.
+fn node_parts(term: &Term) -> (String, Vec<&Term>) {
+    match term {
+        Term::Delay(t) => ("delay".to_string(), vec![t]),
+        Term::Force(t) => ("force".to_string(), vec![t]),
+        Term::Error => ("error".to_string(), Vec::new()),
+        Term::Builtin(tag) => (format!("builtin {}", builtin_label(*tag)), Vec::new()),
+        Term::Constant(constant) => (
+            format!("con {} {}", constant.type_of(), constant),
+            Vec::new(),
+        ),
+        Term::Constr(tag, fields) => (format!("constr {}", tag), fields.iter().collect()),
+        Term::Case(scrutinee, branches) => (
+            "case".to_string(),
+            std::iter::once(&**scrutinee).chain(branches).collect(),
+        ),
+        Term::Var(_) | Term::Lambda(_) | Term::Apply(..) => unreachable!("written separately"),
+    }
+}
+
+impl Constant {
+    /// The constant's type.
+    pub fn type_of(&self) -> Type {
+        match self {
+            Constant::Integer(_) => Type::Integer,
+            Constant::ByteString(_) => Type::ByteString,
+            Constant::String(_) => Type::String,
+            Constant::Unit => Type::Unit,
+            Constant::Bool(_) => Type::Bool,
+            Constant::List(ty, _) => Type::List(Box::new(ty.clone())),
+            Constant::Pair(a, b) => Type::Pair(Box::new(a.type_of()), Box::new(b.type_of())),
+            Constant::Data(_) => Type::Data,
+        }
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constant::Integer(n) => write!(f, "{}", n),
+            Constant::ByteString(bytes) => write!(f, "#{}", hex::encode(bytes)),
+            Constant::String(s) => write!(f, "{:?}", s),
+            Constant::Unit => write!(f, "()"),
+            Constant::Bool(true) => write!(f, "True"),
+            Constant::Bool(false) => write!(f, "False"),
+            Constant::List(_, items) => {
+                let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+            Constant::Pair(a, b) => write!(f, "({}, {})", a, b),
+            Constant::Data(bytes) => match ciborium::from_reader(bytes.as_slice()) {
+                Ok(value) => write!(f, "({})", data_text(&value)),
+                Err(_) => write!(f, "#{}", hex::encode(bytes)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Integer => write!(f, "integer"),
+            Type::ByteString => write!(f, "bytestring"),
+            Type::String => write!(f, "string"),
+            Type::Unit => write!(f, "unit"),
+            Type::Bool => write!(f, "bool"),
+            Type::List(ty) => write!(f, "(list {})", ty),
+            Type::Pair(a, b) => write!(f, "(pair {} {})", a, b),
+            Type::Data => write!(f, "data"),
+        }
+    }
+}
+
+/// Plutus data in the textual syntax: `Constr 0 [I 1, B #00]`.
+fn data_text(value: &ciborium::Value) -> String {
+    use ciborium::Value;
+
+    let list = |items: &[Value]| {
+        let items: Vec<String> = items.iter().map(data_text).collect();
+        format!("[{}]", items.join(", "))
+    };
+    match value {
+        Value::Tag(tag @ (121..=127 | 1280..=1400), fields) => {
+            let constructor = if *tag <= 127 {
+                tag - 121
+            } else {
+                tag - 1280 + 7
+            };
+            match fields.as_array() {
+                Some(fields) => format!("Constr {} {}", constructor, list(fields)),
+                None => "?".to_string(),
+            }
+        }
+        Value::Tag(102, inner) => match inner.as_array().map(Vec::as_slice) {
+            Some([Value::Integer(tag), Value::Array(fields)]) => {
+                format!("Constr {} {}", i128::from(*tag), list(fields))
+            }
+            _ => "?".to_string(),
+        },
+        Value::Tag(tag @ (2 | 3), bytes) => match bytes.as_bytes() {
+            Some(bytes) => {
+                let mut digits: Vec<u8> = bytes.iter().rev().copied().collect();
+                if *tag == 2 {
+                    format!("I {}", to_decimal(&digits, 256))
+                } else {
+                    // -1 - n
+                    increment(&mut digits, 256);
+                    format!("I -{}", to_decimal(&digits, 256))
+                }
+            }
+            None => "?".to_string(),
+        },
+        Value::Integer(n) => format!("I {}", i128::from(*n)),
+        Value::Bytes(bytes) => format!("B #{}", hex::encode(bytes)),
+        Value::Array(items) => format!("List {}", list(items)),
+        Value::Map(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| format!("({}, {})", data_text(k), data_text(v)))
+                .collect();
+            format!("Map [{}]", entries.join(", "))
+        }
+        _ => "?".to_string(),
+    }
+}
+
+/// Add one to a number given as little-endian digits in `base`.
+fn increment(digits: &mut Vec<u8>, base: u16) {
+    for digit in digits.iter_mut() {
+        if (*digit as u16) + 1 < base {
+            *digit += 1;
+            return;
+        }
+        *digit = 0;
+    }
+    digits.push(1);
+}
+
+/// Decimal digits of a number given as little-endian digits in `base`.
+fn to_decimal(digits: &[u8], base: u64) -> String {
+    const LIMB: u64 = 1_000_000_000;
+    let mut limbs: Vec<u64> = vec![0];
+    for &digit in digits.iter().rev() {
+        let mut carry = digit as u64;
+        for limb in limbs.iter_mut() {
+            let value = *limb * base + carry;
+            *limb = value % LIMB;
+            carry = value / LIMB;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+    }
+    let mut text = limbs.last().copied().unwrap_or(0).to_string();
+    for limb in limbs.iter().rev().skip(1) {
+        text.push_str(&format!("{:09}", limb));
+    }
+    text
+}
+
+/// Reads flat-encoded values bit by bit, most significant bit first.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    /// Position in bits.
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn bit(&mut self) -> Result<bool> {
+        let byte = self.bytes.get(self.pos / 8).ok_or_else(|| {
+            Error::DecodeFailed("flat script ends in the middle of a term".to_string())
+        })?;
+        let bit = (byte >> (7 - self.pos % 8)) & 1;
+        self.pos += 1;
+        Ok(bit == 1)
+    }
+
+    fn bits(&mut self, n: u32) -> Result<u8> {
+        (0..n).try_fold(0u8, |acc, _| Ok((acc << 1) | self.bit()? as u8))
+    }
+
+    /// A natural number's 7-bit groups, least significant first.
+    fn digits(&mut self) -> Result<Vec<u8>> {
+        let mut digits = Vec::new();
+        loop {
+            let byte = self.bits(8)?;
+            digits.push(byte & 0x7f);
+            if byte & 0x80 == 0 {
+                return Ok(digits);
+            }
+        }
+    }
+
+    fn natural(&mut self) -> Result<u64> {
+        let digits = self.digits()?;
+        if digits.len() > 9 {
+            return Err(invalid("number too large"));
+        }
+        Ok(digits
+            .iter()
+            .rev()
+            .fold(0u64, |acc, digit| (acc << 7) | *digit as u64))
+    }
+
+    /// A zigzag-encoded integer of any size, in decimal.
+    fn integer(&mut self) -> Result<String> {
+        let mut digits = self.digits()?;
+        let negative = digits[0] & 1 == 1;
+        if negative {
+            // -n is encoded as 2n - 1: add one before halving
+            increment(&mut digits, 128);
+        }
+        let halved: Vec<u8> = (0..digits.len())
+            .map(|i| (digits[i] >> 1) | ((digits.get(i + 1).copied().unwrap_or(0) & 1) << 6))
+            .collect();
+        let magnitude = to_decimal(&halved, 128);
+        Ok(if negative {
+            format!("-{}", magnitude)
+        } else {
+            magnitude
+        })
+    }
+
+    /// Padding to the next byte boundary: zeros, then a one.
+    fn filler(&mut self) -> Result<()> {
+        while !self.bit()? {}
+        Ok(())
+    }
+
+    fn bytestring(&mut self) -> Result<Vec<u8>> {
+        self.filler()?;
+        let mut bytes = Vec::new();
+        loop {
+            let len = self.bits(8)?;
+            if len == 0 {
+                return Ok(bytes);
+            }
+            for _ in 0..len {
+                bytes.push(self.bits(8)?);
+            }
+        }
+    }
+
+    /// Items each preceded by a one bit, ended by a zero bit.
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        while self.bit()? {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    fn term(&mut self, depth: usize) -> Result<Term> {
+        if depth > MAX_DEPTH {
+            return Err(invalid("terms nested too deeply"));
+        }
+        let next = depth + 1;
+        Ok(match self.bits(4)? {
+            0 => Term::Var(self.natural()?),
+            1 => Term::Delay(Box::new(self.term(next)?)),
+            2 => Term::Lambda(Box::new(self.term(next)?)),
+            3 => Term::Apply(Box::new(self.term(next)?), Box::new(self.term(next)?)),
+            4 => {
+                let tags = self.list(|r| r.bits(4))?;
+                let mut tags = tags.into_iter();
+                let ty = parse_type(&mut tags, 0)?;
+                if tags.next().is_some() {
+                    return Err(invalid("malformed constant type"));
+                }
+                Term::Constant(self.constant(&ty)?)
+            }
+            5 => Term::Force(Box::new(self.term(next)?)),
+            6 => Term::Error,
+            7 => Term::Builtin(self.bits(7)?),
+            8 => Term::Constr(self.natural()?, self.list(|r| r.term(next))?),
+            9 => Term::Case(Box::new(self.term(next)?), self.list(|r| r.term(next))?),
+            tag => return Err(invalid(&format!("unknown term tag {}", tag))),
+        })
+    }
+
+    /// A constant of a parsed type; it nests no deeper than the type.
+    fn constant(&mut self, ty: &Type) -> Result<Constant> {
+        Ok(match ty {
+            Type::Integer => Constant::Integer(self.integer()?),
+            Type::ByteString => Constant::ByteString(self.bytestring()?),
+            Type::String => Constant::String(
+                String::from_utf8(self.bytestring()?)
+                    .map_err(|_| invalid("string constant is not UTF-8"))?,
+            ),
+            Type::Unit => Constant::Unit,
+            Type::Bool => Constant::Bool(self.bit()?),
+            Type::List(item) => Constant::List((**item).clone(), self.list(|r| r.constant(item))?),
+            Type::Pair(a, b) => {
+                Constant::Pair(Box::new(self.constant(a)?), Box::new(self.constant(b)?))
+            }
+            Type::Data => Constant::Data(self.bytestring()?),
+        })
+    }
+}
+
+/// A constant type from its 4-bit tags: `7 5 a` is a list, `7 7 6 a b` a pair.
+fn parse_type(tags: &mut impl Iterator<Item = u8>, depth: usize) -> Result<Type> {
+    if depth > MAX_TYPE_DEPTH {
+        return Err(invalid("constant type nested too deeply"));
+    }
+    let next = depth + 1;
+    Ok(match tags.next() {
+        Some(0) => Type::Integer,
+        Some(1) => Type::ByteString,
+        Some(2) => Type::String,
+        Some(3) => Type::Unit,
+        Some(4) => Type::Bool,
+        Some(8) => Type::Data,
+        Some(7) => match tags.next() {
+            Some(5) => Type::List(Box::new(parse_type(tags, next)?)),
+            Some(7) if tags.next() == Some(6) => Type::Pair(
+                Box::new(parse_type(tags, next)?),
+                Box::new(parse_type(tags, next)?),
+            ),
+            _ => return Err(invalid("malformed constant type")),
+        },
+        Some(tag) => return Err(invalid(&format!("unsupported constant type {}", tag))),
+        None => return Err(invalid("malformed constant type")),
+    })
+}
+
+fn invalid(msg: &str) -> Error {
+    Error::DecodeFailed(format!("invalid flat script: {}", msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bits written most significant first, padded with the flat filler.
+    fn flat(bits: &str) -> Vec<u8> {
+        let mut bits: String = bits.split_whitespace().collect();
+        bits.push('1');
+        while bits.len() % 8 != 0 {
+            bits.insert(bits.len() - 1, '0');
+        }
+        (0..bits.len())
+            .step_by(8)
+            .map(|i| u8::from_str_radix(&bits[i..i + 8], 2).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_program() {
+        // (program 1.0.0 (lam i_0 [(builtin addInteger) i_0 (con integer -3)]))
+        let bytes = flat(concat!(
+            "00000001 00000000 00000000",
+            "0010 0011 0011 0111 0000000 0000 00000001",
+            "0100 1 0000 0 00000101",
+        ));
+        let program = decode_program(&bytes).unwrap();
+        assert_eq!(program.version, (1, 0, 0));
+        assert_eq!(
+            program.to_string(),
+            "(program 1.0.0 (lam i_0 [(builtin addInteger) i_0 (con integer -3)]))"
+        );
+
+        let summary = program.summary();
+        assert_eq!(
+            (summary.terms, summary.lambdas, summary.applications),
+            (6, 1, 2)
+        );
+        assert_eq!(summary.builtins["addInteger"], 1);
+
+        // The same program as script bytes, and as a cardano-cli cborHex
+        let wrapped = [&[0x40 | bytes.len() as u8][..], &bytes].concat();
+        assert_eq!(decode_program(&wrapped).unwrap(), program);
+        let twice = [&[0x40 | wrapped.len() as u8][..], &wrapped].concat();
+        assert_eq!(decode_program(&twice).unwrap(), program);
+    }
+
+    #[test]
+    fn test_constants() {
+        // (con (list bytestring) [#ab]), bytes aligned after the list bit
+        let bytes = flat(concat!(
+            "00000001 00000001 00000000",
+            "0100 1 0111 1 0101 1 0001 0",
+            "1 0001 00000001 10101011 00000000 0",
+        ));
+        assert_eq!(
+            decode_program(&bytes).unwrap().to_string(),
+            "(program 1.1.0 (con (list bytestring) [#ab]))"
+        );
+    }
+
+    #[test]
+    fn test_nested_constant_type() {
+        // (con (list (list ... integer)) []) with the list tags repeated
+        let program = |lists: usize| {
+            let tags = "1 0111 1 0101 ".repeat(lists);
+            flat(&format!(
+                "00000001 00000000 00000000 0100 {}1 0000 0 0",
+                tags
+            ))
+        };
+        assert_eq!(
+            decode_program(&program(2)).unwrap().to_string(),
+            "(program 1.0.0 (con (list (list integer)) []))"
+        );
+
+        assert!(decode_program(&program(MAX_TYPE_DEPTH)).is_ok());
+        let err = decode_program(&program(100_000)).unwrap_err();
+        assert!(err.to_string().contains("nested too deeply"), "{}", err);
+    }
+
+    #[test]
+    fn test_large_integer() {
+        // 2^70, zigzag encoded as 2^71: eleven 7-bit groups
+        let mut digits = vec![0x80u8; 10];
+        digits.push(0x02);
+        let mut reader = Reader {
+            bytes: &digits,
+            pos: 0,
+        };
+        assert_eq!(reader.integer().unwrap(), (1u128 << 70).to_string());
+    }
+
+    #[test]
+    fn test_pretty_deep_nesting() {
+        // 60 nested lambdas around the innermost variable
+        let bits = format!(
+            "00000001 00000000 00000000 {} 0000 00000001",
+            "0010 ".repeat(60)
+        );
+        let program = decode_program(&flat(&bits)).unwrap();
+
+        let pretty = program.pretty();
+        assert!(pretty.contains("(lam i_59 i_59)"));
+        let deepest = pretty
+            .lines()
+            .map(|line| line.len() - line.trim_start().len());
+        assert_eq!(deepest.max(), Some(2 * MAX_INDENT));
+    }
+
+    #[test]
+    fn test_double_wrapped_script() {
+        let flat = [0x01, 0x00, 0x00, 0x61];
+        let once = [&[0x44][..], &flat].concat();
+        let twice = [&[0x45][..], &once].concat();
+        assert_eq!(double_wrapped_script(&once), None);
+        assert_eq!(double_wrapped_script(&twice), Some(&once[..]));
+
+        let script = Script::new_plutus_v2(PlutusV2Script::new(twice));
+        assert_eq!(
+            double_wrapped_hash(&script),
+            Some(PlutusV2Script::new(once).hash())
+        );
+    }
+
+    #[test]
+    fn test_truncated_program() {
+        let err = decode_program(&[0x01, 0x00, 0x00, 0x20]).unwrap_err();
+        assert!(err.to_string().contains("ends in the middle"));
+    }
+}
@@ -0,0 +1,258 @@
+//! UTxO sets: the outputs that transaction inputs spend.
+//!
+//! Read from `cardano-cli query utxo --out-file` JSON: an object keyed by
+//! `txid#index` whose entries hold the `address`, the `value` (`lovelace`,
+//! and policy id → asset name hex → quantity), optionally a `datumhash`
+//! or inline datum (`inlineDatumRaw` CBOR, or `inlineDatum` JSON), and a
+//! `referenceScript`. The older CBOR form, a map from input to output, is
+//! read as binary or hex.
+
+use crate::decode::cbor::map_entries;
+use crate::error::{Error, Result};
+use cml_chain::address::Address;
+use cml_chain::assets::{AssetName, MultiAsset, Value};
+use cml_chain::byron::ByronAddress;
+use cml_chain::json::plutus_datums::{
+    CardanoNodePlutusDatumSchema, encode_json_str_to_plutus_datum,
+};
+use cml_chain::plutus::{PlutusData, PlutusV1Script, PlutusV2Script, PlutusV3Script};
+use cml_chain::transaction::{DatumOption, NativeScript, TransactionInput, TransactionOutput};
+use cml_chain::{PolicyId, Script};
+use cml_core::serialization::Deserialize;
+use cml_crypto::{DatumHash, TransactionHash};
+use serde_json::Value as JsonValue;
+
+/// Unspent outputs and the inputs that reference them.
+pub type UtxoSet = Vec<(TransactionInput, TransactionOutput)>;
+
+/// Decode a UTxO set from cardano-cli JSON, or from CBOR as binary or hex.
+pub fn decode_utxo_set(bytes: &[u8]) -> Result<UtxoSet> {
+    let text = std::str::from_utf8(bytes)
+        .map(str::trim)
+        .unwrap_or_default();
+    if text.starts_with('{') {
+        let json = serde_json::from_str(text).map_err(|e| invalid(e.to_string()))?;
+        return parse_utxo_json(&json);
+    }
+    match hex::decode(text.strip_prefix("0x").unwrap_or(text)) {
+        Ok(cbor) if !text.is_empty() => parse_utxo_cbor(&cbor),
+        _ => parse_utxo_cbor(bytes),
+    }
+}
+
+/// Parse the CBOR form of a UTxO set: a map from input to output.
+pub fn parse_utxo_cbor(bytes: &[u8]) -> Result<UtxoSet> {
+    let entries = map_entries(bytes, 0)
+        .map_err(|_| invalid("expected JSON, or a CBOR map from input to output".to_string()))?;
+    entries
+        .iter()
+        .map(|(key, value)| {
+            let input = TransactionInput::from_cbor_bytes(key.slice(bytes))
+                .map_err(|e| invalid(format!("input: {}", e)))?;
+            let output = TransactionOutput::from_cbor_bytes(value.slice(bytes)).map_err(|e| {
+                invalid(format!(
+                    "output of {}#{}: {}",
+                    input.transaction_id.to_hex(),
+                    input.index,
+                    e
+                ))
+            })?;
+            Ok((input, output))
+        })
+        .collect()
+}
+
+/// Parse a cardano-cli UTxO JSON file.
+pub fn parse_utxo_json(json: &JsonValue) -> Result<UtxoSet> {
+    let entries = json
+        .as_object()
+        .ok_or_else(|| invalid("expected an object keyed by txid#index".to_string()))?;
+    entries
+        .iter()
+        .map(|(outpoint, entry)| {
+            let input = parse_outpoint(outpoint)?;
+            let output = parse_output(entry).map_err(|e| match e {
+                Error::DecodeFailed(msg) => Error::DecodeFailed(format!("{} ({})", msg, outpoint)),
+                other => other,
+            })?;
+            Ok((input, output))
+        })
+        .collect()
+}
+
+/// The output an input spends, if the set holds it.
+pub fn find_utxo<'a>(
+    utxos: &'a UtxoSet,
+    input: &TransactionInput,
+) -> Option<&'a TransactionOutput> {
+    utxos
+        .iter()
+        .find(|(utxo, _)| utxo.transaction_id == input.transaction_id && utxo.index == input.index)
+        .map(|(_, output)| output)
+}
+
+/// Parse `txid#index`.
+pub fn parse_outpoint(outpoint: &str) -> Result<TransactionInput> {
+    let (txid, index) = outpoint
+        .split_once('#')
+        .ok_or_else(|| invalid(format!("expected txid#index, got {}", outpoint)))?;
+    let txid = TransactionHash::from_hex(txid)
+        .map_err(|_| invalid(format!("invalid transaction id in {}", outpoint)))?;
+    let index = index
+        .parse()
+        .map_err(|_| invalid(format!("invalid output index in {}", outpoint)))?;
+    Ok(TransactionInput::new(txid, index))
+}
+
+fn parse_output(entry: &JsonValue) -> Result<TransactionOutput> {
+    let address = entry["address"]
+        .as_str()
+        .ok_or_else(|| invalid("missing address".to_string()))?;
+    let address = Address::from_bech32(address)
+        .ok()
+        .or_else(|| {
+            ByronAddress::from_base58(address)
+                .ok()
+                .map(ByronAddress::to_address)
+        })
+        .ok_or_else(|| invalid(format!("invalid address {}", address)))?;
+
+    let datum = if let Some(hash) = entry["datumhash"].as_str() {
+        let hash = DatumHash::from_hex(hash)
+            .map_err(|_| invalid(format!("invalid datum hash {}", hash)))?;
+        Some(DatumOption::new_hash(hash))
+    } else if let Some(raw) = entry["inlineDatumRaw"].as_str() {
+        let datum = hex::decode(raw)
+            .ok()
+            .and_then(|bytes| PlutusData::from_cbor_bytes(&bytes).ok())
+            .ok_or_else(|| invalid("invalid inlineDatumRaw".to_string()))?;
+        Some(DatumOption::new_datum(datum))
+    } else if !entry["inlineDatum"].is_null() {
+        let datum = encode_json_str_to_plutus_datum(
+            &entry["inlineDatum"].to_string(),
+            CardanoNodePlutusDatumSchema::DetailedSchema,
+        )
+        .map_err(|e| invalid(format!("invalid inlineDatum: {}", e)))?;
+        Some(DatumOption::new_datum(datum))
+    } else {
+        None
+    };
+
+    let script_reference = match &entry["referenceScript"] {
+        JsonValue::Null => None,
+        script => Some(parse_script(&script["script"])?),
+    };
+
+    Ok(TransactionOutput::new(
+        address,
+        parse_value(&entry["value"])?,
+        datum,
+        script_reference,
+    ))
+}
+
+/// Parse a text envelope script: `{"type": "PlutusScriptV2", "cborHex": ...}`.
+fn parse_script(script: &JsonValue) -> Result<Script> {
+    let kind = script["type"].as_str().unwrap_or_default();
+    let bytes = script["cborHex"]
+        .as_str()
+        .and_then(|cbor| hex::decode(cbor).ok())
+        .ok_or_else(|| invalid("reference script without cborHex".to_string()))?;
+    let parsed = match kind {
+        "SimpleScript" => NativeScript::from_cbor_bytes(&bytes).map(Script::new_native),
+        "PlutusScriptV1" => PlutusV1Script::from_cbor_bytes(&bytes).map(Script::new_plutus_v1),
+        "PlutusScriptV2" => PlutusV2Script::from_cbor_bytes(&bytes).map(Script::new_plutus_v2),
+        "PlutusScriptV3" => PlutusV3Script::from_cbor_bytes(&bytes).map(Script::new_plutus_v3),
+        other => return Err(invalid(format!("unknown reference script type {}", other))),
+    };
+    parsed.map_err(|e| invalid(format!("invalid {} reference script: {}", kind, e)))
+}
+
+/// Parse `{"lovelace": n, "<policy id>": {"<asset name hex>": n}}`.
+fn parse_value(value: &JsonValue) -> Result<Value> {
+    let fields = value
+        .as_object()
+        .ok_or_else(|| invalid("missing value".to_string()))?;
+    let mut coin = 0;
+    let mut multiasset = MultiAsset::new();
+    for (key, amount) in fields {
+        if key == "lovelace" {
+            coin = quantity(amount)?;
+            continue;
+        }
+        let policy_id =
+            PolicyId::from_hex(key).map_err(|_| invalid(format!("invalid policy id {}", key)))?;
+        for (name, amount) in amount.as_object().into_iter().flatten() {
+            let name = hex::decode(name)
+                .ok()
+                .and_then(|bytes| AssetName::new(bytes).ok())
+                .ok_or_else(|| invalid(format!("invalid asset name {}", name)))?;
+            multiasset.set(policy_id, name, quantity(amount)?);
+        }
+    }
+    Ok(Value::new(coin, multiasset))
+}
+
+fn quantity(amount: &JsonValue) -> Result<u64> {
+    amount
+        .as_u64()
+        .ok_or_else(|| invalid(format!("invalid quantity {}", amount)))
+}
+
+fn invalid(message: String) -> Error {
+    Error::DecodeFailed(format!("invalid UTxO: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_utxo_json() {
+        let txid = "1a48999128069edfa82776fbccb9696ac49d1b2c4a054be42ca9affb111383e9";
+        let policy = "ab".repeat(28);
+        let json = serde_json::json!({
+            format!("{}#1", txid): {
+                "address": "addr_test1vz09v9yfxguvlp0zsnrpa3tdtm7el8xufp3m5lsm7qxzclgmzkket",
+                "value": { "lovelace": 5000000, policy.clone(): { "4e49474854": 7 } },
+                "datumhash": "ff".repeat(32)
+            }
+        });
+
+        let utxos = parse_utxo_json(&json).unwrap();
+        let input = TransactionInput::new(TransactionHash::from_hex(txid).unwrap(), 1);
+        let output = find_utxo(&utxos, &input).unwrap();
+        assert_eq!(output.amount().coin, 5_000_000);
+        assert_eq!(
+            output.amount().multiasset.get(
+                &PolicyId::from_hex(&policy).unwrap(),
+                &AssetName::new(b"NIGHT".to_vec()).unwrap()
+            ),
+            Some(7)
+        );
+        assert!(output.datum_hash().is_some());
+        assert!(find_utxo(&utxos, &TransactionInput::new(input.transaction_id, 0)).is_none());
+
+        assert!(parse_utxo_json(&serde_json::json!({ "nohash": {} })).is_err());
+    }
+
+    #[test]
+    fn test_decode_utxo_set_cbor() {
+        use cml_core::serialization::Serialize;
+
+        let input = TransactionInput::new(TransactionHash::from([7; 32]), 3);
+        let address =
+            Address::from_bech32("addr_test1vz09v9yfxguvlp0zsnrpa3tdtm7el8xufp3m5lsm7qxzclgmzkket")
+                .unwrap();
+        let output = TransactionOutput::new(address, Value::from(2_000_000), None, None);
+        // A one-entry map: {input => output}
+        let cbor = [&[0xa1][..], &input.to_cbor_bytes(), &output.to_cbor_bytes()].concat();
+
+        for bytes in [cbor.clone(), hex::encode(&cbor).into_bytes()] {
+            let utxos = decode_utxo_set(&bytes).unwrap();
+            assert_eq!(utxos.len(), 1);
+            assert_eq!(find_utxo(&utxos, &input).unwrap().amount().coin, 2_000_000);
+        }
+        assert!(decode_utxo_set(b"not a utxo set").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Error types for cq.
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Result type alias for cq operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors that can occur in cq.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// No input was provided (no file, no stdin, no hex).
+    #[error("No input provided. Use: cq <file>, cq <hex>, or pipe CBOR to stdin")]
+    NoInput,
+
+    /// The specified file was not found.
+    #[error("File not found: {0}")]
+    FileNotFound(PathBuf),
+
+    /// An I/O error occurred.
+    #[error("IO error{}: {source}", path.as_ref().map(|p| format!(" reading {}", p.display())).unwrap_or_default())]
+    IoError {
+        path: Option<PathBuf>,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Invalid hex input.
+    #[error("Invalid hex input: {0}")]
+    InvalidHex(#[from] hex::FromHexError),
+
+    /// Invalid CBOR diagnostic notation (for `cq encode`).
+    #[error("Invalid diagnostic notation: {0}")]
+    InvalidDiagnostic(String),
+
+    /// Invalid metadata JSON (for `cq encode --metadata`).
+    #[error("Invalid metadata JSON: {0}")]
+    InvalidMetadata(String),
+
+    /// Invalid config file (`~/.config/cq/config.toml`).
+    #[error("Invalid config file: {0}")]
+    InvalidConfig(String),
+
+    /// Failed to decode CBOR/transaction.
+    #[error("Failed to decode transaction: {0}")]
+    DecodeFailed(String),
+
+    /// A signature did not verify (for `cq cose --key`).
+    #[error("Signature verification failed")]
+    InvalidSignature,
+
+    /// Invalid query syntax.
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
+    /// Requested field was not found.
+    #[error("Field not found: '{0}'")]
+    FieldNotFound(String),
+
+    /// Array index out of bounds.
+    #[error("Index {0} out of bounds")]
+    IndexOutOfBounds(usize),
+
+    /// Output formatting error.
+    #[error("Format error: {0}")]
+    FormatError(String),
+
+    /// The transaction is larger than the protocol allows (--check mode).
+    #[error("Transaction too large: {size} bytes, the limit is {max}")]
+    TxTooLarge { size: usize, max: u64 },
+
+    /// The transaction hash is not the expected one (--expect-hash).
+    #[error("Transaction hash mismatch: expected {expected}, got {actual}")]
+    HashMismatch { expected: String, actual: String },
+
+    /// A Plutus script wrapped in an extra CBOR byte string layer (--check
+    /// mode), so its hash is not the intended one.
+    #[error(
+        "Plutus script {hash} is wrapped in an extra CBOR byte string; unwrapped, its hash is {unwrapped_hash}"
+    )]
+    DoubleCborScript {
+        hash: String,
+        unwrapped_hash: String,
+    },
+
+    /// A pool registration's metadata hash is not the hash of the given
+    /// metadata document (--check with --pool-metadata).
+    #[error(
+        "Pool metadata hash mismatch for pool {pool}: the certificate has {expected}, the document hashes to {actual}"
+    )]
+    PoolMetadataMismatch {
+        pool: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// A transaction input missing from the UTxO set it is resolved against.
+    #[error("Unresolved input: {0} is not in the UTxO set")]
+    UnresolvedInput(String),
+
+    /// Unsupported transaction era.
+    #[error("Unsupported era: only Babbage and Conway transactions are supported")]
+    UnsupportedEra,
+
+    /// Network error (e.g., when checking for updates).
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
+    /// Query produced no usable result (with --exit-status).
+    #[error("Query result is empty, null or false")]
+    EmptyResult,
+}
+
+impl Error {
+    /// Get the appropriate exit code for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            // Validation failure (--check mode)
+            Error::DecodeFailed(_)
+            | Error::UnsupportedEra
+            | Error::InvalidSignature
+            | Error::TxTooLarge { .. }
+            | Error::HashMismatch { .. }
+            | Error::DoubleCborScript { .. }
+            | Error::PoolMetadataMismatch { .. } => 1,
+            // Parse/decode errors
+            Error::InvalidHex(_)
+            | Error::InvalidDiagnostic(_)
+            | Error::InvalidMetadata(_)
+            | Error::InvalidConfig(_) => 2,
+            // I/O errors
+            Error::NoInput
+            | Error::FileNotFound(_)
+            | Error::IoError { .. }
+            | Error::UnresolvedInput(_) => 3,
+            // Query errors
+            Error::InvalidQuery(_) | Error::FieldNotFound(_) | Error::IndexOutOfBounds(_) => 4,
+            // Format errors
+            Error::FormatError(_) => 5,
+            // Network errors (non-fatal for update check)
+            Error::NetworkError(_) => 6,
+            // Empty result with --exit-status
+            Error::EmptyResult => 7,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(Error::NoInput.exit_code(), 3);
+        assert_eq!(Error::DecodeFailed("test".into()).exit_code(), 1);
+        assert_eq!(Error::InvalidQuery("test".into()).exit_code(), 4);
+        assert_eq!(Error::EmptyResult.exit_code(), 7);
+        assert_eq!(Error::InvalidDiagnostic("test".into()).exit_code(), 2);
+        assert_eq!(Error::InvalidMetadata("test".into()).exit_code(), 2);
+        assert_eq!(Error::InvalidSignature.exit_code(), 1);
+        assert_eq!(Error::TxTooLarge { size: 2, max: 1 }.exit_code(), 1);
+        assert_eq!(Error::InvalidConfig("test".into()).exit_code(), 2);
+        assert_eq!(Error::UnresolvedInput("test#0".into()).exit_code(), 3);
+        let mismatch = Error::HashMismatch {
+            expected: "aa".into(),
+            actual: "bb".into(),
+        };
+        assert_eq!(mismatch.exit_code(), 1);
+    }
+
+    #[test]
+    fn test_error_display() {
+        let err = Error::FieldNotFound("fee".into());
+        assert_eq!(err.to_string(), "Field not found: 'fee'");
+    }
+}
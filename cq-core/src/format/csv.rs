@@ -0,0 +1,144 @@
+//! CSV and TSV output for tabular results.
+
+use crate::error::{Error, Result};
+use crate::query::QueryResult;
+use serde_json::Value as JsonValue;
+
+/// Format a result as delimiter-separated rows with a header line.
+///
+/// Each value of a wildcard result (or element of an array) becomes a row.
+/// Nested objects are flattened into dotted column names (`value.coin`);
+/// arrays are written as JSON. Scalar rows use a single `value` column.
+pub fn format_delimited(result: &QueryResult, delimiter: char) -> Result<String> {
+    let rows: Vec<JsonValue> = match result {
+        QueryResult::Multiple(values) => values.iter().cloned().map(JsonValue::from).collect(),
+        QueryResult::Single(value) => match JsonValue::from(value.clone()) {
+            JsonValue::Array(items) => items,
+            other => vec![other],
+        },
+        QueryResult::FullTransaction(_) => {
+            return Err(Error::FormatError(
+                "CSV/TSV output needs a query selecting rows (e.g. outputs.*)".to_string(),
+            ));
+        }
+    };
+
+    // Flatten each row, collecting columns in first-seen order
+    let mut columns: Vec<String> = Vec::new();
+    let flat_rows: Vec<Vec<(String, String)>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = Vec::new();
+            match row {
+                JsonValue::Object(_) => flatten("", row, &mut cells),
+                scalar => cells.push(("value".to_string(), cell_text(scalar))),
+            }
+            for (column, _) in &cells {
+                if !columns.contains(column) {
+                    columns.push(column.clone());
+                }
+            }
+            cells
+        })
+        .collect();
+
+    let mut lines = vec![join_fields(&columns, delimiter)];
+    for cells in flat_rows {
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                cells
+                    .iter()
+                    .find(|(c, _)| c == column)
+                    .map(|(_, v)| v.clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+        lines.push(join_fields(&fields, delimiter));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Flatten an object into `(dotted.path, text)` cells.
+fn flatten(prefix: &str, value: &JsonValue, cells: &mut Vec<(String, String)>) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&path, child, cells);
+            }
+        }
+        other => cells.push((prefix.to_string(), cell_text(other))),
+    }
+}
+
+/// Text of a single cell (strings unquoted, arrays as JSON, null empty).
+fn cell_text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Join fields with the delimiter, escaping as needed.
+fn join_fields(fields: &[String], delimiter: char) -> String {
+    fields
+        .iter()
+        .map(|field| escape_field(field, delimiter))
+        .collect::<Vec<_>>()
+        .join(&delimiter.to_string())
+}
+
+/// Escape a field: RFC 4180 quoting for CSV, whitespace replacement for TSV.
+fn escape_field(field: &str, delimiter: char) -> String {
+    if delimiter == '\t' {
+        return field.replace(['\t', '\n', '\r'], " ");
+    }
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::QueryValue;
+
+    #[test]
+    fn test_csv_flattens_objects() {
+        let result = QueryResult::Multiple(vec![
+            QueryValue::from(serde_json::json!({
+                "address": { "address": "addr1" },
+                "value": { "coin": 5, "multi_assets": [{ "policy_id": "ab" }] }
+            })),
+            QueryValue::from(serde_json::json!({
+                "address": { "address": "addr2" },
+                "value": { "coin": 7 },
+                "datum": { "type": "hash" }
+            })),
+        ]);
+
+        let csv = format_delimited(&result, ',').unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "address.address,value.coin,value.multi_assets,datum.type"
+        );
+        assert_eq!(lines[1], "addr1,5,\"[{\"\"policy_id\"\":\"\"ab\"\"}]\",");
+        assert_eq!(lines[2], "addr2,7,,hash");
+    }
+
+    #[test]
+    fn test_tsv_scalars() {
+        let result = QueryResult::Single(QueryValue::from(serde_json::json!([1, "a\tb"])));
+        assert_eq!(format_delimited(&result, '\t').unwrap(), "value\n1\na b");
+    }
+}
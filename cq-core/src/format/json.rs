@@ -0,0 +1,134 @@
+//! JSON output formatting.
+
+use crate::error::{Error, Result};
+use crate::query::QueryResult;
+use serde_json::Value as JsonValue;
+
+/// Keys whose numbers are lovelace amounts, wherever they appear.
+const LOVELACE_KEYS: [&str; 8] = [
+    "fee",
+    "coin",
+    "total_collateral",
+    "deposit",
+    "pledge",
+    "cost",
+    "donation",
+    "current_treasury_value",
+];
+
+/// Format a query result as JSON.
+pub fn format_json(result: &QueryResult) -> Result<String> {
+    serde_json::to_string_pretty(result).map_err(|e| Error::FormatError(e.to_string()))
+}
+
+/// Format a query result as JSON with ADA amounts (`--ada`).
+///
+/// Lovelace fields keep their number and gain an `{field}_ada` string such
+/// as `"fee_ada": "0.171617"`. A result that is a bare number (or a list of
+/// them), e.g. from `cq fee`, is taken as lovelace and becomes ADA strings.
+pub fn format_json_ada(result: &QueryResult) -> Result<String> {
+    let mut json = serde_json::to_value(result).map_err(|e| Error::FormatError(e.to_string()))?;
+    match (result, &mut json) {
+        (QueryResult::Single(_), JsonValue::Number(_)) => json = ada_value(&json).into(),
+        (QueryResult::Multiple(_), JsonValue::Array(items)) => {
+            for item in items {
+                match item {
+                    JsonValue::Number(_) => *item = ada_value(item).into(),
+                    _ => add_ada_fields(item),
+                }
+            }
+        }
+        _ => add_ada_fields(&mut json),
+    }
+    serde_json::to_string_pretty(&json).map_err(|e| Error::FormatError(e.to_string()))
+}
+
+/// Add an `{key}_ada` string next to every lovelace field.
+fn add_ada_fields(json: &mut JsonValue) {
+    match json {
+        JsonValue::Object(map) => {
+            let amounts: Vec<(String, String)> = map
+                .iter()
+                .filter(|(key, _)| LOVELACE_KEYS.contains(&key.as_str()))
+                .filter_map(|(key, value)| Some((format!("{}_ada", key), ada_value(value)?)))
+                .collect();
+            if let Some(JsonValue::Array(withdrawals)) = map.get_mut("withdrawals") {
+                for withdrawal in withdrawals.iter_mut() {
+                    if let Some(ada) = withdrawal.get("amount").and_then(ada_value) {
+                        withdrawal["amount_ada"] = ada.into();
+                    }
+                }
+            }
+            for value in map.values_mut() {
+                add_ada_fields(value);
+            }
+            map.extend(amounts.into_iter().map(|(key, ada)| (key, ada.into())));
+        }
+        JsonValue::Array(items) => items.iter_mut().for_each(add_ada_fields),
+        _ => {}
+    }
+}
+
+/// A lovelace number as an exact ADA string, e.g. `"1.500000"`.
+fn ada_value(lovelace: &JsonValue) -> Option<String> {
+    let lovelace = lovelace.as_u64()?;
+    Some(format!(
+        "{}.{:06}",
+        lovelace / 1_000_000,
+        lovelace % 1_000_000
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::QueryValue;
+
+    #[test]
+    fn test_format_single_value() {
+        let result = QueryResult::Single(QueryValue::String("test".to_string()));
+        let output = format_json(&result).unwrap();
+        assert_eq!(output.trim(), "\"test\"");
+    }
+
+    #[test]
+    fn test_format_number() {
+        let result = QueryResult::Single(QueryValue::Number(serde_json::Number::from(42)));
+        let output = format_json(&result).unwrap();
+        assert_eq!(output.trim(), "42");
+    }
+
+    #[test]
+    fn test_format_multiple() {
+        let result = QueryResult::Multiple(vec![
+            QueryValue::String("a".to_string()),
+            QueryValue::String("b".to_string()),
+        ]);
+        let output = format_json(&result).unwrap();
+        assert!(output.contains("\"a\""));
+        assert!(output.contains("\"b\""));
+    }
+
+    #[test]
+    fn test_format_json_ada() {
+        let result = QueryResult::Single(QueryValue::Number(171_617.into()));
+        assert_eq!(format_json_ada(&result).unwrap(), "\"0.171617\"");
+
+        let result = QueryResult::FullTransaction(serde_json::json!({
+            "fee": 2_500_000,
+            "outputs": [{ "value": { "coin": 1_000_000, "assets": [{ "amount": 5 }] } }],
+            "withdrawals": [{ "amount": 42 }]
+        }));
+        let json: serde_json::Value =
+            serde_json::from_str(&format_json_ada(&result).unwrap()).unwrap();
+        assert_eq!(json["fee"], 2_500_000);
+        assert_eq!(json["fee_ada"], "2.500000");
+        assert_eq!(json["outputs"][0]["value"]["coin_ada"], "1.000000");
+        assert!(
+            json["outputs"][0]["value"]["assets"][0]
+                .get("amount_ada")
+                .is_none()
+        );
+        assert_eq!(json["withdrawals"][0]["amount_ada"], "0.000042");
+    }
+}
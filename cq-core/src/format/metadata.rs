@@ -0,0 +1,27 @@
+//! Transaction metadata in cardano-cli's detailed JSON schema.
+
+use crate::decode::DecodedTransaction;
+use crate::error::{Error, Result};
+use cml_chain::json::metadatums::{MetadataJsonSchema, decode_metadatum_to_json_str};
+use serde_json::Value as JsonValue;
+
+/// Convert a transaction's metadata to the detailed schema.
+///
+/// The result (e.g. `{"674": {"map": [{"k": {"string": "msg"}, ...}]}}`)
+/// can be passed to `cardano-cli transaction build --metadata-json-file`
+/// with `--json-metadata-detailed-schema`. Without metadata this is `{}`.
+pub fn metadata_detailed_json(tx: &DecodedTransaction) -> Result<JsonValue> {
+    let mut labels = serde_json::Map::new();
+    let Some(metadata) = tx.auxiliary_data().and_then(|aux| aux.metadata()) else {
+        return Ok(JsonValue::Object(labels));
+    };
+
+    for (label, value) in metadata.entries.iter() {
+        let json = decode_metadatum_to_json_str(value, MetadataJsonSchema::DetailedSchema)
+            .map_err(|e| Error::FormatError(format!("metadata label {}: {}", label, e)))?;
+        let value = serde_json::from_str(&json)
+            .map_err(|e| Error::FormatError(format!("metadata label {}: {}", label, e)))?;
+        labels.insert(label.to_string(), value);
+    }
+    Ok(JsonValue::Object(labels))
+}
@@ -0,0 +1,14 @@
+//! Output formats that need no terminal: JSON, CSV/TSV, CBOR diagnostic
+//! notation, templates and cardano-cli's metadata schema.
+
+mod csv;
+mod json;
+mod metadata;
+mod raw;
+mod template;
+
+pub use csv::format_delimited;
+pub use json::{format_json, format_json_ada};
+pub use metadata::metadata_detailed_json;
+pub use raw::{bytes_to_diagnostic, format_raw};
+pub use template::format_template;
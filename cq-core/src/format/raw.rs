@@ -0,0 +1,296 @@
+//! Raw CBOR diagnostic notation output.
+
+use crate::decode::{read_header, skip_tags};
+use crate::error::{Error, Result};
+use crate::query::{QueryResult, QueryValue};
+
+/// Format a query result as raw output (CBOR diagnostic notation for bytes).
+pub fn format_raw(result: &QueryResult) -> Result<String> {
+    match result {
+        QueryResult::FullTransaction(json) => {
+            // Without the original bytes (see bytes_to_diagnostic), output JSON
+            serde_json::to_string_pretty(json).map_err(|e| Error::FormatError(e.to_string()))
+        }
+        QueryResult::Single(value) => format_value_raw(value),
+        QueryResult::Multiple(values) => {
+            let formatted: Result<Vec<String>> = values.iter().map(format_value_raw).collect();
+            Ok(formatted?.join("\n"))
+        }
+    }
+}
+
+/// Format a single value in raw mode.
+fn format_value_raw(value: &QueryValue) -> Result<String> {
+    match value {
+        QueryValue::Null => Ok("null".to_string()),
+        QueryValue::Bool(b) => Ok(b.to_string()),
+        QueryValue::Number(n) => Ok(n.to_string()),
+        QueryValue::String(s) => {
+            // Check if it looks like hex (could be bytes)
+            if s.chars().all(|c| c.is_ascii_hexdigit()) && s.len() >= 2 && s.len() % 2 == 0 {
+                // Format as CBOR diagnostic bytes notation
+                Ok(format!("h'{}'", s))
+            } else {
+                Ok(format!("\"{}\"", s))
+            }
+        }
+        QueryValue::Array(arr) => {
+            let items: Result<Vec<String>> = arr.iter().map(format_value_raw).collect();
+            Ok(format!("[{}]", items?.join(", ")))
+        }
+        QueryValue::Object(map) => {
+            let entries: Vec<String> = map
+                .iter()
+                .map(|(k, v)| {
+                    let v_str = serde_json::to_string(v).unwrap_or_else(|_| "?".to_string());
+                    format!("\"{}\": {}", k, v_str)
+                })
+                .collect();
+            Ok(format!("{{{}}}", entries.join(", ")))
+        }
+    }
+}
+
+/// Convert bytes to CBOR diagnostic notation (RFC 8949 section 8).
+///
+/// Works on the encoded bytes rather than a decoded value, so indefinite
+/// lengths (`[_ ...]`), tags and simple values are shown as encoded.
+/// Containers holding other containers are spread over indented lines.
+pub fn bytes_to_diagnostic(bytes: &[u8]) -> Result<String> {
+    let (text, end) = item_to_diagnostic(bytes, 0)?;
+    if end != bytes.len() {
+        return Err(Error::DecodeFailed(format!(
+            "Trailing bytes after CBOR item at offset {}",
+            end
+        )));
+    }
+    Ok(text)
+}
+
+/// Render the item at `offset`, returning its notation and end offset.
+fn item_to_diagnostic(bytes: &[u8], offset: usize) -> Result<(String, usize)> {
+    let header = read_header(bytes, offset)?;
+    let pos = offset + header.len;
+
+    match (header.major, header.arg) {
+        (0, Some(n)) => Ok((n.to_string(), pos)),
+        (1, Some(n)) => Ok(((-1 - n as i128).to_string(), pos)),
+        (2, Some(len)) => {
+            let end = content_end(bytes, pos, len)?;
+            Ok((format!("h'{}'", hex::encode(&bytes[pos..end])), end))
+        }
+        (3, Some(len)) => {
+            let end = content_end(bytes, pos, len)?;
+            let text = std::str::from_utf8(&bytes[pos..end]).map_err(|_| {
+                Error::DecodeFailed(format!("Invalid UTF-8 text at offset {}", offset))
+            })?;
+            Ok((quote_text(text), end))
+        }
+        // Indefinite-length strings are a sequence of definite chunks
+        (2 | 3, None) => {
+            let (chunks, end) = items_until_break(bytes, pos)?;
+            let chunks: Vec<String> = chunks.into_iter().map(|(text, _)| text).collect();
+            Ok((format!("(_ {})", chunks.join(", ")), end))
+        }
+        (4, count) => {
+            let (items, end) = match count {
+                Some(count) => items_counted(bytes, pos, count)?,
+                None => items_until_break(bytes, pos)?,
+            };
+            let nested = items.iter().any(|(_, nested)| *nested);
+            let items: Vec<String> = items.into_iter().map(|(text, _)| text).collect();
+            let open = if count.is_none() { "[_ " } else { "[" };
+            Ok((layout(open, "]", &items, nested), end))
+        }
+        (5, count) => {
+            let (items, end) = match count {
+                Some(count) => items_counted(bytes, pos, count * 2)?,
+                None => items_until_break(bytes, pos)?,
+            };
+            let nested = items.iter().any(|(_, nested)| *nested);
+            let entries: Vec<String> = items
+                .chunks(2)
+                .map(|pair| match pair {
+                    [(key, _), (value, _)] => format!("{}: {}", key, value),
+                    _ => pair[0].0.clone(),
+                })
+                .collect();
+            let open = if count.is_none() { "{_ " } else { "{" };
+            Ok((layout(open, "}", &entries, nested), end))
+        }
+        (6, Some(tag)) => {
+            let (inner, end) = item_to_diagnostic(bytes, pos)?;
+            Ok((format!("{}({})", tag, inner), end))
+        }
+        (7, Some(value)) => Ok((simple_to_diagnostic(header.info, value), pos)),
+        _ => Err(Error::DecodeFailed(format!(
+            "Malformed CBOR at offset {}",
+            offset
+        ))),
+    }
+}
+
+/// Render `count` consecutive items, noting which are containers.
+fn items_counted(bytes: &[u8], mut pos: usize, count: u64) -> Result<(Vec<(String, bool)>, usize)> {
+    let mut items = Vec::new();
+    for _ in 0..count {
+        let nested = is_container(bytes, pos)?;
+        let (text, end) = item_to_diagnostic(bytes, pos)?;
+        items.push((text, nested));
+        pos = end;
+    }
+    Ok((items, pos))
+}
+
+/// Render items up to a break byte, noting which are containers.
+fn items_until_break(bytes: &[u8], mut pos: usize) -> Result<(Vec<(String, bool)>, usize)> {
+    let mut items = Vec::new();
+    loop {
+        match bytes.get(pos) {
+            Some(0xff) => return Ok((items, pos + 1)),
+            Some(_) => {
+                let nested = is_container(bytes, pos)?;
+                let (text, end) = item_to_diagnostic(bytes, pos)?;
+                items.push((text, nested));
+                pos = end;
+            }
+            None => {
+                return Err(Error::DecodeFailed(format!(
+                    "Missing break at offset {}",
+                    pos
+                )));
+            }
+        }
+    }
+}
+
+/// Check if the item at `offset` is an array or map (possibly tagged).
+fn is_container(bytes: &[u8], offset: usize) -> Result<bool> {
+    let offset = skip_tags(bytes, offset)?;
+    Ok(matches!(read_header(bytes, offset)?.major, 4 | 5))
+}
+
+/// End offset of definite-length string content.
+fn content_end(bytes: &[u8], pos: usize, len: u64) -> Result<usize> {
+    pos.checked_add(len as usize)
+        .filter(|end| *end <= bytes.len())
+        .ok_or_else(|| Error::DecodeFailed(format!("Truncated string at offset {}", pos)))
+}
+
+/// Lay out container items on one line, or one per indented line if nested.
+fn layout(open: &str, close: &str, items: &[String], nested: bool) -> String {
+    if items.is_empty() {
+        return format!("{}{}", open.trim_end(), close);
+    }
+    if !nested {
+        return format!("{}{}{}", open, items.join(", "), close);
+    }
+    let body: Vec<String> = items
+        .iter()
+        .map(|item| format!("  {}", item.replace('\n', "\n  ")))
+        .collect();
+    format!("{}\n{}\n{}", open.trim_end(), body.join(",\n"), close)
+}
+
+/// Quote a text string with JSON-compatible escapes.
+fn quote_text(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| format!("\"{}\"", text))
+}
+
+/// Render a major type 7 item (simple value or float).
+fn simple_to_diagnostic(info: u8, value: u64) -> String {
+    match info {
+        20 => "false".to_string(),
+        21 => "true".to_string(),
+        22 => "null".to_string(),
+        23 => "undefined".to_string(),
+        25 => float_to_diagnostic(half_to_f64(value as u16)),
+        26 => float_to_diagnostic(f32::from_bits(value as u32) as f64),
+        27 => float_to_diagnostic(f64::from_bits(value)),
+        _ => format!("simple({})", value),
+    }
+}
+
+/// Format a float so it always reads as one (`1.0`, `NaN`, `-Infinity`).
+fn float_to_diagnostic(f: f64) -> String {
+    if f.is_nan() {
+        "NaN".to_string()
+    } else if f.is_infinite() {
+        if f > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else {
+        format!("{:?}", f)
+    }
+}
+
+/// Convert IEEE 754 half-precision bits to f64.
+fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f64;
+    sign * match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_hex_string() {
+        let value = QueryValue::String("84a400".to_string());
+        let output = format_value_raw(&value).unwrap();
+        assert_eq!(output, "h'84a400'");
+    }
+
+    #[test]
+    fn test_format_text_string() {
+        let value = QueryValue::String("hello world".to_string());
+        let output = format_value_raw(&value).unwrap();
+        assert_eq!(output, "\"hello world\"");
+    }
+
+    #[test]
+    fn test_cbor_diagnostic() {
+        // Simple CBOR integer
+        let cbor = vec![0x18, 0x64]; // Integer 100
+        let output = bytes_to_diagnostic(&cbor).unwrap();
+        assert_eq!(output, "100");
+    }
+
+    #[test]
+    fn test_cbor_diagnostic_array() {
+        // CBOR array [1, 2, 3]
+        let cbor = vec![0x83, 0x01, 0x02, 0x03];
+        let output = bytes_to_diagnostic(&cbor).unwrap();
+        assert_eq!(output, "[1, 2, 3]");
+    }
+
+    #[test]
+    fn test_cbor_diagnostic_encoding_details() {
+        // 258([_ -1, "a"]) keeps the tag and indefinite length
+        let cbor = [0xd9, 0x01, 0x02, 0x9f, 0x20, 0x61, 0x61, 0xff];
+        assert_eq!(bytes_to_diagnostic(&cbor).unwrap(), "258([_ -1, \"a\"])");
+
+        // [1.5, undefined, simple(16), h'']
+        let cbor = [0x84, 0xf9, 0x3e, 0x00, 0xf7, 0xf0, 0x40];
+        assert_eq!(
+            bytes_to_diagnostic(&cbor).unwrap(),
+            "[1.5, undefined, simple(16), h'']"
+        );
+    }
+
+    #[test]
+    fn test_cbor_diagnostic_nested_layout() {
+        // {0: [1], 1: {}}
+        let cbor = [0xa2, 0x00, 0x81, 0x01, 0x01, 0xa0];
+        assert_eq!(
+            bytes_to_diagnostic(&cbor).unwrap(),
+            "{\n  0: [1],\n  1: {}\n}"
+        );
+        assert!(bytes_to_diagnostic(&[0x01, 0x02]).is_err());
+    }
+}
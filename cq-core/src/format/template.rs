@@ -0,0 +1,197 @@
+//! Template output: interpolate query results into a user-supplied string.
+
+use crate::decode::DecodedTransaction;
+use crate::error::{Error, Result};
+use crate::query::{QueryOptions, QueryResult, QueryValue, execute_query, execute_query_on};
+use serde_json::Value as JsonValue;
+
+/// A piece of a parsed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    /// Text copied verbatim.
+    Literal(String),
+    /// A `{query}` placeholder.
+    Query(String),
+}
+
+/// Render a template once per result value.
+///
+/// Placeholders such as `{address.address}` are queries relative to each
+/// value of a wildcard result (or to the single value). Without a query
+/// they run against the transaction root, so shortcuts like `{fee}` and
+/// `{hash}` work. `{{` and `}}` produce literal braces.
+pub fn format_template(
+    tx: &DecodedTransaction,
+    result: &QueryResult,
+    template: &str,
+    options: &QueryOptions,
+) -> Result<String> {
+    let parts = parse_template(template)?;
+
+    let lines = match result {
+        QueryResult::FullTransaction(_) => vec![render(&parts, |q| execute_query(tx, q, options))?],
+        QueryResult::Single(value) => vec![render_value(tx, &parts, value, options)?],
+        QueryResult::Multiple(values) => values
+            .iter()
+            .map(|value| render_value(tx, &parts, value, options))
+            .collect::<Result<_>>()?,
+    };
+
+    Ok(lines.join("\n"))
+}
+
+/// Render the template with placeholders relative to one value.
+fn render_value(
+    tx: &DecodedTransaction,
+    parts: &[Part],
+    value: &QueryValue,
+    options: &QueryOptions,
+) -> Result<String> {
+    let input = QueryResult::Single(value.clone());
+    render(parts, |q| execute_query_on(tx, &input, q, options))
+}
+
+/// Render the template, evaluating placeholders with `eval`.
+fn render(parts: &[Part], eval: impl Fn(&str) -> Result<QueryResult>) -> Result<String> {
+    let mut output = String::new();
+    for part in parts {
+        match part {
+            Part::Literal(text) => output.push_str(text),
+            Part::Query(query) => output.push_str(&result_text(eval(query)?)),
+        }
+    }
+    Ok(output)
+}
+
+/// Plain text of a placeholder result (multiple values are comma-separated).
+fn result_text(result: QueryResult) -> String {
+    match result {
+        QueryResult::Single(value) => value_text(value.into()),
+        QueryResult::Multiple(values) => values
+            .into_iter()
+            .map(|v| value_text(v.into()))
+            .collect::<Vec<_>>()
+            .join(","),
+        QueryResult::FullTransaction(json) => value_text(json),
+    }
+}
+
+/// Strings unquoted, null empty, everything else as compact JSON.
+fn value_text(value: JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+/// Split a template into literals and `{query}` placeholders.
+///
+/// Placeholders may contain nested braces and quoted strings, so filters
+/// like `{outputs[name == "}"] | length}` work; a placeholder starting with
+/// object construction needs a space (`{ {a: b} | a}`) to differ from `{{`.
+fn parse_template(template: &str) -> Result<Vec<Part>> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => {
+                return Err(Error::InvalidQuery(
+                    "Unmatched '}' in template (use '}}' for a literal brace)".to_string(),
+                ));
+            }
+            '{' => {
+                let mut query = String::new();
+                let mut depth = 0;
+                let mut quote = None;
+                loop {
+                    let Some(c) = chars.next() else {
+                        return Err(Error::InvalidQuery(format!(
+                            "Unclosed placeholder in template: {{{}",
+                            query
+                        )));
+                    };
+                    match (quote, c) {
+                        (Some(q), c) if c == q => quote = None,
+                        (Some(_), _) => {}
+                        (None, '"' | '\'') => quote = Some(c),
+                        (None, '{') => depth += 1,
+                        (None, '}') if depth == 0 => break,
+                        (None, '}') => depth -= 1,
+                        _ => {}
+                    }
+                    query.push(c);
+                }
+
+                let query = query.trim();
+                if query.is_empty() {
+                    return Err(Error::InvalidQuery(
+                        "Empty placeholder in template".to_string(),
+                    ));
+                }
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(Part::Query(query.to_string()));
+            }
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        assert_eq!(
+            parse_template("{a.b}: {{x}} {c | length}").unwrap(),
+            vec![
+                Part::Query("a.b".to_string()),
+                Part::Literal(": {x} ".to_string()),
+                Part::Query("c | length".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_template(r#"{ {a: b} | a} {x[n == "}"]}"#).unwrap(),
+            vec![
+                Part::Query("{a: b} | a".to_string()),
+                Part::Literal(" ".to_string()),
+                Part::Query(r#"x[n == "}"]"#.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_template_errors() {
+        assert!(parse_template("{fee").is_err());
+        assert!(parse_template("fee}").is_err());
+        assert!(parse_template("{ }").is_err());
+    }
+
+    #[test]
+    fn test_result_text() {
+        let multiple = QueryResult::Multiple(vec![
+            QueryValue::String("a".into()),
+            QueryValue::Null,
+            QueryValue::Number(3.into()),
+        ]);
+        assert_eq!(result_text(multiple), "a,,3");
+    }
+}
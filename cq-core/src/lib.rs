@@ -0,0 +1,39 @@
+//! cq-core - the decoding and query engine of cq.
+//!
+//! Decodes Cardano transactions from CBOR and runs cq queries against them,
+//! without cq's command-line dependencies. The main types are:
+//!
+//! - [`DecodedTransaction`]: a transaction decoded with [`decode_transaction`],
+//!   keeping its original bytes and hash
+//! - [`QueryPath`]: a parsed dot-notation path such as `outputs.*.address`
+//! - [`QueryOptions`]: settings such as the network and asset name format
+//!   that change how transactions are converted
+//! - [`QueryResult`]: what [`execute_query`] returns, serializable to JSON
+//!
+//! ```no_run
+//! use cq_core::{QueryOptions, decode_transaction, execute_query};
+//!
+//! let bytes = std::fs::read("tx.cbor")?;
+//! let tx = decode_transaction(&bytes)?;
+//! let fee = execute_query(&tx, "fee", &QueryOptions::default())?;
+//! println!("{}", serde_json::to_string(&fee).unwrap());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub mod decode;
+pub mod error;
+pub mod format;
+pub mod network;
+pub mod pparams;
+pub mod query;
+
+// The README's example is compiled as a doctest
+#[doc = include_str!("../README.md")]
+#[cfg(doctest)]
+pub struct ReadmeDoctests;
+
+pub use decode::{DecodedTransaction, Era, decode_transaction};
+pub use error::{Error, Result};
+pub use query::{
+    QueryOptions, QueryPath, QueryResult, QueryValue, execute_query, transaction_to_json,
+};
@@ -0,0 +1,269 @@
+//! The Cardano network a transaction is read for (`--network`).
+//!
+//! Addresses only say mainnet or testnet (network id 1 or 0). With a
+//! network in the query options, testnet addresses are labeled with the
+//! testnet's name, and credentials that carry no network of their own are
+//! encoded for it. Its slot schedule (or one given with `--slot-config`)
+//! turns slot numbers into times.
+
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// A named Cardano network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkName {
+    Mainnet,
+    Preprod,
+    Preview,
+}
+
+impl NetworkName {
+    /// All networks, in the order they are listed in help text.
+    pub const ALL: [NetworkName; 3] = [
+        NetworkName::Mainnet,
+        NetworkName::Preprod,
+        NetworkName::Preview,
+    ];
+
+    /// Network ID carried in address headers (all testnets use 0).
+    pub fn network_id(self) -> u8 {
+        match self {
+            NetworkName::Mainnet => 1,
+            NetworkName::Preprod | NetworkName::Preview => 0,
+        }
+    }
+
+    /// Protocol magic, which node connections identify the network by.
+    pub fn magic(self) -> u64 {
+        match self {
+            NetworkName::Mainnet => 764_824_073,
+            NetworkName::Preprod => 1,
+            NetworkName::Preview => 2,
+        }
+    }
+
+    /// Slot schedule since the Shelley hard fork.
+    pub fn slot_config(self) -> SlotConfig {
+        match self {
+            NetworkName::Mainnet => SlotConfig {
+                zero_time: 1_596_059_091,
+                zero_slot: 4_492_800,
+                slot_length: 1,
+                epochs: Some((208, 432_000)),
+            },
+            NetworkName::Preprod => SlotConfig {
+                zero_time: 1_655_769_600,
+                zero_slot: 86_400,
+                slot_length: 1,
+                epochs: Some((4, 432_000)),
+            },
+            NetworkName::Preview => SlotConfig {
+                zero_time: 1_666_656_000,
+                zero_slot: 0,
+                slot_length: 1,
+                epochs: Some((0, 86_400)),
+            },
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NetworkName::Mainnet => "mainnet",
+            NetworkName::Preprod => "preprod",
+            NetworkName::Preview => "preview",
+        }
+    }
+}
+
+impl fmt::Display for NetworkName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NetworkName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NetworkName::ALL
+            .into_iter()
+            .find(|network| network.as_str() == s)
+            .ok_or_else(|| format!("unknown network '{}'", s))
+    }
+}
+
+/// A linear slot schedule: slot `zero_slot` starts at `zero_time` and each
+/// slot lasts `slot_length` seconds. With `epochs`, `(epoch, length)`, that
+/// epoch starts at `zero_slot` and each epoch has `length` slots.
+///
+/// Written `ZERO_TIME,ZERO_SLOT,SLOT_LENGTH[,ZERO_EPOCH,EPOCH_LENGTH]` with
+/// the time in Unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotConfig {
+    pub zero_time: i64,
+    pub zero_slot: u64,
+    pub slot_length: u64,
+    pub epochs: Option<(u64, u64)>,
+}
+
+impl SlotConfig {
+    /// Unix time at which `slot` starts, or `None` for slots before the
+    /// schedule.
+    pub fn slot_to_unix(&self, slot: u64) -> Option<i64> {
+        let elapsed = slot
+            .checked_sub(self.zero_slot)?
+            .checked_mul(self.slot_length)?;
+        self.zero_time.checked_add(i64::try_from(elapsed).ok()?)
+    }
+
+    /// UTC time at which `slot` starts, as RFC 3339 (`2024-03-01T12:00:00Z`).
+    pub fn slot_to_utc(&self, slot: u64) -> Option<String> {
+        self.slot_to_unix(slot).map(format_utc)
+    }
+
+    /// Epoch containing `slot`, if the schedule has epochs.
+    pub fn slot_to_epoch(&self, slot: u64) -> Option<u64> {
+        let (zero_epoch, epoch_length) = self.epochs?;
+        Some(zero_epoch + slot.checked_sub(self.zero_slot)? / epoch_length)
+    }
+
+    /// UTC time at which `epoch` starts, if the schedule has epochs.
+    pub fn epoch_start_utc(&self, epoch: u64) -> Option<String> {
+        let (zero_epoch, epoch_length) = self.epochs?;
+        let offset = epoch.checked_sub(zero_epoch)?.checked_mul(epoch_length)?;
+        self.slot_to_utc(self.zero_slot.checked_add(offset)?)
+    }
+}
+
+impl FromStr for SlotConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+        let (zero_time, zero_slot, slot_length, epochs) = match parts.as_slice() {
+            [time, slot, length] => (time, slot, length, None),
+            [time, slot, length, epoch, epoch_length] => {
+                (time, slot, length, Some((epoch, epoch_length)))
+            }
+            _ => {
+                return Err(
+                    "expected ZERO_TIME,ZERO_SLOT,SLOT_LENGTH[,ZERO_EPOCH,EPOCH_LENGTH]"
+                        .to_string(),
+                );
+            }
+        };
+        let number_error = |e: std::num::ParseIntError| e.to_string();
+        let config = SlotConfig {
+            zero_time: zero_time.parse().map_err(number_error)?,
+            zero_slot: zero_slot.parse().map_err(number_error)?,
+            slot_length: slot_length.parse().map_err(number_error)?,
+            epochs: epochs
+                .map(|(epoch, length)| {
+                    Ok::<_, String>((
+                        epoch.parse().map_err(number_error)?,
+                        length.parse().map_err(number_error)?,
+                    ))
+                })
+                .transpose()?,
+        };
+        if config.slot_length == 0 || config.epochs.is_some_and(|(_, length)| length == 0) {
+            return Err("slot and epoch lengths must be at least 1".to_string());
+        }
+        Ok(config)
+    }
+}
+
+/// Format Unix seconds as an RFC 3339 UTC time.
+pub fn format_utc(unix: i64) -> String {
+    let (days, secs) = (unix.div_euclid(86_400), unix.rem_euclid(86_400));
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Label for a network id: `mainnet`, the name of `network` if it is a
+/// testnet, or `testnet`.
+pub fn network_label(network_id: u8, network: Option<NetworkName>) -> &'static str {
+    match (network_id, network) {
+        (1, _) => "mainnet",
+        (0, Some(network)) if network.network_id() == 0 => network.as_str(),
+        (0, _) => "testnet",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_network() {
+        assert_eq!("preview".parse(), Ok(NetworkName::Preview));
+        assert!("testnet".parse::<NetworkName>().is_err());
+        assert_eq!(NetworkName::Preprod.network_id(), 0);
+        assert_eq!(network_label(1, None), "mainnet");
+        assert_eq!(network_label(0, None), "testnet");
+        assert_eq!(network_label(0, Some(NetworkName::Preview)), "preview");
+        assert_eq!(network_label(0, Some(NetworkName::Mainnet)), "testnet");
+    }
+
+    #[test]
+    fn test_slot_to_utc() {
+        let mainnet = NetworkName::Mainnet.slot_config();
+        assert_eq!(
+            mainnet.slot_to_utc(4_492_800).as_deref(),
+            Some("2020-07-29T21:44:51Z")
+        );
+        assert_eq!(
+            mainnet.slot_to_utc(4_492_800 + 86_400 * 366).as_deref(),
+            Some("2021-07-30T21:44:51Z")
+        );
+        assert_eq!(mainnet.slot_to_utc(0), None);
+        assert_eq!(format_utc(951_782_400), "2000-02-29T00:00:00Z");
+
+        let custom: SlotConfig = "1666656000,0,1,0,86400".parse().unwrap();
+        assert_eq!(custom, NetworkName::Preview.slot_config());
+        let custom: SlotConfig = "1666656000,0,1".parse().unwrap();
+        assert_eq!(custom.slot_to_epoch(100), None);
+        assert!("1,2".parse::<SlotConfig>().is_err());
+        assert!("1,2,0".parse::<SlotConfig>().is_err());
+    }
+
+    #[test]
+    fn test_epochs() {
+        let mainnet = NetworkName::Mainnet.slot_config();
+        assert_eq!(mainnet.slot_to_epoch(4_492_800), Some(208));
+        assert_eq!(
+            mainnet.slot_to_epoch(4_492_800 + 432_000 * 300 - 1),
+            Some(507)
+        );
+        assert_eq!(
+            mainnet.epoch_start_utc(209).as_deref(),
+            Some("2020-08-03T21:44:51Z")
+        );
+        assert_eq!(mainnet.epoch_start_utc(100), None);
+
+        let preprod = NetworkName::Preprod.slot_config();
+        assert_eq!(preprod.slot_to_epoch(70_000_000), Some(165));
+    }
+}
@@ -0,0 +1,92 @@
+//! Protocol parameters that transactions are checked against (`--pparams`).
+//!
+//! Only the limits cq reports on are kept. They are read from
+//! `cardano-cli query protocol-parameters` output (`maxTxExecutionUnits`,
+//! `maxTxSize`) or from Blockfrost/Koios style snake_case fields
+//! (`max_tx_ex_mem`, `max_tx_ex_steps`, `max_tx_size`).
+
+use crate::error::{Error, Result};
+use serde_json::Value as JsonValue;
+
+/// Mainnet's maximum transaction size, used when none is given.
+pub const DEFAULT_MAX_TX_SIZE: u64 = 16_384;
+
+/// Transaction limits from the protocol parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProtocolParams {
+    /// Memory units all redeemers of a transaction may use.
+    pub max_tx_ex_mem: Option<u64>,
+    /// CPU steps all redeemers of a transaction may use.
+    pub max_tx_ex_steps: Option<u64>,
+    /// Maximum serialized transaction size, in bytes.
+    pub max_tx_size: Option<u64>,
+}
+
+impl ProtocolParams {
+    /// The maximum transaction size, or mainnet's if not given.
+    pub fn tx_size_limit(&self) -> u64 {
+        self.max_tx_size.unwrap_or(DEFAULT_MAX_TX_SIZE)
+    }
+
+    /// Parse protocol parameters JSON. Unknown fields are ignored; a
+    /// limit that is present but not a number is an error.
+    pub fn from_json(json: &JsonValue) -> Result<Self> {
+        if !json.is_object() {
+            return Err(Error::InvalidConfig(
+                "protocol parameters must be a JSON object".to_string(),
+            ));
+        }
+        let ex_units = &json["maxTxExecutionUnits"];
+        Ok(ProtocolParams {
+            max_tx_ex_mem: limit(&ex_units["memory"], &json["max_tx_ex_mem"])?,
+            max_tx_ex_steps: limit(&ex_units["steps"], &json["max_tx_ex_steps"])?,
+            max_tx_size: limit(&json["maxTxSize"], &json["max_tx_size"])?,
+        })
+    }
+}
+
+/// The first present of two spellings of a limit, as a number or a numeric
+/// string (Blockfrost quotes large values).
+fn limit(value: &JsonValue, alternative: &JsonValue) -> Result<Option<u64>> {
+    let value = if value.is_null() { alternative } else { value };
+    let number = match value {
+        JsonValue::Null => return Ok(None),
+        JsonValue::Number(n) => n.as_u64(),
+        JsonValue::String(s) => s.parse().ok(),
+        _ => None,
+    };
+    number
+        .map(Some)
+        .ok_or_else(|| Error::InvalidConfig(format!("invalid protocol parameter limit: {}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json() {
+        let cardano_cli = serde_json::json!({
+            "maxTxExecutionUnits": { "memory": 14000000, "steps": 10000000000u64 },
+            "maxTxSize": 16384,
+            "txFeePerByte": 44
+        });
+        assert_eq!(
+            ProtocolParams::from_json(&cardano_cli).unwrap(),
+            ProtocolParams {
+                max_tx_ex_mem: Some(14_000_000),
+                max_tx_ex_steps: Some(10_000_000_000),
+                max_tx_size: Some(16_384),
+            }
+        );
+
+        let blockfrost = serde_json::json!({ "max_tx_ex_mem": "14000000", "max_tx_size": 16384 });
+        let params = ProtocolParams::from_json(&blockfrost).unwrap();
+        assert_eq!(params.max_tx_ex_mem, Some(14_000_000));
+        assert_eq!(params.max_tx_ex_steps, None);
+        assert_eq!(ProtocolParams::default().tx_size_limit(), 16_384);
+
+        assert!(ProtocolParams::from_json(&serde_json::json!({ "maxTxSize": "big" })).is_err());
+        assert!(ProtocolParams::from_json(&serde_json::json!([])).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Query path completion for shells (`cq __complete`).
+
+use crate::decode::DecodedTransaction;
+use crate::query::engine::{QueryResult, QueryValue, execute_query, transaction_to_json};
+use crate::query::options::QueryOptions;
+use crate::query::shortcuts::shortcut_names;
+
+/// Suggest completions for a partially typed query path.
+///
+/// Without a transaction only shortcuts are suggested. With one, the prefix
+/// up to its last `.` is evaluated and the fields (or indices and `*`) of
+/// the result that start with the remainder are offered, e.g. `outputs.0.a`
+/// suggests `outputs.0.address` and `outputs.0.amount` if present.
+pub fn complete_query(
+    prefix: &str,
+    tx: Option<&DecodedTransaction>,
+    options: &QueryOptions,
+) -> Vec<String> {
+    let candidates: Vec<String> = match (last_separator(prefix), tx) {
+        (None, tx) => shortcut_names()
+            .map(str::to_string)
+            .chain(tx.map(|tx| root_fields(tx, options)).unwrap_or_default())
+            .filter(|name| name.starts_with(prefix))
+            .collect(),
+        (Some(pos), Some(tx)) => {
+            let (parent, partial) = (&prefix[..pos], &prefix[pos + 1..]);
+            children(tx, parent, options)
+                .into_iter()
+                .filter(|name| name.starts_with(partial))
+                .map(|name| format!("{}.{}", parent, name))
+                .collect()
+        }
+        (Some(_), None) => Vec::new(),
+    };
+
+    let mut suggestions = Vec::new();
+    for candidate in candidates {
+        if !suggestions.contains(&candidate) {
+            suggestions.push(candidate);
+        }
+    }
+    suggestions
+}
+
+/// Top-level fields of the transaction (`body`, `witness_set`, ...).
+fn root_fields(tx: &DecodedTransaction, options: &QueryOptions) -> Vec<String> {
+    match transaction_to_json(tx, options) {
+        Ok(serde_json::Value::Object(map)) => map.keys().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Field names (or `*` and indices) below the value(s) at `path`.
+fn children(tx: &DecodedTransaction, path: &str, options: &QueryOptions) -> Vec<String> {
+    let values = match execute_query(tx, path, options) {
+        Ok(QueryResult::Single(value)) => vec![value],
+        Ok(QueryResult::Multiple(values)) => values,
+        Ok(QueryResult::FullTransaction(json)) => vec![QueryValue::from(json)],
+        Err(_) => return Vec::new(),
+    };
+
+    let mut names = Vec::new();
+    for value in values {
+        match value {
+            QueryValue::Object(map) => names.extend(map.into_iter().map(|(key, _)| key)),
+            QueryValue::Array(items) if !items.is_empty() => {
+                names.push("*".to_string());
+                names.extend((0..items.len()).map(|i| i.to_string()));
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Position of the last `.` outside brackets and braces.
+fn last_separator(prefix: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut last = None;
+    for (i, c) in prefix.char_indices() {
+        match c {
+            '[' | '{' | '(' => depth += 1,
+            ']' | '}' | ')' => depth = depth.saturating_sub(1),
+            '.' if depth == 0 => last = Some(i),
+            _ => {}
+        }
+    }
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_shortcuts() {
+        let options = QueryOptions::default();
+        let suggestions = complete_query("co", None, &options);
+        assert_eq!(
+            suggestions,
+            vec!["collateral", "collateral_return"]
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        );
+        assert!(complete_query("outputs.0.", None, &options).is_empty());
+    }
+
+    #[test]
+    fn test_last_separator() {
+        assert_eq!(last_separator("outputs.0.ad"), Some(9));
+        assert_eq!(last_separator("outputs[value.coin > 1]"), None);
+        assert_eq!(last_separator("fee"), None);
+    }
+}
@@ -0,0 +1,245 @@
+//! Which scripts and datums a transaction needs, and whether its witness
+//! set has them.
+//!
+//! Scripts are needed for minted policies, script-credential certificates,
+//! withdrawals and voters, and proposal guardrails. Scripts locking spent
+//! inputs are only known from the resolved inputs, which a transaction
+//! does not carry: a provided script no known purpose needs may be one of
+//! them, and a needed script the witness set lacks may come from a
+//! reference input. Likewise, a witness datum no output references may
+//! be the datum of a spent input.
+
+use cml_chain::certs::{Certificate, Credential};
+use cml_chain::governance::GovAction;
+use cml_chain::transaction::{DatumOption, Transaction};
+use serde_json::Value as JsonValue;
+
+/// Script hashes the transaction needs, with the purpose of each, in order
+/// of first appearance.
+fn required_scripts(tx: &Transaction) -> Vec<(String, &'static str)> {
+    let body = &tx.body;
+    let mut required: Vec<(String, &'static str)> = Vec::new();
+    let mut add = |hash: String, purpose: &'static str| {
+        if !required.iter().any(|(h, _)| *h == hash) {
+            required.push((hash, purpose));
+        }
+    };
+
+    for policy_id in body.mint.iter().flat_map(|mint| mint.keys()) {
+        add(policy_id.to_hex(), "mint");
+    }
+    for cert in body.certs.iter().flatten() {
+        if let Some(Credential::Script { hash, .. }) = certificate_witness(cert) {
+            add(hash.to_hex(), "cert");
+        }
+    }
+    for account in body.withdrawals.iter().flat_map(|w| w.keys()) {
+        if let Credential::Script { hash, .. } = &account.payment {
+            add(hash.to_hex(), "reward");
+        }
+    }
+    for voter in body.voting_procedures.iter().flat_map(|v| v.keys()) {
+        if let Some(hash) = voter.script_hash() {
+            add(hash.to_hex(), "voting");
+        }
+    }
+    for proposal in body.proposal_procedures.iter().flatten() {
+        let policy_hash = match &proposal.gov_action {
+            GovAction::ParameterChangeAction(action) => action.policy_hash.as_ref(),
+            GovAction::TreasuryWithdrawalsAction(action) => action.policy_hash.as_ref(),
+            _ => None,
+        };
+        if let Some(hash) = policy_hash {
+            add(hash.to_hex(), "proposing");
+        }
+    }
+    required
+}
+
+/// The credential a certificate must be witnessed by, if any. Legacy stake
+/// registrations need no witness.
+fn certificate_witness(cert: &Certificate) -> Option<&Credential> {
+    match cert {
+        Certificate::StakeDeregistration(c) => Some(&c.stake_credential),
+        Certificate::StakeDelegation(c) => Some(&c.stake_credential),
+        Certificate::RegCert(c) => Some(&c.stake_credential),
+        Certificate::UnregCert(c) => Some(&c.stake_credential),
+        Certificate::VoteDelegCert(c) => Some(&c.stake_credential),
+        Certificate::StakeVoteDelegCert(c) => Some(&c.stake_credential),
+        Certificate::StakeRegDelegCert(c) => Some(&c.stake_credential),
+        Certificate::VoteRegDelegCert(c) => Some(&c.stake_credential),
+        Certificate::StakeVoteRegDelegCert(c) => Some(&c.stake_credential),
+        Certificate::AuthCommitteeHotCert(c) => Some(&c.committee_cold_credential),
+        Certificate::ResignCommitteeColdCert(c) => Some(&c.committee_cold_credential),
+        Certificate::RegDrepCert(c) => Some(&c.drep_credential),
+        Certificate::UnregDrepCert(c) => Some(&c.drep_credential),
+        Certificate::UpdateDrepCert(c) => Some(&c.drep_credential),
+        _ => None,
+    }
+}
+
+/// Hashes of the scripts in the witness set.
+fn provided_scripts(tx: &Transaction) -> Vec<String> {
+    let ws = &tx.witness_set;
+    let native = ws.native_scripts.iter().flatten().map(|s| s.hash());
+    let v1 = ws.plutus_v1_scripts.iter().flatten().map(|s| s.hash());
+    let v2 = ws.plutus_v2_scripts.iter().flatten().map(|s| s.hash());
+    let v3 = ws.plutus_v3_scripts.iter().flatten().map(|s| s.hash());
+    native
+        .chain(v1)
+        .chain(v2)
+        .chain(v3)
+        .map(|hash| hash.to_hex())
+        .collect()
+}
+
+/// Add `script_coverage` to the witness set JSON: the scripts needed
+/// (`required`), those the witness set lacks (`missing`) and those no
+/// known purpose needs (`unused`). Nothing is added when the transaction
+/// neither needs nor provides scripts.
+pub(crate) fn add_script_coverage(tx: &Transaction, witness_json: &mut JsonValue) {
+    let required = required_scripts(tx);
+    let provided = provided_scripts(tx);
+    if required.is_empty() && provided.is_empty() {
+        return;
+    }
+
+    let missing: Vec<&String> = required
+        .iter()
+        .map(|(hash, _)| hash)
+        .filter(|hash| !provided.contains(hash))
+        .collect();
+    let unused: Vec<&String> = provided
+        .iter()
+        .filter(|hash| !required.iter().any(|(h, _)| h == *hash))
+        .collect();
+    let required: Vec<JsonValue> = required
+        .iter()
+        .map(|(hash, purpose)| serde_json::json!({ "hash": hash, "purpose": purpose }))
+        .collect();
+
+    witness_json["script_coverage"] = serde_json::json!({
+        "required": required,
+        "missing": missing,
+        "unused": unused
+    });
+}
+
+/// Add `datum_coverage` to the witness set JSON: the datum hashes outputs
+/// reference (`referenced`), those the witness set lacks (`missing`) and
+/// witness datums no output references (`orphans`). Nothing is added when
+/// the transaction neither references nor provides datums.
+pub(crate) fn add_datum_coverage(tx: &Transaction, witness_json: &mut JsonValue) {
+    let referenced: Vec<(String, usize)> = tx
+        .body
+        .outputs
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, output)| match output.datum() {
+            Some(DatumOption::Hash { datum_hash, .. }) => Some((datum_hash.to_hex(), idx)),
+            _ => None,
+        })
+        .collect();
+    let provided: Vec<String> = tx
+        .witness_set
+        .plutus_datums
+        .iter()
+        .flatten()
+        .map(|datum| datum.hash().to_hex())
+        .collect();
+    if referenced.is_empty() && provided.is_empty() {
+        return;
+    }
+
+    let mut missing: Vec<&String> = Vec::new();
+    for (hash, _) in &referenced {
+        if !provided.contains(hash) && !missing.contains(&hash) {
+            missing.push(hash);
+        }
+    }
+    let orphans: Vec<&String> = provided
+        .iter()
+        .filter(|hash| !referenced.iter().any(|(h, _)| h == *hash))
+        .collect();
+    let referenced: Vec<JsonValue> = referenced
+        .iter()
+        .map(|(hash, output)| serde_json::json!({ "hash": hash, "output": output }))
+        .collect();
+
+    witness_json["datum_coverage"] = serde_json::json!({
+        "referenced": referenced,
+        "missing": missing,
+        "orphans": orphans
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cml_chain::address::Address;
+    use cml_chain::assets::{AssetName, Mint};
+    use cml_chain::plutus::PlutusData;
+    use cml_chain::transaction::{
+        NativeScript, TransactionBody, TransactionOutput, TransactionWitnessSet,
+    };
+    use cml_crypto::Ed25519KeyHash;
+
+    #[test]
+    fn test_script_coverage() {
+        let script = |byte: u8| NativeScript::new_script_pubkey(Ed25519KeyHash::from([byte; 28]));
+        let (minting, unrelated, absent) = (script(1), script(2), script(3));
+
+        let mut mint = Mint::new();
+        mint.set(minting.hash(), AssetName::new(b"A".to_vec()).unwrap(), 1);
+        mint.set(absent.hash(), AssetName::new(b"B".to_vec()).unwrap(), 1);
+        let mut body = TransactionBody::new(Vec::new().into(), Vec::new(), 0);
+        body.mint = Some(mint);
+        let mut witness_set = TransactionWitnessSet::new();
+        witness_set.native_scripts = Some(vec![minting.clone(), unrelated.clone()].into());
+        let tx = Transaction::new(body, witness_set, true, None);
+
+        let mut json = serde_json::json!({});
+        add_script_coverage(&tx, &mut json);
+        let coverage = &json["script_coverage"];
+        assert_eq!(coverage["required"][0]["purpose"], "mint");
+        assert_eq!(
+            coverage["missing"],
+            serde_json::json!([absent.hash().to_hex()])
+        );
+        assert_eq!(
+            coverage["unused"],
+            serde_json::json!([unrelated.hash().to_hex()])
+        );
+    }
+
+    #[test]
+    fn test_datum_coverage() {
+        let (paid, orphan, absent) = (
+            PlutusData::new_integer(1u64.into()),
+            PlutusData::new_integer(2u64.into()),
+            PlutusData::new_integer(3u64.into()),
+        );
+        let address = Address::from_raw_bytes(&[0x61; 29]).unwrap();
+        let output = |datum: &PlutusData| {
+            let datum_hash = Some(DatumOption::new_hash(datum.hash()));
+            TransactionOutput::new(address.clone(), 1_000_000.into(), datum_hash, None)
+        };
+        let body = TransactionBody::new(Vec::new().into(), vec![output(&paid), output(&absent)], 0);
+        let mut witness_set = TransactionWitnessSet::new();
+        witness_set.plutus_datums = Some(vec![paid.clone(), orphan.clone()].into());
+        let tx = Transaction::new(body, witness_set, true, None);
+
+        let mut json = serde_json::json!({});
+        add_datum_coverage(&tx, &mut json);
+        let coverage = &json["datum_coverage"];
+        assert_eq!(coverage["referenced"][1]["output"], 1);
+        assert_eq!(
+            coverage["missing"],
+            serde_json::json!([absent.hash().to_hex()])
+        );
+        assert_eq!(
+            coverage["orphans"],
+            serde_json::json!([orphan.hash().to_hex()])
+        );
+    }
+}
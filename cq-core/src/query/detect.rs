@@ -0,0 +1,202 @@
+//! Recognizing CBOR that is not a transaction.
+//!
+//! Input that fails to decode as a transaction is tried against other
+//! Cardano structures, from the most to the least specific: Plutus data
+//! accepts almost any CBOR, so it is tried last.
+
+use crate::decode::{DecodedBlock, decode_block, item_span};
+use crate::error::Result;
+use crate::query::engine::{
+    address_to_detailed_json, decode_plutus_datum_to_json, transaction_to_json, witness_set_to_json,
+};
+use crate::query::options::QueryOptions;
+use cml_chain::Script;
+use cml_chain::address::Address;
+use cml_chain::plutus::PlutusData;
+use cml_chain::transaction::{NativeScript, TransactionWitnessSet};
+use cml_core::serialization::{Deserialize, Serialize};
+use cml_crypto::RawBytesEncoding;
+use serde_json::Value as JsonValue;
+use std::fmt;
+
+/// A kind of input other than a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    /// A block, optionally wrapped as `[era, block]`.
+    Block,
+    /// A transaction witness set.
+    WitnessSet,
+    /// A script in its tagged form (`[language, script]`), as in reference scripts.
+    Script,
+    /// A native script.
+    NativeScript,
+    /// Address bytes (raw or as a CBOR byte string).
+    Address,
+    /// Plutus data (a datum or redeemer).
+    PlutusDatum,
+}
+
+impl fmt::Display for InputKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InputKind::Block => "block",
+            InputKind::WitnessSet => "witness set",
+            InputKind::Script => "script",
+            InputKind::NativeScript => "native script",
+            InputKind::Address => "address",
+            InputKind::PlutusDatum => "Plutus datum",
+        })
+    }
+}
+
+/// Recognize non-transaction input, returning its kind and JSON form.
+pub fn detect_input(bytes: &[u8], options: &QueryOptions) -> Option<(InputKind, JsonValue)> {
+    if let Some(json) = block_to_json(bytes, options) {
+        return Some((InputKind::Block, json));
+    }
+
+    // The remaining kinds are single CBOR items (or raw address bytes)
+    let is_single_item = item_span(bytes, 0).is_ok_and(|span| span.end == bytes.len());
+    if is_single_item {
+        if let Ok(witness_set) = TransactionWitnessSet::from_cbor_bytes(bytes) {
+            return Some((
+                InputKind::WitnessSet,
+                witness_set_to_json(&witness_set, options),
+            ));
+        }
+        if let Ok(script) = Script::from_cbor_bytes(bytes) {
+            return Some((InputKind::Script, script_to_json(&script)));
+        }
+        if let Ok(script) = NativeScript::from_cbor_bytes(bytes) {
+            return Some((InputKind::NativeScript, native_script_to_json(&script)));
+        }
+    }
+
+    let address = Address::from_raw_bytes(bytes)
+        .ok()
+        .or_else(|| is_single_item.then(|| Address::from_cbor_bytes(bytes).ok())?);
+    if let Some(address) = address {
+        return Some((
+            InputKind::Address,
+            address_to_detailed_json(&address, options),
+        ));
+    }
+
+    if is_single_item {
+        let datum = PlutusData::from_cbor_bytes(bytes).ok()?;
+        return Some((InputKind::PlutusDatum, decode_plutus_datum_to_json(&datum)?));
+    }
+    None
+}
+
+/// Decode a block (or an `[era, block]` pair) with its transactions.
+fn block_to_json(bytes: &[u8], options: &QueryOptions) -> Option<JsonValue> {
+    block_json(&decode_block(bytes).ok()?, options).ok()
+}
+
+fn block_json(block: &DecodedBlock, options: &QueryOptions) -> Result<JsonValue> {
+    let header = &block.block.header.header_body;
+    let transactions = block
+        .transactions()
+        .iter()
+        .map(|tx| transaction_to_json(tx, options))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(serde_json::json!({
+        "header": {
+            "block_number": header.block_number,
+            "slot": header.slot,
+            "prev_hash": header.prev_hash.as_ref().map(|h| h.to_hex()),
+            "issuer_vkey": hex::encode(header.issuer_vkey.to_raw_bytes()),
+            "block_body_size": header.block_body_size,
+            "block_body_hash": header.block_body_hash.to_hex(),
+            "protocol_version": {
+                "major": header.protocol_version.major,
+                "minor": header.protocol_version.minor
+            }
+        },
+        "transaction_count": transactions.len(),
+        "transactions": transactions
+    }))
+}
+
+/// Describe a tagged script: its language, hash and size, and for native
+/// scripts the script itself.
+fn script_to_json(script: &Script) -> JsonValue {
+    let (language, size) = match script {
+        Script::Native { script, .. } => ("native", script.to_cbor_bytes().len()),
+        Script::PlutusV1 { script, .. } => ("plutus_v1", script.to_raw_bytes().len()),
+        Script::PlutusV2 { script, .. } => ("plutus_v2", script.to_raw_bytes().len()),
+        Script::PlutusV3 { script, .. } => ("plutus_v3", script.to_raw_bytes().len()),
+    };
+    let mut json = serde_json::json!({
+        "language": language,
+        "hash": script.hash().to_hex(),
+        "size": size
+    });
+    if let Script::Native { script, .. } = script {
+        json["script"] = native_script_to_json(script)["script"].take();
+    }
+    json
+}
+
+/// Describe a native script: its hash and structure.
+fn native_script_to_json(script: &NativeScript) -> JsonValue {
+    serde_json::json!({
+        "hash": script.hash().to_hex(),
+        "script": serde_json::to_value(script).unwrap_or(JsonValue::Null)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(hex: &str) -> Option<(InputKind, JsonValue)> {
+        detect_input(&hex::decode(hex).unwrap(), &QueryOptions::default())
+    }
+
+    #[test]
+    fn test_detect_datum() {
+        // Constr 0 [42]
+        let (kind, json) = detect("d8799f182aff").unwrap();
+        assert_eq!(kind, InputKind::PlutusDatum);
+        assert_eq!(json["constructor"], 0);
+        assert_eq!(json["fields"][0]["int"], 42);
+    }
+
+    #[test]
+    fn test_detect_address() {
+        let raw = "61".to_string() + &"ab".repeat(28);
+        let (kind, json) = detect(&raw).unwrap();
+        assert_eq!(kind, InputKind::Address);
+        assert!(json.to_string().contains("addr1"));
+
+        // As a CBOR byte string
+        let (kind, _) = detect(&format!("581d{}", raw)).unwrap();
+        assert_eq!(kind, InputKind::Address);
+    }
+
+    #[test]
+    fn test_detect_scripts_and_witness_sets() {
+        // ScriptPubkey native script, bare and tagged as a script
+        let native = format!("8200581c{}", "11".repeat(28));
+        let (kind, json) = detect(&native).unwrap();
+        assert_eq!(kind, InputKind::NativeScript);
+        assert_eq!(json["hash"].as_str().unwrap().len(), 56);
+
+        let (kind, json) = detect(&format!("8200{}", native)).unwrap();
+        assert_eq!(kind, InputKind::Script);
+        assert_eq!(json["language"], "native");
+
+        // Witness set with one native script
+        let (kind, json) = detect(&format!("a10181{}", native)).unwrap();
+        assert_eq!(kind, InputKind::WitnessSet);
+        assert_eq!(json["native_scripts"], 1);
+    }
+
+    #[test]
+    fn test_not_detected() {
+        assert!(detect("ff").is_none());
+    }
+}
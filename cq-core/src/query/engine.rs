@@ -0,0 +1,2652 @@
+//! Query execution engine.
+
+use crate::decode::{DecodedTransaction, encode_bech32};
+use crate::error::{Error, Result};
+use crate::query::coverage::{add_datum_coverage, add_script_coverage};
+use crate::query::expr::{ObjectField, parse_object};
+use crate::query::functions::Function;
+use crate::query::governance::{anchor_to_json, proposal_to_json, voting_procedures_to_json};
+use crate::query::identifiers::{add_script_ids, use_bech32_ids};
+use crate::query::options::{AssetNameFormat, LabelConfig, QueryOptions};
+use crate::query::path::{
+    FilterExpr, PathSegment, QueryPath, parse_literal, split_alternatives, split_pipeline,
+};
+use crate::query::redeemers::add_redeemer_targets;
+use crate::query::registry::format_token_amount;
+use crate::query::shortcuts::{
+    ComputedField, expand_shortcut, is_hash_query, split_computed_field,
+};
+use crate::query::transform::group_result;
+use cml_chain::json::plutus_datums::{
+    CardanoNodePlutusDatumSchema, decode_plutus_datum_to_json_str,
+};
+use cml_chain::plutus::PlutusData;
+use cml_crypto::RawBytesEncoding;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+/// Result of a query execution.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum QueryResult {
+    /// The full transaction.
+    FullTransaction(JsonValue),
+    /// A single value.
+    Single(QueryValue),
+    /// Multiple values (from wildcard expansion).
+    Multiple(Vec<QueryValue>),
+}
+
+impl QueryResult {
+    /// Check if the result holds nothing usable: null, false, an empty
+    /// array or object, or only such values.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            QueryResult::FullTransaction(_) => false,
+            QueryResult::Single(value) => value.is_empty(),
+            QueryResult::Multiple(values) => values.iter().all(QueryValue::is_empty),
+        }
+    }
+}
+
+/// A queryable value.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum QueryValue {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    Array(Vec<QueryValue>),
+    Object(serde_json::Map<String, JsonValue>),
+}
+
+impl QueryValue {
+    /// Check if the value is null, false, or an empty array or object.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            QueryValue::Null | QueryValue::Bool(false) => true,
+            QueryValue::Array(arr) => arr.is_empty(),
+            QueryValue::Object(map) => map.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+impl From<JsonValue> for QueryValue {
+    fn from(value: JsonValue) -> Self {
+        match value {
+            JsonValue::Null => QueryValue::Null,
+            JsonValue::Bool(b) => QueryValue::Bool(b),
+            JsonValue::Number(n) => QueryValue::Number(n),
+            JsonValue::String(s) => QueryValue::String(s),
+            JsonValue::Array(arr) => {
+                QueryValue::Array(arr.into_iter().map(QueryValue::from).collect())
+            }
+            JsonValue::Object(map) => QueryValue::Object(map),
+        }
+    }
+}
+
+impl From<QueryValue> for JsonValue {
+    fn from(value: QueryValue) -> Self {
+        match value {
+            QueryValue::Null => JsonValue::Null,
+            QueryValue::Bool(b) => JsonValue::Bool(b),
+            QueryValue::Number(n) => JsonValue::Number(n),
+            QueryValue::String(s) => JsonValue::String(s),
+            QueryValue::Array(arr) => {
+                JsonValue::Array(arr.into_iter().map(JsonValue::from).collect())
+            }
+            QueryValue::Object(map) => JsonValue::Object(map),
+        }
+    }
+}
+
+/// Execute a query against a decoded transaction.
+///
+/// A query is a pipeline of `|`-separated stages. The first stage is a path
+/// from the transaction root (with shortcuts); each later stage is either a
+/// function (`sum`, `count`, ...) or a path relative to the previous result,
+/// e.g. `outputs[value.coin > 1000000] | *.address`. Any stage may list
+/// fallbacks with `//`, e.g. `ttl // 0`, or build an object with
+/// `{key: query, ...}`.
+pub fn execute_query(
+    tx: &DecodedTransaction,
+    query: &str,
+    options: &QueryOptions,
+) -> Result<QueryResult> {
+    execute_pipeline(Root::Transaction(tx, options), None, query)
+}
+
+/// Execute a query against any JSON document (e.g. generic CBOR).
+///
+/// Paths start at the document root and shortcuts do not apply; an empty
+/// query returns the whole document.
+pub fn execute_json_query(root: &JsonValue, query: &str) -> Result<QueryResult> {
+    execute_pipeline(Root::Value(root), None, query)
+}
+
+/// Execute a query relative to a previous result (e.g. one output).
+pub fn execute_query_on(
+    tx: &DecodedTransaction,
+    input: &QueryResult,
+    query: &str,
+    options: &QueryOptions,
+) -> Result<QueryResult> {
+    execute_pipeline(Root::Transaction(tx, options), Some(input), query)
+}
+
+/// What root paths of a query start from.
+#[derive(Clone, Copy)]
+enum Root<'a> {
+    /// A transaction (with shortcuts and computed fields), and how to
+    /// convert it.
+    Transaction(&'a DecodedTransaction<'a>, &'a QueryOptions),
+    /// A plain JSON document.
+    Value(&'a JsonValue),
+}
+
+/// Execute a pipeline against a previous result, or the transaction root.
+fn execute_pipeline(root: Root, input: Option<&QueryResult>, query: &str) -> Result<QueryResult> {
+    let mut result = input.cloned();
+    for stage in split_pipeline(query)? {
+        log::trace!("pipeline stage: {}", stage.trim());
+        let next = execute_alternatives(stage, |alt| execute_stage(root, result.as_ref(), alt))?;
+        result = Some(next);
+    }
+    Ok(result.expect("split_pipeline returns at least one stage"))
+}
+
+/// Execute one stage (without alternatives) against its input.
+fn execute_stage(root: Root, input: Option<&QueryResult>, stage: &str) -> Result<QueryResult> {
+    if let Some(fields) = parse_object(stage)? {
+        return construct_object(root, input, &fields);
+    }
+    match (input, root) {
+        (Some(input), _) => apply_stage(input, stage),
+        (None, Root::Transaction(tx, options)) => execute_root_path(tx, stage, options),
+        (None, Root::Value(json)) => {
+            let path = QueryPath::parse(stage)?;
+            if path.is_empty() {
+                return Ok(QueryResult::Single(QueryValue::from(json.clone())));
+            }
+            execute_parsed_path(json, &path)
+        }
+    }
+}
+
+/// Build an object from `{key: query, ...}`, once per input value.
+fn construct_object(
+    root: Root,
+    input: Option<&QueryResult>,
+    fields: &[ObjectField],
+) -> Result<QueryResult> {
+    let build = |input: Option<&QueryResult>| -> Result<QueryValue> {
+        let mut map = serde_json::Map::new();
+        for field in fields {
+            let value = match execute_pipeline(root, input, &field.query)? {
+                QueryResult::Single(value) => JsonValue::from(value),
+                QueryResult::Multiple(values) => {
+                    JsonValue::Array(values.into_iter().map(JsonValue::from).collect())
+                }
+                QueryResult::FullTransaction(json) => json,
+            };
+            map.insert(field.key.clone(), value);
+        }
+        Ok(QueryValue::Object(map))
+    };
+
+    match input {
+        Some(QueryResult::Multiple(values)) => values
+            .iter()
+            .map(|v| build(Some(&QueryResult::Single(v.clone()))))
+            .collect::<Result<_>>()
+            .map(QueryResult::Multiple),
+        other => build(other).map(QueryResult::Single),
+    }
+}
+
+/// Evaluate `a // b // ...`, returning the first alternative that is present.
+///
+/// An alternative is absent if it is missing, null or false (for wildcard
+/// results: if every value is). Fallbacks may also be literals.
+fn execute_alternatives(
+    stage: &str,
+    eval: impl Fn(&str) -> Result<QueryResult>,
+) -> Result<QueryResult> {
+    let alternatives = split_alternatives(stage)?;
+    let (last, first) = alternatives
+        .split_last()
+        .expect("split_alternatives returns at least one alternative");
+
+    let evaluate = |idx: usize, alt: &str| match parse_literal(alt) {
+        Some(literal) if idx > 0 => Ok(QueryResult::Single(QueryValue::from(literal))),
+        _ => eval(alt),
+    };
+
+    for (idx, alt) in first.iter().enumerate() {
+        match evaluate(idx, alt) {
+            Ok(result) => {
+                if let Some(result) = present(result) {
+                    return Ok(result);
+                }
+            }
+            Err(Error::FieldNotFound(_) | Error::IndexOutOfBounds(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    evaluate(first.len(), last)
+}
+
+/// Keep a result only if it holds a value other than null or false.
+fn present(result: QueryResult) -> Option<QueryResult> {
+    let is_present = |v: &QueryValue| !matches!(v, QueryValue::Null | QueryValue::Bool(false));
+    match result {
+        QueryResult::Single(value) => is_present(&value).then_some(QueryResult::Single(value)),
+        QueryResult::Multiple(values) => {
+            let values: Vec<QueryValue> = values.into_iter().filter(is_present).collect();
+            (!values.is_empty()).then_some(QueryResult::Multiple(values))
+        }
+        full @ QueryResult::FullTransaction(_) => Some(full),
+    }
+}
+
+/// Apply a single pipeline stage to the result of the previous one.
+///
+/// Functions and `group_by(PATH)` consume the whole result. Paths are applied to each value of
+/// a wildcard result (flattening the outputs) or to the single value; the
+/// full transaction is queried in place rather than copied.
+fn apply_stage(input: &QueryResult, stage: &str) -> Result<QueryResult> {
+    if let Some(function) = Function::parse(stage) {
+        return function.apply(input.clone());
+    }
+    if let Some(key) = stage
+        .strip_prefix("group_by(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return group_result(input.clone(), key.trim());
+    }
+
+    // jq-style leading dot is optional; a bare `.` is the identity
+    let stage = match stage.strip_prefix('.') {
+        Some(rest) if !rest.starts_with('.') => rest,
+        _ => stage,
+    };
+    let path = QueryPath::parse(stage)?;
+    if path.is_empty() {
+        return Ok(input.clone());
+    }
+
+    match input {
+        QueryResult::Multiple(values) => {
+            let mut results = Vec::new();
+            for value in values {
+                match execute_parsed_path(&JsonValue::from(value.clone()), &path)? {
+                    QueryResult::Multiple(sub) => results.extend(sub),
+                    QueryResult::Single(v) => results.push(v),
+                    QueryResult::FullTransaction(json) => results.push(QueryValue::from(json)),
+                }
+            }
+            Ok(QueryResult::Multiple(results))
+        }
+        QueryResult::Single(value) => execute_parsed_path(&JsonValue::from(value.clone()), &path),
+        QueryResult::FullTransaction(json) => execute_parsed_path(json, &path),
+    }
+}
+
+/// Execute a path against the transaction root.
+fn execute_root_path(
+    tx: &DecodedTransaction,
+    query: &str,
+    options: &QueryOptions,
+) -> Result<QueryResult> {
+    // Expand shortcuts first
+    let expanded = expand_shortcut(query);
+    if expanded != query {
+        log::debug!("shortcut '{}' expands to '{}'", query, expanded);
+    }
+
+    // Handle special computed fields
+    if is_hash_query(&expanded) {
+        let hash_hex = hex::encode(tx.hash.to_raw_bytes());
+        return Ok(QueryResult::Single(QueryValue::String(hash_hex)));
+    }
+
+    // Computed fields replace the transaction JSON as the query root
+    if let Some((field, rest)) = split_computed_field(&expanded) {
+        let root = computed_field_to_json(tx, field, options);
+        let path = QueryPath::parse(rest)?;
+        if path.is_empty() {
+            return Ok(QueryResult::Single(QueryValue::from(root)));
+        }
+        return execute_parsed_path(&root, &path);
+    }
+
+    // Parse the query path
+    let path = QueryPath::parse(&expanded)?;
+    log::debug!("query segments: {:?}", path.segments);
+
+    // If path is empty, return full transaction
+    if path.is_empty() {
+        return Ok(QueryResult::FullTransaction(transaction_to_json(
+            tx, options,
+        )?));
+    }
+
+    // Convert only what the path reaches to JSON for querying
+    execute_parsed_path(&transaction_json_for_path(tx, &path, options)?, &path)
+}
+
+/// Execute a parsed, non-empty path against a JSON root.
+fn execute_parsed_path(root: &JsonValue, path: &QueryPath) -> Result<QueryResult> {
+    // Execute the path query
+    // Use recursive execution for wildcards OR filters with continuation
+    // (filters return multiple results that need to be iterated)
+    let needs_recursive = path.has_wildcard()
+        || path.has_slice()
+        || path.has_recursive()
+        || path.has_filter_with_continuation();
+    if needs_recursive {
+        let results = execute_path_with_wildcards(root, &path.segments)?;
+        Ok(QueryResult::Multiple(results))
+    } else {
+        let result = execute_path(root, &path.segments)?;
+        Ok(QueryResult::Single(result))
+    }
+}
+
+/// Build the JSON value of a computed field.
+fn computed_field_to_json(
+    tx: &DecodedTransaction,
+    field: ComputedField,
+    options: &QueryOptions,
+) -> JsonValue {
+    match field {
+        ComputedField::Addresses => addresses_to_json(tx, options),
+        ComputedField::Assets => assets_to_json(tx, options),
+    }
+}
+
+/// Aggregate every policy/asset across outputs and mint.
+///
+/// Each entry carries the total quantity sent to outputs, the signed mint
+/// quantity (negative for burns) and the net quantity supplied by inputs
+/// (`output - mint`).
+fn assets_to_json(tx: &DecodedTransaction, options: &QueryOptions) -> JsonValue {
+    use cml_chain::PolicyId;
+    use cml_chain::assets::AssetName;
+
+    let body = &tx.tx.body;
+    let mut entries: Vec<(PolicyId, AssetName, u64, i64)> = Vec::new();
+    let mut add = |policy_id: &PolicyId, name: &AssetName, output: u64, mint: i64| match entries
+        .iter_mut()
+        .find(|(p, n, _, _)| p == policy_id && n == name)
+    {
+        Some((_, _, o, m)) => {
+            *o += output;
+            *m += mint;
+        }
+        None => entries.push((*policy_id, name.clone(), output, mint)),
+    };
+
+    for output in &body.outputs {
+        for (policy_id, assets) in output.amount().multiasset.iter() {
+            for (name, amount) in assets.iter() {
+                add(policy_id, name, *amount, 0);
+            }
+        }
+    }
+    if let Some(ref mint) = body.mint {
+        for (policy_id, assets) in mint.iter() {
+            for (name, amount) in assets.iter() {
+                add(policy_id, name, 0, *amount);
+            }
+        }
+    }
+
+    JsonValue::Array(
+        entries
+            .into_iter()
+            .map(|(policy_id, name, output, mint)| {
+                asset_to_json(
+                    policy_id.to_raw_bytes(),
+                    name.to_raw_bytes(),
+                    serde_json::json!({
+                        "policy_id": hex::encode(policy_id.to_raw_bytes()),
+                        "output": output,
+                        "mint": mint,
+                        "net": (output as i128 - mint as i128) as i64
+                    }),
+                    options,
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Collect every address in a transaction, annotated with the roles it plays.
+///
+/// Covers outputs, the collateral return, withdrawals and certificates.
+/// Certificate credentials are rendered as reward addresses. Addresses are
+/// deduplicated in order of first appearance.
+fn addresses_to_json(tx: &DecodedTransaction, options: &QueryOptions) -> JsonValue {
+    use cml_chain::address::RewardAddress;
+    use cml_chain::certs::Credential;
+
+    let body = &tx.tx.body;
+    let mut entries: Vec<(String, Vec<&'static str>)> = Vec::new();
+    let mut add =
+        |address: String, role: &'static str| match entries.iter_mut().find(|(a, _)| *a == address)
+        {
+            Some((_, roles)) => {
+                if !roles.contains(&role) {
+                    roles.push(role);
+                }
+            }
+            None => entries.push((address, vec![role])),
+        };
+
+    for output in &body.outputs {
+        add(format_address(output.address()), "output");
+    }
+    if let Some(ref collateral_return) = body.collateral_return {
+        add(
+            format_address(collateral_return.address()),
+            "collateral_return",
+        );
+    }
+    if let Some(ref withdrawals) = body.withdrawals {
+        for (reward_addr, _) in withdrawals.iter() {
+            add(
+                format_address(&reward_addr.clone().to_address()),
+                "withdrawal",
+            );
+        }
+    }
+
+    // Certificates carry bare credentials; use the transaction's network
+    let network = tx
+        .network_id()
+        .or_else(|| options.network.map(|n| n.network_id()))
+        .unwrap_or(1);
+    for cert in body.certs.iter().flatten() {
+        for cred in certificate_credentials(cert) {
+            let addr = RewardAddress::new(network, cred).to_address();
+            add(format_address(&addr), "certificate");
+        }
+        if let cml_chain::certs::Certificate::PoolRegistration(pool_reg) = cert {
+            let params = &pool_reg.pool_params;
+            add(
+                format_address(&params.reward_account.clone().to_address()),
+                "pool_reward_account",
+            );
+            for owner in params.pool_owners.iter() {
+                let cred = Credential::new_pub_key(*owner);
+                let addr = RewardAddress::new(network, cred).to_address();
+                add(format_address(&addr), "pool_owner");
+            }
+        }
+    }
+
+    JsonValue::Array(
+        entries
+            .into_iter()
+            .map(|(address, roles)| serde_json::json!({ "address": address, "roles": roles }))
+            .collect(),
+    )
+}
+
+/// Stake credentials referenced by a certificate.
+fn certificate_credentials(
+    cert: &cml_chain::certs::Certificate,
+) -> Vec<cml_chain::certs::Credential> {
+    use cml_chain::certs::Certificate;
+
+    let cred = match cert {
+        Certificate::StakeRegistration(c) => &c.stake_credential,
+        Certificate::StakeDeregistration(c) => &c.stake_credential,
+        Certificate::StakeDelegation(c) => &c.stake_credential,
+        Certificate::RegCert(c) => &c.stake_credential,
+        Certificate::UnregCert(c) => &c.stake_credential,
+        Certificate::VoteDelegCert(c) => &c.stake_credential,
+        Certificate::StakeVoteDelegCert(c) => &c.stake_credential,
+        Certificate::StakeRegDelegCert(c) => &c.stake_credential,
+        Certificate::VoteRegDelegCert(c) => &c.stake_credential,
+        Certificate::StakeVoteRegDelegCert(c) => &c.stake_credential,
+        _ => return Vec::new(),
+    };
+    vec![cred.clone()]
+}
+
+/// Convert a decoded transaction to a JSON value for querying.
+pub fn transaction_to_json(tx: &DecodedTransaction, options: &QueryOptions) -> Result<JsonValue> {
+    let mut tx_json = serde_json::json!({
+        "hash": hex::encode(tx.hash.to_raw_bytes()),
+        "body": body_to_json(tx, None, options),
+        "witness_set": tx_witness_set_to_json(tx, options),
+        "is_valid": tx.tx.is_valid
+    });
+
+    if let Some(aux) = auxiliary_data_to_json(tx, options) {
+        tx_json["auxiliary_data"] = aux;
+    }
+
+    Ok(with_bech32_ids(tx_json, options))
+}
+
+/// Add `script_id` fields or switch identifiers to bech32, as the options
+/// ask.
+fn with_bech32_ids(mut json: JsonValue, options: &QueryOptions) -> JsonValue {
+    if options.script_ids {
+        add_script_ids(&mut json);
+    }
+    if options.bech32_ids {
+        use_bech32_ids(&mut json);
+    }
+    json
+}
+
+/// Build only the part of the transaction JSON a path can reach.
+///
+/// A path starting with plain fields (`body.fee`) needs only that section,
+/// and within the body only that field, so e.g. a fee query does not
+/// convert every output or hash every script. Other paths get the full tree.
+fn transaction_json_for_path(
+    tx: &DecodedTransaction,
+    path: &QueryPath,
+    options: &QueryOptions,
+) -> Result<JsonValue> {
+    let field = |idx: usize| match path.segments.get(idx) {
+        Some(PathSegment::Field(name)) => Some(name.as_str()),
+        _ => None,
+    };
+    let json = match field(0) {
+        Some("body") => serde_json::json!({ "body": body_to_json(tx, field(1), options) }),
+        Some("witness_set") => {
+            serde_json::json!({ "witness_set": tx_witness_set_to_json(tx, options) })
+        }
+        Some("auxiliary_data") => match auxiliary_data_to_json(tx, options) {
+            Some(aux) => serde_json::json!({ "auxiliary_data": aux }),
+            None => serde_json::json!({}),
+        },
+        _ => return transaction_to_json(tx, options),
+    };
+    Ok(with_bech32_ids(json, options))
+}
+
+/// Convert the transaction body to JSON, or only its `only` field.
+fn body_to_json(tx: &DecodedTransaction, only: Option<&str>, options: &QueryOptions) -> JsonValue {
+    use cml_chain::PolicyId;
+    use cml_chain::assets::AssetName;
+
+    let body = &tx.tx.body;
+    let wanted = |name: &str| only.is_none_or(|only| only == name);
+    let inputs_to_json = |inputs: &[cml_chain::transaction::TransactionInput]| {
+        inputs.iter().map(input_to_json).collect::<Vec<_>>()
+    };
+
+    let mut body_json = serde_json::json!({});
+
+    if wanted("inputs") {
+        body_json["inputs"] = serde_json::json!(inputs_to_json(&body.inputs));
+    }
+    if wanted("outputs") {
+        let outputs: Vec<JsonValue> = body
+            .outputs
+            .iter()
+            .map(|output| output_to_json(output, options))
+            .collect();
+        body_json["outputs"] = serde_json::json!(outputs);
+    }
+    if wanted("fee") {
+        body_json["fee"] = serde_json::json!(body.fee);
+    }
+    // Slots, and with a known slot schedule the times they start
+    let slot_config = options.slot_config();
+    let slot_fields = [
+        ("ttl", body.ttl),
+        ("validity_interval_start", body.validity_interval_start),
+    ];
+    for (name, slot) in slot_fields {
+        let Some(slot) = slot else { continue };
+        if wanted(name) {
+            body_json[name] = serde_json::json!(slot);
+        }
+        let Some(config) = slot_config else { continue };
+        let time_name = format!("{}_time", name);
+        if let Some(time) = config.slot_to_utc(slot).filter(|_| wanted(&time_name)) {
+            body_json[time_name] = serde_json::json!(time);
+        }
+        let epoch_name = format!("{}_epoch", name);
+        if let Some(epoch) = config.slot_to_epoch(slot).filter(|_| wanted(&epoch_name)) {
+            body_json[epoch_name] = serde_json::json!(epoch);
+        }
+    }
+
+    // Build mint if present
+    if let Some(m) = body.mint.as_ref().filter(|_| wanted("mint")) {
+        let mint: Vec<JsonValue> = m
+            .iter()
+            .map(|(policy_id, assets): (&PolicyId, _)| {
+                let assets_json: Vec<JsonValue> = assets
+                    .iter()
+                    .map(|(name, amount): (&AssetName, &i64)| {
+                        asset_to_json(
+                            policy_id.to_raw_bytes(),
+                            name.to_raw_bytes(),
+                            serde_json::json!({ "amount": *amount }),
+                            options,
+                        )
+                    })
+                    .collect();
+                serde_json::json!({
+                    "policy_id": hex::encode(policy_id.to_raw_bytes()),
+                    "assets": assets_json
+                })
+            })
+            .collect();
+        body_json["mint"] = serde_json::json!(mint);
+    }
+
+    // Build collateral inputs if present
+    if let Some(c) = body
+        .collateral_inputs
+        .as_ref()
+        .filter(|_| wanted("collateral_inputs"))
+    {
+        body_json["collateral_inputs"] = serde_json::json!(inputs_to_json(c));
+    }
+
+    // Build reference inputs if present (CIP-31)
+    if let Some(r) = body
+        .reference_inputs
+        .as_ref()
+        .filter(|_| wanted("reference_inputs"))
+    {
+        body_json["reference_inputs"] = serde_json::json!(inputs_to_json(r));
+    }
+
+    // Build required signers if present
+    if let Some(signers) = body
+        .required_signers
+        .as_ref()
+        .filter(|_| wanted("required_signers"))
+    {
+        let signers: Vec<String> = signers
+            .iter()
+            .map(|s| hex::encode(s.to_raw_bytes()))
+            .collect();
+        body_json["required_signers"] = serde_json::json!(signers);
+    }
+    if let Some(network_id) = body.network_id.as_ref().filter(|_| wanted("network_id")) {
+        // NetworkId stores network value directly
+        body_json["network_id"] = serde_json::json!(network_id.network);
+    }
+
+    // Build certificates if present
+    if let Some(certs) = body.certs.as_ref().filter(|_| wanted("certs")) {
+        let certs_json: Vec<JsonValue> = certs
+            .iter()
+            .map(|cert| certificate_to_json(cert, options))
+            .collect();
+        if !certs_json.is_empty() {
+            body_json["certs"] = serde_json::json!(certs_json);
+        }
+    }
+
+    // Build votes if present (Conway)
+    if let Some(procedures) = body
+        .voting_procedures
+        .as_ref()
+        .filter(|p| !p.is_empty() && wanted("voting_procedures"))
+    {
+        body_json["voting_procedures"] = voting_procedures_to_json(procedures);
+    }
+
+    // Build governance proposals if present (Conway)
+    if let Some(proposals) = body
+        .proposal_procedures
+        .as_ref()
+        .filter(|_| wanted("proposal_procedures"))
+    {
+        let proposals: Vec<JsonValue> = proposals.iter().map(proposal_to_json).collect();
+        if !proposals.is_empty() {
+            body_json["proposal_procedures"] = serde_json::json!(proposals);
+        }
+    }
+
+    // Build withdrawals if present
+    if let Some(withdrawals) = body.withdrawals.as_ref().filter(|_| wanted("withdrawals")) {
+        let withdrawals_json: Vec<JsonValue> = withdrawals
+            .iter()
+            .map(|(reward_addr, coin)| {
+                // RewardAddress needs to be converted to Address first for bech32
+                // Clone since to_address() takes ownership
+                let addr = reward_addr.clone().to_address();
+                let addr_str = addr
+                    .to_bech32(None)
+                    .unwrap_or_else(|_| hex::encode(addr.to_raw_bytes()));
+                serde_json::json!({
+                    "reward_address": addr_str,
+                    "amount": coin
+                })
+            })
+            .collect();
+        if !withdrawals_json.is_empty() {
+            body_json["withdrawals"] = serde_json::json!(withdrawals_json);
+        }
+    }
+
+    // Add script_data_hash if present
+    if let Some(script_data_hash) = body
+        .script_data_hash
+        .as_ref()
+        .filter(|_| wanted("script_data_hash"))
+    {
+        body_json["script_data_hash"] =
+            serde_json::json!(hex::encode(script_data_hash.to_raw_bytes()));
+    }
+
+    // Add collateral_return if present
+    if let Some(collateral_return) = body
+        .collateral_return
+        .as_ref()
+        .filter(|_| wanted("collateral_return"))
+    {
+        body_json["collateral_return"] = output_to_json(collateral_return, options);
+    }
+
+    // Add total_collateral if present
+    if let Some(total_collateral) = body.total_collateral.filter(|_| wanted("total_collateral")) {
+        body_json["total_collateral"] = serde_json::json!(total_collateral);
+    }
+
+    body_json
+}
+
+/// Convert a transaction's witness set to JSON, with redeemer targets, the
+/// scripts and datums the transaction needs and the required signers no vkey witness
+/// covers (`missing_signers`).
+fn tx_witness_set_to_json(tx: &DecodedTransaction, options: &QueryOptions) -> JsonValue {
+    let mut witness_json = witness_set_to_json(&tx.tx.witness_set, options);
+    add_redeemer_targets(&tx.tx.body, &mut witness_json);
+    add_script_coverage(&tx.tx, &mut witness_json);
+    add_datum_coverage(&tx.tx, &mut witness_json);
+
+    if let Some(required) = &tx.tx.body.required_signers {
+        let signed: Vec<_> = tx
+            .tx
+            .witness_set
+            .vkeywitnesses
+            .iter()
+            .flatten()
+            .map(|w| w.vkey.hash())
+            .collect();
+        let missing: Vec<String> = required
+            .iter()
+            .filter(|signer| !signed.contains(signer))
+            .map(|signer| signer.to_hex())
+            .collect();
+        witness_json["missing_signers"] = serde_json::json!(missing);
+    }
+    witness_json
+}
+
+/// Convert the witness set to JSON (counts, script hashes, datums and redeemers).
+pub(crate) fn witness_set_to_json(
+    witness_set: &cml_chain::transaction::TransactionWitnessSet,
+    options: &QueryOptions,
+) -> JsonValue {
+    use cml_core::serialization::Serialize as CmlSerialize;
+
+    let mut witness_json = serde_json::json!({});
+
+    if let Some(vkeys) = &witness_set.vkeywitnesses {
+        witness_json["vkeywitnesses"] = serde_json::json!(vkeys.len());
+        let key_hashes: Vec<String> = vkeys.iter().map(|w| w.vkey.hash().to_hex()).collect();
+        witness_json["vkey_hashes"] = serde_json::json!(key_hashes);
+    }
+    if let Some(native) = &witness_set.native_scripts {
+        witness_json["native_scripts"] = serde_json::json!(native.len());
+    }
+    if let Some(v1) = &witness_set.plutus_v1_scripts {
+        let scripts: Vec<JsonValue> = v1
+            .iter()
+            .map(|s| {
+                let cbor = s.to_cbor_bytes();
+                let script = cml_chain::Script::new_plutus_v1(s.clone());
+                plutus_script_to_json(&script, &cbor, options)
+            })
+            .collect();
+        witness_json["plutus_v1_scripts"] = serde_json::json!(scripts);
+    }
+    if let Some(v2) = &witness_set.plutus_v2_scripts {
+        let scripts: Vec<JsonValue> = v2
+            .iter()
+            .map(|s| {
+                let cbor = s.to_cbor_bytes();
+                let script = cml_chain::Script::new_plutus_v2(s.clone());
+                plutus_script_to_json(&script, &cbor, options)
+            })
+            .collect();
+        witness_json["plutus_v2_scripts"] = serde_json::json!(scripts);
+    }
+    if let Some(v3) = &witness_set.plutus_v3_scripts {
+        let scripts: Vec<JsonValue> = v3
+            .iter()
+            .map(|s| {
+                let cbor = s.to_cbor_bytes();
+                let script = cml_chain::Script::new_plutus_v3(s.clone());
+                plutus_script_to_json(&script, &cbor, options)
+            })
+            .collect();
+        witness_json["plutus_v3_scripts"] = serde_json::json!(scripts);
+    }
+    if let Some(data) = &witness_set.plutus_datums {
+        // Decode each plutus datum in witness set
+        let datums: Vec<JsonValue> = data
+            .iter()
+            .map(|datum| {
+                let mut datum_json = serde_json::json!({
+                    "hash": datum.hash().to_hex(),
+                    "bytes": hex::encode(datum.to_cbor_bytes())
+                });
+                if let Some(decoded) = decode_plutus_datum_to_json(datum) {
+                    datum_json["value"] = decoded;
+                }
+                datum_json
+            })
+            .collect();
+        witness_json["plutus_datums"] = serde_json::json!(datums);
+    }
+    if let Some(redeemers) = &witness_set.redeemers {
+        // Convert to flat format and decode each redeemer
+        let flat_redeemers = redeemers.clone().to_flat_format();
+        let redeemers_json: Vec<JsonValue> = flat_redeemers
+            .iter()
+            .map(|r| {
+                let purpose = match r.tag {
+                    cml_chain::plutus::RedeemerTag::Spend => "spend",
+                    cml_chain::plutus::RedeemerTag::Mint => "mint",
+                    cml_chain::plutus::RedeemerTag::Cert => "cert",
+                    cml_chain::plutus::RedeemerTag::Reward => "reward",
+                    cml_chain::plutus::RedeemerTag::Voting => "voting",
+                    cml_chain::plutus::RedeemerTag::Proposing => "proposing",
+                };
+                let mut redeemer_json = serde_json::json!({
+                    "purpose": purpose,
+                    "index": r.index,
+                    "ex_units": {
+                        "mem": r.ex_units.mem,
+                        "steps": r.ex_units.steps
+                    }
+                });
+                // Decode the redeemer data (PlutusData)
+                if let Some(decoded) = decode_plutus_datum_to_json(&r.data) {
+                    redeemer_json["data"] = decoded;
+                }
+                redeemer_json
+            })
+            .collect();
+        witness_json["redeemers"] = serde_json::json!(redeemers_json);
+        witness_json["ex_units_total"] = ex_units_total(&flat_redeemers, options);
+    }
+
+    witness_json
+}
+
+/// Total execution units across all redeemers and, with protocol parameters
+/// in the options, the transaction's budget and whether the total fits it.
+fn ex_units_total(
+    redeemers: &[cml_chain::plutus::LegacyRedeemer],
+    options: &QueryOptions,
+) -> JsonValue {
+    let mem = redeemers
+        .iter()
+        .fold(0u64, |total, r| total.saturating_add(r.ex_units.mem));
+    let steps = redeemers
+        .iter()
+        .fold(0u64, |total, r| total.saturating_add(r.ex_units.steps));
+    let mut json = serde_json::json!({ "mem": mem, "steps": steps });
+
+    let params = options.protocol_params.unwrap_or_default();
+    if let Some(max_mem) = params.max_tx_ex_mem {
+        json["max_mem"] = serde_json::json!(max_mem);
+    }
+    if let Some(max_steps) = params.max_tx_ex_steps {
+        json["max_steps"] = serde_json::json!(max_steps);
+    }
+    if params.max_tx_ex_mem.is_some() || params.max_tx_ex_steps.is_some() {
+        json["within_budget"] = serde_json::json!(
+            params.max_tx_ex_mem.is_none_or(|max| mem <= max)
+                && params.max_tx_ex_steps.is_none_or(|max| steps <= max)
+        );
+    }
+    json
+}
+
+/// Convert the auxiliary data (metadata and script counts) to JSON, if present.
+fn auxiliary_data_to_json(tx: &DecodedTransaction, options: &QueryOptions) -> Option<JsonValue> {
+    tx.tx.auxiliary_data.as_ref().map(|aux| {
+        let mut aux_json = serde_json::json!({});
+
+        if let Some(metadata) = aux.metadata() {
+            let labels: Vec<JsonValue> = metadata
+                .entries
+                .iter()
+                .map(|(label, value)| {
+                    serde_json::json!({
+                        "label": label,
+                        "value": decode_metadata_for_label(*label, value, options)
+                    })
+                })
+                .collect();
+            aux_json["metadata"] = serde_json::json!({ "labels": labels });
+        }
+
+        if let Some(native) = aux.native_scripts() {
+            aux_json["native_scripts"] = serde_json::json!(native.len());
+        }
+
+        if let Some(v1) = aux.plutus_v1_scripts() {
+            aux_json["plutus_v1_scripts"] = serde_json::json!(v1.len());
+        }
+
+        if let Some(v2) = aux.plutus_v2_scripts() {
+            aux_json["plutus_v2_scripts"] = serde_json::json!(v2.len());
+        }
+
+        // Note: plutus_v3_scripts not available in AuxiliaryData accessor methods
+
+        aux_json
+    })
+}
+
+/// Convert a UTxO set to JSON: an array of outputs in the transaction
+/// output model, each with the `input` that references it.
+pub fn utxo_set_to_json(utxos: &crate::decode::UtxoSet, options: &QueryOptions) -> JsonValue {
+    let entries = utxos.iter().map(|(input, output)| {
+        let mut json = output_to_json(output, options);
+        json["input"] = input_to_json(input);
+        json
+    });
+    with_bech32_ids(JsonValue::Array(entries.collect()), options)
+}
+
+/// Convert a witness set Plutus script to JSON: its hash and size, and
+/// its program when the options ask for decompilation.
+fn plutus_script_to_json(
+    script: &cml_chain::Script,
+    cbor: &[u8],
+    options: &QueryOptions,
+) -> JsonValue {
+    let mut json = serde_json::json!({
+        "hash": hex::encode(script.hash().to_raw_bytes()),
+        "size": cbor.len()
+    });
+    if let Some(double_cbor) = double_cbor_to_json(script) {
+        json["double_cbor"] = double_cbor;
+    }
+    if options.decompile_scripts {
+        json["uplc"] = match crate::decode::decode_program(cbor) {
+            Ok(program) => program.to_json(),
+            Err(e) => serde_json::json!({ "error": e.to_string() }),
+        };
+    }
+    json
+}
+
+/// Both hashes of a Plutus script wrapped in an extra CBOR byte string
+/// layer: the one the ledger computes and the one without the extra layer.
+fn double_cbor_to_json(script: &cml_chain::Script) -> Option<JsonValue> {
+    let unwrapped = crate::decode::double_wrapped_hash(script)?;
+    Some(serde_json::json!({
+        "hash": script.hash().to_hex(),
+        "unwrapped_hash": unwrapped.to_hex()
+    }))
+}
+
+/// Convert a transaction input to JSON, with its `txid#index` outpoint.
+fn input_to_json(input: &cml_chain::transaction::TransactionInput) -> JsonValue {
+    let transaction_id = hex::encode(input.transaction_id.to_raw_bytes());
+    serde_json::json!({
+        "outpoint": format!("{}#{}", transaction_id, input.index),
+        "transaction_id": transaction_id,
+        "index": input.index
+    })
+}
+
+/// Convert a transaction output to JSON.
+fn output_to_json(
+    output: &cml_chain::transaction::TransactionOutput,
+    options: &QueryOptions,
+) -> JsonValue {
+    use cml_chain::transaction::TransactionOutput;
+    use cml_core::serialization::Serialize as CmlSerialize;
+
+    match output {
+        TransactionOutput::AlonzoFormatTxOut(alonzo) => {
+            let mut json = serde_json::json!({
+                "address": address_to_detailed_json(&alonzo.address, options),
+                "value": value_to_json(&alonzo.amount, options)
+            });
+
+            if let Some(datum_hash) = &alonzo.datum_hash {
+                json["datum"] = serde_json::json!({
+                    "type": "hash",
+                    "hash": hex::encode(datum_hash.to_raw_bytes())
+                });
+            }
+
+            json
+        }
+        TransactionOutput::ConwayFormatTxOut(conway) => {
+            let mut json = serde_json::json!({
+                "address": address_to_detailed_json(&conway.address, options),
+                "value": value_to_json(&conway.amount, options)
+            });
+
+            if let Some(datum_option) = &conway.datum_option {
+                use cml_chain::transaction::DatumOption;
+                match datum_option {
+                    DatumOption::Hash { datum_hash, .. } => {
+                        json["datum"] = serde_json::json!({
+                            "type": "hash",
+                            "hash": hex::encode(datum_hash.to_raw_bytes())
+                        });
+                    }
+                    DatumOption::Datum { datum, .. } => {
+                        let bytes = datum.to_cbor_bytes();
+                        let mut datum_json = serde_json::json!({
+                            "type": "inline",
+                            "bytes": hex::encode(&bytes),
+                            "size": bytes.len()
+                        });
+
+                        // Decode Plutus data to JSON
+                        if let Some(decoded) = decode_plutus_datum_to_json(datum) {
+                            datum_json["value"] = decoded;
+                        }
+
+                        json["datum"] = datum_json;
+                    }
+                }
+            }
+
+            if let Some(script_ref) = &conway.script_reference {
+                let bytes = script_ref.to_cbor_bytes();
+                json["script_ref"] = serde_json::json!({
+                    "size": bytes.len(),
+                    "bytes": hex::encode(&bytes)
+                });
+                if let Some(double_cbor) = double_cbor_to_json(script_ref) {
+                    json["script_ref"]["double_cbor"] = double_cbor;
+                }
+            }
+
+            json
+        }
+    }
+}
+
+/// Decode PlutusData to JSON using DetailedSchema format.
+///
+/// Returns JSON in the format:
+/// - Constructor: `{"constructor": N, "fields": [...]}`
+/// - Integer: `{"int": N}`
+/// - Bytes: `{"bytes": "hexstring"}`
+/// - List: `{"list": [...]}`
+/// - Map: `{"map": [{"k": ..., "v": ...}, ...]}`
+///
+/// Returns `None` for data the schema cannot represent.
+pub fn decode_plutus_datum_to_json(datum: &PlutusData) -> Option<JsonValue> {
+    let json_str =
+        decode_plutus_datum_to_json_str(datum, CardanoNodePlutusDatumSchema::DetailedSchema)
+            .ok()?;
+
+    serde_json::from_str(&json_str).ok()
+}
+
+/// A pool's bech32 ID (`pool1...`), as explorers show it.
+fn pool_id(pool: &cml_crypto::Ed25519KeyHash) -> String {
+    pool.to_bech32("pool").unwrap_or_else(|_| pool.to_hex())
+}
+
+/// Format an address to bech32.
+fn format_address(addr: &cml_chain::address::Address) -> String {
+    // Try to get bech32 representation
+    addr.to_bech32(None).unwrap_or_else(|_| {
+        // Fallback to hex if bech32 fails
+        hex::encode(addr.to_raw_bytes())
+    })
+}
+
+/// Convert an address to detailed JSON with type, network, and credentials.
+pub(crate) fn address_to_detailed_json(
+    addr: &cml_chain::address::Address,
+    options: &QueryOptions,
+) -> JsonValue {
+    use cml_chain::address::Address;
+    use cml_core::serialization::ToBytes;
+
+    let bech32 = format_address(addr);
+
+    // Network ID (CIP-19): 0 for all testnets, so a testnet is only named
+    // when the options give one
+    let network = addr
+        .network_id()
+        .map(|id| options.network_label(id))
+        .unwrap_or("unknown");
+
+    match addr {
+        Address::Base(base_addr) => {
+            serde_json::json!({
+                "address": bech32,
+                "type": "base",
+                "network": network,
+                "payment_credential": credential_to_json(&base_addr.payment),
+                "stake_credential": credential_to_json(&base_addr.stake)
+            })
+        }
+        Address::Enterprise(enterprise_addr) => {
+            serde_json::json!({
+                "address": bech32,
+                "type": "enterprise",
+                "network": network,
+                "payment_credential": credential_to_json(&enterprise_addr.payment)
+            })
+        }
+        Address::Ptr(ptr_addr) => {
+            serde_json::json!({
+                "address": bech32,
+                "type": "pointer",
+                "network": network,
+                "payment_credential": credential_to_json(&ptr_addr.payment),
+                "pointer": {
+                    "slot": ptr_addr.stake.slot(),
+                    "tx_index": ptr_addr.stake.tx_index(),
+                    "cert_index": ptr_addr.stake.cert_index()
+                }
+            })
+        }
+        Address::Reward(reward_addr) => {
+            serde_json::json!({
+                "address": bech32,
+                "type": "reward",
+                "network": network,
+                "stake_credential": credential_to_json(&reward_addr.payment)
+            })
+        }
+        Address::Byron(byron_addr) => {
+            serde_json::json!({
+                "address": bech32,
+                "type": "byron",
+                "network": network,
+                "byron_address": hex::encode(byron_addr.to_bytes())
+            })
+        }
+    }
+}
+
+/// Asset name fields: `name` in the given format, `name_hex`, and
+/// `name_utf8` if the name is printable UTF-8 (no control chars).
+fn asset_name_to_json(bytes: &[u8], format: AssetNameFormat) -> serde_json::Map<String, JsonValue> {
+    let utf8 = std::str::from_utf8(bytes)
+        .ok()
+        .filter(|s| !s.is_empty() && s.chars().all(|c| !c.is_control()));
+    let name = match format {
+        AssetNameFormat::Auto => utf8.map_or_else(|| hex::encode(bytes), str::to_string),
+        AssetNameFormat::Hex => hex::encode(bytes),
+        AssetNameFormat::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+    };
+
+    let mut json = serde_json::Map::new();
+    json.insert("name".to_string(), JsonValue::from(name));
+    json.insert("name_hex".to_string(), JsonValue::from(hex::encode(bytes)));
+    if let Some(utf8) = utf8 {
+        json.insert("name_utf8".to_string(), JsonValue::from(utf8));
+    }
+    json
+}
+
+/// The CIP-14 fingerprint (`asset1...`): blake2b-160 of the policy ID
+/// and asset name.
+fn asset_fingerprint(policy_id: &[u8], name: &[u8]) -> String {
+    let mut hash = [0; 20];
+    cryptoxide::blake2b::Blake2b::blake2b(&mut hash, &[policy_id, name].concat(), &[]);
+    crate::decode::encode_bech32("asset", &hash)
+}
+
+/// JSON for an asset: its name fields, CIP-14 `fingerprint` and `fields`,
+/// and with token metadata in the options its ticker, decimals and
+/// `formatted` amount.
+fn asset_to_json(
+    policy_id: &[u8],
+    name: &[u8],
+    fields: JsonValue,
+    options: &QueryOptions,
+) -> JsonValue {
+    let mut json = asset_name_to_json(name, options.asset_names);
+    json.insert(
+        "fingerprint".to_string(),
+        JsonValue::from(asset_fingerprint(policy_id, name)),
+    );
+    if let JsonValue::Object(fields) = fields {
+        json.extend(fields);
+    }
+
+    let subject = format!("{}{}", hex::encode(policy_id), hex::encode(name));
+    if let Some(info) = options.token_info(&subject) {
+        if let Some(ref ticker) = info.ticker {
+            json.insert("ticker".to_string(), JsonValue::from(ticker.as_str()));
+        }
+        json.insert("decimals".to_string(), JsonValue::from(info.decimals));
+        let amount = json.get("amount").and_then(|a| {
+            a.as_i64()
+                .map(i128::from)
+                .or_else(|| a.as_u64().map(i128::from))
+        });
+        if let Some(amount) = amount {
+            let formatted = format_token_amount(amount, info);
+            json.insert("formatted".to_string(), JsonValue::from(formatted));
+        }
+    }
+    JsonValue::Object(json)
+}
+
+/// Convert a value (coin + multi-assets) to JSON.
+fn value_to_json(value: &cml_chain::assets::Value, options: &QueryOptions) -> JsonValue {
+    use cml_chain::PolicyId;
+    use cml_chain::assets::AssetName;
+
+    let coin = value.coin;
+
+    let multi_assets: Vec<JsonValue> = value
+        .multiasset
+        .iter()
+        .map(|(policy_id, assets): (&PolicyId, _)| {
+            let assets_json: Vec<JsonValue> = assets
+                .iter()
+                .map(|(name, amount): (&AssetName, &u64)| {
+                    asset_to_json(
+                        policy_id.to_raw_bytes(),
+                        name.to_raw_bytes(),
+                        serde_json::json!({ "amount": *amount }),
+                        options,
+                    )
+                })
+                .collect();
+            serde_json::json!({
+                "policy_id": hex::encode(policy_id.to_raw_bytes()),
+                "assets": assets_json
+            })
+        })
+        .collect();
+
+    if multi_assets.is_empty() {
+        serde_json::json!({ "coin": coin })
+    } else {
+        serde_json::json!({
+            "coin": coin,
+            "multi_assets": multi_assets
+        })
+    }
+}
+
+/// Convert metadata value to JSON.
+fn metadata_value_to_json(value: &cml_chain::auxdata::TransactionMetadatum) -> JsonValue {
+    use cml_chain::auxdata::TransactionMetadatum;
+
+    match value {
+        TransactionMetadatum::Int(i) => {
+            // CML Int can be positive or negative
+            serde_json::json!(i.to_string())
+        }
+        TransactionMetadatum::Bytes { bytes, .. } => {
+            // Try to decode bytes as UTF-8 text, fallback to hex
+            let decoded = String::from_utf8(bytes.clone())
+                .ok()
+                .filter(|s| !s.is_empty() && s.chars().all(|c| !c.is_control()));
+            if let Some(text) = decoded {
+                serde_json::json!(text)
+            } else {
+                serde_json::json!({
+                    "bytes": hex::encode(bytes)
+                })
+            }
+        }
+        TransactionMetadatum::Text { text, .. } => {
+            serde_json::json!(text)
+        }
+        TransactionMetadatum::List { elements, .. } => {
+            let arr: Vec<JsonValue> = elements.iter().map(metadata_value_to_json).collect();
+            serde_json::json!(arr)
+        }
+        TransactionMetadatum::Map(map_entries) => {
+            // Convert map to a more readable JSON object when keys are strings
+            let mut obj = serde_json::Map::new();
+            let mut is_string_keyed = true;
+
+            for (k, v) in &map_entries.entries {
+                if let TransactionMetadatum::Text { text, .. } = k {
+                    obj.insert(text.clone(), metadata_value_to_json(v));
+                } else {
+                    is_string_keyed = false;
+                    break;
+                }
+            }
+
+            if is_string_keyed && !obj.is_empty() {
+                JsonValue::Object(obj)
+            } else {
+                // Fallback to array of key-value pairs
+                let map: Vec<JsonValue> = map_entries
+                    .entries
+                    .iter()
+                    .map(|(k, v)| {
+                        serde_json::json!({
+                            "key": metadata_value_to_json(k),
+                            "value": metadata_value_to_json(v)
+                        })
+                    })
+                    .collect();
+                serde_json::json!(map)
+            }
+        }
+    }
+}
+
+/// Decode metadata with CIP standard awareness.
+/// CIP-20 (label 674): Transaction messages
+/// CIP-25 (label 721): NFT metadata
+/// CIP-68 (labels 100, 222, 333, 444): Datum metadata standard
+fn decode_metadata_for_label(
+    label: u64,
+    value: &cml_chain::auxdata::TransactionMetadatum,
+    options: &QueryOptions,
+) -> JsonValue {
+    if let Some(custom) = options.labels.get(&label) {
+        return custom_label_to_json(custom, value);
+    }
+
+    let decoded = metadata_value_to_json(value);
+
+    match label {
+        674 => {
+            // CIP-20: Transaction messages
+            serde_json::json!({
+                "cip": "CIP-20",
+                "standard": "Transaction Message",
+                "data": decoded
+            })
+        }
+        721 => {
+            // CIP-25: NFT Metadata (v2 uses byte-string keys)
+            serde_json::json!({
+                "cip": "CIP-25",
+                "standard": "NFT Metadata",
+                "version": cip25_version(value),
+                "data": cip25_to_json(value)
+            })
+        }
+        867 => {
+            // CIP-88: Token policy registration
+            serde_json::json!({
+                "cip": "CIP-88",
+                "standard": "Token Policy Registration",
+                "data": cip88_to_json(value).unwrap_or(decoded)
+            })
+        }
+        100 => {
+            // CIP-68: Reference NFT
+            serde_json::json!({
+                "cip": "CIP-68",
+                "standard": "Reference NFT (100)",
+                "data": decoded
+            })
+        }
+        222 => {
+            // CIP-68: Non-Fungible Token
+            serde_json::json!({
+                "cip": "CIP-68",
+                "standard": "NFT (222)",
+                "data": decoded
+            })
+        }
+        333 => {
+            // CIP-68: Fungible Token
+            serde_json::json!({
+                "cip": "CIP-68",
+                "standard": "FT (333)",
+                "data": decoded
+            })
+        }
+        444 => {
+            // CIP-68: Rich Fungible Token
+            serde_json::json!({
+                "cip": "CIP-68",
+                "standard": "RFT (444)",
+                "data": decoded
+            })
+        }
+        _ => decoded,
+    }
+}
+
+/// Convert metadata for a label configured in the config file.
+fn custom_label_to_json(
+    config: &LabelConfig,
+    value: &cml_chain::auxdata::TransactionMetadatum,
+) -> JsonValue {
+    use cml_chain::auxdata::TransactionMetadatum;
+
+    let data = match value {
+        TransactionMetadatum::Map(map) if !config.fields.is_empty() => JsonValue::Object(
+            map.entries
+                .iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        TransactionMetadatum::Int(i) => i.to_string(),
+                        TransactionMetadatum::Text { text, .. } => text.clone(),
+                        TransactionMetadatum::Bytes { bytes, .. } => hex::encode(bytes),
+                        other => metadata_value_to_json(other).to_string(),
+                    };
+                    let name = config.fields.get(&key).cloned().unwrap_or(key);
+                    (name, metadata_value_to_json(value))
+                })
+                .collect(),
+        ),
+        _ => metadata_value_to_json(value),
+    };
+
+    serde_json::json!({
+        "standard": config.name,
+        "data": data
+    })
+}
+
+/// CIP-25 version from the top-level `version` key (1 if absent).
+fn cip25_version(value: &cml_chain::auxdata::TransactionMetadatum) -> u64 {
+    use cml_chain::auxdata::TransactionMetadatum;
+
+    let TransactionMetadatum::Map(map) = value else {
+        return 1;
+    };
+    map.entries
+        .iter()
+        .find(|(k, _)| matches!(k, TransactionMetadatum::Text { text, .. } if text == "version"))
+        .and_then(|(_, v)| metadatum_uint(v))
+        .unwrap_or(1)
+}
+
+/// Convert CIP-25 metadata to the v1 shape `{policy_id: {asset_name: {...}}}`.
+///
+/// Version 2 keys policy ids and asset names by raw bytes; policy ids are
+/// shown as hex and asset names as UTF-8 when printable (hex otherwise).
+fn cip25_to_json(value: &cml_chain::auxdata::TransactionMetadatum) -> JsonValue {
+    use cml_chain::auxdata::TransactionMetadatum;
+
+    let TransactionMetadatum::Map(policies) = value else {
+        return metadata_value_to_json(value);
+    };
+
+    let key_string = |key: &TransactionMetadatum, utf8: bool| match key {
+        TransactionMetadatum::Text { text, .. } => text.clone(),
+        TransactionMetadatum::Bytes { bytes, .. } => String::from_utf8(bytes.clone())
+            .ok()
+            .filter(|s| utf8 && !s.is_empty() && !s.chars().any(char::is_control))
+            .unwrap_or_else(|| hex::encode(bytes)),
+        other => match metadata_value_to_json(other) {
+            JsonValue::String(s) => s,
+            json => json.to_string(),
+        },
+    };
+
+    let mut obj = serde_json::Map::new();
+    for (policy, assets) in &policies.entries {
+        let policy = key_string(policy, false);
+        let value = match assets {
+            TransactionMetadatum::Map(assets) if policy != "version" => JsonValue::Object(
+                assets
+                    .entries
+                    .iter()
+                    .map(|(name, asset)| (key_string(name, true), metadata_value_to_json(asset)))
+                    .collect(),
+            ),
+            other => metadata_value_to_json(other),
+        };
+        obj.insert(policy, value);
+    }
+    JsonValue::Object(obj)
+}
+
+/// Convert a CIP-88 registration (`{0: version, 1: payload, 2: witnesses}`).
+///
+/// Returns `None` if the metadata doesn't have the registration structure,
+/// so it can fall back to the generic conversion.
+fn cip88_to_json(value: &cml_chain::auxdata::TransactionMetadatum) -> Option<JsonValue> {
+    use cml_chain::auxdata::TransactionMetadatum;
+
+    let payload = metadatum_field(value, 1)?;
+    let mut obj = serde_json::Map::new();
+    if let Some(version) = metadatum_field(value, 0).and_then(metadatum_uint) {
+        obj.insert("version".to_string(), version.into());
+    }
+
+    if let Some(scope @ TransactionMetadatum::List { elements, .. }) = metadatum_field(payload, 1) {
+        // Native script scopes are [0, policy_id, [script chunks]]
+        let scope = match elements.first().and_then(metadatum_uint) {
+            Some(0) => serde_json::json!({
+                "type": "native_script",
+                "policy_id": elements.get(1).and_then(metadatum_bytes_hex),
+                "script": elements.get(2).and_then(metadatum_bytes_hex)
+            }),
+            _ => metadata_value_to_json(scope),
+        };
+        obj.insert("scope".to_string(), scope);
+    }
+
+    if let Some(TransactionMetadatum::List { elements, .. }) = metadatum_field(payload, 2) {
+        let features: Vec<JsonValue> = elements
+            .iter()
+            .map(|cip| match metadatum_uint(cip) {
+                Some(n) => serde_json::json!(format!("CIP-{:02}", n)),
+                None => metadata_value_to_json(cip),
+            })
+            .collect();
+        obj.insert("feature_set".to_string(), JsonValue::Array(features));
+    }
+
+    if let Some(method @ TransactionMetadatum::List { elements, .. }) = metadatum_field(payload, 3)
+    {
+        let method = match elements.first().and_then(metadatum_uint) {
+            Some(0) => serde_json::json!({ "type": "ed25519_key_signature" }),
+            Some(1) => serde_json::json!({
+                "type": "beacon_token",
+                "token": elements.get(1).map(metadata_value_to_json)
+            }),
+            Some(2) => serde_json::json!({ "type": "cip8_signing" }),
+            _ => metadata_value_to_json(method),
+        };
+        obj.insert("validation_method".to_string(), method);
+    }
+
+    if let Some(nonce) = metadatum_field(payload, 4) {
+        let nonce = metadatum_uint(nonce).map_or_else(|| metadata_value_to_json(nonce), Into::into);
+        obj.insert("nonce".to_string(), nonce);
+    }
+
+    // CIP-specific details keyed by CIP number
+    if let Some(TransactionMetadatum::Map(details)) = metadatum_field(payload, 5) {
+        let details: serde_json::Map<String, JsonValue> = details
+            .entries
+            .iter()
+            .map(|(cip, data)| {
+                let name = match metadatum_uint(cip) {
+                    Some(n) => format!("CIP-{:02}", n),
+                    None => metadata_value_to_json(cip).to_string(),
+                };
+                (name, metadata_value_to_json(data))
+            })
+            .collect();
+        obj.insert("details".to_string(), JsonValue::Object(details));
+    }
+
+    if let Some(TransactionMetadatum::List { elements, .. }) = metadatum_field(value, 2) {
+        let witnesses: Vec<JsonValue> = elements
+            .iter()
+            .map(|witness| match witness {
+                TransactionMetadatum::List { elements, .. } if elements.len() == 2 => {
+                    serde_json::json!({
+                        "vkey": metadatum_bytes_hex(&elements[0]),
+                        "signature": metadatum_bytes_hex(&elements[1])
+                    })
+                }
+                other => metadata_value_to_json(other),
+            })
+            .collect();
+        obj.insert("witnesses".to_string(), JsonValue::Array(witnesses));
+    }
+
+    Some(JsonValue::Object(obj))
+}
+
+/// Value of an integer key in a metadata map.
+fn metadatum_field(
+    value: &cml_chain::auxdata::TransactionMetadatum,
+    key: u64,
+) -> Option<&cml_chain::auxdata::TransactionMetadatum> {
+    let cml_chain::auxdata::TransactionMetadatum::Map(map) = value else {
+        return None;
+    };
+    map.entries
+        .iter()
+        .find(|(k, _)| metadatum_uint(k) == Some(key))
+        .map(|(_, v)| v)
+}
+
+/// Metadata integer as a `u64`, if it is a non-negative integer.
+fn metadatum_uint(value: &cml_chain::auxdata::TransactionMetadatum) -> Option<u64> {
+    match value {
+        cml_chain::auxdata::TransactionMetadatum::Int(i) => i.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Hex of a byte string, or of a list of byte-string chunks joined together.
+fn metadatum_bytes_hex(value: &cml_chain::auxdata::TransactionMetadatum) -> Option<String> {
+    use cml_chain::auxdata::TransactionMetadatum;
+
+    match value {
+        TransactionMetadatum::Bytes { bytes, .. } => Some(hex::encode(bytes)),
+        TransactionMetadatum::List { elements, .. } => elements
+            .iter()
+            .map(|chunk| match chunk {
+                TransactionMetadatum::Bytes { bytes, .. } => Some(hex::encode(bytes)),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Convert a certificate to JSON.
+pub(crate) fn certificate_to_json(
+    cert: &cml_chain::certs::Certificate,
+    options: &QueryOptions,
+) -> JsonValue {
+    use cml_chain::certs::Certificate;
+
+    match cert {
+        Certificate::StakeRegistration(reg) => {
+            serde_json::json!({
+                "type": "stake_registration",
+                "stake_credential": stake_credential_to_json(&reg.stake_credential)
+            })
+        }
+        Certificate::StakeDeregistration(dereg) => {
+            serde_json::json!({
+                "type": "stake_deregistration",
+                "stake_credential": stake_credential_to_json(&dereg.stake_credential)
+            })
+        }
+        Certificate::StakeDelegation(deleg) => {
+            serde_json::json!({
+                "type": "stake_delegation",
+                "stake_credential": stake_credential_to_json(&deleg.stake_credential),
+                "pool_keyhash": hex::encode(deleg.pool.to_raw_bytes()),
+                "pool_id": pool_id(&deleg.pool)
+            })
+        }
+        Certificate::PoolRegistration(pool_reg) => {
+            let mut json = serde_json::json!({
+                "type": "pool_registration",
+                "pool_keyhash": hex::encode(pool_reg.pool_params.operator.to_raw_bytes()),
+                "pool_id": pool_id(&pool_reg.pool_params.operator),
+                "vrf_keyhash": hex::encode(pool_reg.pool_params.vrf_keyhash.to_raw_bytes()),
+                "pledge": pool_reg.pool_params.pledge,
+                "cost": pool_reg.pool_params.cost,
+                "margin": format!("{}/{}", pool_reg.pool_params.margin.start, pool_reg.pool_params.margin.end)
+            });
+            // Shaped like an anchor, so --fetch-anchors checks it too
+            if let Some(metadata) = &pool_reg.pool_params.pool_metadata {
+                json["pool_metadata"] = serde_json::json!({
+                    "url": metadata.url.get(),
+                    "data_hash": metadata.pool_metadata_hash.to_hex()
+                });
+            }
+            json
+        }
+        Certificate::PoolRetirement(pool_ret) => {
+            let mut json = serde_json::json!({
+                "type": "pool_retirement",
+                "pool_keyhash": hex::encode(pool_ret.pool.to_raw_bytes()),
+                "pool_id": pool_id(&pool_ret.pool),
+                "epoch": pool_ret.epoch
+            });
+            // When the retirement takes effect
+            if let Some(time) = options
+                .slot_config()
+                .and_then(|config| config.epoch_start_utc(pool_ret.epoch))
+            {
+                json["epoch_time"] = serde_json::json!(time);
+            }
+            json
+        }
+        Certificate::RegCert(reg) => {
+            serde_json::json!({
+                "type": "reg_cert",
+                "stake_credential": stake_credential_to_json(&reg.stake_credential),
+                "deposit": reg.deposit
+            })
+        }
+        Certificate::UnregCert(unreg) => {
+            serde_json::json!({
+                "type": "unreg_cert",
+                "stake_credential": stake_credential_to_json(&unreg.stake_credential),
+                "deposit": unreg.deposit
+            })
+        }
+        Certificate::VoteDelegCert(vote_deleg) => {
+            serde_json::json!({
+                "type": "vote_deleg_cert",
+                "stake_credential": stake_credential_to_json(&vote_deleg.stake_credential),
+                "drep": drep_to_json(&vote_deleg.d_rep)
+            })
+        }
+        Certificate::StakeVoteDelegCert(stake_vote) => {
+            serde_json::json!({
+                "type": "stake_vote_deleg_cert",
+                "stake_credential": stake_credential_to_json(&stake_vote.stake_credential),
+                "pool_keyhash": hex::encode(stake_vote.pool.to_raw_bytes()),
+                "pool_id": pool_id(&stake_vote.pool),
+                "drep": drep_to_json(&stake_vote.d_rep)
+            })
+        }
+        Certificate::StakeRegDelegCert(stake_reg) => {
+            serde_json::json!({
+                "type": "stake_reg_deleg_cert",
+                "stake_credential": stake_credential_to_json(&stake_reg.stake_credential),
+                "pool_keyhash": hex::encode(stake_reg.pool.to_raw_bytes()),
+                "pool_id": pool_id(&stake_reg.pool),
+                "deposit": stake_reg.deposit
+            })
+        }
+        Certificate::VoteRegDelegCert(vote_reg) => {
+            serde_json::json!({
+                "type": "vote_reg_deleg_cert",
+                "stake_credential": stake_credential_to_json(&vote_reg.stake_credential),
+                "drep": drep_to_json(&vote_reg.d_rep),
+                "deposit": vote_reg.deposit
+            })
+        }
+        Certificate::StakeVoteRegDelegCert(stake_vote_reg) => {
+            serde_json::json!({
+                "type": "stake_vote_reg_deleg_cert",
+                "stake_credential": stake_credential_to_json(&stake_vote_reg.stake_credential),
+                "pool_keyhash": hex::encode(stake_vote_reg.pool.to_raw_bytes()),
+                "pool_id": pool_id(&stake_vote_reg.pool),
+                "drep": drep_to_json(&stake_vote_reg.d_rep),
+                "deposit": stake_vote_reg.deposit
+            })
+        }
+        Certificate::AuthCommitteeHotCert(auth) => {
+            serde_json::json!({
+                "type": "auth_committee_hot_cert",
+                "committee_cold_credential": credential_to_json(&auth.committee_cold_credential),
+                "committee_cold_id": governance_id("cc_cold", 1, &auth.committee_cold_credential),
+                "committee_hot_credential": credential_to_json(&auth.committee_hot_credential),
+                "committee_hot_id": governance_id("cc_hot", 0, &auth.committee_hot_credential)
+            })
+        }
+        Certificate::ResignCommitteeColdCert(resign) => with_anchor(
+            serde_json::json!({
+                "type": "resign_committee_cold_cert",
+                "committee_cold_credential": credential_to_json(&resign.committee_cold_credential),
+                "committee_cold_id": governance_id("cc_cold", 1, &resign.committee_cold_credential)
+            }),
+            resign.anchor.as_ref(),
+        ),
+        Certificate::RegDrepCert(reg_drep) => with_anchor(
+            serde_json::json!({
+                "type": "reg_drep_cert",
+                "drep_credential": credential_to_json(&reg_drep.drep_credential),
+                "drep_id": drep_id(&reg_drep.drep_credential),
+                "drep_cip105_id": drep_cip105_id(&reg_drep.drep_credential),
+                "deposit": reg_drep.deposit
+            }),
+            reg_drep.anchor.as_ref(),
+        ),
+        Certificate::UnregDrepCert(unreg_drep) => {
+            serde_json::json!({
+                "type": "unreg_drep_cert",
+                "drep_credential": credential_to_json(&unreg_drep.drep_credential),
+                "drep_id": drep_id(&unreg_drep.drep_credential),
+                "drep_cip105_id": drep_cip105_id(&unreg_drep.drep_credential),
+                "deposit": unreg_drep.deposit
+            })
+        }
+        Certificate::UpdateDrepCert(update_drep) => with_anchor(
+            serde_json::json!({
+                "type": "update_drep_cert",
+                "drep_credential": credential_to_json(&update_drep.drep_credential),
+                "drep_id": drep_id(&update_drep.drep_credential),
+                "drep_cip105_id": drep_cip105_id(&update_drep.drep_credential)
+            }),
+            update_drep.anchor.as_ref(),
+        ),
+    }
+}
+
+/// Add the certificate's anchor, if it has one.
+fn with_anchor(mut json: JsonValue, anchor: Option<&cml_chain::governance::Anchor>) -> JsonValue {
+    if let Some(anchor) = anchor {
+        json["anchor"] = anchor_to_json(anchor);
+    }
+    json
+}
+
+/// Convert stake credential to JSON.
+fn stake_credential_to_json(cred: &cml_chain::certs::StakeCredential) -> JsonValue {
+    credential_to_json(cred)
+}
+
+/// Convert credential to JSON.
+pub(crate) fn credential_to_json(cred: &cml_chain::certs::Credential) -> JsonValue {
+    use cml_chain::certs::Credential;
+    match cred {
+        Credential::PubKey { hash, .. } => {
+            serde_json::json!({
+                "type": "pubkey",
+                "hash": hex::encode(hash.to_raw_bytes())
+            })
+        }
+        Credential::Script { hash, .. } => {
+            serde_json::json!({
+                "type": "script",
+                "hash": hex::encode(hash.to_raw_bytes())
+            })
+        }
+    }
+}
+
+/// A CIP-129 governance ID: the hash after a header byte whose high
+/// nibble says the role and low nibble 2 for a key or 3 for a script.
+fn governance_id(prefix: &str, role: u8, cred: &cml_chain::certs::Credential) -> String {
+    use cml_chain::certs::Credential;
+    let (header, hash) = match cred {
+        Credential::PubKey { hash, .. } => (role << 4 | 2, hash.to_raw_bytes()),
+        Credential::Script { hash, .. } => (role << 4 | 3, hash.to_raw_bytes()),
+    };
+    encode_bech32(prefix, &[&[header], hash].concat())
+}
+
+/// A DRep's CIP-129 bech32 ID (`drep1...`).
+fn drep_id(cred: &cml_chain::certs::Credential) -> String {
+    governance_id("drep", 2, cred)
+}
+
+/// A DRep's older CIP-105 bech32 ID: `drep1...` for a key hash,
+/// `drep_script1...` for a script hash.
+fn drep_cip105_id(cred: &cml_chain::certs::Credential) -> String {
+    use cml_chain::certs::Credential;
+    match cred {
+        Credential::PubKey { hash, .. } => encode_bech32("drep", hash.to_raw_bytes()),
+        Credential::Script { hash, .. } => encode_bech32("drep_script", hash.to_raw_bytes()),
+    }
+}
+
+/// Convert DRep to JSON.
+fn drep_to_json(drep: &cml_chain::certs::DRep) -> JsonValue {
+    use cml_chain::certs::{Credential, DRep};
+    match drep {
+        DRep::Key { pool, .. } => {
+            let cred = Credential::new_pub_key(*pool);
+            serde_json::json!({
+                "type": "key",
+                "hash": hex::encode(pool.to_raw_bytes()),
+                "id": drep_id(&cred),
+                "cip105_id": drep_cip105_id(&cred)
+            })
+        }
+        DRep::Script { script_hash, .. } => {
+            let cred = Credential::new_script(*script_hash);
+            serde_json::json!({
+                "type": "script",
+                "hash": hex::encode(script_hash.to_raw_bytes()),
+                "id": drep_id(&cred),
+                "cip105_id": drep_cip105_id(&cred)
+            })
+        }
+        DRep::AlwaysAbstain { .. } => {
+            serde_json::json!({ "type": "always_abstain" })
+        }
+        DRep::AlwaysNoConfidence { .. } => {
+            serde_json::json!({ "type": "always_no_confidence" })
+        }
+    }
+}
+
+/// Execute a path query without wildcards.
+///
+/// The path is followed by reference; only the value it ends at is cloned.
+pub(crate) fn execute_path(value: &JsonValue, segments: &[PathSegment]) -> Result<QueryValue> {
+    let mut current = value;
+
+    for (idx, segment) in segments.iter().enumerate() {
+        current = match segment {
+            PathSegment::Field(name) => current
+                .get(name)
+                .ok_or_else(|| Error::FieldNotFound(name.clone()))?,
+            PathSegment::Index(idx) => {
+                lookup_index(current, *idx).ok_or(Error::IndexOutOfBounds(*idx))?
+            }
+            PathSegment::Optional(inner) => match lookup_optional(current, inner) {
+                Some(next) => next,
+                None => return Ok(QueryValue::Null),
+            },
+            PathSegment::Wildcard | PathSegment::Slice { .. } | PathSegment::Recursive(_) => {
+                return Err(Error::InvalidQuery(
+                    "Unexpected wildcard in non-wildcard path".to_string(),
+                ));
+            }
+            PathSegment::Filter(filter) => {
+                // Filter operates on arrays
+                let arr = current
+                    .as_array()
+                    .ok_or_else(|| Error::InvalidQuery("Filter on non-array".to_string()))?;
+
+                // The filtered array is a new value; the rest of the path applies to it
+                let filtered: Vec<JsonValue> = arr
+                    .iter()
+                    .filter(|item| evaluate_filter(item, filter))
+                    .cloned()
+                    .collect();
+                let rest = &segments[idx + 1..];
+                if rest.is_empty() {
+                    return Ok(QueryValue::from(JsonValue::Array(filtered)));
+                }
+                return execute_path(&JsonValue::Array(filtered), rest);
+            }
+        };
+    }
+
+    Ok(QueryValue::from(current.clone()))
+}
+
+/// Execute a path query with wildcards, returning all matching values.
+fn execute_path_with_wildcards(
+    value: &JsonValue,
+    segments: &[PathSegment],
+) -> Result<Vec<QueryValue>> {
+    let mut matches = Vec::new();
+    execute_path_recursive(value, segments, &mut matches)?;
+    Ok(matches
+        .into_iter()
+        .map(|value| QueryValue::from(value.clone()))
+        .collect())
+}
+
+/// Recursively execute path with wildcard expansion, collecting references
+/// to the matching values so that only the final results are cloned.
+fn execute_path_recursive<'a>(
+    value: &'a JsonValue,
+    segments: &[PathSegment],
+    matches: &mut Vec<&'a JsonValue>,
+) -> Result<()> {
+    let Some((current_segment, rest)) = segments.split_first() else {
+        matches.push(value);
+        return Ok(());
+    };
+
+    match current_segment {
+        PathSegment::Field(name) => {
+            let next = value
+                .get(name)
+                .ok_or_else(|| Error::FieldNotFound(name.clone()))?;
+            execute_path_recursive(next, rest, matches)
+        }
+        PathSegment::Index(idx) => {
+            let next = lookup_index(value, *idx).ok_or(Error::IndexOutOfBounds(*idx))?;
+            execute_path_recursive(next, rest, matches)
+        }
+        PathSegment::Wildcard => {
+            let arr = value
+                .as_array()
+                .ok_or_else(|| Error::InvalidQuery("Wildcard on non-array".to_string()))?;
+
+            for item in arr {
+                execute_path_recursive(item, rest, matches)?;
+            }
+            Ok(())
+        }
+        PathSegment::Slice { start, end } => {
+            let arr = value
+                .as_array()
+                .ok_or_else(|| Error::InvalidQuery("Slice on non-array".to_string()))?;
+
+            // Out-of-range bounds are clamped, like Python slices
+            let end = end.unwrap_or(arr.len()).min(arr.len());
+            let start = start.unwrap_or(0).min(end);
+
+            for item in &arr[start..end] {
+                execute_path_recursive(item, rest, matches)?;
+            }
+            Ok(())
+        }
+        PathSegment::Optional(inner) => match lookup_optional(value, inner) {
+            Some(next) => execute_path_recursive(next, rest, matches),
+            None => {
+                matches.push(&JsonValue::Null);
+                Ok(())
+            }
+        },
+        PathSegment::Recursive(name) => {
+            let mut found = Vec::new();
+            collect_fields(value, name, &mut found);
+
+            // Matches lacking the rest of the path are skipped, not errors
+            for item in found {
+                let mut sub_matches = Vec::new();
+                if execute_path_recursive(item, rest, &mut sub_matches).is_ok() {
+                    matches.extend(sub_matches);
+                }
+            }
+            Ok(())
+        }
+        PathSegment::Filter(filter) => {
+            let arr = value
+                .as_array()
+                .ok_or_else(|| Error::InvalidQuery("Filter on non-array".to_string()))?;
+
+            for item in arr {
+                if evaluate_filter(item, filter) {
+                    execute_path_recursive(item, rest, matches)?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Look up the field or index wrapped by an optional segment.
+fn lookup_optional<'a>(value: &'a JsonValue, segment: &PathSegment) -> Option<&'a JsonValue> {
+    match segment {
+        PathSegment::Field(name) => value.get(name),
+        PathSegment::Index(idx) => lookup_index(value, *idx),
+        _ => None,
+    }
+}
+
+/// Look up an index: an array position, or an integer key of an object
+/// (CBOR maps keyed by integers become objects with keys like `"0"`).
+fn lookup_index(value: &JsonValue, idx: usize) -> Option<&JsonValue> {
+    match value {
+        JsonValue::Object(map) => map.get(&idx.to_string()),
+        other => other.get(idx),
+    }
+}
+
+/// Collect every value stored under `name` at any depth, in document order.
+fn collect_fields<'a>(value: &'a JsonValue, name: &str, matches: &mut Vec<&'a JsonValue>) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                if key == name {
+                    matches.push(child);
+                }
+                collect_fields(child, name, matches);
+            }
+        }
+        JsonValue::Array(arr) => {
+            for child in arr {
+                collect_fields(child, name, matches);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Evaluate a filter expression against a JSON value.
+fn evaluate_filter(value: &JsonValue, filter: &FilterExpr) -> bool {
+    use crate::query::path::{FilterOp, FilterValue};
+
+    // Get the field value using dot-notation path
+    let field_value = get_nested_field(value, &filter.field);
+
+    match (&filter.op, &filter.value) {
+        // Numeric comparisons
+        (FilterOp::Gt, FilterValue::Number(n)) => field_value
+            .and_then(|v| v.as_f64())
+            .is_some_and(|fv| fv > *n),
+        (FilterOp::Lt, FilterValue::Number(n)) => field_value
+            .and_then(|v| v.as_f64())
+            .is_some_and(|fv| fv < *n),
+        (FilterOp::Gte, FilterValue::Number(n)) => field_value
+            .and_then(|v| v.as_f64())
+            .is_some_and(|fv| fv >= *n),
+        (FilterOp::Lte, FilterValue::Number(n)) => field_value
+            .and_then(|v| v.as_f64())
+            .is_some_and(|fv| fv <= *n),
+        (FilterOp::Eq, FilterValue::Number(n)) => field_value
+            .and_then(|v| v.as_f64())
+            .is_some_and(|fv| (fv - *n).abs() < f64::EPSILON),
+        (FilterOp::Ne, FilterValue::Number(n)) => field_value
+            .and_then(|v| v.as_f64())
+            .is_some_and(|fv| (fv - *n).abs() >= f64::EPSILON),
+
+        // String comparisons
+        (FilterOp::Eq, FilterValue::String(s)) => field_value
+            .and_then(|v| v.as_str())
+            .is_some_and(|fv| fv == s),
+        (FilterOp::Ne, FilterValue::String(s)) => field_value
+            .and_then(|v| v.as_str())
+            .is_some_and(|fv| fv != s),
+        (FilterOp::Contains, FilterValue::String(s)) => field_value
+            .and_then(|v| v.as_str())
+            .is_some_and(|fv| fv.contains(s.as_str())),
+        (FilterOp::StartsWith, FilterValue::String(s)) => field_value
+            .and_then(|v| v.as_str())
+            .is_some_and(|fv| fv.starts_with(s.as_str())),
+        (FilterOp::EndsWith, FilterValue::String(s)) => field_value
+            .and_then(|v| v.as_str())
+            .is_some_and(|fv| fv.ends_with(s.as_str())),
+        (FilterOp::ContainsIgnoreCase, FilterValue::String(s)) => field_value
+            .and_then(|v| v.as_str())
+            .is_some_and(|fv| fv.to_lowercase().contains(&s.to_lowercase())),
+        (FilterOp::StartsWithIgnoreCase, FilterValue::String(s)) => field_value
+            .and_then(|v| v.as_str())
+            .is_some_and(|fv| fv.to_lowercase().starts_with(&s.to_lowercase())),
+        (FilterOp::EndsWithIgnoreCase, FilterValue::String(s)) => field_value
+            .and_then(|v| v.as_str())
+            .is_some_and(|fv| fv.to_lowercase().ends_with(&s.to_lowercase())),
+
+        // Membership: any list item equal to the field value
+        (FilterOp::In, FilterValue::List(items)) => items.iter().any(|item| match item {
+            FilterValue::Number(n) => field_value
+                .and_then(|v| v.as_f64())
+                .is_some_and(|fv| (fv - *n).abs() < f64::EPSILON),
+            FilterValue::String(s) => field_value.and_then(|v| v.as_str()) == Some(s.as_str()),
+            FilterValue::Null => field_value.is_none_or(|v| v.is_null()),
+            FilterValue::List(_) => false,
+        }),
+
+        // Null comparisons (existence checks)
+        // == null: true if field doesn't exist OR field value is null
+        (FilterOp::Eq, FilterValue::Null) => field_value.is_none_or(|v| v.is_null()),
+        // != null: true if field exists AND field value is not null
+        (FilterOp::Ne, FilterValue::Null) => field_value.is_some_and(|v| !v.is_null()),
+
+        // Other combinations
+        _ => false,
+    }
+}
+
+/// Get a nested field from a JSON value using dot-notation.
+fn get_nested_field<'a>(value: &'a JsonValue, path: &str) -> Option<&'a JsonValue> {
+    let mut current = value;
+    for part in path.split('.') {
+        current = current.get(part)?;
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute_path_simple() {
+        let json = serde_json::json!({
+            "body": {
+                "fee": 200000,
+                "inputs": []
+            }
+        });
+
+        let segments = vec![
+            PathSegment::Field("body".into()),
+            PathSegment::Field("fee".into()),
+        ];
+
+        let result = execute_path(&json, &segments).unwrap();
+        match result {
+            QueryValue::Number(n) => assert_eq!(n.as_u64(), Some(200000)),
+            _ => panic!("Expected number"),
+        }
+    }
+
+    #[test]
+    fn test_execute_path_with_index() {
+        let json = serde_json::json!({
+            "outputs": [
+                { "address": "addr1..." },
+                { "address": "addr2..." }
+            ]
+        });
+
+        let segments = vec![
+            PathSegment::Field("outputs".into()),
+            PathSegment::Index(0),
+            PathSegment::Field("address".into()),
+        ];
+
+        let result = execute_path(&json, &segments).unwrap();
+        match result {
+            QueryValue::String(s) => assert_eq!(s, "addr1..."),
+            _ => panic!("Expected string"),
+        }
+    }
+
+    #[test]
+    fn test_execute_path_with_wildcard() {
+        let json = serde_json::json!({
+            "outputs": [
+                { "address": "addr1" },
+                { "address": "addr2" },
+                { "address": "addr3" }
+            ]
+        });
+
+        let segments = vec![
+            PathSegment::Field("outputs".into()),
+            PathSegment::Wildcard,
+            PathSegment::Field("address".into()),
+        ];
+
+        let results = execute_path_with_wildcards(&json, &segments).unwrap();
+        assert_eq!(results.len(), 3);
+    }
+
+    #[test]
+    fn test_field_not_found() {
+        let json = serde_json::json!({ "body": {} });
+        let segments = vec![
+            PathSegment::Field("body".into()),
+            PathSegment::Field("nonexistent".into()),
+        ];
+
+        let result = execute_path(&json, &segments);
+        assert!(matches!(result, Err(Error::FieldNotFound(_))));
+    }
+
+    #[test]
+    fn test_index_out_of_bounds() {
+        let json = serde_json::json!({ "arr": [1, 2] });
+        let segments = vec![PathSegment::Field("arr".into()), PathSegment::Index(10)];
+
+        let result = execute_path(&json, &segments);
+        assert!(matches!(result, Err(Error::IndexOutOfBounds(10))));
+    }
+
+    #[test]
+    fn test_filter_not_null() {
+        use crate::query::path::{FilterExpr, FilterOp, FilterValue};
+
+        let json = serde_json::json!({
+            "items": [
+                { "name": "a", "datum": { "type": "inline" } },
+                { "name": "b" },
+                { "name": "c", "datum": null }
+            ]
+        });
+
+        let filter = FilterExpr {
+            field: "datum".to_string(),
+            op: FilterOp::Ne,
+            value: FilterValue::Null,
+        };
+
+        let segments = vec![
+            PathSegment::Field("items".into()),
+            PathSegment::Filter(filter),
+        ];
+
+        let result = execute_path(&json, &segments).unwrap();
+        // Should return only item "a" which has datum != null
+        match result {
+            QueryValue::Array(arr) => {
+                assert_eq!(arr.len(), 1, "Expected 1 item with datum != null");
+                if let QueryValue::Object(obj) = &arr[0] {
+                    assert_eq!(obj.get("name").and_then(|v| v.as_str()), Some("a"));
+                } else {
+                    panic!("Expected object");
+                }
+            }
+            _ => panic!("Expected array"),
+        }
+    }
+
+    #[test]
+    fn test_filter_then_index() {
+        use crate::query::path::{FilterExpr, FilterOp, FilterValue};
+
+        let json = serde_json::json!({
+            "items": [{ "name": "a", "n": 1 }, { "name": "b", "n": 5 }, { "name": "c", "n": 9 }]
+        });
+        let segments = vec![
+            PathSegment::Field("items".into()),
+            PathSegment::Filter(FilterExpr {
+                field: "n".to_string(),
+                op: FilterOp::Gt,
+                value: FilterValue::Number(2.0),
+            }),
+            PathSegment::Index(1),
+            PathSegment::Field("name".into()),
+        ];
+
+        // The index applies to the filtered array
+        match execute_path(&json, &segments).unwrap() {
+            QueryValue::String(name) => assert_eq!(name, "c"),
+            other => panic!("Expected string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_filter_is_null() {
+        use crate::query::path::{FilterExpr, FilterOp, FilterValue};
+
+        let json = serde_json::json!({
+            "items": [
+                { "name": "a", "datum": { "type": "inline" } },
+                { "name": "b" },
+                { "name": "c", "datum": null }
+            ]
+        });
+
+        let filter = FilterExpr {
+            field: "datum".to_string(),
+            op: FilterOp::Eq,
+            value: FilterValue::Null,
+        };
+
+        let segments = vec![
+            PathSegment::Field("items".into()),
+            PathSegment::Filter(filter),
+        ];
+
+        let result = execute_path(&json, &segments).unwrap();
+        // Should return items "b" (missing datum) and "c" (datum is null)
+        match result {
+            QueryValue::Array(arr) => {
+                assert_eq!(arr.len(), 2, "Expected 2 items with datum == null");
+            }
+            _ => panic!("Expected array"),
+        }
+    }
+
+    #[test]
+    fn test_filter_integration_parsed() {
+        use crate::query::path::QueryPath;
+
+        let json = serde_json::json!({
+            "outputs": [
+                { "address": "addr1", "datum": { "type": "inline" } },
+                { "address": "addr2" },
+                { "address": "addr3" }
+            ]
+        });
+
+        // Parse like the real code does
+        let path = QueryPath::parse("outputs[datum != null]").unwrap();
+
+        eprintln!("Segments: {:?}", path.segments);
+        eprintln!("Has wildcard: {}", path.has_wildcard());
+        eprintln!("Has filter: {}", path.has_filter());
+
+        let result = execute_path(&json, &path.segments).unwrap();
+        eprintln!("Result: {:?}", result);
+
+        match result {
+            QueryValue::Array(arr) => {
+                eprintln!("Array length: {}", arr.len());
+                assert_eq!(arr.len(), 1, "Expected 1 item with datum != null");
+            }
+            _ => panic!("Expected array"),
+        }
+    }
+
+    #[test]
+    fn test_result_is_empty() {
+        assert!(QueryResult::Single(QueryValue::Null).is_empty());
+        assert!(QueryResult::Single(QueryValue::Array(vec![])).is_empty());
+        assert!(QueryResult::Multiple(vec![QueryValue::Null, QueryValue::Bool(false)]).is_empty());
+        assert!(!QueryResult::Single(QueryValue::Number(0.into())).is_empty());
+        assert!(!QueryResult::Multiple(vec![QueryValue::Null, QueryValue::Bool(true)]).is_empty());
+    }
+
+    #[test]
+    fn test_apply_stage_maps_over_multiple() {
+        let input = QueryResult::Multiple(vec![
+            QueryValue::from(serde_json::json!({ "coin": 1 })),
+            QueryValue::from(serde_json::json!({ "coin": 2 })),
+        ]);
+
+        match apply_stage(&input, ".coin").unwrap() {
+            QueryResult::Multiple(values) => assert_eq!(values.len(), 2),
+            other => panic!("Expected multiple, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_apply_stage_single_and_identity() {
+        let input = || QueryResult::Single(QueryValue::from(serde_json::json!([{ "a": 1 }])));
+
+        assert!(matches!(
+            apply_stage(&input(), "0.a").unwrap(),
+            QueryResult::Single(QueryValue::Number(_))
+        ));
+        assert!(matches!(
+            apply_stage(&input(), ".").unwrap(),
+            QueryResult::Single(QueryValue::Array(_))
+        ));
+        assert!(apply_stage(&input(), "0.missing").is_err());
+    }
+
+    #[test]
+    fn test_optional_segments() {
+        let json = serde_json::json!({
+            "body": { "fee": 1 },
+            "outputs": [{ "datum": 1 }, {}]
+        });
+
+        let path = QueryPath::parse("body.ttl?").unwrap();
+        assert!(matches!(
+            execute_path(&json, &path.segments).unwrap(),
+            QueryValue::Null
+        ));
+
+        // Missing optional fields yield null per wildcard element
+        let path = QueryPath::parse("outputs.*.datum?").unwrap();
+        match execute_parsed_path(&json, &path).unwrap() {
+            QueryResult::Multiple(values) => {
+                assert!(matches!(values[0], QueryValue::Number(_)));
+                assert!(matches!(values[1], QueryValue::Null));
+            }
+            other => panic!("Expected multiple, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_recursive_descent() {
+        let json = serde_json::json!({
+            "mint": [{ "policy_id": "aa" }],
+            "outputs": [{ "value": { "multi_assets": [{ "policy_id": "bb" }] } }]
+        });
+
+        let path = QueryPath::parse("..policy_id").unwrap();
+        match execute_parsed_path(&json, &path).unwrap() {
+            QueryResult::Multiple(values) => assert_eq!(values.len(), 2),
+            other => panic!("Expected multiple, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_plutus_datum_to_json() {
+        use cml_chain::plutus::{ConstrPlutusData, PlutusData};
+
+        // Create a simple Constr(0, [Int(42), Bytes("hello")])
+        let datum = PlutusData::new_constr_plutus_data(ConstrPlutusData::new(
+            0,
+            vec![
+                PlutusData::new_integer(cml_chain::utils::BigInteger::from(42)),
+                PlutusData::new_bytes(b"hello".to_vec()),
+            ],
+        ));
+
+        let result = decode_plutus_datum_to_json(&datum);
+        assert!(result.is_some());
+
+        let json = result.unwrap();
+        assert_eq!(json["constructor"], 0);
+        assert!(json["fields"].is_array());
+
+        let fields = json["fields"].as_array().unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0]["int"], 42);
+        assert_eq!(fields[1]["bytes"], "68656c6c6f"); // "hello" in hex
+    }
+
+    #[test]
+    fn test_cip25_v2_keys() {
+        use cml_chain::auxdata::TransactionMetadatum;
+        use cml_core::serialization::Deserialize;
+
+        // {h'<policy>': {h'4e4654': {"name": "NFT"}}, "version": 2}
+        let policy = "11".repeat(28);
+        let cbor = format!(
+            "a2581c{}a1434e4654a1646e616d65634e4654 6776657273696f6e02",
+            policy
+        )
+        .replace(' ', "");
+        let metadata = TransactionMetadatum::from_cbor_bytes(&hex::decode(cbor).unwrap()).unwrap();
+
+        let json = decode_metadata_for_label(721, &metadata, &QueryOptions::default());
+        assert_eq!(json["version"], 2);
+        assert_eq!(json["data"][&policy]["NFT"]["name"], "NFT");
+    }
+
+    #[test]
+    fn test_missing_signers() {
+        use cml_chain::crypto::Vkeywitness;
+        use cml_chain::transaction::{Transaction, TransactionBody, TransactionWitnessSet};
+        use cml_crypto::PrivateKey;
+
+        let (signer, absent) = (
+            PrivateKey::generate_ed25519(),
+            PrivateKey::generate_ed25519(),
+        );
+        let mut body = TransactionBody::new(Vec::new().into(), Vec::new(), 0);
+        body.required_signers =
+            Some(vec![signer.to_public().hash(), absent.to_public().hash()].into());
+        let mut witness_set = TransactionWitnessSet::new();
+        witness_set.vkeywitnesses =
+            Some(vec![Vkeywitness::new(signer.to_public(), signer.sign(b"tx"))].into());
+        let tx = DecodedTransaction {
+            original_bytes: Vec::new().into(),
+            hash: body.hash(),
+            tx: Transaction::new(body, witness_set, true, None),
+        };
+
+        let json = transaction_to_json(&tx, &QueryOptions::default()).unwrap();
+        assert_eq!(
+            json["witness_set"]["missing_signers"],
+            serde_json::json!([absent.to_public().hash().to_hex()])
+        );
+        assert_eq!(
+            json["witness_set"]["vkey_hashes"],
+            serde_json::json!([signer.to_public().hash().to_hex()])
+        );
+    }
+
+    #[test]
+    fn test_asset_name_to_json() {
+        let json = JsonValue::Object(asset_name_to_json(b"NIGHT", AssetNameFormat::Auto));
+        assert_eq!(json["name"], "NIGHT");
+        assert_eq!(json["name_hex"], "4e49474854");
+        assert_eq!(json["name_utf8"], "NIGHT");
+
+        let json = JsonValue::Object(asset_name_to_json(&[0x00, 0xff], AssetNameFormat::Auto));
+        assert_eq!(json["name"], "00ff");
+        assert!(json.get("name_utf8").is_none());
+    }
+
+    #[test]
+    fn test_asset_fingerprint() {
+        // CIP-14 test vectors
+        let policy =
+            hex::decode("7eae28af2208be856f7a119668ae52a49b73725e326dc16579dcc373").unwrap();
+        assert_eq!(
+            asset_fingerprint(&policy, b""),
+            "asset1rjklcrnsdzqp65wjgrg55sy9723kw09mlgvlc3"
+        );
+        let policy =
+            hex::decode("1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209").unwrap();
+        assert_eq!(
+            asset_fingerprint(&policy, &hex::decode("504154415445").unwrap()),
+            "asset1hv4p5tv2a837mzqrst04d0dcptdjmluqvdx9k3"
+        );
+    }
+
+    #[test]
+    fn test_cip88_registration() {
+        use cml_chain::auxdata::TransactionMetadatum;
+        use cml_core::serialization::Deserialize;
+
+        // {0: 1, 1: {1: [0, policy, [h'010203']], 2: [25], 3: [0], 4: 12345}, 2: [[vkey, sig]]}
+        let cbor = format!(
+            "a30001 01a4 01830058 1c{} 8143010203 02811819 038100 04193039 02818258 20{}5840{}",
+            "aa".repeat(28),
+            "bb".repeat(32),
+            "cc".repeat(64)
+        )
+        .replace(' ', "");
+        let metadata = TransactionMetadatum::from_cbor_bytes(&hex::decode(cbor).unwrap()).unwrap();
+
+        let json = decode_metadata_for_label(867, &metadata, &QueryOptions::default());
+        assert_eq!(json["cip"], "CIP-88");
+        let data = &json["data"];
+        assert_eq!(data["version"], 1);
+        assert_eq!(data["scope"]["type"], "native_script");
+        assert_eq!(data["scope"]["policy_id"], "aa".repeat(28));
+        assert_eq!(data["scope"]["script"], "010203");
+        assert_eq!(data["feature_set"], serde_json::json!(["CIP-25"]));
+        assert_eq!(data["validation_method"]["type"], "ed25519_key_signature");
+        assert_eq!(data["nonce"], 12345);
+        assert_eq!(data["witnesses"][0]["vkey"], "bb".repeat(32));
+    }
+
+    #[test]
+    fn test_drep_certificate_anchor() {
+        use cml_chain::certs::{Certificate, Credential, Url};
+        use cml_chain::governance::Anchor;
+        use cml_crypto::{AnchorDocHash, Ed25519KeyHash};
+
+        let credential = Credential::new_pub_key(Ed25519KeyHash::from([0x01; 28]));
+        let anchor = Anchor::new(
+            Url::new("https://example.com/drep.json".to_string()).unwrap(),
+            AnchorDocHash::from([0xcd; 32]),
+        );
+        let json = certificate_to_json(
+            &Certificate::new_reg_drep_cert(credential.clone(), 500_000_000, Some(anchor)),
+            &QueryOptions::default(),
+        );
+        assert_eq!(json["anchor"]["url"], "https://example.com/drep.json");
+        assert_eq!(json["anchor"]["data_hash"], "cd".repeat(32));
+
+        let json = certificate_to_json(
+            &Certificate::new_update_drep_cert(credential, None),
+            &QueryOptions::default(),
+        );
+        assert!(json.get("anchor").is_none());
+    }
+
+    #[test]
+    fn test_committee_ids() {
+        use cml_chain::certs::{Certificate, Credential};
+        use cml_crypto::{Ed25519KeyHash, ScriptHash};
+
+        let cold = Credential::new_pub_key(Ed25519KeyHash::from([0x01; 28]));
+        let hot = Credential::new_script(ScriptHash::from([0x02; 28]));
+        let json = certificate_to_json(
+            &Certificate::new_auth_committee_hot_cert(cold, hot),
+            &QueryOptions::default(),
+        );
+        // Header bytes: 0x12 for a cold key, 0x03 for a hot script
+        assert_eq!(
+            json["committee_cold_id"],
+            encode_bech32("cc_cold", &[&[0x12][..], &[0x01; 28]].concat())
+        );
+        assert_eq!(
+            json["committee_hot_id"],
+            encode_bech32("cc_hot", &[&[0x03][..], &[0x02; 28]].concat())
+        );
+        assert!(
+            json["committee_cold_id"]
+                .as_str()
+                .unwrap()
+                .starts_with("cc_cold1zg")
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Query expressions beyond plain paths.
+
+use crate::error::{Error, Result};
+use crate::query::path::split_top_level;
+
+/// A field of an object construction: output key and the query producing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectField {
+    /// Key in the constructed object.
+    pub key: String,
+    /// Query evaluated against the stage input.
+    pub query: String,
+}
+
+/// Parse an object construction such as `{fee: fee, outs: outputs | length}`.
+///
+/// Returns `None` if the stage is not wrapped in braces. A bare key
+/// (`{fee, hash}`) uses itself as the query; keys may be quoted.
+pub fn parse_object(stage: &str) -> Result<Option<Vec<ObjectField>>> {
+    let stage = stage.trim();
+    let Some(inner) = stage.strip_prefix('{').and_then(|s| s.strip_suffix('}')) else {
+        return Ok(None);
+    };
+
+    if inner.trim().is_empty() {
+        return Ok(Some(Vec::new()));
+    }
+
+    split_top_level(inner, ",")
+        .into_iter()
+        .map(parse_field)
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
+}
+
+/// Parse a single `key: query` (or bare `key`) entry.
+fn parse_field(entry: &str) -> Result<ObjectField> {
+    if entry.is_empty() {
+        return Err(Error::InvalidQuery(
+            "Empty field in object construction".to_string(),
+        ));
+    }
+
+    // Quoted keys may contain ':' or spaces
+    let (key, rest) = match entry.chars().next() {
+        Some(q @ ('"' | '\'')) => {
+            let close = entry[1..].find(q).ok_or_else(|| {
+                Error::InvalidQuery(format!("Unclosed quote in object key: {}", entry))
+            })?;
+            (&entry[1..close + 1], entry[close + 2..].trim_start())
+        }
+        _ => match entry.find(':') {
+            Some(pos) => (entry[..pos].trim(), &entry[pos..]),
+            None => (entry, ""),
+        },
+    };
+
+    if key.is_empty() {
+        return Err(Error::InvalidQuery(format!(
+            "Missing key in object construction: '{}'",
+            entry
+        )));
+    }
+
+    let query = match rest.strip_prefix(':') {
+        Some(query) if query.trim().is_empty() => {
+            return Err(Error::InvalidQuery(format!(
+                "Missing value for key '{}'",
+                key
+            )));
+        }
+        Some(query) => query.trim().to_string(),
+        None if rest.is_empty() => key.to_string(),
+        None => {
+            return Err(Error::InvalidQuery(format!(
+                "Expected ':' after key '{}'",
+                key
+            )));
+        }
+    };
+
+    Ok(ObjectField {
+        key: key.to_string(),
+        query,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(key: &str, query: &str) -> ObjectField {
+        ObjectField {
+            key: key.to_string(),
+            query: query.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_object() {
+        let fields = parse_object("{fee: fee, outs: outputs | length, hash}")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                field("fee", "fee"),
+                field("outs", "outputs | length"),
+                field("hash", "hash"),
+            ]
+        );
+
+        // Commas and colons inside filters, slices and nested objects
+        let fields = parse_object(
+            "{\"first two\": outputs.0:2, big: outputs[value.coin > 1], n: {a: fee, b: hash}}",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(fields[0], field("first two", "outputs.0:2"));
+        assert_eq!(fields[1], field("big", "outputs[value.coin > 1]"));
+        assert_eq!(fields[2], field("n", "{a: fee, b: hash}"));
+    }
+
+    #[test]
+    fn test_parse_object_errors() {
+        assert_eq!(parse_object("fee").unwrap(), None);
+        assert_eq!(parse_object("{}").unwrap(), Some(vec![]));
+        assert!(parse_object("{fee:}").is_err());
+        assert!(parse_object("{fee: fee,}").is_err());
+        assert!(parse_object("{\"fee\" fee}").is_err());
+    }
+}
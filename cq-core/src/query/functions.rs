@@ -0,0 +1,301 @@
+//! Built-in query functions applied with the `|` operator.
+
+use crate::error::{Error, Result};
+use crate::query::engine::{QueryResult, QueryValue};
+use crate::query::transform::unique_result;
+
+/// A built-in function that can follow a path in a pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    /// Sum of numeric values.
+    Sum,
+    /// Number of values.
+    Count,
+    /// Smallest numeric value.
+    Min,
+    /// Largest numeric value.
+    Max,
+    /// Arithmetic mean of numeric values.
+    Avg,
+    /// Length of an array, object or string.
+    Length,
+    /// Keys of an object (or indices of an array).
+    Keys,
+    /// Values with duplicates removed, in first-seen order.
+    Unique,
+}
+
+impl Function {
+    /// Look up a function by name.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "sum" => Some(Function::Sum),
+            "count" => Some(Function::Count),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "avg" => Some(Function::Avg),
+            "length" => Some(Function::Length),
+            "keys" => Some(Function::Keys),
+            "unique" => Some(Function::Unique),
+            _ => None,
+        }
+    }
+
+    /// Function name as written in queries.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Function::Sum => "sum",
+            Function::Count => "count",
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Avg => "avg",
+            Function::Length => "length",
+            Function::Keys => "keys",
+            Function::Unique => "unique",
+        }
+    }
+
+    /// Check if the function consumes all values rather than mapping over them.
+    fn is_aggregate(&self) -> bool {
+        !matches!(self, Function::Length | Function::Keys)
+    }
+
+    /// Apply the function to a query result.
+    ///
+    /// Aggregates consume every value of a wildcard result, or the elements
+    /// of a single array value. Other functions map over each value.
+    pub fn apply(&self, input: QueryResult) -> Result<QueryResult> {
+        if *self == Function::Unique {
+            return Ok(unique_result(input));
+        }
+
+        if !self.is_aggregate() {
+            return match input {
+                QueryResult::Multiple(values) => Ok(QueryResult::Multiple(
+                    values
+                        .iter()
+                        .map(|v| self.apply_value(v))
+                        .collect::<Result<_>>()?,
+                )),
+                QueryResult::Single(value) => Ok(QueryResult::Single(self.apply_value(&value)?)),
+                QueryResult::FullTransaction(json) => Ok(QueryResult::Single(
+                    self.apply_value(&QueryValue::from(json))?,
+                )),
+            };
+        }
+
+        let values = aggregate_input(input);
+
+        let result = match self {
+            Function::Count => QueryValue::Number(values.len().into()),
+            Function::Sum => {
+                let numbers = self.numbers(&values)?;
+                number_value(numbers.iter().copied().sum::<Number>())
+            }
+            Function::Min => {
+                let numbers = self.numbers(&values)?;
+                numbers
+                    .into_iter()
+                    .reduce(|a, b| if b.as_f64() < a.as_f64() { b } else { a })
+                    .map_or(QueryValue::Null, number_value)
+            }
+            Function::Max => {
+                let numbers = self.numbers(&values)?;
+                numbers
+                    .into_iter()
+                    .reduce(|a, b| if b.as_f64() > a.as_f64() { b } else { a })
+                    .map_or(QueryValue::Null, number_value)
+            }
+            Function::Avg => {
+                let numbers = self.numbers(&values)?;
+                if numbers.is_empty() {
+                    QueryValue::Null
+                } else {
+                    let total: f64 = numbers.iter().map(Number::as_f64).sum();
+                    number_value(Number::Float(total / numbers.len() as f64))
+                }
+            }
+            Function::Length | Function::Keys | Function::Unique => {
+                unreachable!("not an aggregate")
+            }
+        };
+
+        Ok(QueryResult::Single(result))
+    }
+
+    /// Apply a per-value function.
+    fn apply_value(&self, value: &QueryValue) -> Result<QueryValue> {
+        match (self, value) {
+            (Function::Length, QueryValue::Null) => Ok(QueryValue::Number(0.into())),
+            (Function::Length, QueryValue::Array(arr)) => Ok(QueryValue::Number(arr.len().into())),
+            (Function::Length, QueryValue::Object(map)) => Ok(QueryValue::Number(map.len().into())),
+            (Function::Length, QueryValue::String(s)) => {
+                Ok(QueryValue::Number(s.chars().count().into()))
+            }
+            (Function::Keys, QueryValue::Object(map)) => Ok(QueryValue::Array(
+                map.keys().cloned().map(QueryValue::String).collect(),
+            )),
+            (Function::Keys, QueryValue::Array(arr)) => Ok(QueryValue::Array(
+                (0..arr.len())
+                    .map(|i| QueryValue::Number(i.into()))
+                    .collect(),
+            )),
+            _ => Err(Error::InvalidQuery(format!(
+                "{}: not defined for {}",
+                self.name(),
+                type_name(value)
+            ))),
+        }
+    }
+
+    /// Extract numbers from values, rejecting anything non-numeric.
+    fn numbers(&self, values: &[QueryValue]) -> Result<Vec<Number>> {
+        values
+            .iter()
+            .map(|v| match v {
+                QueryValue::Number(n) => Ok(Number::from_json(n)),
+                _ => Err(Error::InvalidQuery(format!(
+                    "{}: expected numbers, found {}",
+                    self.name(),
+                    type_name(v)
+                ))),
+            })
+            .collect()
+    }
+}
+
+/// Flatten a query result into the values an aggregate operates on.
+fn aggregate_input(input: QueryResult) -> Vec<QueryValue> {
+    match input {
+        QueryResult::Multiple(values) => values,
+        QueryResult::Single(QueryValue::Array(values)) => values,
+        QueryResult::Single(value) => vec![value],
+        QueryResult::FullTransaction(json) => vec![QueryValue::from(json)],
+    }
+}
+
+/// Human-readable type name for error messages.
+fn type_name(value: &QueryValue) -> &'static str {
+    match value {
+        QueryValue::Null => "null",
+        QueryValue::Bool(_) => "boolean",
+        QueryValue::Number(_) => "number",
+        QueryValue::String(_) => "string",
+        QueryValue::Array(_) => "array",
+        QueryValue::Object(_) => "object",
+    }
+}
+
+/// Numeric accumulator keeping integers exact where possible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl Number {
+    fn from_json(n: &serde_json::Number) -> Self {
+        if let Some(u) = n.as_u64() {
+            Number::Int(u as i128)
+        } else if let Some(i) = n.as_i64() {
+            Number::Int(i as i128)
+        } else {
+            Number::Float(n.as_f64().unwrap_or(0.0))
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        match self {
+            Number::Int(i) => *i as f64,
+            Number::Float(f) => *f,
+        }
+    }
+}
+
+impl std::iter::Sum for Number {
+    fn sum<I: Iterator<Item = Number>>(iter: I) -> Self {
+        iter.fold(Number::Int(0), |acc, n| match (acc, n) {
+            (Number::Int(a), Number::Int(b)) => Number::Int(a + b),
+            (a, b) => Number::Float(a.as_f64() + b.as_f64()),
+        })
+    }
+}
+
+/// Convert a number back into a query value.
+fn number_value(n: Number) -> QueryValue {
+    let json = match n {
+        Number::Int(i) => u64::try_from(i)
+            .map(serde_json::Number::from)
+            .or_else(|_| i64::try_from(i).map(serde_json::Number::from))
+            .ok()
+            .or_else(|| serde_json::Number::from_f64(i as f64)),
+        Number::Float(f) => serde_json::Number::from_f64(f),
+    };
+    json.map_or(QueryValue::Null, QueryValue::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(values: &[i64]) -> QueryResult {
+        QueryResult::Multiple(
+            values
+                .iter()
+                .map(|n| QueryValue::Number((*n).into()))
+                .collect(),
+        )
+    }
+
+    fn single_json(result: QueryResult) -> serde_json::Value {
+        match result {
+            QueryResult::Single(v) => v.into(),
+            other => panic!("expected single value, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let input = || numbers(&[3, -1, 10]);
+        assert_eq!(single_json(Function::Sum.apply(input()).unwrap()), 12);
+        assert_eq!(single_json(Function::Count.apply(input()).unwrap()), 3);
+        assert_eq!(single_json(Function::Min.apply(input()).unwrap()), -1);
+        assert_eq!(single_json(Function::Max.apply(input()).unwrap()), 10);
+        assert_eq!(single_json(Function::Avg.apply(input()).unwrap()), 4.0);
+    }
+
+    #[test]
+    fn test_length_and_keys() {
+        let object = QueryValue::from(serde_json::json!({ "fee": 1, "inputs": [] }));
+        let keys = Function::Keys.apply(QueryResult::Single(object.clone()));
+        assert_eq!(
+            single_json(keys.unwrap()),
+            serde_json::json!(["fee", "inputs"])
+        );
+        assert_eq!(
+            single_json(Function::Length.apply(QueryResult::Single(object)).unwrap()),
+            2
+        );
+
+        // Per-value functions map over wildcard results
+        let strings = QueryResult::Multiple(vec![
+            QueryValue::String("abc".into()),
+            QueryValue::String("".into()),
+        ]);
+        match Function::Length.apply(strings).unwrap() {
+            QueryResult::Multiple(values) => assert_eq!(values.len(), 2),
+            other => panic!("expected multiple values, got {:?}", other),
+        }
+
+        assert!(Function::Keys.apply(numbers(&[1])).is_err());
+    }
+
+    #[test]
+    fn test_aggregates_empty_and_invalid() {
+        assert_eq!(single_json(Function::Sum.apply(numbers(&[])).unwrap()), 0);
+        assert!(single_json(Function::Max.apply(numbers(&[])).unwrap()).is_null());
+
+        let strings = QueryResult::Multiple(vec![QueryValue::String("a".into())]);
+        assert!(Function::Sum.apply(strings).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+//! Conway governance: proposals, votes and the anchors they carry.
+//!
+//! An anchor points at off-chain metadata (a DRep's profile, a vote's
+//! rationale) by URL, with the blake2b-256 hash of the document.
+
+use crate::decode::param_update_to_json;
+use crate::query::engine::credential_to_json;
+use cml_chain::address::RewardAddress;
+use cml_chain::governance::{
+    Anchor, GovAction, GovActionId, ProposalProcedure, Vote, Voter, VotingProcedures,
+};
+use cml_crypto::RawBytesEncoding;
+use serde_json::Value as JsonValue;
+
+/// Convert an anchor to `{"url", "data_hash"}`.
+pub(crate) fn anchor_to_json(anchor: &Anchor) -> JsonValue {
+    serde_json::json!({
+        "url": anchor.anchor_url.get(),
+        "data_hash": hex::encode(anchor.anchor_doc_hash.to_raw_bytes())
+    })
+}
+
+/// The voter's key or script hash.
+pub(crate) fn voter_hash(voter: &Voter) -> Vec<u8> {
+    voter
+        .key_hash()
+        .map(|hash| hash.to_raw_bytes().to_vec())
+        .or_else(|| voter.script_hash().map(|hash| hash.to_raw_bytes().to_vec()))
+        .unwrap_or_default()
+}
+
+/// Convert a voter to `{"role", "type", "hash"}`.
+pub(crate) fn voter_to_json(voter: &Voter) -> JsonValue {
+    let role = match voter {
+        Voter::ConstitutionalCommitteeHotKeyHash { .. }
+        | Voter::ConstitutionalCommitteeHotScriptHash { .. } => "committee",
+        Voter::DRepKeyHash { .. } | Voter::DRepScriptHash { .. } => "drep",
+        Voter::StakingPoolKeyHash { .. } => "pool",
+    };
+    serde_json::json!({
+        "role": role,
+        "type": if voter.script_hash().is_some() { "script" } else { "pubkey" },
+        "hash": hex::encode(voter_hash(voter))
+    })
+}
+
+/// Convert voting procedures to one entry per voter, each with its votes
+/// on governance actions (`"txid#index"`) and their anchors.
+pub(crate) fn voting_procedures_to_json(procedures: &VotingProcedures) -> JsonValue {
+    let voters: Vec<JsonValue> = procedures
+        .iter()
+        .map(|(voter, votes)| {
+            let votes: Vec<JsonValue> = votes
+                .iter()
+                .map(|(action_id, procedure)| {
+                    let vote = match procedure.vote {
+                        Vote::Yes => "yes",
+                        Vote::No => "no",
+                        Vote::Abstain => "abstain",
+                    };
+                    let mut json = serde_json::json!({
+                        "action_id": action_id_to_string(action_id),
+                        "vote": vote
+                    });
+                    if let Some(anchor) = &procedure.anchor {
+                        json["anchor"] = anchor_to_json(anchor);
+                    }
+                    json
+                })
+                .collect();
+            serde_json::json!({ "voter": voter_to_json(voter), "votes": votes })
+        })
+        .collect();
+    JsonValue::Array(voters)
+}
+
+/// A governance action id as `"txid#index"`.
+fn action_id_to_string(action_id: &GovActionId) -> String {
+    format!(
+        "{}#{}",
+        action_id.transaction_id.to_hex(),
+        action_id.gov_action_index
+    )
+}
+
+fn reward_address_to_string(account: &RewardAddress) -> String {
+    let address = account.clone().to_address();
+    address
+        .to_bech32(None)
+        .unwrap_or_else(|_| hex::encode(address.to_raw_bytes()))
+}
+
+/// Convert a proposal to its deposit, return address, anchor and decoded
+/// action.
+pub(crate) fn proposal_to_json(proposal: &ProposalProcedure) -> JsonValue {
+    serde_json::json!({
+        "deposit": proposal.deposit,
+        "reward_account": reward_address_to_string(&proposal.reward_account),
+        "anchor": anchor_to_json(&proposal.anchor),
+        "action": gov_action_to_json(&proposal.gov_action)
+    })
+}
+
+/// Convert a governance action to JSON with its `type` and payload. Actions
+/// that follow an earlier one of their kind name it as `previous_action`.
+fn gov_action_to_json(action: &GovAction) -> JsonValue {
+    let (mut json, previous) = match action {
+        GovAction::ParameterChangeAction(action) => {
+            let mut json = serde_json::json!({
+                "type": "parameter_change",
+                "update": param_update_to_json(&action.update)
+            });
+            if let Some(hash) = &action.policy_hash {
+                json["policy_hash"] = hash.to_hex().into();
+            }
+            (json, action.action_id.as_ref())
+        }
+        GovAction::HardForkInitiationAction(action) => (
+            serde_json::json!({
+                "type": "hard_fork_initiation",
+                "version": { "major": action.version.major, "minor": action.version.minor }
+            }),
+            action.action_id.as_ref(),
+        ),
+        GovAction::TreasuryWithdrawalsAction(action) => {
+            let withdrawals: Vec<JsonValue> = action
+                .withdrawal
+                .iter()
+                .map(|(account, amount)| {
+                    serde_json::json!({
+                        "reward_address": reward_address_to_string(account),
+                        "amount": amount
+                    })
+                })
+                .collect();
+            let mut json = serde_json::json!({
+                "type": "treasury_withdrawals",
+                "withdrawals": withdrawals
+            });
+            if let Some(hash) = &action.policy_hash {
+                json["policy_hash"] = hash.to_hex().into();
+            }
+            (json, None)
+        }
+        GovAction::NoConfidence(action) => (
+            serde_json::json!({ "type": "no_confidence" }),
+            action.action_id.as_ref(),
+        ),
+        GovAction::UpdateCommittee(action) => {
+            let remove: Vec<JsonValue> = action
+                .cold_credentials
+                .iter()
+                .map(credential_to_json)
+                .collect();
+            let add: Vec<JsonValue> = action
+                .credentials
+                .iter()
+                .map(|(credential, epoch)| {
+                    serde_json::json!({
+                        "credential": credential_to_json(credential),
+                        "expires_epoch": epoch
+                    })
+                })
+                .collect();
+            (
+                serde_json::json!({
+                    "type": "update_committee",
+                    "remove": remove,
+                    "add": add,
+                    "threshold": format!(
+                        "{}/{}",
+                        action.unit_interval.start, action.unit_interval.end
+                    )
+                }),
+                action.action_id.as_ref(),
+            )
+        }
+        GovAction::NewConstitution(action) => {
+            let mut constitution =
+                serde_json::json!({ "anchor": anchor_to_json(&action.constitution.anchor) });
+            if let Some(hash) = &action.constitution.script_hash {
+                constitution["script_hash"] = hash.to_hex().into();
+            }
+            (
+                serde_json::json!({
+                    "type": "new_constitution",
+                    "constitution": constitution
+                }),
+                action.action_id.as_ref(),
+            )
+        }
+        GovAction::InfoAction { .. } => (serde_json::json!({ "type": "info" }), None),
+    };
+    if let Some(previous) = previous {
+        json["previous_action"] = action_id_to_string(previous).into();
+    }
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cml_chain::certs::Url;
+    use cml_chain::governance::{GovActionId, VotingProcedure};
+    use cml_crypto::{AnchorDocHash, Ed25519KeyHash, TransactionHash};
+
+    #[test]
+    fn test_voting_procedures_to_json() {
+        let anchor = Anchor::new(
+            Url::new("https://example.com/rationale.json".to_string()).unwrap(),
+            AnchorDocHash::from([0xab; 32]),
+        );
+        let action = GovActionId::new(TransactionHash::from([0x11; 32]), 2);
+        let mut votes = cml_core::ordered_hash_map::OrderedHashMap::new();
+        votes.insert(action, VotingProcedure::new(Vote::Yes, Some(anchor)));
+        let mut procedures = VotingProcedures::new();
+        procedures.insert(
+            Voter::new_d_rep_key_hash(Ed25519KeyHash::from([0x22; 28])),
+            votes,
+        );
+
+        let json = voting_procedures_to_json(&procedures);
+        assert_eq!(json[0]["voter"]["role"], "drep");
+        assert_eq!(json[0]["voter"]["type"], "pubkey");
+        let vote = &json[0]["votes"][0];
+        assert_eq!(vote["action_id"], format!("{}#2", "11".repeat(32)));
+        assert_eq!(vote["vote"], "yes");
+        assert_eq!(vote["anchor"]["url"], "https://example.com/rationale.json");
+        assert_eq!(vote["anchor"]["data_hash"], "ab".repeat(32));
+    }
+
+    #[test]
+    fn test_proposal_to_json() {
+        use cml_chain::block::ProtocolVersion;
+        use cml_chain::certs::Credential;
+
+        let account =
+            RewardAddress::new(1, Credential::new_pub_key(Ed25519KeyHash::from([0x33; 28])));
+        let anchor = Anchor::new(
+            Url::new("ipfs://proposal".to_string()).unwrap(),
+            AnchorDocHash::from([0x44; 32]),
+        );
+        let previous = GovActionId::new(TransactionHash::from([0x55; 32]), 0);
+        let action =
+            GovAction::new_hard_fork_initiation_action(Some(previous), ProtocolVersion::new(10, 0));
+        let proposal = ProposalProcedure::new(100_000_000_000, account, action, anchor);
+
+        let json = proposal_to_json(&proposal);
+        assert_eq!(json["deposit"], 100_000_000_000u64);
+        assert!(
+            json["reward_account"]
+                .as_str()
+                .unwrap()
+                .starts_with("stake1")
+        );
+        assert_eq!(json["anchor"]["url"], "ipfs://proposal");
+        assert_eq!(
+            json["action"],
+            serde_json::json!({
+                "type": "hard_fork_initiation",
+                "version": { "major": 10, "minor": 0 },
+                "previous_action": format!("{}#0", "55".repeat(32))
+            })
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Bech32 forms of the identifiers in transaction JSON (`--script-ids`,
+//! `--bech32-ids`).
+//!
+//! The JSON carries hashes as hex. Explorers and governance tooling key on
+//! bech32 instead: CIP-5 for pools, scripts and key hashes, CIP-129 for
+//! DReps and committee members, whose header byte says key or script.
+//! Policy IDs stay hex, as every tool shows them.
+
+use crate::decode::encode_bech32;
+use serde_json::{Map, Value as JsonValue};
+
+/// Add `script_id` (`script1...`) next to every script credential hash
+/// and policy ID.
+pub(crate) fn add_script_ids(json: &mut JsonValue) {
+    match json {
+        JsonValue::Object(map) => {
+            let hash = match (map.get("type"), map.get("hash"), map.get("policy_id")) {
+                (_, _, Some(JsonValue::String(policy_id))) => Some(policy_id),
+                (Some(kind), Some(JsonValue::String(hash)), _) if kind == "script" => Some(hash),
+                _ => None,
+            };
+            if let Some(bytes) = hash.and_then(|hash| hex::decode(hash).ok()) {
+                let id = encode_bech32("script", &bytes);
+                map.insert("script_id".to_string(), id.into());
+            }
+            map.values_mut().for_each(add_script_ids);
+        }
+        JsonValue::Array(items) => items.iter_mut().for_each(add_script_ids),
+        _ => {}
+    }
+}
+
+/// Replace hex pool, DRep, committee, script, key and VRF key hashes
+/// with their bech32 forms.
+pub(crate) fn use_bech32_ids(json: &mut JsonValue) {
+    bech32_ids_in(json, None);
+}
+
+fn bech32_ids_in(json: &mut JsonValue, key: Option<&str>) {
+    match json {
+        JsonValue::Object(map) => {
+            // Certificates carry the bech32 forms next to the hex
+            for (field, id) in [
+                ("pool_keyhash", "pool_id"),
+                ("drep_credential", "drep_id"),
+                ("committee_cold_credential", "committee_cold_id"),
+                ("committee_hot_credential", "committee_hot_id"),
+            ] {
+                if let Some(JsonValue::String(id)) = map.get(id).cloned() {
+                    match map.get_mut(field) {
+                        Some(JsonValue::Object(credential)) => {
+                            credential.insert("hash".to_string(), id.into());
+                        }
+                        Some(value) => *value = id.into(),
+                        None => {}
+                    }
+                }
+            }
+            if let Some(JsonValue::String(hash)) = map.get("vrf_keyhash")
+                && let Some(id) = bech32("vrf_vkh", None, hash)
+            {
+                map.insert("vrf_keyhash".to_string(), id.into());
+            }
+            if let Some(id) = credential_id(map, key) {
+                map.insert("hash".to_string(), id.into());
+            }
+            for (field, value) in map.iter_mut() {
+                bech32_ids_in(value, Some(field));
+            }
+        }
+        JsonValue::Array(items) => match key {
+            // Required signers are payment key hashes
+            Some("required_signers" | "missing_signers") => {
+                for item in items.iter_mut() {
+                    if let Some(id) = item
+                        .as_str()
+                        .and_then(|hash| bech32("addr_vkh", None, hash))
+                    {
+                        *item = id.into();
+                    }
+                }
+            }
+            _ => items.iter_mut().for_each(|item| bech32_ids_in(item, key)),
+        },
+        _ => {}
+    }
+}
+
+/// The bech32 form of a `{"type", "hash"}` credential, by where it is.
+fn credential_id(map: &Map<String, JsonValue>, key: Option<&str>) -> Option<String> {
+    let hash = map.get("hash")?.as_str()?;
+    let script = match map.get("type")?.as_str()? {
+        "script" => true,
+        "pubkey" | "key" => false,
+        _ => return None,
+    };
+    // CIP-129 header: the role in the high nibble, key 2 or script 3 below
+    let header = |role: u8| Some(role << 4 | if script { 3 } else { 2 });
+    match (key, map.get("role").and_then(|v| v.as_str())) {
+        (Some("drep"), _) | (Some("voter"), Some("drep")) => bech32("drep", header(2), hash),
+        (Some("voter"), Some("committee")) => bech32("cc_hot", header(0), hash),
+        (Some("voter"), Some("pool")) => bech32("pool", None, hash),
+        _ if script => bech32("script", None, hash),
+        (Some("stake_credential"), _) => bech32("stake_vkh", None, hash),
+        (Some("payment_credential"), _) => bech32("addr_vkh", None, hash),
+        _ => None,
+    }
+}
+
+/// Hex bytes as bech32, after a header byte if any; `None` if not hex.
+fn bech32(prefix: &str, header: Option<u8>, hash: &str) -> Option<String> {
+    let bytes = hex::decode(hash).ok()?;
+    let bytes: Vec<u8> = header.into_iter().chain(bytes).collect();
+    Some(encode_bech32(prefix, &bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bech32_ids() {
+        let hash = "11".repeat(28);
+        let mut json = serde_json::json!({
+            "certs": [{
+                "pool_keyhash": hash,
+                "pool_id": "pool1xyz",
+                "stake_credential": { "type": "pubkey", "hash": hash },
+                "drep": { "type": "script", "hash": hash }
+            }],
+            "required_signers": [hash],
+            "votes": [{ "voter": { "role": "committee", "type": "pubkey", "hash": hash } }],
+            "mint": [{ "policy_id": hash }]
+        });
+        use_bech32_ids(&mut json);
+
+        let bytes = hex::decode(&hash).unwrap();
+        let with_header = |header: u8| [&[header][..], &bytes].concat();
+        let cert = &json["certs"][0];
+        assert_eq!(cert["pool_keyhash"], "pool1xyz");
+        assert_eq!(
+            cert["stake_credential"]["hash"],
+            encode_bech32("stake_vkh", &bytes)
+        );
+        assert_eq!(
+            cert["drep"]["hash"],
+            encode_bech32("drep", &with_header(0x23))
+        );
+        assert_eq!(
+            json["required_signers"][0],
+            encode_bech32("addr_vkh", &bytes)
+        );
+        assert_eq!(
+            json["votes"][0]["voter"]["hash"],
+            encode_bech32("cc_hot", &with_header(0x02))
+        );
+        assert_eq!(json["mint"][0]["policy_id"], hash);
+    }
+}
@@ -0,0 +1,161 @@
+//! Locate the original CBOR bytes of a query path.
+
+use crate::decode::{
+    CborSpan, DecodedTransaction, array_items, item_span, map_entries, map_field_names, read_uint,
+    skip_tags,
+};
+use crate::error::{Error, Result};
+use crate::query::{PathSegment, QueryPath, expand_shortcut, split_alternatives, split_pipeline};
+
+/// Find the spans of the original CBOR encoding selected by a query path.
+///
+/// Supports field names, indices, wildcards and slices that follow the
+/// transaction's CBOR structure (e.g. `outputs.0`, `body.fee`,
+/// `witness_set.vkeywitnesses.*`). Computed fields, filters and pipelines
+/// have no single encoding and are rejected.
+pub fn locate_cbor(tx: &DecodedTransaction, query: &str) -> Result<Vec<CborSpan>> {
+    let bytes = &tx.original_bytes;
+    let expanded = expand_shortcut(query);
+    let is_plain_path = split_pipeline(query)?.len() == 1
+        && split_alternatives(query)?.len() == 1
+        && !expanded.starts_with("__")
+        && !query.trim_start().starts_with('{');
+    if !is_plain_path {
+        return Err(unsupported(query));
+    }
+    let path = QueryPath::parse(&expanded)?;
+
+    // (position in the path, span) pairs still being resolved
+    let mut current: Vec<(Vec<String>, CborSpan)> = vec![(Vec::new(), item_span(bytes, 0)?)];
+
+    for segment in &path.segments {
+        let mut next = Vec::new();
+        for (names, span) in current {
+            let children = match segment {
+                PathSegment::Field(name) => vec![field(bytes, &names, span, name)?],
+                PathSegment::Index(idx) => {
+                    let items = elements(bytes, &names, span)?;
+                    let item = items
+                        .get(*idx)
+                        .copied()
+                        .ok_or(Error::IndexOutOfBounds(*idx))?;
+                    vec![(idx.to_string(), item)]
+                }
+                PathSegment::Wildcard => enumerate(elements(bytes, &names, span)?),
+                PathSegment::Slice { start, end } => {
+                    let items = elements(bytes, &names, span)?;
+                    let end = end.unwrap_or(items.len()).min(items.len());
+                    let start = start.unwrap_or(0).min(end);
+                    enumerate(items)
+                        .into_iter()
+                        .skip(start)
+                        .take(end - start)
+                        .collect()
+                }
+                _ => return Err(unsupported(query)),
+            };
+            for (name, child) in children {
+                let mut path = names.clone();
+                path.push(name);
+                next.push((path, child));
+            }
+        }
+        current = next;
+    }
+
+    Ok(current.into_iter().map(|(_, span)| span).collect())
+}
+
+/// Resolve a named field within the item at `span`.
+fn field(bytes: &[u8], names: &[String], span: CborSpan, name: &str) -> Result<(String, CborSpan)> {
+    let path: Vec<&str> = names.iter().map(String::as_str).collect();
+    let not_found = || Error::FieldNotFound(join(&path, name));
+
+    // The transaction itself is an array of named sections
+    if path.is_empty() {
+        let parts = array_items(bytes, span.start)?;
+        let idx = match (name, parts.len()) {
+            ("body", _) => 0,
+            ("witness_set", _) => 1,
+            ("is_valid", 4) => 2,
+            ("auxiliary_data", 3) => 2,
+            ("auxiliary_data", 4) => 3,
+            _ => return Err(not_found()),
+        };
+        return Ok((name.to_string(), parts[idx]));
+    }
+
+    // Values are a bare coin or [coin, multiassets]
+    if path.last() == Some(&"value") && matches!(name, "coin" | "multi_assets") {
+        let content = skip_tags(bytes, span.start)?;
+        return match (array_items(bytes, content), name) {
+            (Ok(items), "coin") => items.first().copied(),
+            (Ok(items), _) => items.get(1).copied(),
+            (Err(_), "coin") => Some(span),
+            (Err(_), _) => None,
+        }
+        .map(|item| (name.to_string(), item))
+        .ok_or_else(not_found);
+    }
+
+    let names_fn = map_field_names(&path);
+    let key_matches =
+        |key: u64| names_fn.and_then(|f| f(key)) == Some(name) || key.to_string() == name;
+
+    let content = skip_tags(bytes, span.start)?;
+    if let Ok(entries) = map_entries(bytes, content) {
+        return entries
+            .into_iter()
+            .find(|(key, _)| read_uint(bytes, key.start).is_ok_and(key_matches))
+            .map(|(_, value)| (name.to_string(), value))
+            .ok_or_else(not_found);
+    }
+
+    // Legacy outputs are arrays whose positions follow the output field names
+    if names_fn.is_some() {
+        if let Ok(items) = array_items(bytes, content) {
+            return items
+                .into_iter()
+                .enumerate()
+                .find(|(idx, _)| key_matches(*idx as u64))
+                .map(|(_, item)| (name.to_string(), item))
+                .ok_or_else(not_found);
+        }
+    }
+
+    Err(not_found())
+}
+
+/// Elements of the array at `span` (sets tagged 258 included).
+fn elements(bytes: &[u8], names: &[String], span: CborSpan) -> Result<Vec<CborSpan>> {
+    array_items(bytes, span.start).map_err(|_| {
+        Error::InvalidQuery(format!(
+            "'{}' is not an array in the CBOR encoding",
+            names.join(".")
+        ))
+    })
+}
+
+/// Pair each span with its index as a path name.
+fn enumerate(items: Vec<CborSpan>) -> Vec<(String, CborSpan)> {
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(idx, item)| (idx.to_string(), item))
+        .collect()
+}
+
+fn join(path: &[&str], name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path.join("."), name)
+    }
+}
+
+fn unsupported(query: &str) -> Error {
+    Error::InvalidQuery(format!(
+        "--cbor supports field, index, wildcard and slice paths: {}",
+        query
+    ))
+}
@@ -0,0 +1,39 @@
+//! Query engine module for dot-notation queries.
+
+mod complete;
+mod coverage;
+mod detect;
+mod engine;
+mod expr;
+mod functions;
+mod governance;
+mod identifiers;
+mod locate;
+mod options;
+mod path;
+mod paths;
+mod redeemers;
+mod registry;
+mod shortcuts;
+mod transform;
+
+pub use complete::complete_query;
+pub use detect::{InputKind, detect_input};
+pub use engine::{
+    QueryResult, QueryValue, decode_plutus_datum_to_json, execute_json_query, execute_query,
+    execute_query_on, transaction_to_json, utxo_set_to_json,
+};
+pub use expr::{ObjectField, parse_object};
+pub use functions::Function;
+pub use locate::locate_cbor;
+pub use options::{AssetNameFormat, LabelConfig, QueryOptions};
+pub use path::{
+    PathSegment, QueryPath, parse_literal, split_alternatives, split_pipeline, strip_comments,
+};
+pub use paths::leaf_paths;
+pub use registry::{TokenInfo, TokenRegistry, format_token_amount, parse_token_registry};
+pub use shortcuts::{expand_shortcut, shortcut_names};
+pub use transform::{
+    group_result, head_result, outpoints_result, sort_result, tail_result, to_address_result,
+    unique_result,
+};
@@ -0,0 +1,91 @@
+//! Options that change how transactions are converted to JSON.
+
+use crate::network::{NetworkName, SlotConfig, network_label};
+use crate::pparams::ProtocolParams;
+use crate::query::registry::{TokenInfo, TokenRegistry};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// How to present an application-specific metadata label.
+///
+/// In cq's config file:
+///
+/// ```toml
+/// [labels.1967]
+/// name = "Acme Audit Record"
+/// fields = { 0 = "version", 1 = "auditor", 2 = "report_hash" }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LabelConfig {
+    /// Name shown as the metadata's `standard`.
+    pub name: String,
+    /// Names for the keys of the label's top-level map (integer keys as strings).
+    pub fields: BTreeMap<String, String>,
+}
+
+/// How asset names are shown in an asset's `name`.
+///
+/// Asset JSON always carries the bytes as `name_hex`, and `name_utf8` when
+/// they are printable UTF-8.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AssetNameFormat {
+    /// Printable UTF-8 as text, anything else as hex.
+    #[default]
+    Auto,
+    /// Always hex.
+    Hex,
+    /// Always text, with invalid UTF-8 replaced.
+    Utf8,
+}
+
+/// Settings for converting and querying transactions.
+///
+/// The default decodes transactions as they are: no network, slot schedule,
+/// protocol parameters or token metadata, and hex identifiers.
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+    /// Annotations for application-specific metadata labels, taking
+    /// precedence over the built-in CIP standards.
+    pub labels: BTreeMap<u64, LabelConfig>,
+    /// How asset names are shown.
+    pub asset_names: AssetNameFormat,
+    /// Token metadata, adding a ticker, decimals and `formatted` amounts to
+    /// assets.
+    pub token_registry: TokenRegistry,
+    /// Protocol parameters to check execution units against.
+    pub protocol_params: Option<ProtocolParams>,
+    /// The network transactions are read for: names testnet addresses and
+    /// gives bare certificate credentials a network.
+    pub network: Option<NetworkName>,
+    /// Slot schedule for slot times and epochs, overriding the network's.
+    pub slot_config: Option<SlotConfig>,
+    /// Decompile witness set Plutus scripts, adding a `uplc` field with
+    /// their term counts and program.
+    pub decompile_scripts: bool,
+    /// Add the CIP-5 bech32 form (`script1...`) of script credentials and
+    /// policy IDs as `script_id`.
+    pub script_ids: bool,
+    /// Replace hex pool, DRep, committee, script and key hashes with their
+    /// bech32 forms (`pool1...`, `drep1...`, `script1...`, `stake_vkh1...`).
+    pub bech32_ids: bool,
+}
+
+impl QueryOptions {
+    /// The slot schedule to convert slots with: the custom one, or the
+    /// network's.
+    pub fn slot_config(&self) -> Option<SlotConfig> {
+        self.slot_config
+            .or_else(|| self.network.map(NetworkName::slot_config))
+    }
+
+    /// Label for a network id, naming the testnet if it is the network.
+    pub(crate) fn network_label(&self, network_id: u8) -> &'static str {
+        network_label(network_id, self.network)
+    }
+
+    /// Token metadata for the token with this subject.
+    pub(crate) fn token_info(&self, subject: &str) -> Option<&TokenInfo> {
+        self.token_registry.get(subject)
+    }
+}
@@ -0,0 +1,771 @@
+//! Query path parsing.
+
+use crate::error::{Error, Result};
+
+/// A segment in a query path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    /// Named field access (e.g., "body", "fee").
+    Field(String),
+    /// Array index access (e.g., "0", "1").
+    Index(usize),
+    /// Wildcard for all array elements (e.g., "*").
+    Wildcard,
+    /// Range of array elements (e.g., "0:3", "[1:]"); bounds are optional.
+    Slice {
+        start: Option<usize>,
+        end: Option<usize>,
+    },
+    /// Filter expression (e.g., "[value.coin > 1000000]").
+    Filter(FilterExpr),
+    /// Recursive descent to every field with this name (e.g., "..policy_id").
+    Recursive(String),
+    /// Field or index that yields null when missing (e.g., "ttl?", "0?").
+    Optional(Box<PathSegment>),
+}
+
+/// A filter expression for array filtering.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterExpr {
+    /// Field path to compare (dot-notation within the element).
+    pub field: String,
+    /// Comparison operator.
+    pub op: FilterOp,
+    /// Value to compare against.
+    pub value: FilterValue,
+}
+
+/// Filter comparison operators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterOp {
+    /// Greater than (>).
+    Gt,
+    /// Less than (<).
+    Lt,
+    /// Greater than or equal (>=).
+    Gte,
+    /// Less than or equal (<=).
+    Lte,
+    /// Equal (==).
+    Eq,
+    /// Not equal (!=).
+    Ne,
+    /// String contains (~).
+    Contains,
+    /// String starts with (^=).
+    StartsWith,
+    /// String ends with ($=).
+    EndsWith,
+    /// Case-insensitive string contains (~*).
+    ContainsIgnoreCase,
+    /// Case-insensitive string starts with (^=*).
+    StartsWithIgnoreCase,
+    /// Case-insensitive string ends with ($=*).
+    EndsWithIgnoreCase,
+    /// Membership in a list (in).
+    In,
+}
+
+/// Filter comparison value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    /// Numeric value.
+    Number(f64),
+    /// String value.
+    String(String),
+    /// Null (for existence checks).
+    Null,
+    /// List of values (for `in`).
+    List(Vec<FilterValue>),
+}
+
+/// Split a query into `|`-separated stages.
+///
+/// Pipes inside filter brackets or quoted strings are not treated as
+/// separators. Each stage is trimmed; empty stages are rejected.
+pub fn split_pipeline(query: &str) -> Result<Vec<&str>> {
+    let stages = split_top_level(query, "|");
+    if stages.len() > 1 && stages.iter().skip(1).any(|s| s.is_empty()) {
+        return Err(Error::InvalidQuery("Empty stage after '|'".to_string()));
+    }
+    Ok(stages)
+}
+
+/// Normalize a multi-line query: drop `#` comments and join lines.
+///
+/// A `#` inside a quoted string is kept.
+pub fn strip_comments(query: &str) -> String {
+    let mut lines = Vec::new();
+    for line in query.lines() {
+        let mut quote: Option<char> = None;
+        let mut end = line.len();
+        for (i, c) in line.char_indices() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') => quote = Some(c),
+                (None, '#') => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let line = line[..end].trim();
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines.join(" ")
+}
+
+/// Split a pipeline stage into `//`-separated alternatives.
+pub fn split_alternatives(stage: &str) -> Result<Vec<&str>> {
+    let alternatives = split_top_level(stage, "//");
+    if alternatives.len() > 1 && alternatives.iter().any(|s| s.is_empty()) {
+        return Err(Error::InvalidQuery(
+            "Missing expression around '//'".to_string(),
+        ));
+    }
+    Ok(alternatives)
+}
+
+/// Split on a separator outside of brackets and quoted strings, trimming parts.
+pub(crate) fn split_top_level<'a>(input: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    let mut skip_until = 0;
+
+    for (i, c) in input.char_indices() {
+        if i < skip_until {
+            continue;
+        }
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth = depth.saturating_sub(1),
+            (None, _) if depth == 0 && input[i..].starts_with(separator) => {
+                parts.push(input[start..i].trim());
+                start = i + separator.len();
+                skip_until = start;
+            }
+            _ => {}
+        }
+    }
+    parts.push(input[start..].trim());
+    parts
+}
+
+/// Parse a literal value (number, quoted string, `true`, `false` or `null`).
+pub fn parse_literal(s: &str) -> Option<serde_json::Value> {
+    let s = s.trim();
+    match s {
+        "null" => return Some(serde_json::Value::Null),
+        "true" => return Some(serde_json::Value::Bool(true)),
+        "false" => return Some(serde_json::Value::Bool(false)),
+        _ => {}
+    }
+
+    if s.len() >= 2
+        && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')))
+    {
+        return Some(serde_json::Value::String(s[1..s.len() - 1].to_string()));
+    }
+
+    // Prefer exact integers, then fall back to floats
+    if let Ok(n) = s.parse::<i64>() {
+        return Some(n.into());
+    }
+    if let Ok(n) = s.parse::<u64>() {
+        return Some(n.into());
+    }
+    s.parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(serde_json::Value::Number)
+}
+
+/// A parsed query path.
+#[derive(Debug, Clone)]
+pub struct QueryPath {
+    /// The segments that make up this path.
+    pub segments: Vec<PathSegment>,
+}
+
+impl QueryPath {
+    /// Parse a dot-notation query path.
+    ///
+    /// # Examples
+    ///
+    /// - `"body.fee"` → `[Field("body"), Field("fee")]`
+    /// - `"outputs.0.address"` → `[Field("outputs"), Index(0), Field("address")]`
+    /// - `"outputs.*.value"` → `[Field("outputs"), Wildcard, Field("value")]`
+    /// - `"outputs[value.coin > 1000000]"` → `[Field("outputs"), Filter(...)]`
+    /// - `"outputs.0:3"` / `"outputs[0:3]"` → `[Field("outputs"), Slice { .. }]`
+    /// - `"..policy_id"` → `[Recursive("policy_id")]`
+    /// - `"body.ttl?"` → `[Field("body"), Optional(Field("ttl"))]`
+    pub fn parse(input: &str) -> Result<Self> {
+        if input.is_empty() {
+            return Ok(QueryPath { segments: vec![] });
+        }
+
+        let mut segments = Vec::new();
+        let mut remaining = input;
+
+        // Leading recursive descent: ..field
+        if let Some(rest) = remaining.strip_prefix("..") {
+            let name_end = rest.find(['.', '[']).unwrap_or(rest.len());
+            let name = &rest[..name_end];
+            if name.is_empty() {
+                return Err(Error::InvalidQuery(
+                    "Expected a field name after '..'".to_string(),
+                ));
+            }
+            segments.push(PathSegment::Recursive(name.to_string()));
+            remaining = &rest[name_end..];
+            remaining = remaining.strip_prefix('.').unwrap_or(remaining);
+        }
+
+        while !remaining.is_empty() {
+            // Check for filter syntax: field[filter]
+            if let Some(bracket_start) = remaining.find('[') {
+                // Parse field name before bracket
+                let field_part = &remaining[..bracket_start];
+                if !field_part.is_empty() {
+                    // Handle dot-separated fields before the filter
+                    for part in field_part.split('.') {
+                        if !part.is_empty() {
+                            segments.push(Self::parse_segment(part)?);
+                        }
+                    }
+                }
+
+                // Find matching closing bracket
+                let bracket_end = remaining
+                    .find(']')
+                    .ok_or_else(|| Error::InvalidQuery("Unclosed bracket in filter".to_string()))?;
+
+                // Parse slice or filter expression
+                let filter_str = &remaining[bracket_start + 1..bracket_end];
+                match Self::parse_slice(filter_str.trim())? {
+                    Some(slice) => segments.push(slice),
+                    None => segments.push(PathSegment::Filter(Self::parse_filter(filter_str)?)),
+                }
+
+                // Continue with rest after bracket
+                remaining = &remaining[bracket_end + 1..];
+                if remaining.starts_with('.') {
+                    remaining = &remaining[1..];
+                }
+            } else {
+                // No more filters, parse remaining as dot-notation
+                let parts: Vec<&str> = remaining.split('.').collect();
+                for (i, part) in parts.iter().enumerate() {
+                    if part.is_empty() {
+                        // Allow trailing empty (e.g., from "foo.") but not consecutive dots
+                        if i < parts.len() - 1 {
+                            return Err(Error::InvalidQuery(
+                                "Empty path segment (consecutive dots?)".to_string(),
+                            ));
+                        }
+                    } else {
+                        segments.push(Self::parse_segment(part)?);
+                    }
+                }
+                break;
+            }
+        }
+
+        Ok(QueryPath { segments })
+    }
+
+    /// Parse a single path segment (without filter).
+    fn parse_segment(s: &str) -> Result<PathSegment> {
+        if s.is_empty() {
+            return Err(Error::InvalidQuery(
+                "Empty path segment (consecutive dots?)".to_string(),
+            ));
+        }
+
+        // Optional suffix: null instead of an error when missing
+        if let Some(inner) = s.strip_suffix('?') {
+            return match Self::parse_segment(inner)? {
+                segment @ (PathSegment::Field(_) | PathSegment::Index(_)) => {
+                    Ok(PathSegment::Optional(Box::new(segment)))
+                }
+                _ => Err(Error::InvalidQuery(format!(
+                    "'?' is only supported on fields and indexes: '{}'",
+                    s
+                ))),
+            };
+        }
+
+        // Wildcard
+        if s == "*" {
+            return Ok(PathSegment::Wildcard);
+        }
+
+        // Try to parse as array index
+        if let Ok(idx) = s.parse::<usize>() {
+            return Ok(PathSegment::Index(idx));
+        }
+
+        // Array slice
+        if let Some(slice) = Self::parse_slice(s)? {
+            return Ok(slice);
+        }
+
+        // Otherwise it's a field name
+        Ok(PathSegment::Field(s.to_string()))
+    }
+
+    /// Parse a slice (`start:end`, either bound optional).
+    ///
+    /// Returns `None` if the string is not slice-shaped.
+    fn parse_slice(s: &str) -> Result<Option<PathSegment>> {
+        let Some((start, end)) = s.split_once(':') else {
+            return Ok(None);
+        };
+        let is_bound = |b: &str| b.trim().chars().all(|c| c.is_ascii_digit());
+        if !is_bound(start) || !is_bound(end) {
+            return Ok(None);
+        }
+
+        let parse_bound = |b: &str| -> Result<Option<usize>> {
+            let b = b.trim();
+            if b.is_empty() {
+                return Ok(None);
+            }
+            b.parse()
+                .map(Some)
+                .map_err(|_| Error::InvalidQuery(format!("Invalid slice bound: '{}'", b)))
+        };
+
+        Ok(Some(PathSegment::Slice {
+            start: parse_bound(start)?,
+            end: parse_bound(end)?,
+        }))
+    }
+
+    /// Parse a filter expression inside brackets.
+    /// Syntax: `field.path op value`
+    /// Examples: `value.coin > 1000000`, `address ~ "addr1"`, `datum != null`,
+    /// `name ^=* "hosky"`,
+    /// `address.address in ("addr1...", "addr1...")`
+    fn parse_filter(s: &str) -> Result<FilterExpr> {
+        let s = s.trim();
+
+        // Longer operators first so `>=` wins over `>` at the same position
+        let ops = [
+            (" in ", FilterOp::In),
+            ("^=*", FilterOp::StartsWithIgnoreCase),
+            ("$=*", FilterOp::EndsWithIgnoreCase),
+            ("^=", FilterOp::StartsWith),
+            ("$=", FilterOp::EndsWith),
+            (">=", FilterOp::Gte),
+            ("<=", FilterOp::Lte),
+            ("!=", FilterOp::Ne),
+            ("==", FilterOp::Eq),
+            (">", FilterOp::Gt),
+            ("<", FilterOp::Lt),
+            ("~*", FilterOp::ContainsIgnoreCase),
+            ("~", FilterOp::Contains),
+        ];
+
+        // The earliest operator splits field from value, so operators
+        // inside the value (e.g. a quoted string) are left alone
+        let found = ops
+            .into_iter()
+            .filter_map(|(op_str, op)| s.find(op_str).map(|pos| (pos, op_str, op)))
+            .min_by_key(|(pos, _, _)| *pos);
+
+        if let Some((pos, op_str, op)) = found {
+            let field = s[..pos].trim().to_string();
+            let value_str = s[pos + op_str.len()..].trim();
+
+            if field.is_empty() {
+                return Err(Error::InvalidQuery("Filter field is empty".to_string()));
+            }
+
+            let value = if op == FilterOp::In {
+                Self::parse_filter_list(value_str)?
+            } else {
+                Self::parse_filter_value(value_str)?
+            };
+
+            return Ok(FilterExpr { field, op, value });
+        }
+
+        Err(Error::InvalidQuery(format!(
+            "Invalid filter syntax: '{}'. Expected: field op value",
+            s
+        )))
+    }
+
+    /// Parse a parenthesized list of filter values: `("a", "b")`, `(1, 2)`.
+    fn parse_filter_list(s: &str) -> Result<FilterValue> {
+        let inner = s
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .ok_or_else(|| {
+                Error::InvalidQuery(format!(
+                    "Expected a list like (\"a\", \"b\") after 'in', got '{}'",
+                    s
+                ))
+            })?;
+
+        if inner.trim().is_empty() {
+            return Ok(FilterValue::List(Vec::new()));
+        }
+
+        split_top_level(inner, ",")
+            .into_iter()
+            .map(|item| {
+                if item.is_empty() {
+                    return Err(Error::InvalidQuery("Empty item in 'in' list".to_string()));
+                }
+                Self::parse_filter_value(item)
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(FilterValue::List)
+    }
+
+    /// Parse a filter value (number, string, or null).
+    fn parse_filter_value(s: &str) -> Result<FilterValue> {
+        let s = s.trim();
+
+        // Null
+        if s == "null" {
+            return Ok(FilterValue::Null);
+        }
+
+        // Quoted string
+        if (s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')) {
+            let inner = &s[1..s.len() - 1];
+            return Ok(FilterValue::String(inner.to_string()));
+        }
+
+        // Try number
+        if let Ok(n) = s.parse::<f64>() {
+            return Ok(FilterValue::Number(n));
+        }
+
+        // Treat as unquoted string
+        Ok(FilterValue::String(s.to_string()))
+    }
+
+    /// Check if this path contains any wildcards.
+    pub fn has_wildcard(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, PathSegment::Wildcard))
+    }
+
+    /// Check if this path contains any slices.
+    pub fn has_slice(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, PathSegment::Slice { .. }))
+    }
+
+    /// Check if this path contains a recursive descent.
+    pub fn has_recursive(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, PathSegment::Recursive(_)))
+    }
+
+    /// Check if this path contains any filters.
+    pub fn has_filter(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, PathSegment::Filter(_)))
+    }
+
+    /// Check if this path has a filter followed by more segments.
+    /// This requires recursive execution since filters return arrays.
+    pub fn has_filter_with_continuation(&self) -> bool {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if matches!(segment, PathSegment::Filter(_)) && i < self.segments.len() - 1 {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Check if this path is empty (no segments).
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_pipeline() {
+        assert_eq!(split_pipeline("fee").unwrap(), vec!["fee"]);
+        assert_eq!(
+            split_pipeline("outputs.*.value.coin | sum").unwrap(),
+            vec!["outputs.*.value.coin", "sum"]
+        );
+        assert_eq!(
+            split_pipeline("outputs[address ~ \"a|b\"] | count").unwrap(),
+            vec!["outputs[address ~ \"a|b\"]", "count"]
+        );
+        assert!(split_pipeline("fee |").is_err());
+    }
+
+    #[test]
+    fn test_strip_comments() {
+        let query = "# large outputs\noutputs[value.coin > 1000000]  # > 1 ADA\n  | length\n";
+        assert_eq!(
+            strip_comments(query),
+            "outputs[value.coin > 1000000] | length"
+        );
+        assert_eq!(
+            strip_comments("outputs[address ~ \"#1\"]"),
+            "outputs[address ~ \"#1\"]"
+        );
+    }
+
+    #[test]
+    fn test_split_alternatives() {
+        assert_eq!(split_alternatives("ttl // 0").unwrap(), vec!["ttl", "0"]);
+        assert_eq!(
+            split_alternatives("outputs[address ~ \"a//b\"]").unwrap(),
+            vec!["outputs[address ~ \"a//b\"]"]
+        );
+        assert!(split_alternatives("ttl //").is_err());
+    }
+
+    #[test]
+    fn test_parse_literal() {
+        assert_eq!(parse_literal("0"), Some(serde_json::json!(0)));
+        assert_eq!(parse_literal("-1.5"), Some(serde_json::json!(-1.5)));
+        assert_eq!(parse_literal("'none'"), Some(serde_json::json!("none")));
+        assert_eq!(parse_literal("null"), Some(serde_json::Value::Null));
+        assert_eq!(parse_literal("ttl"), None);
+    }
+
+    #[test]
+    fn test_parse_simple_path() {
+        let path = QueryPath::parse("body.fee").unwrap();
+        assert_eq!(path.segments.len(), 2);
+        assert_eq!(path.segments[0], PathSegment::Field("body".into()));
+        assert_eq!(path.segments[1], PathSegment::Field("fee".into()));
+    }
+
+    #[test]
+    fn test_parse_with_index() {
+        let path = QueryPath::parse("outputs.0.address").unwrap();
+        assert_eq!(path.segments.len(), 3);
+        assert_eq!(path.segments[0], PathSegment::Field("outputs".into()));
+        assert_eq!(path.segments[1], PathSegment::Index(0));
+        assert_eq!(path.segments[2], PathSegment::Field("address".into()));
+    }
+
+    #[test]
+    fn test_parse_with_wildcard() {
+        let path = QueryPath::parse("outputs.*.address").unwrap();
+        assert_eq!(path.segments.len(), 3);
+        assert_eq!(path.segments[0], PathSegment::Field("outputs".into()));
+        assert_eq!(path.segments[1], PathSegment::Wildcard);
+        assert_eq!(path.segments[2], PathSegment::Field("address".into()));
+        assert!(path.has_wildcard());
+    }
+
+    #[test]
+    fn test_parse_slices() {
+        let path = QueryPath::parse("outputs.0:3.address").unwrap();
+        assert_eq!(
+            path.segments[1],
+            PathSegment::Slice {
+                start: Some(0),
+                end: Some(3)
+            }
+        );
+        assert_eq!(path.segments[2], PathSegment::Field("address".into()));
+        assert!(path.has_slice());
+
+        let path = QueryPath::parse("outputs[1:]").unwrap();
+        assert_eq!(
+            path.segments[1],
+            PathSegment::Slice {
+                start: Some(1),
+                end: None
+            }
+        );
+
+        // Filters containing ':' are not slices
+        let path = QueryPath::parse("outputs[address ~ \"a:b\"]").unwrap();
+        assert!(matches!(path.segments[1], PathSegment::Filter(_)));
+    }
+
+    #[test]
+    fn test_parse_recursive_descent() {
+        let path = QueryPath::parse("..policy_id").unwrap();
+        assert_eq!(
+            path.segments,
+            vec![PathSegment::Recursive("policy_id".into())]
+        );
+        assert!(path.has_recursive());
+
+        let path = QueryPath::parse("..assets.0.name").unwrap();
+        assert_eq!(path.segments.len(), 3);
+        assert_eq!(path.segments[1], PathSegment::Index(0));
+
+        assert!(QueryPath::parse("..").is_err());
+    }
+
+    #[test]
+    fn test_parse_optional() {
+        let path = QueryPath::parse("body.ttl?").unwrap();
+        assert_eq!(
+            path.segments[1],
+            PathSegment::Optional(Box::new(PathSegment::Field("ttl".into())))
+        );
+
+        let path = QueryPath::parse("outputs.3?.datum?").unwrap();
+        assert_eq!(
+            path.segments[1],
+            PathSegment::Optional(Box::new(PathSegment::Index(3)))
+        );
+
+        assert!(QueryPath::parse("outputs.*?").is_err());
+        assert!(QueryPath::parse("?").is_err());
+    }
+
+    #[test]
+    fn test_parse_string_match_filters() {
+        let op = |query: &str| match QueryPath::parse(query).unwrap().segments.pop() {
+            Some(PathSegment::Filter(f)) => f.op,
+            other => panic!("Expected filter, got {:?}", other),
+        };
+
+        assert_eq!(
+            op("outputs[address.address ^= addr1]"),
+            FilterOp::StartsWith
+        );
+        assert_eq!(op("outputs[address.address $= xyz]"), FilterOp::EndsWith);
+        assert_eq!(op("mint[name ~* night]"), FilterOp::ContainsIgnoreCase);
+        assert_eq!(op("mint[name ^=* NI]"), FilterOp::StartsWithIgnoreCase);
+        assert_eq!(op("mint[name $=* HT]"), FilterOp::EndsWithIgnoreCase);
+    }
+
+    #[test]
+    fn test_parse_in_filter() {
+        let path = QueryPath::parse("outputs[address.address in (\"addr1a\", 'addr1b')]").unwrap();
+        match &path.segments[1] {
+            PathSegment::Filter(f) => {
+                assert_eq!(f.field, "address.address");
+                assert_eq!(f.op, FilterOp::In);
+                assert_eq!(
+                    f.value,
+                    FilterValue::List(vec![
+                        FilterValue::String("addr1a".into()),
+                        FilterValue::String("addr1b".into()),
+                    ])
+                );
+            }
+            other => panic!("Expected filter, got {:?}", other),
+        }
+
+        let path = QueryPath::parse("outputs[value.coin in (1, 2)]").unwrap();
+        assert!(matches!(
+            &path.segments[1],
+            PathSegment::Filter(FilterExpr { value: FilterValue::List(items), .. }) if items.len() == 2
+        ));
+
+        // Operators inside a string value don't split the filter
+        let path = QueryPath::parse("mint[name == \"a in b\"]").unwrap();
+        assert!(matches!(
+            &path.segments[1],
+            PathSegment::Filter(FilterExpr {
+                op: FilterOp::Eq,
+                ..
+            })
+        ));
+
+        assert!(QueryPath::parse("outputs[value.coin in 5]").is_err());
+    }
+
+    #[test]
+    fn test_parse_single_field() {
+        let path = QueryPath::parse("fee").unwrap();
+        assert_eq!(path.segments.len(), 1);
+        assert_eq!(path.segments[0], PathSegment::Field("fee".into()));
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        let path = QueryPath::parse("").unwrap();
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn test_parse_consecutive_dots_error() {
+        let result = QueryPath::parse("body..fee");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_has_wildcard() {
+        assert!(!QueryPath::parse("body.fee").unwrap().has_wildcard());
+        assert!(QueryPath::parse("outputs.*").unwrap().has_wildcard());
+    }
+
+    #[test]
+    fn test_parse_filter_gt() {
+        let path = QueryPath::parse("outputs[value.coin > 1000000]").unwrap();
+        assert_eq!(path.segments.len(), 2);
+        assert_eq!(path.segments[0], PathSegment::Field("outputs".into()));
+        if let PathSegment::Filter(f) = &path.segments[1] {
+            assert_eq!(f.field, "value.coin");
+            assert_eq!(f.op, FilterOp::Gt);
+            assert_eq!(f.value, FilterValue::Number(1000000.0));
+        } else {
+            panic!("Expected Filter segment");
+        }
+        assert!(path.has_filter());
+    }
+
+    #[test]
+    fn test_parse_filter_contains() {
+        let path = QueryPath::parse("outputs[address.address ~ \"addr1\"]").unwrap();
+        assert_eq!(path.segments.len(), 2);
+        if let PathSegment::Filter(f) = &path.segments[1] {
+            assert_eq!(f.field, "address.address");
+            assert_eq!(f.op, FilterOp::Contains);
+            assert_eq!(f.value, FilterValue::String("addr1".into()));
+        } else {
+            panic!("Expected Filter segment");
+        }
+    }
+
+    #[test]
+    fn test_parse_filter_not_null() {
+        let path = QueryPath::parse("outputs[datum != null]").unwrap();
+        if let PathSegment::Filter(f) = &path.segments[1] {
+            assert_eq!(f.field, "datum");
+            assert_eq!(f.op, FilterOp::Ne);
+            assert_eq!(f.value, FilterValue::Null);
+        } else {
+            panic!("Expected Filter segment");
+        }
+    }
+
+    #[test]
+    fn test_parse_filter_with_continuation() {
+        let path = QueryPath::parse("outputs[value.coin > 1000000].address").unwrap();
+        assert_eq!(path.segments.len(), 3);
+        assert_eq!(path.segments[0], PathSegment::Field("outputs".into()));
+        assert!(matches!(path.segments[1], PathSegment::Filter(_)));
+        assert_eq!(path.segments[2], PathSegment::Field("address".into()));
+    }
+}
@@ -0,0 +1,77 @@
+//! Enumerate the queryable leaf paths of a result (`--paths`).
+
+use crate::query::engine::QueryResult;
+use serde_json::Value as JsonValue;
+
+/// List every leaf path of a query result, in document order.
+///
+/// Paths are relative to `prefix` (the query that produced the result), so
+/// each can be passed back to cq. Empty arrays and objects are leaves. The
+/// values of a multi-value result share the prefix, so their paths are
+/// merged (e.g. `outputs.*` lists `outputs.*.address` once).
+pub fn leaf_paths(result: &QueryResult, prefix: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    match result {
+        QueryResult::FullTransaction(json) => collect(json, prefix, &mut paths),
+        QueryResult::Single(value) => collect(&value.clone().into(), prefix, &mut paths),
+        QueryResult::Multiple(values) => {
+            for value in values {
+                collect(&value.clone().into(), prefix, &mut paths);
+            }
+        }
+    }
+    paths
+}
+
+fn collect(value: &JsonValue, path: &str, paths: &mut Vec<String>) {
+    let child = |name: &str| {
+        if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", path, name)
+        }
+    };
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                collect(value, &child(key), paths);
+            }
+        }
+        JsonValue::Array(items) if !items.is_empty() => {
+            for (idx, value) in items.iter().enumerate() {
+                collect(value, &child(&idx.to_string()), paths);
+            }
+        }
+        _ => {
+            if !path.is_empty() && !paths.iter().any(|p| p == path) {
+                paths.push(path.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::engine::QueryValue;
+
+    #[test]
+    fn test_leaf_paths() {
+        let json = serde_json::json!({
+            "body": { "fee": 1, "outputs": [{ "address": "a" }], "certs": [] }
+        });
+        assert_eq!(
+            leaf_paths(&QueryResult::FullTransaction(json), ""),
+            vec!["body.certs", "body.fee", "body.outputs.0.address"]
+        );
+
+        let values = vec![
+            QueryValue::from(serde_json::json!({ "coin": 1 })),
+            QueryValue::from(serde_json::json!({ "coin": 2, "multi_assets": {} })),
+        ];
+        assert_eq!(
+            leaf_paths(&QueryResult::Multiple(values), "outputs.*.value"),
+            vec!["outputs.*.value.coin", "outputs.*.value.multi_assets"]
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! What each redeemer applies to.
+//!
+//! A redeemer names its target by purpose and index: the index is a
+//! position in the ledger's sorted order of spent inputs, minted policies,
+//! withdrawals or voters, or in body order for certificates and proposals.
+//! These orders differ from what cq prints, so each redeemer gets a
+//! `target` resolving the index against the transaction body.
+
+use crate::query::engine::certificate_to_json;
+use crate::query::governance::{voter_hash, voter_to_json};
+use crate::query::options::QueryOptions;
+use cml_chain::certs::Credential;
+use cml_chain::governance::Voter;
+use cml_chain::transaction::TransactionBody;
+use cml_crypto::RawBytesEncoding;
+use serde_json::Value as JsonValue;
+
+/// Add a `target` to each redeemer in the witness set JSON. Redeemers whose
+/// index points past the end of their list get none.
+pub(crate) fn add_redeemer_targets(body: &TransactionBody, witness_json: &mut JsonValue) {
+    let Some(redeemers) = witness_json
+        .get_mut("redeemers")
+        .and_then(|r| r.as_array_mut())
+    else {
+        return;
+    };
+    for redeemer in redeemers {
+        let purpose = redeemer["purpose"].as_str().unwrap_or_default();
+        let index = redeemer["index"].as_u64().unwrap_or(u64::MAX);
+        let target = usize::try_from(index)
+            .ok()
+            .and_then(|index| redeemer_target(body, purpose, index));
+        if let Some(target) = target {
+            redeemer["target"] = target;
+        }
+    }
+}
+
+/// The item a redeemer with this purpose and index applies to.
+fn redeemer_target(body: &TransactionBody, purpose: &str, index: usize) -> Option<JsonValue> {
+    match purpose {
+        "spend" => {
+            let mut inputs: Vec<(usize, _)> = body.inputs.iter().enumerate().collect();
+            inputs.sort_by_key(|(_, input)| (input.transaction_id.to_raw_bytes(), input.index));
+            let (position, input) = inputs.get(index)?;
+            Some(serde_json::json!({
+                "input": position,
+                "outpoint": format!("{}#{}", input.transaction_id.to_hex(), input.index)
+            }))
+        }
+        "mint" => {
+            let mut policies: Vec<_> = body.mint.as_ref()?.keys().collect();
+            policies.sort_by_key(|policy| policy.to_raw_bytes());
+            Some(serde_json::json!({ "policy_id": policies.get(index)?.to_hex() }))
+        }
+        "cert" => {
+            let cert = body.certs.as_ref()?.get(index)?;
+            Some(serde_json::json!({
+                "certificate": index,
+                "type": certificate_to_json(cert, &QueryOptions::default())["type"]
+            }))
+        }
+        "reward" => {
+            // Ledger order: network, then script credentials before key hashes
+            let mut accounts: Vec<_> = body.withdrawals.as_ref()?.keys().collect();
+            accounts
+                .sort_by_key(|account| (account.network, credential_sort_key(&account.payment)));
+            let address = (*accounts.get(index)?).clone().to_address();
+            Some(serde_json::json!({
+                "reward_address": address
+                    .to_bech32(None)
+                    .unwrap_or_else(|_| hex::encode(address.to_raw_bytes()))
+            }))
+        }
+        "voting" => {
+            let mut voters: Vec<_> = body.voting_procedures.as_ref()?.keys().collect();
+            voters.sort_by_key(|voter| voter_sort_key(voter));
+            Some(serde_json::json!({ "voter": voter_to_json(voters.get(index)?) }))
+        }
+        "proposing" => {
+            body.proposal_procedures.as_ref()?.get(index)?;
+            Some(serde_json::json!({ "proposal": index }))
+        }
+        _ => None,
+    }
+}
+
+/// Scripts sort before key hashes, then by hash.
+fn credential_sort_key(credential: &Credential) -> (u8, Vec<u8>) {
+    match credential {
+        Credential::Script { hash, .. } => (0, hash.to_raw_bytes().to_vec()),
+        Credential::PubKey { hash, .. } => (1, hash.to_raw_bytes().to_vec()),
+    }
+}
+
+/// Committee members, then DReps, then pools; scripts before key hashes.
+fn voter_sort_key(voter: &Voter) -> (u8, Vec<u8>) {
+    let (role, credential_rank) = match voter {
+        Voter::ConstitutionalCommitteeHotScriptHash { .. } => (0, 0),
+        Voter::ConstitutionalCommitteeHotKeyHash { .. } => (0, 1),
+        Voter::DRepScriptHash { .. } => (1, 0),
+        Voter::DRepKeyHash { .. } => (1, 1),
+        Voter::StakingPoolKeyHash { .. } => (2, 1),
+    };
+    (role * 2 + credential_rank, voter_hash(voter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cml_chain::transaction::TransactionInput;
+    use cml_crypto::TransactionHash;
+
+    #[test]
+    fn test_spend_target_uses_sorted_inputs() {
+        let input =
+            |byte: u8, index: u64| TransactionInput::new(TransactionHash::from([byte; 32]), index);
+        // Body order: b#0, a#1, a#0; sorted: a#0, a#1, b#0
+        let body = TransactionBody::new(
+            vec![input(0xbb, 0), input(0xaa, 1), input(0xaa, 0)].into(),
+            Vec::new(),
+            0,
+        );
+        let mut witness_json = serde_json::json!({
+            "redeemers": [
+                { "purpose": "spend", "index": 0 },
+                { "purpose": "spend", "index": 2 },
+                { "purpose": "spend", "index": 3 }
+            ]
+        });
+        add_redeemer_targets(&body, &mut witness_json);
+
+        let redeemers = &witness_json["redeemers"];
+        assert_eq!(redeemers[0]["target"]["input"], 2);
+        assert_eq!(
+            redeemers[0]["target"]["outpoint"],
+            format!("{}#0", "aa".repeat(32))
+        );
+        assert_eq!(redeemers[1]["target"]["input"], 0);
+        assert!(redeemers[2].get("target").is_none());
+    }
+}
@@ -0,0 +1,130 @@
+//! Token metadata from the Cardano token registry (`--token-registry`).
+//!
+//! With a registry in the query options, asset JSON carries the token's
+//! `ticker` and `decimals`, and amounts get a `formatted` form such as
+//! `"12.5 MIN"`.
+
+use crate::error::{Error, Result};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+/// Display metadata for a token.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub name: Option<String>,
+    pub ticker: Option<String>,
+    /// Digits after the decimal point in display amounts.
+    pub decimals: u8,
+}
+
+/// Token metadata keyed by subject: policy id hex followed by asset name hex.
+pub type TokenRegistry = BTreeMap<String, TokenInfo>;
+
+/// Parse token metadata from JSON: token registry entries (one, or an
+/// array of them), or a mapping from subject to `{ticker, decimals, name}`.
+///
+/// Registry entries wrap each property as `{"value": ...}`; plain values
+/// are accepted too.
+pub fn parse_token_registry(json: &JsonValue) -> Result<TokenRegistry> {
+    let entries: Vec<(String, &JsonValue)> = match json {
+        JsonValue::Array(items) => items
+            .iter()
+            .map(|item| Ok((subject_of(item)?, item)))
+            .collect::<Result<_>>()?,
+        JsonValue::Object(map) if map.contains_key("subject") => vec![(subject_of(json)?, json)],
+        JsonValue::Object(map) => map.iter().map(|(k, v)| (k.clone(), v)).collect(),
+        _ => {
+            return Err(Error::InvalidConfig(
+                "token registry must be a JSON object or array".to_string(),
+            ));
+        }
+    };
+
+    entries
+        .into_iter()
+        .map(|(subject, entry)| Ok((subject.to_lowercase(), token_info_from_json(entry)?)))
+        .collect()
+}
+
+fn subject_of(entry: &JsonValue) -> Result<String> {
+    entry["subject"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| Error::InvalidConfig("token registry entry without a subject".to_string()))
+}
+
+fn token_info_from_json(entry: &JsonValue) -> Result<TokenInfo> {
+    let property = |key: &str| {
+        let value = entry.get(key)?;
+        Some(value.get("value").unwrap_or(value).clone())
+    };
+    let decimals = match property("decimals") {
+        None => 0,
+        Some(value) => value
+            .as_u64()
+            .and_then(|d| u8::try_from(d).ok())
+            .filter(|&d| d <= 38)
+            .ok_or_else(|| Error::InvalidConfig(format!("invalid token decimals: {}", value)))?,
+    };
+    Ok(TokenInfo {
+        name: property("name").and_then(|v| v.as_str().map(str::to_string)),
+        ticker: property("ticker").and_then(|v| v.as_str().map(str::to_string)),
+        decimals,
+    })
+}
+
+/// Format an amount with the token's decimals and ticker, e.g. `12.5 MIN`.
+pub fn format_token_amount(amount: i128, info: &TokenInfo) -> String {
+    let scale = 10u128.pow(u32::from(info.decimals));
+    let (whole, fraction) = (amount.unsigned_abs() / scale, amount.unsigned_abs() % scale);
+    let sign = if amount < 0 { "-" } else { "" };
+
+    let mut number = format!("{}{}", sign, whole);
+    if fraction > 0 {
+        let digits = format!("{:0width$}", fraction, width = usize::from(info.decimals));
+        number.push('.');
+        number.push_str(digits.trim_end_matches('0'));
+    }
+    match &info.ticker {
+        Some(ticker) => format!("{} {}", number, ticker),
+        None => number,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_token_amount() {
+        let min = TokenInfo {
+            ticker: Some("MIN".to_string()),
+            decimals: 6,
+            ..Default::default()
+        };
+        assert_eq!(format_token_amount(12_500_000, &min), "12.5 MIN");
+        assert_eq!(format_token_amount(-3, &min), "-0.000003 MIN");
+        assert_eq!(format_token_amount(7, &TokenInfo::default()), "7");
+    }
+
+    #[test]
+    fn test_parse_token_registry() {
+        // Registry entry format
+        let registry = parse_token_registry(&serde_json::json!([{
+            "subject": "AB01",
+            "ticker": { "value": "MIN", "sequenceNumber": 0 },
+            "decimals": { "value": 6 }
+        }]))
+        .unwrap();
+        assert_eq!(registry["ab01"].ticker.as_deref(), Some("MIN"));
+        assert_eq!(registry["ab01"].decimals, 6);
+
+        // Simple mapping
+        let registry =
+            parse_token_registry(&serde_json::json!({ "ab01": { "decimals": 2 } })).unwrap();
+        assert_eq!(registry["ab01"].decimals, 2);
+
+        assert!(parse_token_registry(&serde_json::json!([{ "ticker": "X" }])).is_err());
+        assert!(parse_token_registry(&serde_json::json!({ "ab": { "decimals": -1 } })).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Query shortcut expansion.
+
+/// Shortcut names and their expansions.
+const SHORTCUTS: &[(&str, &str)] = &[
+    ("fee", "body.fee"),
+    ("inputs", "body.inputs"),
+    ("outputs", "body.outputs"),
+    ("metadata", "auxiliary_data.metadata"),
+    ("witnesses", "witness_set"),
+    ("hash", "__hash__"),
+    ("ttl", "body.ttl"),
+    ("mint", "body.mint"),
+    ("certs", "body.certs"),
+    ("withdrawals", "body.withdrawals"),
+    ("votes", "body.voting_procedures"),
+    ("proposals", "body.proposal_procedures"),
+    ("collateral", "body.collateral_inputs"),
+    ("reference_inputs", "body.reference_inputs"),
+    ("redeemers", "witness_set.redeemers"),
+    ("required_signers", "body.required_signers"),
+    ("network_id", "body.network_id"),
+    ("validity_start", "body.validity_interval_start"),
+    ("script_data_hash", "body.script_data_hash"),
+    ("collateral_return", "body.collateral_return"),
+    ("total_collateral", "body.total_collateral"),
+    ("addresses", "__addresses__"),
+    ("assets", "__assets__"),
+];
+
+/// Get the expansion for a shortcut prefix.
+fn shortcut_expansion(shortcut: &str) -> Option<&'static str> {
+    SHORTCUTS
+        .iter()
+        .find(|(name, _)| *name == shortcut)
+        .map(|(_, expansion)| *expansion)
+}
+
+/// Names of all query shortcuts.
+pub fn shortcut_names() -> impl Iterator<Item = &'static str> {
+    SHORTCUTS.iter().map(|(name, _)| *name)
+}
+
+/// Expand a query shortcut to its full path.
+///
+/// Handles both exact matches and prefixes:
+/// - `outputs` → `body.outputs`
+/// - `outputs.0.address` → `body.outputs.0.address`
+/// - `outputs[filter]` → `body.outputs[filter]`
+/// - `ttl?` → `body.ttl?`
+///
+/// # Shortcuts
+///
+/// - `fee` → `body.fee`
+/// - `inputs` → `body.inputs`
+/// - `outputs` → `body.outputs`
+/// - `metadata` → `auxiliary_data.metadata`
+/// - `witnesses` → `witness_set`
+/// - `hash` → `__hash__` (special computed field)
+/// - `ttl` → `body.ttl`
+/// - `mint` → `body.mint`
+/// - `certs` → `body.certs`
+/// - `withdrawals` → `body.withdrawals`
+/// - `collateral` → `body.collateral_inputs`
+/// - `addresses` → `__addresses__` (computed list of all addresses)
+/// - `assets` → `__assets__` (computed per-asset totals)
+pub fn expand_shortcut(query: &str) -> String {
+    // Check for exact match first
+    if let Some(expanded) = shortcut_expansion(query) {
+        return expanded.to_string();
+    }
+
+    // Find the first delimiter (dot, bracket or optional marker)
+    if let Some(pos) = query.find(['.', '[', '?']) {
+        let prefix = &query[..pos];
+        let rest = &query[pos..]; // includes the delimiter
+
+        if let Some(expanded_prefix) = shortcut_expansion(prefix) {
+            return format!("{}{}", expanded_prefix, rest);
+        }
+    }
+
+    // No shortcut found, return as-is
+    query.to_string()
+}
+
+/// Check if a query is the special hash computed field.
+pub fn is_hash_query(expanded: &str) -> bool {
+    expanded == "__hash__"
+}
+
+/// Computed fields derived from the transaction rather than read from its JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComputedField {
+    /// Every address in the transaction, annotated with its roles.
+    Addresses,
+    /// Per-asset totals across outputs and mint.
+    Assets,
+}
+
+/// Split a computed field from the start of an expanded query.
+///
+/// Returns the field and the remaining path (without the leading dot),
+/// so `__addresses__.0.address` yields `(Addresses, "0.address")`.
+pub fn split_computed_field(expanded: &str) -> Option<(ComputedField, &str)> {
+    let fields = [
+        ("__addresses__", ComputedField::Addresses),
+        ("__assets__", ComputedField::Assets),
+    ];
+
+    for (name, field) in fields {
+        if let Some(rest) = expanded.strip_prefix(name) {
+            if rest.is_empty() || rest.starts_with('.') || rest.starts_with('[') {
+                return Some((field, rest.strip_prefix('.').unwrap_or(rest)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_shortcuts() {
+        assert_eq!(expand_shortcut("fee"), "body.fee");
+        assert_eq!(expand_shortcut("inputs"), "body.inputs");
+        assert_eq!(expand_shortcut("outputs"), "body.outputs");
+        assert_eq!(expand_shortcut("metadata"), "auxiliary_data.metadata");
+        assert_eq!(expand_shortcut("witnesses"), "witness_set");
+        assert_eq!(expand_shortcut("hash"), "__hash__");
+    }
+
+    #[test]
+    fn test_expand_shortcuts_nested() {
+        assert_eq!(
+            expand_shortcut("outputs.0.address"),
+            "body.outputs.0.address"
+        );
+        assert_eq!(
+            expand_shortcut("inputs.0.transaction_id"),
+            "body.inputs.0.transaction_id"
+        );
+        assert_eq!(expand_shortcut("outputs.*.value"), "body.outputs.*.value");
+    }
+
+    #[test]
+    fn test_passthrough() {
+        assert_eq!(expand_shortcut("body.fee"), "body.fee");
+        assert_eq!(
+            expand_shortcut("body.outputs.0.address"),
+            "body.outputs.0.address"
+        );
+        assert_eq!(expand_shortcut("unknown"), "unknown");
+        assert_eq!(expand_shortcut("unknown.field"), "unknown.field");
+    }
+
+    #[test]
+    fn test_expand_shortcuts_with_filter() {
+        assert_eq!(
+            expand_shortcut("outputs[value.coin > 1000000]"),
+            "body.outputs[value.coin > 1000000]"
+        );
+        assert_eq!(
+            expand_shortcut("outputs[value.coin > 1000000].address"),
+            "body.outputs[value.coin > 1000000].address"
+        );
+    }
+
+    #[test]
+    fn test_expand_shortcuts_optional() {
+        assert_eq!(expand_shortcut("ttl?"), "body.ttl?");
+        assert_eq!(expand_shortcut("mint?.0"), "body.mint?.0");
+    }
+
+    #[test]
+    fn test_split_computed_field() {
+        assert_eq!(
+            split_computed_field("__addresses__"),
+            Some((ComputedField::Addresses, ""))
+        );
+        assert_eq!(
+            split_computed_field("__addresses__.0.address"),
+            Some((ComputedField::Addresses, "0.address"))
+        );
+        assert_eq!(
+            split_computed_field("__assets__[net > 0]"),
+            Some((ComputedField::Assets, "[net > 0]"))
+        );
+        assert_eq!(split_computed_field("__addresses__x"), None);
+        assert_eq!(split_computed_field("body.fee"), None);
+    }
+
+    #[test]
+    fn test_is_hash_query() {
+        assert!(is_hash_query("__hash__"));
+        assert!(!is_hash_query("hash"));
+        assert!(!is_hash_query("body.fee"));
+    }
+}
@@ -0,0 +1,403 @@
+//! Post-processing of query results (sorting, truncation and similar).
+
+use crate::error::{Error, Result};
+use crate::query::engine::{QueryResult, QueryValue, execute_path};
+use crate::query::path::QueryPath;
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+
+/// Sort the values of a result by a key path relative to each value.
+///
+/// Applies to wildcard results and to single array values; other results
+/// are returned unchanged. A key of `.` sorts by the values themselves.
+/// Values missing the key sort first (last with `desc`).
+pub fn sort_result(result: QueryResult, key: &str, desc: bool) -> Result<QueryResult> {
+    let key = key.strip_prefix('.').unwrap_or(key);
+    let path = QueryPath::parse(key)?;
+
+    let sort = |values: Vec<QueryValue>| {
+        let mut keyed: Vec<(JsonValue, QueryValue)> = values
+            .into_iter()
+            .map(|value| {
+                let json = JsonValue::from(value.clone());
+                let sort_key = execute_path(&json, &path.segments)
+                    .map(JsonValue::from)
+                    .unwrap_or(JsonValue::Null);
+                (sort_key, value)
+            })
+            .collect();
+
+        keyed.sort_by(|(a, _), (b, _)| {
+            let ordering = compare_json(a, b);
+            if desc { ordering.reverse() } else { ordering }
+        });
+        keyed.into_iter().map(|(_, value)| value).collect()
+    };
+
+    Ok(map_values(result, sort))
+}
+
+/// Group the values of a result by a key path relative to each value.
+///
+/// Produces one `{"key", "values"}` object per distinct key, in order of
+/// first appearance; values missing the key are grouped under `null`.
+/// Applies to wildcard results and to single array values.
+pub fn group_result(result: QueryResult, key: &str) -> Result<QueryResult> {
+    let key = key.strip_prefix('.').unwrap_or(key);
+    let path = QueryPath::parse(key)?;
+
+    let values = match result {
+        QueryResult::Multiple(values) | QueryResult::Single(QueryValue::Array(values)) => values,
+        _ => {
+            return Err(Error::InvalidQuery(
+                "group_by needs multiple values (e.g. outputs.* | group_by(address.address))"
+                    .to_string(),
+            ));
+        }
+    };
+
+    let mut groups: Vec<(JsonValue, Vec<JsonValue>)> = Vec::new();
+    for value in values {
+        let json = JsonValue::from(value);
+        let group_key = execute_path(&json, &path.segments)
+            .map(JsonValue::from)
+            .unwrap_or(JsonValue::Null);
+        match groups.iter_mut().find(|(k, _)| *k == group_key) {
+            Some((_, members)) => members.push(json),
+            None => groups.push((group_key, vec![json])),
+        }
+    }
+
+    Ok(QueryResult::Multiple(
+        groups
+            .into_iter()
+            .map(|(key, values)| {
+                QueryValue::from(serde_json::json!({ "key": key, "values": values }))
+            })
+            .collect(),
+    ))
+}
+
+/// Remove duplicate values from a result, keeping first occurrences in order.
+pub fn unique_result(result: QueryResult) -> QueryResult {
+    map_values(result, |values| {
+        let mut seen: Vec<JsonValue> = Vec::new();
+        values
+            .into_iter()
+            .filter(|value| {
+                let json = JsonValue::from(value.clone());
+                if seen.contains(&json) {
+                    false
+                } else {
+                    seen.push(json);
+                    true
+                }
+            })
+            .collect()
+    })
+}
+
+/// Keep only the outputs paying an address: values whose `address` (or
+/// which themselves) have this bech32 address or payment credential hash.
+///
+/// Values without an address are an error rather than silently dropped,
+/// as the filter sees the query's values, not the outputs they came from.
+pub fn to_address_result(result: QueryResult, target: &str) -> Result<QueryResult> {
+    let target = target.trim();
+    let mut without_address = false;
+    let result = map_values(result, |values| {
+        values
+            .into_iter()
+            .filter(|value| {
+                let json = JsonValue::from(value.clone());
+                let address = json
+                    .get("address")
+                    .filter(|a| a.is_object())
+                    .unwrap_or(&json);
+                let bech32 = address.get("address").unwrap_or(address).as_str();
+                let credential = address.pointer("/payment_credential/hash");
+                if bech32.is_none() {
+                    without_address = true;
+                }
+                [bech32, credential.and_then(|v| v.as_str())]
+                    .into_iter()
+                    .flatten()
+                    .any(|s| s.eq_ignore_ascii_case(target))
+            })
+            .collect()
+    });
+    if without_address {
+        return Err(Error::InvalidQuery(
+            "--to filters outputs or addresses; query them directly (e.g. `cq outputs --to ADDRESS`)"
+                .to_string(),
+        ));
+    }
+    Ok(result)
+}
+
+/// Keep only the first `n` values of a result.
+pub fn head_result(result: QueryResult, n: usize) -> QueryResult {
+    map_values(result, |mut values| {
+        values.truncate(n);
+        values
+    })
+}
+
+/// Keep only the last `n` values of a result.
+pub fn tail_result(result: QueryResult, n: usize) -> QueryResult {
+    map_values(result, |mut values| {
+        let skip = values.len().saturating_sub(n);
+        values.drain(..skip);
+        values
+    })
+}
+
+/// Show inputs in a result as their `txid#index` outpoint strings.
+///
+/// Any object with `outpoint`, `transaction_id` and `index` is an input;
+/// the full transaction is returned unchanged.
+pub fn outpoints_result(result: QueryResult) -> QueryResult {
+    fn replace(json: JsonValue) -> JsonValue {
+        match json {
+            JsonValue::Object(map)
+                if map.len() == 3
+                    && map.contains_key("transaction_id")
+                    && map.contains_key("index") =>
+            {
+                match map.get("outpoint") {
+                    Some(outpoint) => outpoint.clone(),
+                    None => JsonValue::Object(map),
+                }
+            }
+            JsonValue::Object(map) => {
+                JsonValue::Object(map.into_iter().map(|(k, v)| (k, replace(v))).collect())
+            }
+            JsonValue::Array(values) => JsonValue::Array(values.into_iter().map(replace).collect()),
+            other => other,
+        }
+    }
+    let replace_value = |value: QueryValue| QueryValue::from(replace(JsonValue::from(value)));
+
+    match result {
+        QueryResult::Single(value) => QueryResult::Single(replace_value(value)),
+        QueryResult::Multiple(values) => {
+            QueryResult::Multiple(values.into_iter().map(replace_value).collect())
+        }
+        full @ QueryResult::FullTransaction(_) => full,
+    }
+}
+
+/// Apply a transformation to the values of a multi-value result.
+fn map_values(
+    result: QueryResult,
+    f: impl FnOnce(Vec<QueryValue>) -> Vec<QueryValue>,
+) -> QueryResult {
+    match result {
+        QueryResult::Multiple(values) => QueryResult::Multiple(f(values)),
+        QueryResult::Single(QueryValue::Array(values)) => {
+            QueryResult::Single(QueryValue::Array(f(values)))
+        }
+        other => other,
+    }
+}
+
+/// Total order over JSON values: null < bool < number < string < array < object.
+fn compare_json(a: &JsonValue, b: &JsonValue) -> Ordering {
+    fn rank(v: &JsonValue) -> u8 {
+        match v {
+            JsonValue::Null => 0,
+            JsonValue::Bool(_) => 1,
+            JsonValue::Number(_) => 2,
+            JsonValue::String(_) => 3,
+            JsonValue::Array(_) => 4,
+            JsonValue::Object(_) => 5,
+        }
+    }
+
+    match (a, b) {
+        (JsonValue::Bool(x), JsonValue::Bool(y)) => x.cmp(y),
+        (JsonValue::Number(x), JsonValue::Number(y)) => match (x.as_u64(), y.as_u64()) {
+            // Compare lovelace-sized integers exactly
+            (Some(x), Some(y)) => x.cmp(&y),
+            _ => x
+                .as_f64()
+                .unwrap_or(0.0)
+                .total_cmp(&y.as_f64().unwrap_or(0.0)),
+        },
+        (JsonValue::String(x), JsonValue::String(y)) => x.cmp(y),
+        (JsonValue::Array(x), JsonValue::Array(y)) => x
+            .iter()
+            .zip(y)
+            .map(|(x, y)| compare_json(x, y))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| x.len().cmp(&y.len())),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outputs() -> QueryResult {
+        QueryResult::Multiple(
+            [3, 10, 1]
+                .into_iter()
+                .map(|coin| QueryValue::from(serde_json::json!({ "value": { "coin": coin } })))
+                .chain([QueryValue::from(serde_json::json!({}))])
+                .collect(),
+        )
+    }
+
+    fn coins(result: QueryResult) -> Vec<JsonValue> {
+        match result {
+            QueryResult::Multiple(values) => values
+                .into_iter()
+                .map(|v| JsonValue::from(v)["value"]["coin"].clone())
+                .collect(),
+            other => panic!("Expected multiple, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sort_ascending_and_descending() {
+        let sorted = sort_result(outputs(), "value.coin", false).unwrap();
+        assert_eq!(
+            coins(sorted),
+            vec![JsonValue::Null, 1.into(), 3.into(), 10.into()]
+        );
+
+        let sorted = sort_result(outputs(), "value.coin", true).unwrap();
+        assert_eq!(
+            coins(sorted),
+            vec![10.into(), 3.into(), 1.into(), JsonValue::Null]
+        );
+    }
+
+    #[test]
+    fn test_head_and_tail() {
+        let sorted = sort_result(outputs(), "value.coin", true).unwrap();
+        assert_eq!(
+            coins(head_result(sorted, 2)),
+            vec![JsonValue::from(10), JsonValue::from(3)]
+        );
+
+        assert_eq!(coins(tail_result(outputs(), 1)), vec![JsonValue::Null]);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let result = QueryResult::Multiple(
+            [("a", 1), ("b", 2), ("a", 3)]
+                .into_iter()
+                .map(|(addr, coin)| {
+                    QueryValue::from(serde_json::json!({ "addr": addr, "coin": coin }))
+                })
+                .chain([QueryValue::from(serde_json::json!({}))])
+                .collect(),
+        );
+        let groups: Vec<JsonValue> = match group_result(result, ".addr").unwrap() {
+            QueryResult::Multiple(values) => values.into_iter().map(JsonValue::from).collect(),
+            other => panic!("Expected multiple, got {:?}", other),
+        };
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0]["key"], "a");
+        assert_eq!(groups[0]["values"][1]["coin"], 3);
+        assert_eq!(groups[1]["key"], "b");
+        assert_eq!(groups[2]["key"], JsonValue::Null);
+
+        let single = QueryResult::Single(QueryValue::from(serde_json::json!(1)));
+        assert!(group_result(single, "addr").is_err());
+    }
+
+    #[test]
+    fn test_to_address() {
+        let output = |address: &str, hash: &str| {
+            QueryValue::from(serde_json::json!({
+                "address": { "address": address, "payment_credential": { "hash": hash } }
+            }))
+        };
+        let result = QueryResult::Single(QueryValue::Array(vec![
+            output("addr_test1a", "aa11"),
+            output("addr_test1b", "bb22"),
+            output("addr_test1c", "AA11"),
+        ]));
+
+        let kept = |result: QueryResult| match result {
+            QueryResult::Single(QueryValue::Array(values)) => values.len(),
+            other => panic!("Expected array, got {:?}", other),
+        };
+        assert_eq!(
+            kept(to_address_result(result.clone(), "addr_test1b").unwrap()),
+            1
+        );
+        assert_eq!(kept(to_address_result(result.clone(), "AA11").unwrap()), 2);
+        assert_eq!(kept(to_address_result(result, "addr_test1z").unwrap()), 0);
+        assert!(to_address_result(outputs(), "aa11").is_err());
+
+        // Address objects themselves
+        let addresses = QueryResult::Multiple(vec![QueryValue::from(serde_json::json!({
+            "address": "addr_test1a", "payment_credential": { "hash": "aa11" }
+        }))]);
+        assert!(matches!(
+            to_address_result(addresses, "aa11").unwrap(),
+            QueryResult::Multiple(values) if values.len() == 1
+        ));
+        assert_eq!(coins(tail_result(outputs(), 10)).len(), 4);
+
+        // Scalars are untouched
+        let fee = QueryResult::Single(QueryValue::Number(1.into()));
+        assert!(matches!(
+            head_result(fee, 0),
+            QueryResult::Single(QueryValue::Number(_))
+        ));
+    }
+
+    #[test]
+    fn test_unique_preserves_order() {
+        let result = QueryResult::Multiple(
+            ["b", "a", "b", "c", "a"]
+                .into_iter()
+                .map(|s| QueryValue::String(s.to_string()))
+                .collect(),
+        );
+        match unique_result(result) {
+            QueryResult::Multiple(values) => assert_eq!(
+                JsonValue::from(QueryValue::Array(values)),
+                serde_json::json!(["b", "a", "c"])
+            ),
+            other => panic!("Expected multiple, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_outpoints() {
+        let input = serde_json::json!({
+            "outpoint": "ab#1",
+            "transaction_id": "ab",
+            "index": 1
+        });
+        let result = QueryResult::Single(QueryValue::from(serde_json::json!({
+            "inputs": [input],
+            "fee": 1
+        })));
+        match outpoints_result(result) {
+            QueryResult::Single(value) => assert_eq!(
+                JsonValue::from(value),
+                serde_json::json!({ "inputs": ["ab#1"], "fee": 1 })
+            ),
+            other => panic!("Expected single, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sort_by_self() {
+        let result = QueryResult::Single(QueryValue::from(serde_json::json!(["b", "a", "c"])));
+        match sort_result(result, ".", false).unwrap() {
+            QueryResult::Single(value) => {
+                assert_eq!(JsonValue::from(value), serde_json::json!(["a", "b", "c"]))
+            }
+            other => panic!("Expected single, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Anchor verification (`--fetch-anchors`).
+//!
+//! Every anchor (`{"url", "data_hash"}`) in a result is downloaded and
+//! hashed with blake2b-256. The anchor gains `fetched_hash` and
+//! `hash_matches`, or `fetch_error` if the document could not be read.
+//! `ipfs://` URLs are fetched through a public gateway.
+//!
+//! `--pool-metadata` checks pool registrations the same way against a
+//! metadata document given as a file or URL.
+
+use crate::error::{Error, Result as CqResult};
+use crate::query::{QueryResult, QueryValue};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
+
+/// Gateway for `ipfs://` anchors.
+const IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+/// Largest anchor document read, in bytes.
+const MAX_DOCUMENT_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Fetch and check every anchor in the result.
+pub fn verify_anchors(result: QueryResult) -> QueryResult {
+    let mut fetched = HashMap::new();
+    let mut verify = |value: QueryValue| {
+        let mut json = JsonValue::from(value);
+        verify_anchors_in(&mut json, &mut fetched);
+        QueryValue::from(json)
+    };
+    match result {
+        QueryResult::FullTransaction(mut json) => {
+            verify_anchors_in(&mut json, &mut fetched);
+            QueryResult::FullTransaction(json)
+        }
+        QueryResult::Single(value) => QueryResult::Single(verify(value)),
+        QueryResult::Multiple(values) => {
+            QueryResult::Multiple(values.into_iter().map(verify).collect())
+        }
+    }
+}
+
+/// Check the anchors in a JSON tree, fetching each URL once.
+fn verify_anchors_in(json: &mut JsonValue, fetched: &mut HashMap<String, Result<String, String>>) {
+    match json {
+        JsonValue::Object(map) => {
+            let anchor = match (map.get("url"), map.get("data_hash")) {
+                (Some(JsonValue::String(url)), Some(JsonValue::String(hash))) => {
+                    Some((url.clone(), hash.clone()))
+                }
+                _ => None,
+            };
+            if let Some((url, data_hash)) = anchor {
+                let outcome = fetched
+                    .entry(url.clone())
+                    .or_insert_with(|| fetch_hash(&url));
+                match outcome {
+                    Ok(hash) => {
+                        map.insert("fetched_hash".to_string(), hash.clone().into());
+                        map.insert("hash_matches".to_string(), (*hash == data_hash).into());
+                    }
+                    Err(e) => {
+                        map.insert("fetch_error".to_string(), e.clone().into());
+                    }
+                }
+                return;
+            }
+            map.values_mut()
+                .for_each(|value| verify_anchors_in(value, fetched));
+        }
+        JsonValue::Array(items) => items
+            .iter_mut()
+            .for_each(|value| verify_anchors_in(value, fetched)),
+        _ => {}
+    }
+}
+
+/// Download a document and return its blake2b-256 hash as hex.
+fn fetch_hash(url: &str) -> Result<String, String> {
+    fetch_document(url).map(|document| hex::encode(cml_crypto::blake2b256(&document)))
+}
+
+/// Download a document, at most [`MAX_DOCUMENT_SIZE`] bytes.
+fn fetch_document(url: &str) -> Result<Vec<u8>, String> {
+    let url = match url.strip_prefix("ipfs://") {
+        Some(cid) => format!("{}{}", IPFS_GATEWAY, cid),
+        None => url.to_string(),
+    };
+    log::info!("fetching anchor {}", url);
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .build();
+    let response = agent
+        .get(&url)
+        .set("User-Agent", "cq-anchor-fetch")
+        .call()
+        .map_err(|e| e.to_string())?;
+
+    let mut document = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_DOCUMENT_SIZE + 1)
+        .read_to_end(&mut document)
+        .map_err(|e| e.to_string())?;
+    if document.len() as u64 > MAX_DOCUMENT_SIZE {
+        return Err(format!("document larger than {} bytes", MAX_DOCUMENT_SIZE));
+    }
+    Ok(document)
+}
+
+/// The blake2b-256 hash of a pool metadata document, read from a file or
+/// downloaded from an `http(s)://` or `ipfs://` URL. The hash covers the
+/// bytes as served, whitespace included.
+pub fn pool_metadata_hash(source: &str) -> CqResult<String> {
+    let document = if ["http://", "https://", "ipfs://"]
+        .iter()
+        .any(|scheme| source.starts_with(scheme))
+    {
+        fetch_document(source).map_err(|e| {
+            Error::NetworkError(format!("cannot fetch pool metadata {}: {}", source, e))
+        })?
+    } else {
+        std::fs::read(source).map_err(|e| Error::IoError {
+            path: Some(source.into()),
+            source: e,
+        })?
+    };
+    Ok(hex::encode(cml_crypto::blake2b256(&document)))
+}
+
+/// Check the metadata hash of every pool registration in the result
+/// against a document's hash: `pool_metadata` gains `document_hash` and
+/// `hash_matches`. The result must hold the certificates, not just their
+/// `pool_metadata`.
+pub fn verify_pool_metadata(result: QueryResult, document_hash: &str) -> QueryResult {
+    let verify = |value: QueryValue| {
+        let mut json = JsonValue::from(value);
+        verify_pool_metadata_in(&mut json, document_hash);
+        QueryValue::from(json)
+    };
+    match result {
+        QueryResult::FullTransaction(mut json) => {
+            verify_pool_metadata_in(&mut json, document_hash);
+            QueryResult::FullTransaction(json)
+        }
+        QueryResult::Single(value) => QueryResult::Single(verify(value)),
+        QueryResult::Multiple(values) => {
+            QueryResult::Multiple(values.into_iter().map(verify).collect())
+        }
+    }
+}
+
+fn verify_pool_metadata_in(json: &mut JsonValue, document_hash: &str) {
+    match json {
+        JsonValue::Object(map) => {
+            if let Some(JsonValue::Object(metadata)) = map.get_mut("pool_metadata") {
+                let matches =
+                    metadata.get("data_hash").and_then(|v| v.as_str()) == Some(document_hash);
+                metadata.insert("document_hash".to_string(), document_hash.into());
+                metadata.insert("hash_matches".to_string(), matches.into());
+                return;
+            }
+            map.values_mut()
+                .for_each(|value| verify_pool_metadata_in(value, document_hash));
+        }
+        JsonValue::Array(items) => items
+            .iter_mut()
+            .for_each(|value| verify_pool_metadata_in(value, document_hash)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_anchors() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", server.server_addr().to_ip().unwrap());
+        std::thread::spawn(move || {
+            for request in server.incoming_requests().take(2) {
+                let _ = request.respond(tiny_http::Response::from_string("rationale"));
+            }
+        });
+
+        let expected = hex::encode(cml_crypto::blake2b256(b"rationale"));
+        let mut json = serde_json::json!({
+            "votes": [
+                { "anchor": { "url": format!("{}/a.json", base), "data_hash": expected } },
+                { "anchor": { "url": format!("{}/b.json", base), "data_hash": "00".repeat(32) } },
+                { "anchor": { "url": "http://127.0.0.1:1/", "data_hash": expected } }
+            ]
+        });
+        verify_anchors_in(&mut json, &mut HashMap::new());
+
+        let votes = &json["votes"];
+        assert_eq!(votes[0]["anchor"]["hash_matches"], true);
+        assert_eq!(votes[0]["anchor"]["fetched_hash"], expected);
+        assert_eq!(votes[1]["anchor"]["hash_matches"], false);
+        assert!(votes[2]["anchor"]["fetch_error"].is_string());
+    }
+
+    #[test]
+    fn test_verify_pool_metadata() {
+        let registration = |hash: &str| {
+            serde_json::json!({
+                "type": "pool_registration",
+                "pool_metadata": { "url": "https://example.com/pool.json", "data_hash": hash }
+            })
+        };
+        let expected = "ab".repeat(32);
+        let mut json = serde_json::json!({
+            "certs": [registration(&expected), registration(&"00".repeat(32))]
+        });
+        verify_pool_metadata_in(&mut json, &expected);
+
+        let certs = &json["certs"];
+        assert_eq!(certs[0]["pool_metadata"]["hash_matches"], true);
+        assert_eq!(certs[1]["pool_metadata"]["hash_matches"], false);
+        assert_eq!(certs[1]["pool_metadata"]["document_hash"], expected);
+    }
+}
@@ -1,6 +1,10 @@
 //! CLI argument parsing for cq.
 
-use clap::{Parser, Subcommand};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{ArgMatches, CommandFactory, Parser, Subcommand, ValueEnum};
+use cq_core::network::{NetworkName, SlotConfig};
+use serde::Deserialize;
+use std::ffi::{OsStr, OsString};
 use std::path::PathBuf;
 
 /// CBOR Query Tool for Cardano transactions.
@@ -20,9 +24,71 @@ use std::path::PathBuf;
     cq fee tx.cbor --ada           Show fee in ADA
     cq outputs.0.address tx.cbor   Nested field access
     cq outputs.*.address tx.cbor   Wildcard (all addresses)
+    cq outputs.0:3.address tx.cbor Slice (first three outputs)
+    cq ..policy_id tx.cbor         Recursive descent (field at any depth)
+    cq 'ttl // 0' tx.cbor          Default when missing or null
+    cq '{fee: fee, outs: outputs | length}' tx.cbor
+                                   Build a custom JSON record
+    cq 'outputs.*.value.coin | sum' tx.cbor
+                                   Aggregate (sum, count, min, max, avg)
+    cq outputs.* tx.cbor --sort-by value.coin --desc --head 5
+                                   Five largest outputs
+    cq -q fee -q hash tx.cbor      Several queries, one decode
+    cq -f query.cq tx.cbor         Read the query from a file
     cq tx.cbor --json              JSON output
+    cq tx.cbor --paths             List every queryable path
+    cq outputs.0 tx.cbor --cbor    Original CBOR hex of the first output
+    cq fee --stream < txs.hex      One JSON line per hex transaction line
+    cq fee batch.cbor --json       One JSON line per transaction of a CBOR sequence or array
+    cq --generic 0.1 blob.cbor     Query any CBOR, not just transactions
+    cq tx.cbor --no-truncate       Full hashes and addresses in tables
+    cq inputs tx.cbor --outpoints  Inputs as txid#index strings
+    cq mint tx.cbor --script-ids   Policy IDs also as script1... bech32
+    cq certs tx.cbor --bech32-ids  Pools, DReps, scripts and key hashes as bech32
+    cq tx.cbor --table-style ascii --max-width 80
+                                   Tables for CI logs and narrow terminals
+    cq 'outputs.* | {addr: address.address, coin: value.coin}' tx.cbor --csv
+                                   CSV rows (or --tsv)
+    cq 'outputs.*' tx.cbor --format '{address.address}: {value.coin}'
+                                   One templated line per output
+    cq outputs tx.cbor --to addr1q8mnd...
+                                   Outputs paying an address (or payment key hash)
     cq tx.cbor --check             Validate only (exit code)
+    cq tx.signed --check --expect-hash 9581e5...
+                                   Exit code 1 unless the transaction hash matches
+    cq -e 'outputs[address.address ~ "addr1xyz"]' tx.cbor
+                                   Exit code 7 if nothing matches
     cq addr addr1q8mnd...          Decode any Cardano address
+    cq addr 61a3f1...              Addresses as hex bytes (or Byron base58)
+    cq addr build --payment-keyhash 1c12... --network preprod
+                                   Build an address from key hashes
+    cq addr addr1q8mnd... --stake  Stake address of a base address
+    cq addr addr1q8mnd... --hex    Address bytes as hex (--from-hex for the reverse)
+    cq summary tx.cbor             One-screen transaction summary
+    cq tui tx.cbor                 Interactive tree explorer
+    cq serve                       HTTP API on 127.0.0.1:8080
+    cq mempool --socket node.socket -q fee
+                                   One JSON line per transaction entering the mempool
+    cq follow --socket node.socket -q "body.outputs[address.address ~ 'addr1...']"
+                                   Watch new blocks for transactions paying an address
+    cq stats tx.cbor --json        Transaction totals as JSON
+    cq size tx.cbor                Byte-size breakdown per section
+    cq script validator.plutus     Decompile a Plutus script to UPLC
+    cq hash datum d8799f...        Datum hash of Plutus data CBOR
+    cq hash blake2b-256 poolMetadata.json
+                                   blake2b-256 (or blake2b-224) of any payload
+    cq graph tx.cbor --dot | dot -Tsvg > tx.svg
+                                   Inputs → tx → outputs diagram (Mermaid by default)
+    cq flow tx.cbor --utxos utxo.json
+                                   Net ADA and assets per address
+    cq utxo utxo.json -q '*[value.coin > 5000000]' --sort-by value.coin
+                                   Query a cardano-cli UTxO dump like tx outputs
+    cq grep 1a4899 tx.cbor         Where a hash or hex fragment appears
+    cq pparams update.cbor         Decode a protocol parameter update
+    cq votes tx.cbor --fetch-anchors
+                                   Check vote rationale documents against their hashes
+    cq certs tx.cbor --pool-metadata poolMetadata.json
+                                   Check pool registration metadata hashes
 
 QUERY SHORTCUTS:
     fee        → body.fee
@@ -30,7 +96,9 @@ QUERY SHORTCUTS:
     outputs    → body.outputs
     metadata   → auxiliary_data.metadata
     witnesses  → witness_set
-    hash       → (computed transaction hash)"#
+    hash       → (computed transaction hash)
+    addresses  → (computed list of all addresses)
+    assets     → (computed per-asset totals)"#
 )]
 pub struct Args {
     /// Subcommand to run.
@@ -47,25 +115,335 @@ pub struct Args {
     #[arg(value_name = "INPUT")]
     pub second: Option<String>,
 
+    /// Query to run (repeatable; the positional argument is then the input).
+    #[arg(long = "query", short = 'q', value_name = "QUERY")]
+    pub queries: Vec<String>,
+
+    /// Read the query from a file (multi-line, `#` comments allowed).
+    #[arg(long = "query-file", short = 'f', value_name = "FILE")]
+    pub query_file: Option<PathBuf>,
+
+    /// Remove duplicate results, keeping the first occurrence.
+    #[arg(long, global = true)]
+    pub unique: bool,
+
+    /// Sort multiple results by a field path (e.g. value.coin, or . for the value).
+    #[arg(long, global = true, value_name = "PATH")]
+    pub sort_by: Option<String>,
+
+    /// Sort in descending order (with --sort-by).
+    #[arg(long, global = true, requires = "sort_by")]
+    pub desc: bool,
+
+    /// Keep only outputs paying this bech32 address or payment credential
+    /// hash (e.g. `cq outputs --to addr1...`).
+    #[arg(long, global = true, value_name = "ADDRESS")]
+    pub to: Option<String>,
+
+    /// Keep only the first N results (applied after sorting).
+    #[arg(long, global = true, visible_alias = "limit", value_name = "N")]
+    pub head: Option<usize>,
+
+    /// Keep only the last N results (applied after sorting).
+    #[arg(long, global = true, value_name = "N", conflicts_with = "head")]
+    pub tail: Option<usize>,
+
     /// Output as JSON.
-    #[arg(long, short = 'j')]
+    #[arg(long, short = 'j', overrides_with = "no_json")]
     pub json: bool,
 
+    /// Pretty output even when `json = true` is set in the config file.
+    #[arg(long, overrides_with = "json")]
+    pub no_json: bool,
+
     /// Output raw CBOR diagnostic notation.
     #[arg(long, short = 'r')]
     pub raw: bool,
 
-    /// Display ADA amounts instead of lovelace.
-    #[arg(long, short = 'a')]
+    /// Output rows as CSV (objects are flattened into dotted columns).
+    #[arg(long, conflicts_with_all = ["json", "raw", "tsv"])]
+    pub csv: bool,
+
+    /// Output rows as TSV (objects are flattened into dotted columns).
+    #[arg(long, conflicts_with_all = ["json", "raw"])]
+    pub tsv: bool,
+
+    /// Print the original CBOR bytes (hex) of the queried path.
+    #[arg(long, conflicts_with_all = ["json", "raw", "csv", "tsv", "format"])]
+    pub cbor: bool,
+
+    /// List every queryable leaf path of the transaction (or of the query result).
+    #[arg(long, conflicts_with_all = ["raw", "csv", "tsv", "cbor", "format"])]
+    pub paths: bool,
+
+    /// Read one hex transaction per stdin line and print one JSON line per transaction.
+    #[arg(long, conflicts_with_all = ["raw", "csv", "tsv", "cbor", "format", "paths", "check"])]
+    pub stream: bool,
+
+    /// Query any CBOR item, not just transactions (maps, arrays, tags, bytes as hex).
+    #[arg(long, conflicts_with_all = ["cbor", "format", "stream"])]
+    pub generic: bool,
+
+    /// Print metadata in cardano-cli's detailed JSON schema (with the `metadata` query).
+    #[arg(long, conflicts_with_all = ["json", "raw", "csv", "tsv", "cbor", "format", "paths", "stream", "generic"])]
+    pub cardano_cli_schema: bool,
+
+    /// Render each result with a template, e.g. "{address.address}: {value.coin}".
+    #[arg(long, value_name = "TEMPLATE", conflicts_with_all = ["json", "raw", "csv", "tsv"])]
+    pub format: Option<String>,
+
+    /// Display ADA amounts instead of lovelace (JSON keeps lovelace and adds
+    /// `{field}_ada` strings).
+    #[arg(long, short = 'a', overrides_with = "no_ada")]
     pub ada: bool,
 
-    /// Validate only (exit code indicates result: 0=valid, 1=invalid).
+    /// Display lovelace even when `ada = true` is set in the config file.
+    #[arg(long, overrides_with = "ada")]
+    pub no_ada: bool,
+
+    /// Show asset names as hex (JSON also has `name_hex` and `name_utf8`).
+    #[arg(long, conflicts_with = "utf8_asset_names")]
+    pub hex_asset_names: bool,
+
+    /// Show asset names as UTF-8 text even when not printable.
+    #[arg(long)]
+    pub utf8_asset_names: bool,
+
+    /// Decompile witness set Plutus scripts to UPLC (`uplc` field with term
+    /// counts and the program).
+    #[arg(long)]
+    pub uplc: bool,
+
+    /// Add the bech32 form (`script1...`) of script credentials and policy
+    /// IDs as `script_id`, and show it in pretty output.
+    #[arg(long)]
+    pub script_ids: bool,
+
+    /// Render identifiers as bech32 in pretty and JSON output: pools
+    /// (pool1), DReps (drep1, CIP-129), committee members (cc_cold1,
+    /// cc_hot1), scripts (script1) and key hashes (addr_vkh1, stake_vkh1).
+    /// Policy IDs stay hex.
+    #[arg(long, overrides_with = "no_bech32_ids")]
+    pub bech32_ids: bool,
+
+    /// Keep identifiers hex even when `bech32_ids = true` is set in the
+    /// config file.
+    #[arg(long, overrides_with = "bech32_ids")]
+    pub no_bech32_ids: bool,
+
+    /// Token registry JSON (entries or a subject → {ticker, decimals} map), or
+    /// a directory of registry entry files, for asset tickers and decimals.
+    #[arg(long, value_name = "PATH")]
+    pub token_registry: Option<PathBuf>,
+
+    /// Validate only (exit code indicates result: 0=valid, 1=invalid or larger
+    /// than maxTxSize from --pparams, default 16384 bytes).
     #[arg(long, short = 'c')]
     pub check: bool,
 
+    /// Fail with exit code 1 unless the transaction hash is TXID. Combine
+    /// with --check to verify an artifact before submitting it.
+    #[arg(long, value_name = "TXID", value_parser = parse_tx_hash)]
+    pub expect_hash: Option<String>,
+
     /// Disable colored output.
-    #[arg(long)]
+    #[arg(long, overrides_with = "color")]
     pub no_color: bool,
+
+    /// Colored output even when `color = false` is set in the config file.
+    #[arg(long, overrides_with = "no_color")]
+    pub color: bool,
+
+    /// Print full hashes and addresses in pretty output instead of shortening them.
+    #[arg(long, overrides_with = "truncate")]
+    pub no_truncate: bool,
+
+    /// Shorten hashes and addresses even when `no_truncate = true` is set in
+    /// the config file.
+    #[arg(long, overrides_with = "no_truncate")]
+    pub truncate: bool,
+
+    /// Show inputs in pretty output as full `txid#index` outpoints, ready to
+    /// copy into other tools (JSON always has `outpoint`).
+    #[arg(long, overrides_with = "no_outpoints")]
+    pub outpoints: bool,
+
+    /// Show inputs' transaction IDs and indexes apart even when
+    /// `outpoints = true` is set in the config file.
+    #[arg(long, overrides_with = "outpoints")]
+    pub no_outpoints: bool,
+
+    /// Border style for tables in pretty output.
+    #[arg(long, value_enum, default_value_t = TableStyle::Utf8)]
+    pub table_style: TableStyle,
+
+    /// Maximum table width in characters (content wraps to fit).
+    #[arg(long, value_name = "N")]
+    pub max_width: Option<u16>,
+
+    /// Exit with code 7 if a query result is null, false or empty.
+    #[arg(long, short = 'e')]
+    pub exit_status: bool,
+
+    /// Network to read transactions for: names testnet addresses, encodes
+    /// bare credentials, shows slot times, and warns if the transaction is
+    /// for another network.
+    #[arg(long, global = true, value_name = "NETWORK", value_parser = network_parser())]
+    pub network: Option<NetworkName>,
+
+    /// Custom slot schedule for slot times and epochs, overriding
+    /// --network's: Unix time of a slot, that slot, the slot length in
+    /// seconds, and optionally the slot's epoch and the epoch length in slots.
+    #[arg(
+        long,
+        global = true,
+        value_name = "ZERO_TIME,ZERO_SLOT,SLOT_LENGTH[,ZERO_EPOCH,EPOCH_LENGTH]"
+    )]
+    pub slot_config: Option<SlotConfig>,
+
+    /// Protocol parameters JSON (from `cardano-cli query protocol-parameters`)
+    /// to check execution units and transaction size against.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub pparams: Option<PathBuf>,
+
+    /// Download each governance anchor URL and check its blake2b-256 hash
+    /// against the anchor's data hash.
+    #[arg(long, global = true)]
+    pub fetch_anchors: bool,
+
+    /// Check pool registration metadata hashes against this metadata
+    /// document (a file, or an http(s):// or ipfs:// URL).
+    #[arg(long, global = true, value_name = "FILE|URL")]
+    pub pool_metadata: Option<String>,
+
+    /// Log diagnostics to stderr: input detection, era, query parsing (-vv for more).
+    #[arg(long, short = 'v', action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Config file to use instead of ~/.config/cq/config.toml.
+    #[arg(long, value_name = "FILE", global = true)]
+    pub config: Option<PathBuf>,
+}
+
+/// A transaction hash: 64 hex digits, normalized to lowercase.
+fn parse_tx_hash(value: &str) -> Result<String, String> {
+    let hash = value.trim().to_lowercase();
+    if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err("expected a transaction hash of 64 hex digits".to_string());
+    }
+    Ok(hash)
+}
+
+/// Parser for `--network` values, listing them in help.
+fn network_parser() -> impl TypedValueParser<Value = NetworkName> {
+    PossibleValuesParser::new(NetworkName::ALL.map(NetworkName::as_str))
+        .map(|name| name.parse().expect("listed networks parse"))
+}
+
+/// Border style for tables in pretty output.
+#[derive(ValueEnum, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TableStyle {
+    /// Unicode box-drawing borders.
+    #[default]
+    Utf8,
+    /// ASCII borders (`+`, `-`, `|`).
+    Ascii,
+    /// No borders, columns separated by spaces.
+    Plain,
+}
+
+/// Kinds of items `cq extract` can write to files.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractKind {
+    /// Native and Plutus scripts (witness set, auxiliary data, reference scripts).
+    Scripts,
+    /// Inline datums and witness set datums (CBOR plus detailed-schema JSON).
+    Datums,
+}
+
+/// `cq addr` subcommands.
+#[derive(Subcommand, Debug)]
+pub enum AddressCommand {
+    /// Build a bech32 address from key hashes.
+    ///
+    /// A payment and a stake credential give a base address, the payment
+    /// key hash alone an enterprise address, and the stake credential alone
+    /// a reward address. The stake credential can come from a stake address.
+    /// Needs --network.
+    Build {
+        /// Payment key hash (28 bytes of hex).
+        #[arg(long, value_name = "HEX", required_unless_present_any = ["stake_keyhash", "stake_address"])]
+        payment_keyhash: Option<String>,
+
+        /// Stake key hash (28 bytes of hex).
+        #[arg(long, value_name = "HEX")]
+        stake_keyhash: Option<String>,
+
+        /// Stake address (stake1.../stake_test1...) whose credential to use.
+        #[arg(long, value_name = "ADDR", conflicts_with = "stake_keyhash")]
+        stake_address: Option<String>,
+
+        /// Output the decoded address as JSON instead of the bech32 string.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+}
+
+/// `cq hash` utilities.
+#[derive(Subcommand, Debug)]
+pub enum HashUtility {
+    /// Datum hash (blake2b-256) of Plutus data CBOR.
+    ///
+    /// The bytes are hashed as given, not re-encoded; --json adds the
+    /// decoded value.
+    Datum(HashInput),
+
+    /// blake2b-224 of any payload.
+    ///
+    /// Files are hashed byte for byte, a trailing newline included; hex
+    /// (given directly or on stdin) is decoded first.
+    #[command(name = "blake2b-224")]
+    Blake2b224(HashInput),
+
+    /// blake2b-256 of any payload.
+    ///
+    /// Files are hashed byte for byte, a trailing newline included; hex
+    /// (given directly or on stdin) is decoded first.
+    #[command(name = "blake2b-256")]
+    Blake2b256(HashInput),
+}
+
+/// Input of a `cq hash` utility.
+#[derive(clap::Args, Debug)]
+pub struct HashInput {
+    /// Hex string, file, or - for stdin (the default).
+    pub input: Option<String>,
+
+    /// Output as JSON.
+    #[arg(long, short = 'j')]
+    pub json: bool,
+}
+
+impl HashUtility {
+    /// The utility's input and options.
+    pub fn input(&self) -> &HashInput {
+        match self {
+            HashUtility::Datum(input)
+            | HashUtility::Blake2b224(input)
+            | HashUtility::Blake2b256(input) => input,
+        }
+    }
+}
+
+impl HashInput {
+    /// Where to read the payload: hex, a file, or stdin when omitted or `-`.
+    pub fn spec(&self) -> InputSpec {
+        match self.input.as_deref() {
+            None | Some("-") => InputSpec::Stdin,
+            Some(input) => InputSpec::detect(input).or_any_hex(),
+        }
+    }
 }
 
 /// Available subcommands.
@@ -75,23 +453,495 @@ pub enum Command {
     ///
     /// Parses a bech32 Cardano address and shows its components including
     /// type (base, enterprise, reward, pointer, byron), network,
-    /// and payment/stake credentials.
-    #[command(name = "addr")]
+    /// and payment/stake credentials. `cq addr build` goes the other way.
+    #[command(
+        name = "addr",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
     Address {
-        /// The bech32 address to decode (e.g., addr1..., stake1..., addr_test1...).
-        address: String,
+        #[command(subcommand)]
+        action: Option<AddressCommand>,
+
+        /// The address to decode: bech32 (addr1..., stake1..., addr_test1...),
+        /// hex address bytes, or a base58 Byron address.
+        #[arg(required = true)]
+        address: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+
+        /// Print only the stake (reward) address of a base address.
+        #[arg(long, conflicts_with_all = ["json", "hex", "from_hex"])]
+        stake: bool,
+
+        /// Print only the raw address bytes (header and payload) as hex.
+        #[arg(long, conflicts_with_all = ["json", "from_hex"])]
+        hex: bool,
+
+        /// Read the address as hex bytes and print it as bech32 (base58 for Byron).
+        #[arg(long, conflicts_with = "json")]
+        from_hex: bool,
+    },
+
+    /// Decode (and verify) a CIP-8 / CIP-30 COSE_Sign1 signature.
+    ///
+    /// Accepts COSE_Sign1 hex, or the JSON `{"signature", "key"}` returned
+    /// by a wallet's `signData`. With a key (from --key or the JSON), the
+    /// signature is verified and the key checked against the signing address.
+    #[command(name = "cose")]
+    Cose {
+        /// COSE_Sign1 hex or DataSignature JSON, inline or as a file (reads stdin if omitted).
+        input: Option<String>,
+
+        /// COSE_Key hex or raw Ed25519 public key hex to verify against.
+        #[arg(long)]
+        key: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Decode a protocol parameter update.
+    ///
+    /// Accepts protocol parameter update CBOR, or a transaction whose
+    /// parameter-change proposals are decoded. Cost models are summarized
+    /// by language.
+    #[command(name = "pparams")]
+    Pparams {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Show a compact one-screen summary of a transaction.
+    ///
+    /// Prints era, hash, input/output counts, total output, fee,
+    /// minted assets, certificates, metadata labels and script usage.
+    #[command(name = "summary")]
+    Summary {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Compute transaction totals.
+    ///
+    /// Reports total output lovelace, per-policy asset quantities,
+    /// withdrawals, deposits, witness counts and serialized size.
+    #[command(name = "stats")]
+    Stats {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Show the net value flow per address.
+    ///
+    /// Resolves the spent inputs against a UTxO file and reports, for each
+    /// address, the lovelace spent and received, the net change, and the
+    /// net change of each asset. A transaction whose scripts fail spends
+    /// its collateral instead.
+    #[command(name = "flow")]
+    Flow {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// UTxO file holding the spent inputs (`cardano-cli query utxo --out-file`
+        /// JSON, or CBOR).
+        #[arg(long, value_name = "FILE")]
+        utxos: PathBuf,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Decode a UTxO set into the transaction output model.
+    ///
+    /// Reads `cardano-cli query utxo --out-file` JSON, or the older CBOR
+    /// form (a map from input to output, binary or hex). Each UTxO is an
+    /// output with the `input` that references it, queryable with the usual
+    /// paths and filters (--to, --sort-by, --head, ...).
+    #[command(name = "utxo")]
+    Utxo {
+        /// UTxO file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Query to run on the UTxO array (all UTxOs if omitted).
+        #[arg(long, short = 'q', value_name = "QUERY")]
+        query: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Draw the transaction as a graph: inputs → transaction → outputs.
+    ///
+    /// Prints a Mermaid flowchart (or Graphviz DOT with --dot) labelled
+    /// with input references, output addresses and values, and the fee.
+    #[command(name = "graph")]
+    Graph {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Print Graphviz DOT instead of Mermaid.
+        #[arg(long)]
+        dot: bool,
+
+        /// Include collateral inputs and the collateral return.
+        #[arg(long)]
+        collateral: bool,
+
+        /// Include reference inputs.
+        #[arg(long)]
+        reference_inputs: bool,
+    },
+
+    /// Show a per-section byte-size breakdown of a transaction.
+    ///
+    /// Reports how many bytes of the original CBOR are used by the body,
+    /// each output, witnesses, scripts, datums and metadata.
+    #[command(name = "size")]
+    Size {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Decompile a Plutus script to UPLC.
+    ///
+    /// Takes a cardano-cli script file, script CBOR, or the script bytes
+    /// (flat, or wrapped in CBOR byte strings), and prints the language,
+    /// hash, term counts and the program in the textual UPLC syntax.
+    #[command(name = "script")]
+    Script {
+        /// Script file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Hash a datum or any payload.
+    ///
+    /// Without a utility, `cq hash <input>` is the `hash` query shortcut:
+    /// the transaction hash.
+    #[command(name = "hash", args_conflicts_with_subcommands = true)]
+    Hash {
+        #[command(subcommand)]
+        utility: Option<HashUtility>,
+
+        /// Input and options of the `hash` query shortcut.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, hide = true)]
+        args: Vec<String>,
+    },
+
+    /// Find where a hash or hex fragment appears in a transaction.
+    ///
+    /// Prints the query path of every decoded value containing the pattern
+    /// (input ids, signers, policy ids, script hashes, ...) and the offsets
+    /// of its occurrences in the raw bytes. Exits with code 7 if there are
+    /// none.
+    #[command(name = "grep")]
+    Grep {
+        /// Hex pattern to search for (an optional `0x` is ignored).
+        pattern: String,
+
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Print an annotated hex dump of a transaction's CBOR.
+    ///
+    /// Shows every item's offset, bytes, structure (array/map headers,
+    /// integers, byte strings) and query path, e.g. which bytes are the fee.
+    #[command(name = "explain")]
+    Explain {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
 
         /// Output as JSON.
         #[arg(long, short = 'j')]
         json: bool,
     },
 
+    /// Check whether a transaction re-encodes to the same bytes.
+    ///
+    /// Re-serializes each section with CML (preserving encoding details,
+    /// and canonically) and reports differences, including whether the
+    /// transaction id would change under canonical re-encoding.
+    #[command(name = "canonical")]
+    Canonical {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Output as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Encode CBOR diagnostic notation (or JSON) into CBOR.
+    ///
+    /// Accepts the notation printed by `--raw`, so a transaction can be
+    /// dumped, edited and re-encoded. With --metadata, the input is
+    /// label-keyed metadata JSON (cardano-cli detailed or no-schema) and
+    /// the output is auxiliary data. Prints hex unless --output is given.
+    #[command(name = "encode")]
+    Encode {
+        /// Diagnostic notation file or inline text (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Treat the input as metadata JSON and emit auxiliary data CBOR.
+        #[arg(long)]
+        metadata: bool,
+
+        /// Print the blake2b-256 hash of the encoded CBOR instead of the CBOR.
+        #[arg(long)]
+        hash: bool,
+
+        /// Write binary CBOR to this file instead of printing hex.
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+
+    /// Remove witnesses from a signed transaction.
+    ///
+    /// Drops vkey and bootstrap signatures by default, keeping scripts,
+    /// datums and redeemers. The body bytes are copied unchanged, so the
+    /// transaction id is preserved. Prints hex unless --output is given.
+    #[command(name = "strip")]
+    Strip {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Replace the whole witness set with an empty one.
+        #[arg(long, conflicts_with = "body")]
+        all: bool,
+
+        /// Emit only the transaction body.
+        #[arg(long)]
+        body: bool,
+
+        /// Write binary CBOR to this file instead of printing hex.
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+
+    /// Add witnesses to a transaction body or unsigned transaction.
+    ///
+    /// Each witness file may hold a witness set, a cardano-cli key witness
+    /// or a bare [vkey, signature] pair. Existing witnesses are kept and
+    /// duplicates merged; the body bytes (and transaction id) are unchanged.
+    #[command(name = "assemble")]
+    Assemble {
+        /// Transaction body or unsigned transaction file.
+        input: String,
+
+        /// Witness files to add.
+        #[arg(required = true)]
+        witnesses: Vec<String>,
+
+        /// Write binary CBOR to this file instead of printing hex.
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+
+    /// Apply targeted edits to a transaction and re-emit its CBOR.
+    ///
+    /// Untouched sections and fields keep their original bytes. Changing
+    /// the TTL or removing auxiliary data alters the body, so the
+    /// transaction id changes and existing signatures no longer verify.
+    #[command(name = "edit")]
+    Edit {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Remove the auxiliary data (and the body's auxiliary data hash).
+        #[arg(long)]
+        remove_aux: bool,
+
+        /// Drop the vkey witness at this index (repeatable).
+        #[arg(long, value_name = "INDEX")]
+        drop_witness: Vec<usize>,
+
+        /// Set the is_valid flag.
+        #[arg(long, value_name = "BOOL")]
+        valid: Option<bool>,
+
+        /// Set the TTL (invalid-hereafter slot).
+        #[arg(long, value_name = "SLOT")]
+        ttl: Option<u64>,
+
+        /// Write binary CBOR to this file instead of printing hex.
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+
+    /// Write the scripts or datums in a transaction to files.
+    ///
+    /// Each item is written as `<hash>.cbor` in the output directory (datums
+    /// also as `<hash>.json`), along with a `manifest.json` listing where
+    /// each one was found.
+    #[command(name = "extract")]
+    Extract {
+        /// What to extract.
+        what: ExtractKind,
+
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+
+        /// Directory to write files into (created if missing).
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        out_dir: PathBuf,
+
+        /// Print the manifest as JSON.
+        #[arg(long, short = 'j')]
+        json: bool,
+    },
+
+    /// Decode transactions as they appear in a directory (or as a file changes).
+    ///
+    /// Prints the query result for each new or changed file; with --json,
+    /// one JSON object per line (`{"file": ..., "result": ...}`). Files that
+    /// fail to decode are reported on stderr and watching continues.
+    #[command(name = "watch")]
+    Watch {
+        /// Directory or file to watch.
+        path: PathBuf,
+
+        /// Query to run on each transaction (the full transaction if omitted).
+        #[arg(long, short = 'q', value_name = "QUERY")]
+        query: Option<String>,
+
+        /// Output one JSON object per line.
+        #[arg(long, short = 'j')]
+        json: bool,
+
+        /// Polling interval in milliseconds.
+        #[arg(long, value_name = "MS", default_value_t = 500)]
+        interval: u64,
+
+        /// Also decode the files already present when watching starts.
+        #[arg(long)]
+        existing: bool,
+    },
+
+    /// Stream the transactions entering a local node's mempool.
+    ///
+    /// Connects to the node's socket and monitors its mempool
+    /// (LocalTxMonitor), printing one JSON line per new transaction
+    /// (`{"hash": ..., "result": ...}`) as with --stream. The network magic
+    /// comes from --network (mainnet by default) or --network-magic.
+    #[command(name = "mempool")]
+    Mempool {
+        /// Path of the node's socket (also `socket` in the config defaults).
+        #[arg(long, env = "CARDANO_NODE_SOCKET_PATH", value_name = "PATH")]
+        socket: Option<PathBuf>,
+
+        /// Query to run on each transaction (the full transaction if omitted).
+        #[arg(long, short = 'q', value_name = "QUERY")]
+        query: Option<String>,
+
+        /// Network magic of a network other than mainnet, preprod and preview.
+        #[arg(long, value_name = "MAGIC")]
+        network_magic: Option<u64>,
+
+        /// Print the transactions in the mempool now and exit instead of
+        /// waiting for new ones.
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// Follow new blocks on a local node's chain and print matching transactions.
+    ///
+    /// Connects to the node's socket and follows its chain from the tip
+    /// (ChainSync), running the query on each transaction of each new
+    /// block. Transactions whose result is not null, false or empty are
+    /// printed as one JSON line each (`{"slot", "block", "hash", "result"}`);
+    /// a rollback prints `{"rollback": {"slot", "hash"}}`.
+    #[command(name = "follow")]
+    Follow {
+        /// Path of the node's socket (also `socket` in the config defaults).
+        #[arg(long, env = "CARDANO_NODE_SOCKET_PATH", value_name = "PATH")]
+        socket: Option<PathBuf>,
+
+        /// Query to run on each transaction (every transaction matches if omitted).
+        #[arg(long, short = 'q', value_name = "QUERY")]
+        query: Option<String>,
+
+        /// Network magic of a network other than mainnet, preprod and preview.
+        #[arg(long, value_name = "MAGIC")]
+        network_magic: Option<u64>,
+
+        /// Exit after this many blocks.
+        #[arg(long, value_name = "N")]
+        blocks: Option<u64>,
+    },
+
+    /// Serve decoding and queries over HTTP.
+    ///
+    /// POST a transaction (binary CBOR or hex) to /decode for the full
+    /// transaction, or to /query?q=fee for a query result, as JSON.
+    /// GET /health reports the server is up.
+    #[command(name = "serve")]
+    Serve {
+        /// Address to listen on.
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        listen: String,
+    },
+
+    /// Explore a transaction interactively as a collapsible tree.
+    ///
+    /// Arrow keys (or hjkl) move and expand/collapse, / searches keys,
+    /// paths and values, n/N jump between matches, y copies the selected
+    /// value to the clipboard and q quits.
+    #[command(name = "tui")]
+    Tui {
+        /// Input file or hex string (reads stdin if omitted).
+        input: Option<String>,
+    },
+
     /// Check for updates and show upgrade instructions.
     ///
     /// Queries crates.io for the latest version and displays
     /// upgrade instructions if a newer version is available.
     #[command(name = "update")]
     Update,
+
+    /// Suggest query path completions (for shell completion scripts).
+    ///
+    /// Prints one suggestion per line: shortcuts matching the prefix and,
+    /// when a transaction is given, its field paths below the prefix.
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// Partially typed query path.
+        #[arg(default_value = "")]
+        prefix: String,
+
+        /// Transaction to read field paths from (file or hex).
+        input: Option<String>,
+    },
 }
 
 /// Specifies how to obtain input bytes.
@@ -106,6 +956,29 @@ pub enum InputSpec {
 }
 
 impl Args {
+    /// Parse a command line, exiting on errors like clap's `get_matches`.
+    ///
+    /// `hash` is both a subcommand and a query shortcut: without a hash
+    /// utility after it, the command line is parsed again with `hash` as a
+    /// `--query`, so `cq hash tx.cbor --json` keeps every transaction option.
+    pub fn matches_from(argv: Vec<OsString>) -> ArgMatches {
+        let matches = Args::command().get_matches_from(&argv);
+        let shortcut_args: Vec<&OsStr> = match matches.subcommand() {
+            Some(("hash", hash)) if hash.subcommand().is_none() => {
+                hash.get_raw("args").into_iter().flatten().collect()
+            }
+            _ => return matches,
+        };
+        let (prefix, rest) = argv.split_at(argv.len() - shortcut_args.len());
+        match prefix.split_last() {
+            Some((name, prefix)) if name == "hash" && rest.iter().eq(shortcut_args) => {
+                let query = [OsString::from("--query"), OsString::from("hash")];
+                Args::command().get_matches_from(prefix.iter().chain(&query).chain(rest))
+            }
+            _ => matches,
+        }
+    }
+
     /// Resolve the query and input from positional arguments.
     ///
     /// Returns (optional query path, input specification).
@@ -117,8 +990,10 @@ impl Args {
             // One argument: could be query (with stdin) or input
             (Some(first), None) => {
                 if Self::looks_like_query(first) {
+                    log::debug!("'{}' looks like a query; reading input from stdin", first);
                     (Some(first.as_str()), InputSpec::Stdin)
                 } else {
+                    log::debug!("'{}' does not look like a query; using it as input", first);
                     (None, InputSpec::detect(first))
                 }
             }
@@ -131,6 +1006,27 @@ impl Args {
         }
     }
 
+    /// Resolve all queries (`-q` flags plus any positional query) and the input.
+    ///
+    /// With `-q` or `-f`, a single positional argument is always the input.
+    /// The query file itself is read by the caller.
+    pub fn resolve_queries(&self) -> (Vec<&str>, InputSpec) {
+        if self.queries.is_empty() && self.query_file.is_none() {
+            let (query, input) = self.resolve();
+            return (query.into_iter().collect(), input);
+        }
+
+        let flags = self.queries.iter().map(String::as_str);
+        match (&self.first, &self.second) {
+            (Some(query), Some(input)) => (
+                std::iter::once(query.as_str()).chain(flags).collect(),
+                InputSpec::detect(input),
+            ),
+            (Some(input), None) => (flags.collect(), InputSpec::detect(input)),
+            (None, _) => (flags.collect(), InputSpec::Stdin),
+        }
+    }
+
     /// Heuristic to determine if a string looks like a query path.
     fn looks_like_query(s: &str) -> bool {
         // Known shortcuts
@@ -146,6 +1042,8 @@ impl Args {
             "certs",
             "withdrawals",
             "collateral",
+            "addresses",
+            "assets",
         ];
 
         if shortcuts.contains(&s) {
@@ -160,8 +1058,9 @@ impl Args {
             }
         }
 
-        // Dot notation or wildcard patterns
-        if s.contains('.') || s.contains('*') {
+        // Dot notation, wildcard, filter/slice brackets, pipelines, optionals
+        // or object construction
+        if s.contains(['.', '*', '[', '|', '?', '{']) {
             return true;
         }
 
@@ -175,6 +1074,11 @@ impl Args {
 }
 
 impl InputSpec {
+    /// Resolve an optional subcommand input argument, defaulting to stdin.
+    pub fn from_arg(input: Option<&str>) -> Self {
+        input.map_or(InputSpec::Stdin, InputSpec::detect)
+    }
+
     /// Detect input type from a string argument.
     pub fn detect(s: &str) -> Self {
         // Strip optional 0x prefix for hex detection
@@ -188,17 +1092,83 @@ impl InputSpec {
             && hex_candidate.chars().all(|c| c.is_ascii_hexdigit())
             && hex_candidate.starts_with("84")
         {
+            log::debug!("input is hex ({} characters)", hex_candidate.len());
             return InputSpec::Hex(hex_candidate.to_string());
         }
 
         // Otherwise treat as file path
+        log::debug!("input is a file path: {}", s);
         InputSpec::File(PathBuf::from(s))
     }
+
+    /// Treat a missing file path that is valid hex as hex, for input that
+    /// need not be a transaction (`--generic`).
+    pub fn or_any_hex(self) -> Self {
+        match self {
+            InputSpec::File(path) if !path.exists() => {
+                let text = path.to_string_lossy();
+                let hex_candidate = text.strip_prefix("0x").unwrap_or(&text);
+                if !hex_candidate.is_empty()
+                    && hex_candidate.len() % 2 == 0
+                    && hex_candidate.chars().all(|c| c.is_ascii_hexdigit())
+                {
+                    log::debug!("input is hex ({} characters)", hex_candidate.len());
+                    return InputSpec::Hex(hex_candidate.to_string());
+                }
+                InputSpec::File(path)
+            }
+            other => other,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use clap::FromArgMatches;
+
+    #[test]
+    fn test_hash_shortcut_and_utilities() {
+        let parse = |argv: &[&str]| {
+            let matches = Args::matches_from(argv.iter().map(OsString::from).collect());
+            Args::from_arg_matches(&matches).unwrap()
+        };
+
+        let args = parse(&["cq", "--json", "hash", "tx.cbor", "--raw"]);
+        assert!(args.command.is_none());
+        assert_eq!(args.queries, vec!["hash"]);
+        assert_eq!(args.first.as_deref(), Some("tx.cbor"));
+        assert!(args.json && args.raw);
+
+        let args = parse(&["cq", "hash"]);
+        assert!(args.command.is_none());
+        assert!(
+            matches!(args.resolve_queries(), (queries, InputSpec::Stdin) if queries == ["hash"])
+        );
+
+        let args = parse(&["cq", "hash", "blake2b-224", "-", "--json"]);
+        match args.command {
+            Some(Command::Hash {
+                utility: Some(HashUtility::Blake2b224(input)),
+                ..
+            }) => assert!(input.json && matches!(input.spec(), InputSpec::Stdin)),
+            other => panic!("expected blake2b-224, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_resolve_queries() {
+        let args = Args::parse_from(["cq", "-q", "fee", "-q", "hash", "tx.cbor"]);
+        let (queries, input) = args.resolve_queries();
+        assert_eq!(queries, vec!["fee", "hash"]);
+        assert!(matches!(input, InputSpec::File(_)));
+
+        let args = Args::parse_from(["cq", "outputs", "tx.cbor", "-q", "fee"]);
+        assert_eq!(args.resolve_queries().0, vec!["outputs", "fee"]);
+
+        let args = Args::parse_from(["cq", "fee", "tx.cbor"]);
+        assert_eq!(args.resolve_queries().0, vec!["fee"]);
+    }
 
     #[test]
     fn test_looks_like_query_shortcuts() {
@@ -0,0 +1,247 @@
+//! User configuration (`~/.config/cq/config.toml`).
+
+use crate::cli::{Args, Command, TableStyle};
+use crate::error::{Error, Result};
+use crate::network::NetworkName;
+use crate::query::LabelConfig;
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Settings read from the config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Defaults for output options.
+    pub defaults: Defaults,
+    /// Annotations for application-specific metadata labels, keyed by label.
+    #[serde(deserialize_with = "label_keys")]
+    pub labels: BTreeMap<u64, LabelConfig>,
+}
+
+/// Output options applied unless given on the command line.
+///
+/// ```toml
+/// [defaults]
+/// ada = true
+/// color = false
+/// table_style = "ascii"
+/// network = "preprod"
+/// socket = "/run/cardano-node/node.socket"
+/// ```
+///
+/// Switches set here are turned off on the command line with their
+/// negation, such as `--no-ada` or `--color`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Defaults {
+    /// Display ADA amounts instead of lovelace (`--ada`).
+    pub ada: bool,
+    /// Colored output; `false` is the same as `--no-color`.
+    pub color: Option<bool>,
+    /// JSON output (`--json`) unless another output format is requested.
+    pub json: bool,
+    /// Full hashes and addresses in pretty output (`--no-truncate`).
+    pub no_truncate: bool,
+    /// Inputs as full `txid#index` outpoints in pretty output (`--outpoints`).
+    pub outpoints: bool,
+    /// Identifiers as bech32 (`--bech32-ids`).
+    pub bech32_ids: bool,
+    /// Table border style (`--table-style`).
+    pub table_style: Option<TableStyle>,
+    /// Maximum table width (`--max-width`).
+    pub max_width: Option<u16>,
+    /// Network to read transactions for (`--network`).
+    pub network: Option<NetworkName>,
+    /// Token registry file or directory (`--token-registry`).
+    pub token_registry: Option<PathBuf>,
+    /// Node socket for `cq mempool` and `cq follow` (`--socket`).
+    pub socket: Option<PathBuf>,
+}
+
+impl Config {
+    /// Default config file location: `$XDG_CONFIG_HOME/cq/config.toml`,
+    /// falling back to `~/.config/cq/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        let env_dir = |name: &str| {
+            std::env::var_os(name)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        };
+        env_dir("XDG_CONFIG_HOME")
+            .or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+            .map(|dir| dir.join("cq").join("config.toml"))
+    }
+
+    /// Load the config from `path`, or from the default location if it
+    /// exists (empty otherwise).
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        if let Some(path) = path {
+            if !path.exists() {
+                return Err(Error::FileNotFound(path.to_path_buf()));
+            }
+            return Self::from_file(path);
+        }
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::from_file(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    /// Load the config from a file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        log::debug!("reading config file {}", path.display());
+        let text = std::fs::read_to_string(path).map_err(|source| Error::IoError {
+            path: Some(path.to_path_buf()),
+            source,
+        })?;
+        Self::parse(&text).map_err(|e| match e {
+            Error::InvalidConfig(message) => {
+                Error::InvalidConfig(format!("{}: {}", path.display(), message))
+            }
+            other => other,
+        })
+    }
+
+    /// Parse config TOML.
+    pub fn parse(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|e| Error::InvalidConfig(e.message().to_string()))
+    }
+}
+
+impl Defaults {
+    /// Fill in options that were not given on the command line.
+    pub fn apply(&self, args: &mut Args, matches: &ArgMatches) {
+        args.ada |= self.ada && !args.no_ada;
+        args.no_truncate |= self.no_truncate && !args.truncate;
+        args.outpoints |= self.outpoints && !args.no_outpoints;
+        args.bech32_ids |= self.bech32_ids && !args.no_bech32_ids;
+        if self.color == Some(false) && !args.color {
+            args.no_color = true;
+        }
+
+        let other_format = args.raw
+            || args.csv
+            || args.tsv
+            || args.cbor
+            || args.cardano_cli_schema
+            || args.format.is_some();
+        args.json |= self.json && !other_format && !args.no_json;
+
+        if let Some(style) = self.table_style {
+            if matches.value_source("table_style") != Some(ValueSource::CommandLine) {
+                args.table_style = style;
+            }
+        }
+        args.max_width = args.max_width.or(self.max_width);
+        args.network = args.network.or(self.network);
+        if args.token_registry.is_none() {
+            args.token_registry = self.token_registry.clone();
+        }
+        if let Some(Command::Mempool { socket, .. } | Command::Follow { socket, .. }) =
+            &mut args.command
+        {
+            if socket.is_none() {
+                *socket = self.socket.clone();
+            }
+        }
+    }
+}
+
+/// Deserialize a table keyed by metadata label (TOML keys are always strings).
+fn label_keys<'de, D>(deserializer: D) -> std::result::Result<BTreeMap<u64, LabelConfig>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    BTreeMap::<String, LabelConfig>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, label)| {
+            key.parse()
+                .map(|key| (key, label))
+                .map_err(|_| serde::de::Error::custom(format!("label '{}' is not a number", key)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_labels() {
+        let config = Config::parse(
+            r#"
+            [labels.1967]
+            name = "Acme Audit Record"
+            fields = { 0 = "version", 1 = "auditor" }
+            "#,
+        )
+        .unwrap();
+
+        let label = &config.labels[&1967];
+        assert_eq!(label.name, "Acme Audit Record");
+        assert_eq!(label.fields["1"], "auditor");
+
+        assert!(Config::parse("").unwrap().labels.is_empty());
+        assert!(Config::parse("[labels.abc]\nname = \"x\"").is_err());
+    }
+
+    #[test]
+    fn test_apply_defaults() {
+        use clap::{CommandFactory, FromArgMatches};
+
+        let config = Config::parse(
+            r#"
+            [defaults]
+            ada = true
+            json = true
+            table_style = "ascii"
+            max_width = 80
+            network = "preprod"
+            socket = "node.socket"
+            "#,
+        )
+        .unwrap();
+
+        let parse = |argv: &[&str]| {
+            let matches = Args::command().get_matches_from(argv);
+            let mut args = Args::from_arg_matches(&matches).unwrap();
+            config.defaults.apply(&mut args, &matches);
+            args
+        };
+
+        let args = parse(&["cq", "tx.cbor"]);
+        assert!(args.ada && args.json);
+        assert_eq!(args.table_style, TableStyle::Ascii);
+        assert_eq!(args.max_width, Some(80));
+        assert_eq!(args.network, Some(NetworkName::Preprod));
+
+        // Command-line flags win
+        let args = parse(&["cq", "tx.cbor", "--csv", "--table-style", "utf8"]);
+        assert!(!args.json);
+        assert_eq!(args.table_style, TableStyle::Utf8);
+        let args = parse(&["cq", "tx.cbor", "--network", "preview"]);
+        assert_eq!(args.network, Some(NetworkName::Preview));
+
+        // Negated flags turn configured switches off, the last flag winning
+        let args = parse(&["cq", "tx.cbor", "--no-ada", "--no-json"]);
+        assert!(!args.ada && !args.json);
+        let args = parse(&["cq", "tx.cbor", "--no-ada", "--ada"]);
+        assert!(args.ada);
+
+        let socket = |args: Args| match args.command {
+            Some(Command::Mempool { socket, .. }) => socket,
+            _ => None,
+        };
+        let args = parse(&["cq", "mempool", "--socket", "other.socket"]);
+        assert_eq!(socket(args), Some(PathBuf::from("other.socket")));
+        if std::env::var_os("CARDANO_NODE_SOCKET_PATH").is_none() {
+            let args = parse(&["cq", "mempool"]);
+            assert_eq!(socket(args), Some(PathBuf::from("node.socket")));
+        }
+
+        assert!(Config::parse("[defaults]\ncolour = false").is_err());
+    }
+}
@@ -0,0 +1,228 @@
+//! Combining a transaction body with witness sets.
+
+use super::{TxParts, encode_array};
+use crate::decode::{
+    CborSpan, array_items, decode_transaction, item_span, map_entries, read_header, read_uint,
+    witness_field_name,
+};
+use crate::encode::write_header;
+use crate::error::{Error, Result};
+use std::collections::BTreeMap;
+
+/// Witness set key for vkey witnesses.
+const VKEY_WITNESSES: u64 = 0;
+/// Witness set key for bootstrap (Byron) witnesses.
+const BOOTSTRAP_WITNESSES: u64 = 2;
+/// Tag marking a CBOR set.
+const SET_TAG: u64 = 258;
+
+/// Add witnesses to a transaction body or unsigned transaction.
+///
+/// `base` is either a bare body (a map) or a complete transaction whose
+/// existing witnesses are kept. Each witness is a witness set map, a
+/// `cardano-cli` key witness (`[0, vkeywitness]` or `[1, bootstrap]`) or a
+/// bare `[vkey, signature]` pair. Duplicate witnesses are merged and the
+/// body bytes are copied unchanged, so the transaction id is preserved.
+pub fn assemble(base: &[u8], witnesses: &[impl AsRef<[u8]>]) -> Result<Vec<u8>> {
+    let tx = match read_header(base, 0)?.major {
+        5 => None,
+        _ => Some(decode_transaction(base)?),
+    };
+    let parts = tx.as_ref().map(TxParts::split).transpose()?;
+
+    let mut merged = WitnessSet::default();
+    if let Some(parts) = &parts {
+        merged.add_map(parts.witness_set)?;
+    }
+    for witness in witnesses {
+        merged.add(witness.as_ref())?;
+    }
+    let witness_set = merged.encode();
+
+    let bytes = match parts {
+        Some(mut parts) => {
+            parts.witness_set = &witness_set;
+            parts.encode()
+        }
+        // A bare body is valid and carries no auxiliary data
+        None => {
+            let body = item_span(base, 0)?.slice(base);
+            encode_array(&[body, &witness_set, &[0xf5], &[0xf6]])
+        }
+    };
+
+    // Make sure the result is a transaction CML accepts
+    decode_transaction(&bytes)?;
+    Ok(bytes)
+}
+
+/// Witness set entries collected from several sources.
+#[derive(Default)]
+struct WitnessSet {
+    fields: BTreeMap<u64, Field>,
+}
+
+/// The elements of one witness set field.
+#[derive(Default)]
+struct Field {
+    /// Whether any source encoded the field as a tagged set.
+    tagged: bool,
+    /// Whether the field is a map (Conway redeemers) rather than an array.
+    is_map: bool,
+    /// (identity, encoding) pairs; the identity is the map key for maps.
+    items: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl WitnessSet {
+    /// Add a witness in any supported shape.
+    fn add(&mut self, bytes: &[u8]) -> Result<()> {
+        let header = read_header(bytes, 0)?;
+        if header.major == 5 {
+            return self.add_map(bytes);
+        }
+
+        let items = array_items(bytes, 0).map_err(|_| unsupported())?;
+        if items.len() != 2 {
+            return Err(unsupported());
+        }
+        let is_bytes = |item: &CborSpan| read_header(bytes, item.start).is_ok_and(|h| h.major == 2);
+        let (key, witness) = if items.iter().all(is_bytes) {
+            let pair: Vec<&[u8]> = items.iter().map(|item| item.slice(bytes)).collect();
+            (VKEY_WITNESSES, encode_array(&pair))
+        } else {
+            let key = match read_uint(bytes, items[0].start) {
+                Ok(0) => VKEY_WITNESSES,
+                Ok(1) => BOOTSTRAP_WITNESSES,
+                _ => return Err(unsupported()),
+            };
+            (key, items[1].slice(bytes).to_vec())
+        };
+
+        let field = self.fields.entry(key).or_default();
+        field.push(witness.clone(), witness);
+        Ok(())
+    }
+
+    /// Add every entry of a witness set map.
+    fn add_map(&mut self, bytes: &[u8]) -> Result<()> {
+        for (key, value) in map_entries(bytes, 0)? {
+            let key = read_uint(bytes, key.start)?;
+            if witness_field_name(key).is_none() {
+                return Err(unsupported());
+            }
+            let field = self.fields.entry(key).or_default();
+
+            let mut offset = value.start;
+            let mut header = read_header(bytes, offset)?;
+            if header.major == 6 && header.arg == Some(SET_TAG) {
+                field.tagged = true;
+                offset += header.len;
+                header = read_header(bytes, offset)?;
+            }
+
+            match header.major {
+                4 => {
+                    for item in array_items(bytes, offset)? {
+                        let item = item.slice(bytes).to_vec();
+                        field.push(item.clone(), item);
+                    }
+                }
+                5 => {
+                    field.is_map = true;
+                    for (k, v) in map_entries(bytes, offset)? {
+                        let mut entry = k.slice(bytes).to_vec();
+                        entry.extend_from_slice(v.slice(bytes));
+                        field.push(k.slice(bytes).to_vec(), entry);
+                    }
+                }
+                _ => {
+                    return Err(Error::DecodeFailed(format!(
+                        "Witness set field {} is not an array or map",
+                        key
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode the merged witness set with fields in key order.
+    fn encode(&self) -> Vec<u8> {
+        let fields: Vec<_> = self
+            .fields
+            .iter()
+            .filter(|(_, field)| !field.items.is_empty())
+            .collect();
+
+        let mut out = Vec::new();
+        write_header(&mut out, 5, fields.len() as u64);
+        for (key, field) in fields {
+            write_header(&mut out, 0, *key);
+            if field.tagged {
+                write_header(&mut out, 6, SET_TAG);
+            }
+            write_header(
+                &mut out,
+                if field.is_map { 5 } else { 4 },
+                field.items.len() as u64,
+            );
+            for (_, item) in &field.items {
+                out.extend_from_slice(item);
+            }
+        }
+        out
+    }
+}
+
+impl Field {
+    /// Add an item unless one with the same identity is already present.
+    fn push(&mut self, id: Vec<u8>, item: Vec<u8>) {
+        if !self.items.iter().any(|(existing, _)| *existing == id) {
+            self.items.push((id, item));
+        }
+    }
+}
+
+fn unsupported() -> Error {
+    Error::DecodeFailed(
+        "Expected a witness set, a [type, witness] key witness or a [vkey, signature] pair"
+            .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_witness_shapes() {
+        let mut set = WitnessSet::default();
+        // {0: [[h'01', h'02']]}
+        set.add(&[0xa1, 0x00, 0x81, 0x82, 0x41, 0x01, 0x41, 0x02])
+            .unwrap();
+        // The same witness as a bare pair, then as a cardano-cli key witness
+        set.add(&[0x82, 0x41, 0x01, 0x41, 0x02]).unwrap();
+        set.add(&[0x82, 0x00, 0x82, 0x41, 0x03, 0x41, 0x04])
+            .unwrap();
+        assert_eq!(
+            set.encode(),
+            [
+                0xa1, 0x00, 0x82, 0x82, 0x41, 0x01, 0x41, 0x02, 0x82, 0x41, 0x03, 0x41, 0x04
+            ]
+        );
+
+        assert!(set.add(&[0x83, 0x00, 0x01, 0x02]).is_err());
+        assert!(set.add(&[0xa1, 0x18, 0x63, 0x80]).is_err());
+    }
+
+    #[test]
+    fn test_merge_keeps_set_tag() {
+        let mut set = WitnessSet::default();
+        // {0: 258([[h'01', h'02']])}
+        set.add(&[
+            0xa1, 0x00, 0xd9, 0x01, 0x02, 0x81, 0x82, 0x41, 0x01, 0x41, 0x02,
+        ])
+        .unwrap();
+        assert_eq!(set.encode()[2..5], [0xd9, 0x01, 0x02]);
+    }
+}
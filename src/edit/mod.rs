@@ -0,0 +1,152 @@
+//! Producing modified transactions from their original bytes.
+//!
+//! Sections that are not changed are copied byte-for-byte, so the body
+//! (and therefore the transaction id) survives unless it is edited.
+
+mod assemble;
+mod mutate;
+mod strip;
+
+pub use assemble::assemble;
+pub use mutate::{Edits, edit};
+pub use strip::{StripMode, strip};
+
+use crate::decode::{DecodedTransaction, array_items, map_entries, read_uint, skip_tags};
+use crate::encode::write_header;
+use crate::error::Result;
+
+/// Original encodings of a transaction's top-level sections.
+pub(crate) struct TxParts<'a> {
+    pub body: &'a [u8],
+    pub witness_set: &'a [u8],
+    /// Present only in the four-element (Alonzo and later) layout.
+    pub is_valid: Option<&'a [u8]>,
+    pub auxiliary_data: &'a [u8],
+}
+
+impl<'a> TxParts<'a> {
+    /// Split a transaction's original bytes into its sections.
+    pub fn split(tx: &'a DecodedTransaction) -> Result<Self> {
+        let bytes = &tx.original_bytes;
+        let parts = array_items(bytes, 0)?;
+        let section = |idx: usize| parts[idx].slice(bytes);
+        Ok(TxParts {
+            body: section(0),
+            witness_set: section(1),
+            is_valid: (parts.len() == 4).then(|| section(2)),
+            auxiliary_data: section(parts.len() - 1),
+        })
+    }
+
+    /// Re-assemble the sections into a transaction.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut items = vec![self.body, self.witness_set];
+        items.extend(self.is_valid);
+        items.push(self.auxiliary_data);
+        encode_array(&items)
+    }
+}
+
+/// Encode a definite-length array from already-encoded items.
+pub(crate) fn encode_array(items: &[&[u8]]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_header(&mut out, 4, items.len() as u64);
+    for item in items {
+        out.extend_from_slice(item);
+    }
+    out
+}
+
+/// Re-encode a map with unsigned keys, keeping the entries `keep` accepts.
+///
+/// Retained keys and values are copied byte-for-byte.
+pub(crate) fn filter_map(bytes: &[u8], keep: impl Fn(u64) -> bool) -> Result<Vec<u8>> {
+    let entries = map_entries(bytes, skip_tags(bytes, 0)?)?;
+    let kept: Vec<_> = entries
+        .iter()
+        .filter(|(key, _)| !read_uint(bytes, key.start).is_ok_and(|k| !keep(k)))
+        .map(|(key, value)| (key.slice(bytes), value.slice(bytes)))
+        .collect();
+    Ok(encode_map(&kept))
+}
+
+/// Re-encode a map with unsigned keys, replacing, inserting or (with
+/// `None`) removing the entry for `key`.
+///
+/// New entries go before the first larger key; all other entries are
+/// copied byte-for-byte.
+pub(crate) fn set_map_entry(bytes: &[u8], key: u64, value: Option<&[u8]>) -> Result<Vec<u8>> {
+    let entries = map_entries(bytes, skip_tags(bytes, 0)?)?;
+    let mut encoded_key = Vec::new();
+    write_header(&mut encoded_key, 0, key);
+
+    let mut out = Vec::new();
+    let mut placed = false;
+    for (k, v) in &entries {
+        let existing = read_uint(bytes, k.start).ok();
+        if !placed && existing.is_some_and(|e| e >= key) {
+            out.extend(value.map(|value| (encoded_key.as_slice(), value)));
+            placed = true;
+        }
+        if existing != Some(key) {
+            out.push((k.slice(bytes), v.slice(bytes)));
+        }
+    }
+    if !placed {
+        out.extend(value.map(|value| (encoded_key.as_slice(), value)));
+    }
+    Ok(encode_map(&out))
+}
+
+/// Encode a definite-length map from already-encoded keys and values.
+fn encode_map(entries: &[(&[u8], &[u8])]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_header(&mut out, 5, entries.len() as u64);
+    for (key, value) in entries {
+        out.extend_from_slice(key);
+        out.extend_from_slice(value);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_map_keeps_entry_bytes() {
+        // {0: h'aa', 5: [1], 2: 0}
+        let bytes = [0xa3, 0x00, 0x41, 0xaa, 0x05, 0x81, 0x01, 0x02, 0x00];
+        assert_eq!(
+            filter_map(&bytes, |k| k == 5).unwrap(),
+            [0xa1, 0x05, 0x81, 0x01]
+        );
+        assert_eq!(filter_map(&bytes, |_| false).unwrap(), [0xa0]);
+    }
+
+    #[test]
+    fn test_set_map_entry() {
+        // {0: 1, 5: 2}
+        let bytes = [0xa2, 0x00, 0x01, 0x05, 0x02];
+        let set = |key, value: Option<&[u8]>| set_map_entry(&bytes, key, value).unwrap();
+        assert_eq!(set(5, Some(&[0x07])), [0xa2, 0x00, 0x01, 0x05, 0x07]);
+        assert_eq!(
+            set(3, Some(&[0x07])),
+            [0xa3, 0x00, 0x01, 0x03, 0x07, 0x05, 0x02]
+        );
+        assert_eq!(
+            set(9, Some(&[0x07])),
+            [0xa3, 0x00, 0x01, 0x05, 0x02, 0x09, 0x07]
+        );
+        assert_eq!(set(0, None), [0xa1, 0x05, 0x02]);
+        assert_eq!(set(3, None), bytes);
+    }
+
+    #[test]
+    fn test_encode_array() {
+        assert_eq!(
+            encode_array(&[&[0x01], &[0x42, 0xaa, 0xbb]]),
+            [0x82, 0x01, 0x42, 0xaa, 0xbb]
+        );
+    }
+}
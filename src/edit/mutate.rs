@@ -0,0 +1,102 @@
+//! Targeted edits of a transaction's sections.
+
+use super::{TxParts, set_map_entry};
+use crate::decode::{
+    DecodedTransaction, array_items, decode_transaction, map_entries, read_uint, skip_tags,
+};
+use crate::encode::write_header;
+use crate::error::{Error, Result};
+
+/// Body key holding the time-to-live slot.
+const TTL: u64 = 3;
+/// Body key holding the auxiliary data hash.
+const AUXILIARY_DATA_HASH: u64 = 7;
+/// Witness set key for vkey witnesses.
+const VKEY_WITNESSES: u64 = 0;
+
+/// Edits to apply to a transaction.
+#[derive(Debug, Clone, Default)]
+pub struct Edits {
+    /// Remove the auxiliary data and the body's hash of it.
+    pub remove_auxiliary_data: bool,
+    /// Indices of vkey witnesses to drop.
+    pub drop_witnesses: Vec<usize>,
+    /// New value for the is_valid flag.
+    pub is_valid: Option<bool>,
+    /// New TTL slot.
+    pub ttl: Option<u64>,
+}
+
+/// Apply edits, copying untouched sections and fields byte-for-byte.
+///
+/// Body edits (TTL, removing auxiliary data) change the transaction id and
+/// invalidate existing signatures.
+pub fn edit(tx: &DecodedTransaction, edits: &Edits) -> Result<Vec<u8>> {
+    let mut parts = TxParts::split(tx)?;
+
+    let mut body = parts.body.to_vec();
+    if let Some(ttl) = edits.ttl {
+        let mut value = Vec::new();
+        write_header(&mut value, 0, ttl);
+        body = set_map_entry(&body, TTL, Some(&value))?;
+    }
+    if edits.remove_auxiliary_data {
+        body = set_map_entry(&body, AUXILIARY_DATA_HASH, None)?;
+        parts.auxiliary_data = &[0xf6];
+    }
+    parts.body = &body;
+
+    let witness_set = drop_witnesses(parts.witness_set, &edits.drop_witnesses)?;
+    parts.witness_set = &witness_set;
+
+    let is_valid = edits
+        .is_valid
+        .map(|valid| [if valid { 0xf5 } else { 0xf4 }]);
+    if let Some(flag) = &is_valid {
+        if parts.is_valid.is_none() {
+            return Err(Error::InvalidQuery(
+                "Transaction has no is_valid flag (pre-Alonzo layout)".to_string(),
+            ));
+        }
+        parts.is_valid = Some(flag);
+    }
+
+    let bytes = parts.encode();
+    decode_transaction(&bytes)?;
+    Ok(bytes)
+}
+
+/// Remove vkey witnesses by index, keeping a set tag if present.
+fn drop_witnesses(witness_set: &[u8], indices: &[usize]) -> Result<Vec<u8>> {
+    if indices.is_empty() {
+        return Ok(witness_set.to_vec());
+    }
+
+    let (_, value) = map_entries(witness_set, 0)?
+        .into_iter()
+        .find(|(key, _)| read_uint(witness_set, key.start).is_ok_and(|k| k == VKEY_WITNESSES))
+        .ok_or_else(|| Error::FieldNotFound("witness_set.vkeywitnesses".to_string()))?;
+
+    let items = array_items(witness_set, value.start)?;
+    if let Some(idx) = indices.iter().find(|idx| **idx >= items.len()) {
+        return Err(Error::IndexOutOfBounds(*idx));
+    }
+    let kept: Vec<_> = items
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| !indices.contains(idx))
+        .map(|(_, item)| item.slice(witness_set))
+        .collect();
+
+    // An empty witness list is omitted rather than encoded as []
+    if kept.is_empty() {
+        return set_map_entry(witness_set, VKEY_WITNESSES, None);
+    }
+    let content = skip_tags(witness_set, value.start)?;
+    let mut encoded = witness_set[value.start..content].to_vec();
+    write_header(&mut encoded, 4, kept.len() as u64);
+    for item in kept {
+        encoded.extend_from_slice(item);
+    }
+    set_map_entry(witness_set, VKEY_WITNESSES, Some(&encoded))
+}
@@ -0,0 +1,34 @@
+//! Removing witnesses from a signed transaction.
+
+use super::{TxParts, filter_map};
+use crate::decode::DecodedTransaction;
+use crate::error::Result;
+
+/// Witness set keys holding signatures.
+const VKEY_WITNESSES: u64 = 0;
+const BOOTSTRAP_WITNESSES: u64 = 2;
+
+/// What to remove from a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StripMode {
+    /// Drop vkey and bootstrap signatures, keeping scripts, datums and redeemers.
+    Signatures,
+    /// Replace the witness set with an empty map.
+    AllWitnesses,
+    /// Emit only the transaction body.
+    BodyOnly,
+}
+
+/// Strip witnesses, preserving the body bytes (and so the transaction id).
+pub fn strip(tx: &DecodedTransaction, mode: StripMode) -> Result<Vec<u8>> {
+    let mut parts = TxParts::split(tx)?;
+    let witness_set = match mode {
+        StripMode::BodyOnly => return Ok(parts.body.to_vec()),
+        StripMode::AllWitnesses => vec![0xa0],
+        StripMode::Signatures => filter_map(parts.witness_set, |key| {
+            key != VKEY_WITNESSES && key != BOOTSTRAP_WITNESSES
+        })?,
+    };
+    parts.witness_set = &witness_set;
+    Ok(parts.encode())
+}
@@ -0,0 +1,417 @@
+//! CBOR diagnostic notation (RFC 8949 section 8) to CBOR bytes.
+//!
+//! Accepts the notation printed by `--raw`, including tags, indefinite
+//! lengths (`[_ ...]`, `{_ ...}`, `(_ h'..', ...)`), simple values and
+//! `/ comments /`. Plain JSON is a subset and encodes as generic CBOR.
+
+use super::write_header;
+use crate::error::{Error, Result};
+
+/// Encode diagnostic notation into CBOR bytes.
+///
+/// Headers use the shortest encoding and floats the smallest width that
+/// represents the value exactly, so output from `--raw` round-trips to
+/// the original bytes for canonically encoded input.
+pub fn encode_diagnostic(text: &str) -> Result<Vec<u8>> {
+    let mut parser = Parser {
+        text: text.as_bytes(),
+        pos: 0,
+    };
+    let mut out = Vec::new();
+    parser.item(&mut out)?;
+    parser.skip_whitespace()?;
+    if parser.pos < parser.text.len() {
+        return Err(parser.error("unexpected trailing input"));
+    }
+    Ok(out)
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// Parse one data item, appending its encoding to `out`.
+    fn item(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        self.skip_whitespace()?;
+        match self.peek() {
+            Some(b'[') => self.array(out),
+            Some(b'{') => self.map(out),
+            Some(b'(') => self.chunked_string(out),
+            Some(b'"') => {
+                let text = self.text_string()?;
+                write_header(out, 3, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+                Ok(())
+            }
+            Some(b'h') if self.text.get(self.pos + 1) == Some(&b'\'') => {
+                let bytes = self.byte_string()?;
+                write_header(out, 2, bytes.len() as u64);
+                out.extend_from_slice(&bytes);
+                Ok(())
+            }
+            Some(b'-' | b'0'..=b'9') => self.number(out),
+            Some(c) if c.is_ascii_alphabetic() => self.keyword(out),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// `[a, b]` or `[_ a, b]`.
+    fn array(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        self.expect(b'[')?;
+        let indefinite = self.indefinite_marker()?;
+        let mut items = Vec::new();
+        let count = self.sequence(b']', |p| p.item(&mut items))?;
+        write_container(out, 4, indefinite, count, &items);
+        Ok(())
+    }
+
+    /// `{k: v}` or `{_ k: v}`.
+    fn map(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        self.expect(b'{')?;
+        let indefinite = self.indefinite_marker()?;
+        let mut entries = Vec::new();
+        let count = self.sequence(b'}', |p| {
+            p.item(&mut entries)?;
+            p.skip_whitespace()?;
+            p.expect(b':')?;
+            p.item(&mut entries)
+        })?;
+        write_container(out, 5, indefinite, count, &entries);
+        Ok(())
+    }
+
+    /// Indefinite-length string: `(_ h'01', h'02')` or `(_ "a", "b")`.
+    fn chunked_string(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        self.expect(b'(')?;
+        if !self.indefinite_marker()? {
+            return Err(self.error("expected '_' after '('"));
+        }
+        let mut chunks = Vec::new();
+        let mut major = None;
+        self.sequence(b')', |p| {
+            p.skip_whitespace()?;
+            let chunk_major = if p.peek() == Some(b'"') { 3 } else { 2 };
+            if *major.get_or_insert(chunk_major) != chunk_major {
+                return Err(p.error("string chunks must all be bytes or all text"));
+            }
+            p.item(&mut chunks)
+        })?;
+        out.push((major.unwrap_or(2) << 5) | 31);
+        out.extend_from_slice(&chunks);
+        out.push(0xff);
+        Ok(())
+    }
+
+    /// Parse comma-separated elements up to `close`, returning their count.
+    fn sequence(
+        &mut self,
+        close: u8,
+        mut element: impl FnMut(&mut Self) -> Result<()>,
+    ) -> Result<u64> {
+        let mut count = 0;
+        self.skip_whitespace()?;
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(0);
+        }
+        loop {
+            element(self)?;
+            count += 1;
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    return Ok(count);
+                }
+                _ => return Err(self.error(&format!("expected ',' or '{}'", close as char))),
+            }
+        }
+    }
+
+    /// Consume a `_` indefinite-length marker if present.
+    fn indefinite_marker(&mut self) -> Result<bool> {
+        self.skip_whitespace()?;
+        if self.peek() == Some(b'_') {
+            self.pos += 1;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Integer, float, or tag (`258(...)`).
+    fn number(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+            if self.text[self.pos..].starts_with(b"Infinity") {
+                self.pos += "Infinity".len();
+                write_float(out, f64::NEG_INFINITY);
+                return Ok(());
+            }
+        }
+        while matches!(
+            self.peek(),
+            Some(b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-')
+        ) {
+            self.pos += 1;
+        }
+        let literal = std::str::from_utf8(&self.text[start..self.pos]).unwrap_or_default();
+
+        if literal.contains(['.', 'e', 'E']) {
+            let value: f64 = literal
+                .parse()
+                .map_err(|_| self.error_at(start, "invalid float"))?;
+            write_float(out, value);
+            return Ok(());
+        }
+
+        let value: i128 = literal
+            .parse()
+            .map_err(|_| self.error_at(start, "invalid integer"))?;
+
+        // An unsigned integer directly followed by '(' is a tag number
+        if self.peek() == Some(b'(') {
+            let tag = u64::try_from(value).map_err(|_| self.error_at(start, "invalid tag"))?;
+            self.pos += 1;
+            write_header(out, 6, tag);
+            self.item(out)?;
+            self.skip_whitespace()?;
+            return self.expect(b')');
+        }
+
+        let (major, arg) = if value >= 0 {
+            (0, u64::try_from(value))
+        } else {
+            (1, u64::try_from(-1 - value))
+        };
+        let arg = arg.map_err(|_| self.error_at(start, "integer out of range"))?;
+        write_header(out, major, arg);
+        Ok(())
+    }
+
+    /// `true`, `false`, `null`, `undefined`, `NaN`, `Infinity`, `simple(n)`.
+    fn keyword(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphanumeric()) {
+            self.pos += 1;
+        }
+        match &self.text[start..self.pos] {
+            b"false" => out.push(0xf4),
+            b"true" => out.push(0xf5),
+            b"null" => out.push(0xf6),
+            b"undefined" => out.push(0xf7),
+            b"NaN" => write_float(out, f64::NAN),
+            b"Infinity" => write_float(out, f64::INFINITY),
+            b"simple" => {
+                self.expect(b'(')?;
+                let mut number = Vec::new();
+                self.number(&mut number)?;
+                self.expect(b')')?;
+                match number.as_slice() {
+                    [n @ 0..=23] => out.push(0xe0 | n),
+                    [0x18, n @ 32..=255] => out.extend_from_slice(&[0xf8, *n]),
+                    _ => return Err(self.error_at(start, "invalid simple value")),
+                }
+            }
+            _ => return Err(self.error_at(start, "unknown keyword")),
+        }
+        Ok(())
+    }
+
+    /// A JSON-style quoted text string.
+    fn text_string(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                Some(b'"') => break,
+                Some(b'\\') => self.pos += 2,
+                Some(_) => self.pos += 1,
+                None => return Err(self.error_at(start, "unterminated string")),
+            }
+        }
+        self.pos += 1;
+        let literal = std::str::from_utf8(&self.text[start..self.pos])
+            .map_err(|_| self.error_at(start, "invalid UTF-8 in string"))?;
+        serde_json::from_str(literal).map_err(|_| self.error_at(start, "invalid string escape"))
+    }
+
+    /// `h'0011ff'` (whitespace inside the quotes is ignored).
+    fn byte_string(&mut self) -> Result<Vec<u8>> {
+        let start = self.pos;
+        self.pos += 2;
+        let end = self.text[self.pos..]
+            .iter()
+            .position(|c| *c == b'\'')
+            .map(|i| self.pos + i)
+            .ok_or_else(|| self.error_at(start, "unterminated byte string"))?;
+        let digits: Vec<u8> = self.text[self.pos..end]
+            .iter()
+            .copied()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect();
+        self.pos = end + 1;
+        hex::decode(digits).map_err(|_| self.error_at(start, "invalid hex in byte string"))
+    }
+
+    /// Skip whitespace and `/ comments /`.
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_ascii_whitespace() => self.pos += 1,
+                Some(b'/') => {
+                    let start = self.pos;
+                    let end = self.text[self.pos + 1..]
+                        .iter()
+                        .position(|c| *c == b'/')
+                        .ok_or_else(|| self.error_at(start, "unterminated comment"))?;
+                    self.pos += end + 2;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c as char)))
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> Error {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> Error {
+        Error::InvalidDiagnostic(format!("{} at offset {}", message, pos))
+    }
+}
+
+/// Write an array or map from its already-encoded contents.
+fn write_container(out: &mut Vec<u8>, major: u8, indefinite: bool, count: u64, contents: &[u8]) {
+    if indefinite {
+        out.push((major << 5) | 31);
+        out.extend_from_slice(contents);
+        out.push(0xff);
+    } else {
+        write_header(out, major, count);
+        out.extend_from_slice(contents);
+    }
+}
+
+/// Write a float using the smallest exact width (half, single or double).
+fn write_float(out: &mut Vec<u8>, value: f64) {
+    if let Some(half) = to_half(value) {
+        out.push(0xf9);
+        out.extend_from_slice(&half.to_be_bytes());
+    } else if (value as f32) as f64 == value {
+        out.push(0xfa);
+        out.extend_from_slice(&(value as f32).to_be_bytes());
+    } else {
+        out.push(0xfb);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+/// Convert to IEEE 754 half-precision bits if no precision is lost.
+fn to_half(value: f64) -> Option<u16> {
+    if value.is_nan() {
+        return Some(0x7e00);
+    }
+    let sign = if value.is_sign_negative() { 0x8000 } else { 0 };
+    if value.is_infinite() {
+        return Some(sign | 0x7c00);
+    }
+    if value == 0.0 {
+        return Some(sign);
+    }
+
+    let single = value as f32;
+    if single as f64 != value {
+        return None;
+    }
+    let bits = single.to_bits();
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127;
+    let mantissa = bits & 0x7f_ffff;
+
+    match exponent {
+        // Normal half-precision range
+        -14..=15 if mantissa & 0x1fff == 0 => {
+            Some(sign | (((exponent + 15) as u16) << 10) | (mantissa >> 13) as u16)
+        }
+        // Subnormal: value is a multiple of 2^-24 below 2^-14
+        -24..=-15 => {
+            let scaled = value.abs() * 2f64.powi(24);
+            (scaled.fract() == 0.0).then_some(sign | scaled as u16)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::bytes_to_diagnostic;
+
+    fn encode_hex(text: &str) -> String {
+        hex::encode(encode_diagnostic(text).unwrap())
+    }
+
+    #[test]
+    fn test_encode_scalars() {
+        assert_eq!(encode_hex("100"), "1864");
+        assert_eq!(encode_hex("-1"), "20");
+        assert_eq!(encode_hex("18446744073709551615"), "1bffffffffffffffff");
+        assert_eq!(encode_hex("-18446744073709551616"), "3bffffffffffffffff");
+        assert_eq!(encode_hex("h'ab cd'"), "42abcd");
+        assert_eq!(encode_hex("\"a\\u00fc\""), "6361c3bc");
+        assert_eq!(
+            encode_hex("[true, null, undefined, simple(16)]"),
+            "84f5f6f7f0"
+        );
+        assert_eq!(encode_hex("1.5"), "f93e00");
+        assert_eq!(encode_hex("0.1"), "fb3fb999999999999a");
+        assert_eq!(encode_hex("-Infinity"), "f9fc00");
+    }
+
+    #[test]
+    fn test_encode_containers() {
+        assert_eq!(encode_hex("258([_ 1, 2])"), "d901029f0102ff");
+        assert_eq!(
+            encode_hex("{0: [], 1: {_ }}"),
+            "a20080 01bfff".replace(' ', "")
+        );
+        assert_eq!(encode_hex("(_ h'01', h'02')"), "5f41014102ff");
+        assert_eq!(
+            encode_hex("{\"a\": [1, 2] / comment /}"),
+            "a161618201 02".replace(' ', "")
+        );
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let cbor = hex::decode("a2008201d8185f4101ff01fb3fb999999999999a").unwrap();
+        let text = bytes_to_diagnostic(&cbor).unwrap();
+        assert_eq!(encode_diagnostic(&text).unwrap(), cbor);
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        assert!(encode_diagnostic("[1, 2").is_err());
+        assert!(encode_diagnostic("1 2").is_err());
+        assert!(encode_diagnostic("h'abc'").is_err());
+        assert!(encode_diagnostic("(_ h'01', \"a\")").is_err());
+        assert!(encode_diagnostic("18446744073709551616").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! Metadata JSON to auxiliary data CBOR.
+
+use crate::error::{Error, Result};
+use cml_chain::auxdata::Metadata;
+use cml_chain::json::metadatums::{MetadataJsonSchema, encode_json_str_to_metadatum};
+use cml_core::serialization::Serialize;
+use serde_json::Value as JsonValue;
+
+/// Type keys of cardano-cli's detailed schema.
+const DETAILED_KEYS: [&str; 5] = ["int", "bytes", "string", "list", "map"];
+
+/// Encode label-keyed metadata JSON as auxiliary data CBOR.
+///
+/// Accepts cardano-cli's detailed schema (`{"674": {"map": [...]}}`) or
+/// its no-schema form (`{"674": {"msg": ["hi"]}}`, `0x` strings as bytes),
+/// detected per label. The result is a metadata map, the auxiliary data
+/// format whose hash goes in the body's `auxiliary_data_hash`.
+pub fn encode_metadata(text: &str) -> Result<Vec<u8>> {
+    let json: JsonValue =
+        serde_json::from_str(text).map_err(|e| Error::InvalidMetadata(e.to_string()))?;
+    let JsonValue::Object(labels) = json else {
+        return Err(Error::InvalidMetadata(
+            "expected an object keyed by metadata label".to_string(),
+        ));
+    };
+
+    let mut entries = labels
+        .iter()
+        .map(|(label, value)| {
+            let label: u64 = label
+                .parse()
+                .map_err(|_| Error::InvalidMetadata(format!("invalid label '{}'", label)))?;
+            let metadatum = encode_json_str_to_metadatum(&value.to_string(), schema_of(value))
+                .map_err(|e| Error::InvalidMetadata(format!("label {}: {}", label, e)))?;
+            Ok((label, metadatum))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    entries.sort_by_key(|(label, _)| *label);
+
+    let mut metadata = Metadata::new();
+    for (label, metadatum) in entries {
+        metadata.set(label, metadatum);
+    }
+    Ok(metadata.to_cbor_bytes())
+}
+
+/// Detailed-schema values are objects with a single type key.
+fn schema_of(value: &JsonValue) -> MetadataJsonSchema {
+    match value {
+        JsonValue::Object(map)
+            if map.len() == 1 && map.keys().all(|k| DETAILED_KEYS.contains(&k.as_str())) =>
+        {
+            MetadataJsonSchema::DetailedSchema
+        }
+        _ => MetadataJsonSchema::BasicConversions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_metadata_schemas() {
+        let detailed =
+            r#"{"674": {"map": [{"k": {"string": "msg"}, "v": {"list": [{"string": "hi"}]}}]}}"#;
+        let no_schema = r#"{"674": {"msg": ["hi"]}}"#;
+        assert_eq!(
+            hex::encode(encode_metadata(detailed).unwrap()),
+            "a11902a2a1636d736781626869"
+        );
+        assert_eq!(
+            encode_metadata(detailed).unwrap(),
+            encode_metadata(no_schema).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_metadata_labels() {
+        // Labels are sorted numerically and 0x strings become bytes
+        let bytes = encode_metadata(r#"{"10": 1, "9": "0xab"}"#).unwrap();
+        assert_eq!(hex::encode(bytes), "a20941ab0a01");
+
+        assert!(encode_metadata(r#"{"msg": 1}"#).is_err());
+        assert!(encode_metadata("[1]").is_err());
+        assert!(encode_metadata(r#"{"1": null}"#).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+//! Encoding text representations (diagnostic notation, metadata JSON)
+//! back into CBOR.
+
+mod diagnostic;
+mod metadata;
+
+pub use diagnostic::encode_diagnostic;
+pub use metadata::encode_metadata;
+
+/// Write a header with the shortest argument encoding.
+pub(crate) fn write_header(out: &mut Vec<u8>, major: u8, arg: u64) {
+    let major = major << 5;
+    match arg {
+        0..=23 => out.push(major | arg as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, arg as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(arg as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(arg as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&arg.to_be_bytes());
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Datums from outputs and the witness set.
+
+use super::Extraction;
+use crate::decode::DecodedTransaction;
+use crate::error::Result;
+use crate::query::decode_plutus_datum_to_json;
+use cml_chain::crypto::hash::hash_plutus_data;
+use cml_chain::plutus::PlutusData;
+use cml_chain::transaction::{DatumOption, TransactionOutput};
+use cml_core::serialization::Serialize;
+use cml_crypto::RawBytesEncoding;
+
+/// Collect every inline datum and witness set datum in the transaction.
+///
+/// Each datum is written with its original CBOR (so its hash matches) and
+/// a JSON file in cardano-node's detailed schema.
+pub fn extract_datums(tx: &DecodedTransaction) -> Result<Extraction> {
+    let mut extraction = Extraction::default();
+    let mut add = |datum: &PlutusData, kind, source: String| {
+        extraction.add(
+            hex::encode(hash_plutus_data(datum).to_raw_bytes()),
+            kind,
+            source,
+            datum.to_cbor_bytes(),
+            decode_plutus_datum_to_json(datum),
+        )
+    };
+
+    let body = tx.body();
+    let outputs = body
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(idx, output)| (format!("outputs.{}", idx), output))
+        .chain(
+            body.collateral_return
+                .iter()
+                .map(|output| ("collateral_return".to_string(), output)),
+        );
+    for (path, output) in outputs {
+        if let TransactionOutput::ConwayFormatTxOut(out) = output {
+            if let Some(DatumOption::Datum { datum, .. }) = &out.datum_option {
+                add(datum, "inline", format!("{}.datum", path));
+            }
+        }
+    }
+
+    for (idx, datum) in tx.witness_set().plutus_datums.iter().flatten().enumerate() {
+        add(
+            datum,
+            "witness",
+            format!("witness_set.plutus_datums.{}", idx),
+        );
+    }
+
+    Ok(extraction)
+}
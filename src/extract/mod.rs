@@ -0,0 +1,139 @@
+//! Extracting scripts and datums from a transaction into files.
+
+mod datums;
+mod scripts;
+
+pub use datums::extract_datums;
+pub use scripts::extract_scripts;
+
+use crate::error::{Error, Result};
+use colored::Colorize;
+use serde_json::Value as JsonValue;
+use std::path::Path;
+
+/// Name of the manifest written alongside the extracted files.
+pub const MANIFEST: &str = "manifest.json";
+
+/// A distinct item found in a transaction.
+pub struct ExtractedItem {
+    /// Hex hash identifying the item (used as the file name).
+    pub hash: String,
+    /// Item type (e.g. `native`, `plutus_v2`, `inline`).
+    pub kind: &'static str,
+    /// Query-style paths where the item appears (e.g. `outputs.0.script_ref`).
+    pub sources: Vec<String>,
+    /// CBOR encoding written to `<hash>.cbor`.
+    pub cbor: Vec<u8>,
+    /// Decoded form written to `<hash>.json`, if any.
+    pub json: Option<JsonValue>,
+}
+
+/// Items extracted from a transaction, deduplicated by hash.
+#[derive(Default)]
+pub struct Extraction {
+    pub items: Vec<ExtractedItem>,
+}
+
+impl ExtractedItem {
+    fn cbor_file(&self) -> String {
+        format!("{}.cbor", self.hash)
+    }
+
+    fn json_file(&self) -> Option<String> {
+        self.json.as_ref().map(|_| format!("{}.json", self.hash))
+    }
+}
+
+impl Extraction {
+    /// Record an item, merging sources when the hash was already seen.
+    fn add(
+        &mut self,
+        hash: String,
+        kind: &'static str,
+        source: String,
+        cbor: Vec<u8>,
+        json: Option<JsonValue>,
+    ) {
+        match self.items.iter_mut().find(|item| item.hash == hash) {
+            Some(item) => item.sources.push(source),
+            None => self.items.push(ExtractedItem {
+                hash,
+                kind,
+                sources: vec![source],
+                cbor,
+                json,
+            }),
+        }
+    }
+
+    /// Write every item and the manifest into `dir`, creating it if needed.
+    pub fn write_to(&self, dir: &Path) -> Result<()> {
+        let write = |name: &str, contents: &[u8]| {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).map_err(|e| Error::IoError {
+                path: Some(path),
+                source: e,
+            })
+        };
+
+        std::fs::create_dir_all(dir).map_err(|e| Error::IoError {
+            path: Some(dir.to_path_buf()),
+            source: e,
+        })?;
+        for item in &self.items {
+            write(&item.cbor_file(), &item.cbor)?;
+            if let (Some(name), Some(json)) = (item.json_file(), &item.json) {
+                write(&name, to_json_string(json)?.as_bytes())?;
+            }
+        }
+        write(MANIFEST, to_json_string(&self.to_json())?.as_bytes())
+    }
+
+    /// Convert to the JSON manifest.
+    pub fn to_json(&self) -> JsonValue {
+        let items: Vec<JsonValue> = self
+            .items
+            .iter()
+            .map(|item| {
+                let mut json = serde_json::json!({
+                    "hash": item.hash,
+                    "type": item.kind,
+                    "sources": item.sources,
+                    "file": item.cbor_file(),
+                    "size": item.cbor.len()
+                });
+                if let Some(name) = item.json_file() {
+                    json["json_file"] = JsonValue::String(name);
+                }
+                json
+            })
+            .collect();
+        JsonValue::Array(items)
+    }
+
+    /// Format a listing of the written files.
+    pub fn to_pretty(&self, dir: &Path) -> String {
+        let mut output = format!(
+            "{} {} item(s) to {}\n",
+            "Wrote".bold().cyan(),
+            self.items.len(),
+            dir.display()
+        );
+        for item in &self.items {
+            output.push_str(&format!(
+                "  {}  {:<10} {:>6} bytes  {}\n",
+                item.cbor_file(),
+                item.kind,
+                item.cbor.len(),
+                item.sources.join(", ").dimmed()
+            ));
+        }
+        output
+    }
+}
+
+fn to_json_string(value: &JsonValue) -> Result<String> {
+    serde_json::to_string_pretty(value)
+        .map(|json| json + "\n")
+        .map_err(|e| Error::FormatError(format!("JSON error: {}", e)))
+}
@@ -0,0 +1,138 @@
+//! Scripts from the witness set, auxiliary data and reference scripts.
+
+use super::Extraction;
+use crate::decode::DecodedTransaction;
+use crate::error::Result;
+use cml_chain::Script;
+use cml_chain::auxdata::AuxiliaryData;
+use cml_chain::transaction::TransactionOutput;
+use cml_core::serialization::Serialize;
+use cml_crypto::{RawBytesEncoding, ScriptHash};
+
+/// Collect every native and Plutus script in the transaction.
+///
+/// Each script's CBOR is taken as it appears in the transaction: the
+/// native script structure, or the byte string wrapping a Plutus script
+/// (the `cborHex` of a cardano-cli script file).
+pub fn extract_scripts(tx: &DecodedTransaction) -> Result<Extraction> {
+    let mut extraction = Extraction::default();
+    let mut add = |hash: ScriptHash, kind, source: String, cbor| {
+        extraction.add(hex::encode(hash.to_raw_bytes()), kind, source, cbor, None)
+    };
+
+    let witness_set = tx.witness_set();
+    let source = |field: &str, idx: usize| format!("witness_set.{}.{}", field, idx);
+    for (idx, s) in witness_set.native_scripts.iter().flatten().enumerate() {
+        add(
+            s.hash(),
+            "native",
+            source("native_scripts", idx),
+            s.to_cbor_bytes(),
+        );
+    }
+    for (idx, s) in witness_set.plutus_v1_scripts.iter().flatten().enumerate() {
+        add(
+            s.hash(),
+            "plutus_v1",
+            source("plutus_v1_scripts", idx),
+            s.to_cbor_bytes(),
+        );
+    }
+    for (idx, s) in witness_set.plutus_v2_scripts.iter().flatten().enumerate() {
+        add(
+            s.hash(),
+            "plutus_v2",
+            source("plutus_v2_scripts", idx),
+            s.to_cbor_bytes(),
+        );
+    }
+    for (idx, s) in witness_set.plutus_v3_scripts.iter().flatten().enumerate() {
+        add(
+            s.hash(),
+            "plutus_v3",
+            source("plutus_v3_scripts", idx),
+            s.to_cbor_bytes(),
+        );
+    }
+
+    let source = |field: &str, idx: usize| format!("auxiliary_data.{}.{}", field, idx);
+    match tx.auxiliary_data() {
+        Some(AuxiliaryData::ShelleyMA(aux)) => {
+            for (idx, s) in aux.auxiliary_scripts.iter().enumerate() {
+                add(
+                    s.hash(),
+                    "native",
+                    source("native_scripts", idx),
+                    s.to_cbor_bytes(),
+                );
+            }
+        }
+        Some(AuxiliaryData::Conway(aux)) => {
+            for (idx, s) in aux.native_scripts.iter().flatten().enumerate() {
+                add(
+                    s.hash(),
+                    "native",
+                    source("native_scripts", idx),
+                    s.to_cbor_bytes(),
+                );
+            }
+            for (idx, s) in aux.plutus_v1_scripts.iter().flatten().enumerate() {
+                add(
+                    s.hash(),
+                    "plutus_v1",
+                    source("plutus_v1_scripts", idx),
+                    s.to_cbor_bytes(),
+                );
+            }
+            for (idx, s) in aux.plutus_v2_scripts.iter().flatten().enumerate() {
+                add(
+                    s.hash(),
+                    "plutus_v2",
+                    source("plutus_v2_scripts", idx),
+                    s.to_cbor_bytes(),
+                );
+            }
+            for (idx, s) in aux.plutus_v3_scripts.iter().flatten().enumerate() {
+                add(
+                    s.hash(),
+                    "plutus_v3",
+                    source("plutus_v3_scripts", idx),
+                    s.to_cbor_bytes(),
+                );
+            }
+        }
+        Some(AuxiliaryData::Shelley(_)) | None => {}
+    }
+
+    let body = tx.body();
+    let outputs = body
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(idx, output)| (format!("outputs.{}", idx), output))
+        .chain(
+            body.collateral_return
+                .iter()
+                .map(|output| ("collateral_return".to_string(), output)),
+        );
+    for (path, output) in outputs {
+        if let TransactionOutput::ConwayFormatTxOut(out) = output {
+            if let Some(script) = &out.script_reference {
+                let (kind, cbor) = script_cbor(script);
+                add(script.hash(), kind, format!("{}.script_ref", path), cbor);
+            }
+        }
+    }
+
+    Ok(extraction)
+}
+
+/// Type and CBOR of the script inside a reference script.
+fn script_cbor(script: &Script) -> (&'static str, Vec<u8>) {
+    match script {
+        Script::Native { script, .. } => ("native", script.to_cbor_bytes()),
+        Script::PlutusV1 { script, .. } => ("plutus_v1", script.to_cbor_bytes()),
+        Script::PlutusV2 { script, .. } => ("plutus_v2", script.to_cbor_bytes()),
+        Script::PlutusV3 { script, .. } => ("plutus_v3", script.to_cbor_bytes()),
+    }
+}
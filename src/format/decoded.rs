@@ -0,0 +1,266 @@
+//! Pretty terminal output for decoded addresses, COSE signatures and
+//! protocol parameter updates.
+
+use crate::decode::{DecodedAddress, DecodedCoseSign1, Network};
+use colored::Colorize;
+use serde_json::Value as JsonValue;
+
+/// Format a decoded address for terminal output.
+pub fn address_to_pretty(address: &DecodedAddress, use_color: bool) -> String {
+    let mut output = String::new();
+
+    // Title
+    if use_color {
+        output.push_str(&format!("{}\n", "Address Details".bold().cyan()));
+    } else {
+        output.push_str("Address Details\n");
+    }
+
+    // Bech32
+    if use_color {
+        output.push_str(&format!("  {}: {}\n", "Address".bold(), address.bech32));
+    } else {
+        output.push_str(&format!("  Address: {}\n", address.bech32));
+    }
+
+    // Type
+    if use_color {
+        output.push_str(&format!(
+            "  {}: {}\n",
+            "Type".bold(),
+            address.address_type.description().green()
+        ));
+    } else {
+        output.push_str(&format!("  Type: {}\n", address.address_type.description()));
+    }
+
+    // Network
+    let network_str = address.network.as_str();
+    if use_color {
+        let colored_network = if matches!(address.network, Network::Mainnet) {
+            network_str.yellow()
+        } else {
+            network_str.blue()
+        };
+        output.push_str(&format!("  {}: {}\n", "Network".bold(), colored_network));
+    } else {
+        output.push_str(&format!("  Network: {}\n", network_str));
+    }
+
+    // Payment credential
+    if let Some(ref payment) = address.payment_credential {
+        if use_color {
+            output.push_str(&format!(
+                "  {}: {} {}\n",
+                "Payment".bold(),
+                payment.cred_type.as_str().cyan(),
+                payment.hash.dimmed()
+            ));
+        } else {
+            output.push_str(&format!(
+                "  Payment: {} {}\n",
+                payment.cred_type.as_str(),
+                payment.hash
+            ));
+        }
+    }
+
+    // Stake credential
+    if let Some(ref stake) = address.stake_credential {
+        if use_color {
+            output.push_str(&format!(
+                "  {}: {} {}\n",
+                "Stake".bold(),
+                stake.cred_type.as_str().cyan(),
+                stake.hash.dimmed()
+            ));
+        } else {
+            output.push_str(&format!(
+                "  Stake: {} {}\n",
+                stake.cred_type.as_str(),
+                stake.hash
+            ));
+        }
+    }
+
+    // Stake address
+    if let Some(ref stake_address) = address.stake_address {
+        if use_color {
+            output.push_str(&format!(
+                "  {}: {}\n",
+                "Stake address".bold(),
+                stake_address
+            ));
+        } else {
+            output.push_str(&format!("  Stake address: {}\n", stake_address));
+        }
+    }
+
+    // Pointer
+    if let Some(ref ptr) = address.pointer {
+        if use_color {
+            output.push_str(&format!(
+                "  {}: slot={}, tx={}, cert={}\n",
+                "Pointer".bold(),
+                ptr.slot,
+                ptr.tx_index,
+                ptr.cert_index
+            ));
+        } else {
+            output.push_str(&format!(
+                "  Pointer: slot={}, tx={}, cert={}\n",
+                ptr.slot, ptr.tx_index, ptr.cert_index
+            ));
+        }
+    }
+
+    output
+}
+
+/// Format a decoded COSE_Sign1 signature, with its verification if it was
+/// checked, for terminal output.
+pub fn cose_to_pretty(cose: &DecodedCoseSign1, use_color: bool) -> String {
+    let label = |name: &str| {
+        let padded = format!("{:<11}", format!("{}:", name));
+        if use_color {
+            padded.bold().to_string()
+        } else {
+            padded
+        }
+    };
+    let title = if use_color {
+        "COSE_Sign1".bold().cyan().to_string()
+    } else {
+        "COSE_Sign1".to_string()
+    };
+
+    let mut output = format!("{}\n", title);
+    if let Some(alg) = cose.algorithm_name() {
+        output.push_str(&format!("  {} {}\n", label("Algorithm"), alg));
+    }
+    if let Some(address) = cose.address_display() {
+        output.push_str(&format!("  {} {}\n", label("Address"), address));
+    }
+    if let Some(kid) = &cose.key_id {
+        output.push_str(&format!("  {} {}\n", label("Key id"), hex::encode(kid)));
+    }
+    let payload = match (&cose.payload, cose.payload_text()) {
+        (None, _) => "(detached)".to_string(),
+        (Some(_), Some(text)) => format!("{:?}", text),
+        (Some(bytes), None) => hex::encode(bytes),
+    };
+    output.push_str(&format!("  {} {}\n", label("Payload"), payload));
+    if cose.hashed {
+        output.push_str(&format!("  {} yes\n", label("Hashed")));
+    }
+    output.push_str(&format!(
+        "  {} {}\n",
+        label("Signature"),
+        hex::encode(&cose.signature)
+    ));
+
+    if let Some(v) = &cose.verification {
+        let status = |ok: bool, yes: &str, no: &str| match (ok, use_color) {
+            (true, true) => yes.green().to_string(),
+            (false, true) => no.red().to_string(),
+            (true, false) => yes.to_string(),
+            (false, false) => no.to_string(),
+        };
+        output.push_str(&format!(
+            "  {} {}\n",
+            label("Public key"),
+            hex::encode(&v.public_key)
+        ));
+        output.push_str(&format!(
+            "  {} {}\n",
+            label("Verified"),
+            status(v.valid, "valid", "INVALID")
+        ));
+        if let Some(matches) = v.key_matches_address {
+            output.push_str(&format!(
+                "  {} {}\n",
+                label("Key/addr"),
+                status(matches, "key matches address", "key does not match address")
+            ));
+        }
+    }
+    output
+}
+
+/// Render decoded updates as indented `name: value` lines, with cost models
+/// as their parameter counts.
+pub fn param_updates_to_pretty(json: &JsonValue, use_color: bool) -> String {
+    let heading = |text: String| {
+        if use_color {
+            text.bold().cyan().to_string()
+        } else {
+            text
+        }
+    };
+    match json {
+        JsonValue::Array(updates) => updates
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{}\n{}",
+                    heading(format!("Proposal {}", entry["proposal"])),
+                    update_lines(&entry["update"], 1)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        update => format!(
+            "{}\n{}",
+            heading("Protocol parameter update".to_string()),
+            update_lines(update, 1)
+        ),
+    }
+}
+
+fn update_lines(json: &JsonValue, depth: usize) -> String {
+    let indent = "  ".repeat(depth);
+    let Some(fields) = json.as_object() else {
+        return format!("{}{}\n", indent, json);
+    };
+    let mut output = String::new();
+    for (name, value) in fields {
+        match value {
+            JsonValue::Object(_) if name == "cost_models" => {
+                output.push_str(&format!("{}{}:\n", indent, name));
+                for (language, model) in value.as_object().into_iter().flatten() {
+                    output.push_str(&format!(
+                        "{}  {}: {} parameters\n",
+                        indent, language, model["parameters"]
+                    ));
+                }
+            }
+            JsonValue::Object(_) => {
+                output.push_str(&format!("{}{}:\n", indent, name));
+                output.push_str(&update_lines(value, depth + 1));
+            }
+            JsonValue::String(s) => output.push_str(&format!("{}{}: {}\n", indent, name, s)),
+            other => output.push_str(&format!("{}{}: {}\n", indent, name, other)),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_param_updates_to_pretty() {
+        let json = serde_json::json!({
+            "max_tx_size": 16384,
+            "cost_models": { "plutus_v2": { "parameters": 3, "values": [1, 2, 3] } },
+            "execution_prices": { "mem": "577/10000", "steps": "721/10000000" }
+        });
+
+        let pretty = param_updates_to_pretty(&json, false);
+        assert!(pretty.starts_with("Protocol parameter update\n"));
+        assert!(pretty.contains("  max_tx_size: 16384\n"));
+        assert!(pretty.contains("    plutus_v2: 3 parameters\n"));
+        assert!(pretty.contains("    mem: 577/10000\n"));
+    }
+}
@@ -1,21 +1,37 @@
 //! Output formatting module.
+//!
+//! Terminal output (pretty tables and colours) lives here; the other formats
+//! come from cq-core.
 
-mod json;
+mod decoded;
 mod pretty;
-mod raw;
+mod table;
 
 use crate::cli::Args;
 use crate::error::Result;
 use crate::query::QueryResult;
 
-pub use json::format_json;
+pub use cq_core::format::{
+    bytes_to_diagnostic, format_delimited, format_json, format_json_ada, format_raw,
+    format_template, metadata_detailed_json,
+};
+pub use decoded::{address_to_pretty, cose_to_pretty, param_updates_to_pretty};
 pub use pretty::format_pretty;
-pub use raw::format_raw;
+pub(crate) use pretty::{
+    display_address, display_hash, format_number_with_separators, format_uplc_summary,
+};
+pub(crate) use table::new_table;
 
 /// Format a query result according to the output flags.
 pub fn format_output(result: &QueryResult, args: &Args) -> Result<String> {
-    if args.json {
+    if args.json && args.ada {
+        format_json_ada(result)
+    } else if args.json {
         format_json(result)
+    } else if args.csv {
+        format_delimited(result, ',')
+    } else if args.tsv {
+        format_delimited(result, '\t')
     } else if args.raw {
         format_raw(result)
     } else {
@@ -2,10 +2,12 @@
 
 use crate::cli::Args;
 use crate::error::{Error, Result};
+use crate::format::new_table;
 use crate::query::{QueryResult, QueryValue};
 use colored::Colorize;
-use comfy_table::{Cell, ContentArrangement, Table, presets};
+use comfy_table::Cell;
 use serde_json::Value as JsonValue;
+use std::collections::BTreeSet;
 
 /// Format a query result as pretty terminal output.
 pub fn format_pretty(result: &QueryResult, args: &Args) -> Result<String> {
@@ -61,12 +63,22 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
 
         // TTL
         if let Some(ttl) = body.get("ttl").and_then(|v| v.as_u64()) {
-            output.push_str(&format!("  {} {}\n", "TTL:".dimmed(), ttl));
+            output.push_str(&format!(
+                "  {} {}{}\n",
+                "TTL:".dimmed(),
+                ttl,
+                slot_time(body, "ttl")
+            ));
         }
 
         // Validity interval start
         if let Some(start) = body.get("validity_interval_start").and_then(|v| v.as_u64()) {
-            output.push_str(&format!("  {} {}\n", "Valid from:".dimmed(), start));
+            output.push_str(&format!(
+                "  {} {}{}\n",
+                "Valid from:".dimmed(),
+                start,
+                slot_time(body, "validity_interval_start")
+            ));
         }
 
         output.push('\n');
@@ -74,7 +86,7 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
         // Inputs table
         if let Some(inputs) = body.get("inputs").and_then(|v| v.as_array()) {
             output.push_str(&format!("{} ({})\n", "Inputs".bold().cyan(), inputs.len()));
-            output.push_str(&format_inputs_table(inputs)?);
+            output.push_str(&format_inputs_table(inputs, args)?);
             output.push('\n');
         }
 
@@ -86,6 +98,15 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
                 outputs.len()
             ));
             output.push_str(&format_outputs_table(outputs, args)?);
+            for (idx, out) in outputs.iter().enumerate() {
+                if let Some(double_cbor) = out.pointer("/script_ref/double_cbor") {
+                    output.push_str(&format!(
+                        "  Output {} reference script: {}\n",
+                        idx,
+                        format_double_cbor(double_cbor, args)
+                    ));
+                }
+            }
             output.push('\n');
         }
 
@@ -93,7 +114,7 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
         if let Some(mint) = body.get("mint").and_then(|v| v.as_array()) {
             if !mint.is_empty() {
                 output.push_str(&format!("{}\n", "Mint".bold().cyan()));
-                output.push_str(&format_mint(mint)?);
+                output.push_str(&format_mint(mint, args)?);
                 output.push('\n');
             }
         }
@@ -106,7 +127,7 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
                     "Collateral".bold().cyan(),
                     collateral.len()
                 ));
-                output.push_str(&format_inputs_table(collateral)?);
+                output.push_str(&format_inputs_table(collateral, args)?);
                 output.push('\n');
             }
         }
@@ -134,7 +155,7 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
             output.push_str(&format!(
                 "  {} {}\n",
                 "Script data hash:".dimmed(),
-                truncate_hash(hash, 16)
+                display_hash(hash, 16, args)
             ));
         }
 
@@ -142,9 +163,20 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
         if let Some(signers) = body.get("required_signers").and_then(|v| v.as_array()) {
             if !signers.is_empty() {
                 output.push_str(&format!("{}\n", "Required Signers".bold().cyan()));
+                let missing = json
+                    .get("witness_set")
+                    .and_then(|w| w.get("missing_signers"))
+                    .and_then(|v| v.as_array());
                 for signer in signers {
                     if let Some(s) = signer.as_str() {
-                        output.push_str(&format!("  {}\n", truncate_hash(s, 16)));
+                        let status = match missing {
+                            Some(missing) if missing.contains(signer) => {
+                                format!(" {}", "✗ missing".red())
+                            }
+                            Some(_) => format!(" {}", "✓ signed".green()),
+                            None => String::new(),
+                        };
+                        output.push_str(&format!("  {}{}\n", display_hash(s, 16, args), status));
                     }
                 }
                 output.push('\n');
@@ -159,7 +191,7 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
                     "Certificates".bold().cyan(),
                     certs.len()
                 ));
-                output.push_str(&format_certificates(certs)?);
+                output.push_str(&format_certificates(certs, args)?);
                 output.push('\n');
             }
         }
@@ -176,12 +208,34 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
                 output.push('\n');
             }
         }
+
+        // Governance proposals
+        if let Some(proposals) = body.get("proposal_procedures").and_then(|v| v.as_array()) {
+            if !proposals.is_empty() {
+                output.push_str(&format!(
+                    "{} ({})\n",
+                    "Proposals".bold().cyan(),
+                    proposals.len()
+                ));
+                output.push_str(&format_proposals(proposals, args)?);
+                output.push('\n');
+            }
+        }
+
+        // Votes
+        if let Some(voters) = body.get("voting_procedures").and_then(|v| v.as_array()) {
+            if !voters.is_empty() {
+                output.push_str(&format!("{} ({})\n", "Votes".bold().cyan(), voters.len()));
+                output.push_str(&format_votes(voters, args)?);
+                output.push('\n');
+            }
+        }
     }
 
     // Witness set
     if let Some(witnesses) = json.get("witness_set") {
         output.push_str(&format!("{}\n", "Witnesses".bold().cyan()));
-        output.push_str(&format_witnesses(witnesses)?);
+        output.push_str(&format_witnesses(witnesses, args)?);
         output.push('\n');
     }
 
@@ -195,10 +249,20 @@ fn format_full_transaction(json: &JsonValue, args: &Args) -> Result<String> {
 }
 
 /// Format inputs as a table.
-fn format_inputs_table(inputs: &[JsonValue]) -> Result<String> {
-    let mut table = Table::new();
-    table.load_preset(presets::UTF8_FULL_CONDENSED);
-    table.set_content_arrangement(ContentArrangement::Dynamic);
+fn format_inputs_table(inputs: &[JsonValue], args: &Args) -> Result<String> {
+    let mut table = new_table(args);
+    if args.outpoints {
+        table.set_header(vec![
+            Cell::new("#").fg(comfy_table::Color::DarkGrey),
+            Cell::new("Outpoint").fg(comfy_table::Color::DarkGrey),
+        ]);
+        for (idx, input) in inputs.iter().enumerate() {
+            let outpoint = input.get("outpoint").and_then(|v| v.as_str());
+            table.add_row(vec![Cell::new(idx), Cell::new(outpoint.unwrap_or("?"))]);
+        }
+        return Ok(format!("{}\n", table));
+    }
+
     table.set_header(vec![
         Cell::new("#").fg(comfy_table::Color::DarkGrey),
         Cell::new("Transaction ID").fg(comfy_table::Color::DarkGrey),
@@ -214,7 +278,7 @@ fn format_inputs_table(inputs: &[JsonValue]) -> Result<String> {
 
         table.add_row(vec![
             Cell::new(idx),
-            Cell::new(truncate_hash(tx_id, 16)),
+            Cell::new(display_hash(tx_id, 16, args)),
             Cell::new(index),
         ]);
     }
@@ -224,9 +288,7 @@ fn format_inputs_table(inputs: &[JsonValue]) -> Result<String> {
 
 /// Format outputs as a table.
 fn format_outputs_table(outputs: &[JsonValue], args: &Args) -> Result<String> {
-    let mut table = Table::new();
-    table.load_preset(presets::UTF8_FULL_CONDENSED);
-    table.set_content_arrangement(ContentArrangement::Dynamic);
+    let mut table = new_table(args);
     table.set_header(vec![
         Cell::new("#").fg(comfy_table::Color::DarkGrey),
         Cell::new("Address").fg(comfy_table::Color::DarkGrey),
@@ -234,10 +296,13 @@ fn format_outputs_table(outputs: &[JsonValue], args: &Args) -> Result<String> {
         Cell::new("Datum").fg(comfy_table::Color::DarkGrey),
     ]);
 
+    let mut total_coin = 0u64;
+    let mut total_assets = BTreeSet::new();
+
     for (idx, output) in outputs.iter().enumerate() {
         let address = output
             .get("address")
-            .and_then(|v| v.as_str())
+            .and_then(|v| v.as_str().or_else(|| v.get("address")?.as_str()))
             .unwrap_or("?");
 
         let value = output.get("value");
@@ -250,6 +315,16 @@ fn format_outputs_table(outputs: &[JsonValue], args: &Args) -> Result<String> {
             .and_then(|v| v.get("multi_assets"))
             .and_then(|v| v.as_array());
 
+        total_coin = total_coin.saturating_add(coin);
+        for policy in multi_assets.into_iter().flatten() {
+            let policy_id = policy.get("policy_id").and_then(|v| v.as_str());
+            let assets = policy.get("assets").and_then(|v| v.as_array());
+            for asset in assets.into_iter().flatten() {
+                let name = asset.get("name_hex").or_else(|| asset.get("name"));
+                total_assets.insert((policy_id, name.and_then(|v| v.as_str())));
+            }
+        }
+
         let value_str = if let Some(assets) = multi_assets {
             if assets.is_empty() {
                 format_lovelace(coin, args)
@@ -270,7 +345,7 @@ fn format_outputs_table(outputs: &[JsonValue], args: &Args) -> Result<String> {
                 match datum_type {
                     "hash" => {
                         let hash = datum.get("hash").and_then(|v| v.as_str()).unwrap_or("?");
-                        format!("hash: {}", truncate_hash(hash, 8))
+                        format!("hash: {}", display_hash(hash, 8, args))
                     }
                     "inline" => {
                         let size = datum.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
@@ -284,17 +359,36 @@ fn format_outputs_table(outputs: &[JsonValue], args: &Args) -> Result<String> {
 
         table.add_row(vec![
             Cell::new(idx),
-            Cell::new(truncate_address(address, 24)),
+            Cell::new(display_address(address, 24, args)),
             Cell::new(value_str),
             Cell::new(datum_str),
         ]);
     }
 
+    // Footer with the summed value, counting each distinct asset once
+    if outputs.len() > 1 {
+        let total_str = if total_assets.is_empty() {
+            format_lovelace(total_coin, args)
+        } else {
+            format!(
+                "{} + {} asset(s)",
+                format_lovelace(total_coin, args),
+                total_assets.len()
+            )
+        };
+        table.add_row(vec![
+            Cell::new("Total").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new(""),
+            Cell::new(total_str).add_attribute(comfy_table::Attribute::Bold),
+            Cell::new(""),
+        ]);
+    }
+
     Ok(format!("{}\n", table))
 }
 
 /// Format mint information.
-fn format_mint(mint: &[JsonValue]) -> Result<String> {
+fn format_mint(mint: &[JsonValue], args: &Args) -> Result<String> {
     let mut output = String::new();
 
     for entry in mint {
@@ -304,30 +398,38 @@ fn format_mint(mint: &[JsonValue]) -> Result<String> {
             .unwrap_or("?");
 
         output.push_str(&format!(
-            "  {} {}\n",
+            "  {} {}",
             "Policy:".dimmed(),
-            truncate_hash(policy_id, 16)
+            display_hash(policy_id, 16, args)
         ));
+        if let Some(script_id) = entry.get("script_id").and_then(|v| v.as_str()) {
+            output.push_str(&format!(" ({})", display_address(script_id, 24, args)));
+        }
+        output.push('\n');
 
         if let Some(assets) = entry.get("assets").and_then(|v| v.as_array()) {
             for asset in assets {
                 let name = asset.get("name").and_then(|v| v.as_str()).unwrap_or("");
                 let amount = asset.get("amount").and_then(|v| v.as_i64()).unwrap_or(0);
 
+                let name_hex = asset.get("name_hex").and_then(|v| v.as_str());
                 let name_display = if name.is_empty() {
                     "(empty)".dimmed().to_string()
+                } else if name_hex == Some(name) {
+                    display_hash(name, 16, args)
                 } else {
-                    // Try to decode as UTF-8
-                    hex::decode(name)
-                        .ok()
-                        .and_then(|bytes| String::from_utf8(bytes).ok())
-                        .unwrap_or_else(|| truncate_hash(name, 16))
+                    name.to_string()
                 };
 
+                let amount_str = asset
+                    .get("formatted")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| amount.to_string());
                 let amount_color = if amount > 0 {
-                    format!("+{}", amount).green()
+                    format!("+{}", amount_str).green()
                 } else {
-                    format!("{}", amount).red()
+                    amount_str.red()
                 };
 
                 output.push_str(&format!("    {} {}\n", name_display, amount_color));
@@ -339,10 +441,8 @@ fn format_mint(mint: &[JsonValue]) -> Result<String> {
 }
 
 /// Format certificates.
-fn format_certificates(certs: &[JsonValue]) -> Result<String> {
-    let mut table = Table::new();
-    table.load_preset(presets::UTF8_FULL_CONDENSED);
-    table.set_content_arrangement(ContentArrangement::Dynamic);
+fn format_certificates(certs: &[JsonValue], args: &Args) -> Result<String> {
+    let mut table = new_table(args);
     table.set_header(vec![
         Cell::new("#").fg(comfy_table::Color::DarkGrey),
         Cell::new("Type").fg(comfy_table::Color::DarkGrey),
@@ -355,7 +455,15 @@ fn format_certificates(certs: &[JsonValue]) -> Result<String> {
             .and_then(|v| v.as_str())
             .unwrap_or("unknown");
 
-        let details = format_certificate_details(cert);
+        let mut details = format_certificate_details(cert, args);
+        if let Some(anchor) = cert.get("anchor") {
+            let anchor = format_anchor(anchor, args);
+            details = if details == "-" {
+                anchor
+            } else {
+                format!("{}\n{}", details, anchor)
+            };
+        }
 
         table.add_row(vec![
             Cell::new(idx),
@@ -392,34 +500,58 @@ fn format_cert_type(cert_type: &str) -> String {
 }
 
 /// Format certificate details based on type.
-fn format_certificate_details(cert: &JsonValue) -> String {
+fn format_certificate_details(cert: &JsonValue, args: &Args) -> String {
     let cert_type = cert.get("type").and_then(|v| v.as_str()).unwrap_or("");
-
-    match cert_type {
+    // The bech32 pool ID, or the key hash from older JSON
+    let pool = match (cert.get("pool_id"), cert.get("pool_keyhash")) {
+        (Some(JsonValue::String(id)), _) => Some(display_address(id, 24, args)),
+        (None, Some(JsonValue::String(hash))) => Some(display_hash(hash, 12, args)),
+        _ => None,
+    };
+
+    let details = match cert_type {
         "stake_delegation" => {
-            let pool = cert
-                .get("pool_keyhash")
-                .and_then(|v| v.as_str())
-                .map(|h| truncate_hash(h, 12))
-                .unwrap_or_else(|| "?".to_string());
-            format!("pool: {}", pool)
+            format!("pool: {}", pool.as_deref().unwrap_or("?"))
         }
         "pool_registration" => {
-            let pool = cert
-                .get("pool_keyhash")
-                .and_then(|v| v.as_str())
-                .map(|h| truncate_hash(h, 12))
-                .unwrap_or_else(|| "?".to_string());
+            let pool = pool.as_deref().unwrap_or("?");
             let margin = cert.get("margin").and_then(|v| v.as_str()).unwrap_or("?");
-            format!("{}, margin: {}", pool, margin)
+            match cert.get("pool_metadata") {
+                Some(metadata) => format!(
+                    "{}, margin: {}\n{}",
+                    pool,
+                    margin,
+                    format_document_link("metadata", metadata, args)
+                ),
+                None => format!("{}, margin: {}", pool, margin),
+            }
+        }
+        "auth_committee_hot_cert" | "resign_committee_cold_cert" => {
+            let id = |key: &str| {
+                let id = cert.get(key).and_then(|v| v.as_str())?;
+                Some(display_address(id, 24, args))
+            };
+            let ids: Vec<String> = [("cold", "committee_cold_id"), ("hot", "committee_hot_id")]
+                .into_iter()
+                .filter_map(|(label, key)| Some(format!("{}: {}", label, id(key)?)))
+                .collect();
+            if ids.is_empty() {
+                "-".to_string()
+            } else {
+                ids.join(", ")
+            }
         }
         "pool_retirement" => {
+            let pool = pool.as_deref().unwrap_or("?");
             let epoch = cert.get("epoch").and_then(|v| v.as_u64()).unwrap_or(0);
-            format!("epoch: {}", epoch)
+            match cert.get("epoch_time").and_then(|v| v.as_str()) {
+                Some(time) => format!("{}, epoch: {} (from {})", pool, epoch, time),
+                None => format!("{}, epoch: {}", pool, epoch),
+            }
         }
         "vote_deleg_cert" | "stake_vote_deleg_cert" => {
             if let Some(drep) = cert.get("drep") {
-                format_drep_details(drep)
+                format_drep_details(drep, args)
             } else {
                 "-".to_string()
             }
@@ -443,26 +575,55 @@ fn format_certificate_details(cert: &JsonValue) -> String {
         _ => {
             // For other types, show stake credential hash if present
             if let Some(cred) = cert.get("stake_credential") {
+                if let Some(id) = cred.get("script_id").and_then(|v| v.as_str()) {
+                    return display_address(id, 24, args);
+                }
                 if let Some(hash) = cred.get("hash").and_then(|v| v.as_str()) {
-                    return truncate_hash(hash, 16);
+                    return display_hash(hash, 16, args);
                 }
             }
             "-".to_string()
         }
+    };
+
+    // DRep certificates name the DRep first
+    if let Some(id) = cert.get("drep_id").and_then(|v| v.as_str()) {
+        let drep = format!("drep: {}", display_address(id, 24, args));
+        return if details == "-" {
+            drep
+        } else {
+            format!("{}, {}", drep, details)
+        };
+    }
+
+    // Delegations that also register or pick a DRep name their pool first
+    match (cert_type, pool) {
+        (
+            "stake_vote_deleg_cert" | "stake_reg_deleg_cert" | "stake_vote_reg_deleg_cert",
+            Some(pool),
+        ) => {
+            if details == "-" {
+                format!("pool: {}", pool)
+            } else {
+                format!("pool: {}, {}", pool, details)
+            }
+        }
+        _ => details,
     }
 }
 
 /// Format DRep details for display.
-fn format_drep_details(drep: &JsonValue) -> String {
+fn format_drep_details(drep: &JsonValue, args: &Args) -> String {
     let drep_type = drep.get("type").and_then(|v| v.as_str()).unwrap_or("?");
     match drep_type {
         "key" | "script" => {
-            let hash = drep
-                .get("hash")
-                .and_then(|v| v.as_str())
-                .map(|h| truncate_hash(h, 12))
-                .unwrap_or_else(|| "?".to_string());
-            format!("drep: {} ({})", hash, drep_type)
+            // The CIP-129 ID, or the hash from older JSON
+            let id = match (drep.get("id"), drep.get("hash")) {
+                (Some(JsonValue::String(id)), _) => display_address(id, 24, args),
+                (None, Some(JsonValue::String(hash))) => display_hash(hash, 12, args),
+                _ => "?".to_string(),
+            };
+            format!("drep: {} ({})", id, drep_type)
         }
         "always_abstain" => "drep: always_abstain".to_string(),
         "always_no_confidence" => "drep: always_no_confidence".to_string(),
@@ -470,11 +631,203 @@ fn format_drep_details(drep: &JsonValue) -> String {
     }
 }
 
+/// Format an anchor as its URL and document hash.
+fn format_anchor(anchor: &JsonValue, args: &Args) -> String {
+    format_document_link("anchor", anchor, args)
+}
+
+/// Format a `{"url", "data_hash"}` document link, with the outcome of any
+/// hash check.
+fn format_document_link(label: &str, anchor: &JsonValue, args: &Args) -> String {
+    let url = anchor.get("url").and_then(|v| v.as_str()).unwrap_or("?");
+    let hash = anchor
+        .get("data_hash")
+        .and_then(|v| v.as_str())
+        .map(|h| display_hash(h, 16, args))
+        .unwrap_or_else(|| "?".to_string());
+    let check = match anchor.get("hash_matches").and_then(|v| v.as_bool()) {
+        Some(true) => " ✓ hash matches".green().to_string(),
+        Some(false) => " ✗ hash mismatch".red().to_string(),
+        None => match anchor.get("fetch_error").and_then(|v| v.as_str()) {
+            Some(e) => format!(" ? {}", e).yellow().to_string(),
+            None => String::new(),
+        },
+    };
+    format!("{}: {} ({}){}", label, url, hash, check)
+}
+
+/// Format governance proposals: action type, payload, deposit and anchor.
+fn format_proposals(proposals: &[JsonValue], args: &Args) -> Result<String> {
+    let mut table = new_table(args);
+    table.set_header(vec![
+        Cell::new("#").fg(comfy_table::Color::DarkGrey),
+        Cell::new("Action").fg(comfy_table::Color::DarkGrey),
+        Cell::new("Details").fg(comfy_table::Color::DarkGrey),
+    ]);
+
+    for (idx, proposal) in proposals.iter().enumerate() {
+        let action = &proposal["action"];
+        let mut details = format_gov_action_details(action, args);
+        if let Some(previous) = action.get("previous_action").and_then(|v| v.as_str()) {
+            details.push(format!("follows: {}", display_hash(previous, 20, args)));
+        }
+        if let Some(deposit) = proposal.get("deposit").and_then(|v| v.as_u64()) {
+            details.push(format!("deposit: {}", format_lovelace(deposit, args)));
+        }
+        if let Some(anchor) = proposal.get("anchor") {
+            details.push(format_anchor(anchor, args));
+        }
+
+        table.add_row(vec![
+            Cell::new(idx),
+            Cell::new(format_gov_action_type(
+                action["type"].as_str().unwrap_or("unknown"),
+            )),
+            Cell::new(details.join("\n")),
+        ]);
+    }
+
+    Ok(format!("{}\n", table))
+}
+
+/// Format governance action type for display.
+fn format_gov_action_type(action_type: &str) -> String {
+    match action_type {
+        "parameter_change" => "Parameter Change".to_string(),
+        "hard_fork_initiation" => "Hard Fork".to_string(),
+        "treasury_withdrawals" => "Treasury Withdrawals".to_string(),
+        "no_confidence" => "No Confidence".to_string(),
+        "update_committee" => "Update Committee".to_string(),
+        "new_constitution" => "New Constitution".to_string(),
+        "info" => "Info".to_string(),
+        _ => action_type.to_string(),
+    }
+}
+
+/// Detail lines for a governance action's payload.
+fn format_gov_action_details(action: &JsonValue, args: &Args) -> Vec<String> {
+    let mut lines = Vec::new();
+    match action["type"].as_str().unwrap_or("") {
+        "parameter_change" => {
+            for (name, value) in action["update"].as_object().into_iter().flatten() {
+                let value = match value {
+                    JsonValue::String(s) => s.clone(),
+                    JsonValue::Object(_) if name == "cost_models" => value
+                        .as_object()
+                        .into_iter()
+                        .flatten()
+                        .map(|(language, model)| {
+                            format!("{} ({} parameters)", language, model["parameters"])
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    other => other.to_string(),
+                };
+                lines.push(format!("{}: {}", name, value));
+            }
+        }
+        "hard_fork_initiation" => {
+            let version = &action["version"];
+            lines.push(format!(
+                "protocol version: {}.{}",
+                version["major"], version["minor"]
+            ));
+        }
+        "treasury_withdrawals" => {
+            for withdrawal in action["withdrawals"].as_array().into_iter().flatten() {
+                lines.push(format!(
+                    "{}: {}",
+                    display_address(
+                        withdrawal["reward_address"].as_str().unwrap_or("?"),
+                        32,
+                        args
+                    ),
+                    format_lovelace(withdrawal["amount"].as_u64().unwrap_or(0), args)
+                ));
+            }
+        }
+        "update_committee" => {
+            for member in action["remove"].as_array().into_iter().flatten() {
+                let hash = member["hash"].as_str().unwrap_or("?");
+                lines.push(format!("remove: {}", display_hash(hash, 16, args)));
+            }
+            for member in action["add"].as_array().into_iter().flatten() {
+                let hash = member["credential"]["hash"].as_str().unwrap_or("?");
+                lines.push(format!(
+                    "add: {} (until epoch {})",
+                    display_hash(hash, 16, args),
+                    member["expires_epoch"]
+                ));
+            }
+            if let Some(threshold) = action["threshold"].as_str() {
+                lines.push(format!("threshold: {}", threshold));
+            }
+        }
+        "new_constitution" => {
+            let constitution = &action["constitution"];
+            if let Some(anchor) = constitution.get("anchor") {
+                lines.push(format!("constitution {}", format_anchor(anchor, args)));
+            }
+            if let Some(hash) = constitution["script_hash"].as_str() {
+                lines.push(format!(
+                    "guardrail script: {}",
+                    display_hash(hash, 16, args)
+                ));
+            }
+        }
+        _ => {}
+    }
+    if let Some(hash) = action["policy_hash"].as_str() {
+        lines.push(format!(
+            "guardrail script: {}",
+            display_hash(hash, 16, args)
+        ));
+    }
+    lines
+}
+
+/// Format votes, one row per vote.
+fn format_votes(voters: &[JsonValue], args: &Args) -> Result<String> {
+    let mut table = new_table(args);
+    table.set_header(vec![
+        Cell::new("Voter").fg(comfy_table::Color::DarkGrey),
+        Cell::new("Action").fg(comfy_table::Color::DarkGrey),
+        Cell::new("Vote").fg(comfy_table::Color::DarkGrey),
+    ]);
+
+    for entry in voters {
+        let voter = &entry["voter"];
+        let voter = format!(
+            "{} {}",
+            voter["role"].as_str().unwrap_or("?"),
+            voter["hash"]
+                .as_str()
+                .map(|h| display_hash(h, 12, args))
+                .unwrap_or_else(|| "?".to_string())
+        );
+        for vote in entry["votes"].as_array().into_iter().flatten() {
+            let action = vote["action_id"]
+                .as_str()
+                .map(|id| display_hash(id, 20, args))
+                .unwrap_or_else(|| "?".to_string());
+            let mut decision = vote["vote"].as_str().unwrap_or("?").to_string();
+            if let Some(anchor) = vote.get("anchor") {
+                decision = format!("{}\n{}", decision, format_anchor(anchor, args));
+            }
+            table.add_row(vec![
+                Cell::new(&voter),
+                Cell::new(action),
+                Cell::new(decision),
+            ]);
+        }
+    }
+
+    Ok(format!("{}\n", table))
+}
+
 /// Format withdrawals.
 fn format_withdrawals(withdrawals: &[JsonValue], args: &Args) -> Result<String> {
-    let mut table = Table::new();
-    table.load_preset(presets::UTF8_FULL_CONDENSED);
-    table.set_content_arrangement(ContentArrangement::Dynamic);
+    let mut table = new_table(args);
     table.set_header(vec![
         Cell::new("#").fg(comfy_table::Color::DarkGrey),
         Cell::new("Reward Address").fg(comfy_table::Color::DarkGrey),
@@ -493,7 +846,7 @@ fn format_withdrawals(withdrawals: &[JsonValue], args: &Args) -> Result<String>
 
         table.add_row(vec![
             Cell::new(idx),
-            Cell::new(truncate_address(reward_addr, 32)),
+            Cell::new(display_address(reward_addr, 32, args)),
             Cell::new(format_lovelace(amount, args)),
         ]);
     }
@@ -501,8 +854,39 @@ fn format_withdrawals(withdrawals: &[JsonValue], args: &Args) -> Result<String>
     Ok(format!("{}\n", table))
 }
 
+/// One line describing a decompiled program: version, flat size, term
+/// counts and the builtins it uses.
+pub(crate) fn format_uplc_summary(uplc: &JsonValue) -> String {
+    if let Some(error) = uplc.get("error").and_then(|v| v.as_str()) {
+        return format!("{} {}", "UPLC:".dimmed(), error.red());
+    }
+    let count = |field: &str| {
+        format_number_with_separators(uplc.get(field).and_then(|v| v.as_u64()).unwrap_or(0))
+    };
+    let mut line = format!(
+        "{} {}, {} B flat, {} terms ({} lambdas, {} applications, {} constants)",
+        "UPLC".dimmed(),
+        uplc.get("version").and_then(|v| v.as_str()).unwrap_or("?"),
+        count("size"),
+        count("terms"),
+        count("lambdas"),
+        count("applications"),
+        count("constants"),
+    );
+    if let Some(builtins) = uplc.get("builtins").and_then(|v| v.as_object())
+        && !builtins.is_empty()
+    {
+        let builtins: Vec<String> = builtins
+            .iter()
+            .map(|(name, count)| format!("{} ×{}", name, count))
+            .collect();
+        line.push_str(&format!(", builtins: {}", builtins.join(", ")));
+    }
+    line
+}
+
 /// Format witness set summary.
-fn format_witnesses(witnesses: &JsonValue) -> Result<String> {
+fn format_witnesses(witnesses: &JsonValue, args: &Args) -> Result<String> {
     let mut output = String::new();
 
     if let Some(count) = witnesses.get("vkeywitnesses").and_then(|v| v.as_u64()) {
@@ -527,17 +911,68 @@ fn format_witnesses(witnesses: &JsonValue) -> Result<String> {
             for script in scripts {
                 let hash = script.get("hash").and_then(|v| v.as_str()).unwrap_or("?");
                 let size = script.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
-                output.push_str(&format!("    {} <{} B>\n", truncate_hash(hash, 12), size));
+                output.push_str(&format!(
+                    "    {} <{} B>\n",
+                    display_hash(hash, 12, args),
+                    size
+                ));
+                if let Some(double_cbor) = script.get("double_cbor") {
+                    output.push_str(&format!(
+                        "      {}\n",
+                        format_double_cbor(double_cbor, args)
+                    ));
+                }
+                if let Some(uplc) = script.get("uplc") {
+                    output.push_str(&format!("      {}\n", format_uplc_summary(uplc)));
+                    if let Some(program) = uplc.get("program").and_then(|v| v.as_str()) {
+                        for line in program.lines() {
+                            output.push_str(&format!("      {}\n", line));
+                        }
+                    }
+                }
             }
         }
     }
 
+    if let Some(coverage) = witnesses.get("script_coverage") {
+        output.push_str(&format_script_coverage(coverage, args));
+    }
+
     if let Some(count) = witnesses.get("plutus_data").and_then(|v| v.as_u64()) {
         output.push_str(&format!("  {} {}\n", "Plutus data:".dimmed(), count));
     }
 
-    if let Some(count) = witnesses.get("redeemers").and_then(|v| v.as_u64()) {
-        output.push_str(&format!("  {} {}\n", "Redeemers:".dimmed(), count));
+    if let Some(coverage) = witnesses.get("datum_coverage") {
+        output.push_str(&format_datum_coverage(coverage, args));
+    }
+
+    if let Some(redeemers) = witnesses.get("redeemers").and_then(|v| v.as_array()) {
+        output.push_str(&format!(
+            "  {} {}\n",
+            "Redeemers:".dimmed(),
+            redeemers.len()
+        ));
+        for redeemer in redeemers {
+            let purpose = redeemer
+                .get("purpose")
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            let index = redeemer.get("index").and_then(|v| v.as_u64()).unwrap_or(0);
+            output.push_str(&format!(
+                "    {} #{} → {}\n",
+                purpose,
+                index,
+                format_redeemer_target(redeemer.get("target"), args)
+            ));
+        }
+    }
+
+    if let Some(total) = witnesses.get("ex_units_total") {
+        output.push_str(&format!(
+            "  {} {}\n",
+            "Ex units:".dimmed(),
+            format_ex_units(total)
+        ));
     }
 
     if output.is_empty() {
@@ -547,6 +982,168 @@ fn format_witnesses(witnesses: &JsonValue) -> Result<String> {
     Ok(output)
 }
 
+/// Format the scripts a transaction needs and the witness set's coverage.
+fn format_script_coverage(coverage: &JsonValue, args: &Args) -> String {
+    let list = |key: &str| {
+        coverage
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|items| items.as_slice())
+            .unwrap_or_default()
+    };
+    let mut output = format!(
+        "  {} {}",
+        "Scripts needed:".dimmed(),
+        list("required").len()
+    );
+    if list("missing").is_empty() {
+        output.push_str(&format!(" {}", "✓ all in witness set".green()));
+    }
+    output.push('\n');
+
+    for required in list("required") {
+        let hash = required.get("hash").and_then(|v| v.as_str()).unwrap_or("?");
+        if list("missing").iter().any(|m| m.as_str() == Some(hash)) {
+            let purpose = required
+                .get("purpose")
+                .and_then(|v| v.as_str())
+                .unwrap_or("?");
+            output.push_str(&format!(
+                "    {} {} ({}, unless a reference script)\n",
+                "✗ missing".red(),
+                display_hash(hash, 12, args),
+                purpose
+            ));
+        }
+    }
+    for hash in list("unused").iter().filter_map(|v| v.as_str()) {
+        output.push_str(&format!(
+            "    {} {} (not needed by mint, certificates, withdrawals or votes; may lock a spent input)\n",
+            "? unused".yellow(),
+            display_hash(hash, 12, args)
+        ));
+    }
+    output
+}
+
+/// Format the datum hashes outputs reference and the witness set's coverage.
+fn format_datum_coverage(coverage: &JsonValue, args: &Args) -> String {
+    let list = |key: &str| {
+        coverage
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|items| items.as_slice())
+            .unwrap_or_default()
+    };
+    let mut output = format!(
+        "  {} {}",
+        "Datums referenced:".dimmed(),
+        list("referenced").len()
+    );
+    if list("missing").is_empty() {
+        output.push_str(&format!(" {}", "✓ all in witness set".green()));
+    }
+    output.push('\n');
+
+    for hash in list("missing").iter().filter_map(|v| v.as_str()) {
+        let outputs: Vec<String> = list("referenced")
+            .iter()
+            .filter(|r| r.get("hash").and_then(|v| v.as_str()) == Some(hash))
+            .filter_map(|r| r.get("output").and_then(|v| v.as_u64()))
+            .map(|idx| idx.to_string())
+            .collect();
+        output.push_str(&format!(
+            "    {} {} (output {})\n",
+            "✗ missing".red(),
+            display_hash(hash, 12, args),
+            outputs.join(", ")
+        ));
+    }
+    for hash in list("orphans").iter().filter_map(|v| v.as_str()) {
+        output.push_str(&format!(
+            "    {} {} (no output references it; may be the datum of a spent input)\n",
+            "? orphan".yellow(),
+            display_hash(hash, 12, args)
+        ));
+    }
+    output
+}
+
+/// Warn about a Plutus script wrapped in an extra CBOR byte string.
+fn format_double_cbor(double_cbor: &JsonValue, args: &Args) -> String {
+    let hash = |key: &str| {
+        let hash = double_cbor.get(key).and_then(|v| v.as_str()).unwrap_or("?");
+        display_hash(hash, 12, args)
+    };
+    format!(
+        "{} (hashes to {}; without the extra layer, {})",
+        "✗ double CBOR-wrapped".red(),
+        hash("hash"),
+        hash("unwrapped_hash")
+    )
+}
+
+/// Format what a redeemer applies to, e.g. `input 3 (1a4899...1383e9#0)`.
+fn format_redeemer_target(target: Option<&JsonValue>, args: &Args) -> String {
+    let Some(target) = target else {
+        return "nothing at this index".red().to_string();
+    };
+    let str_field = |key: &str| target.get(key).and_then(|v| v.as_str()).unwrap_or("?");
+    if let Some(input) = target.get("input").and_then(|v| v.as_u64()) {
+        let (tx_id, index) = str_field("outpoint").split_once('#').unwrap_or(("?", "?"));
+        format!(
+            "input {} ({}#{})",
+            input,
+            display_hash(tx_id, 16, args),
+            index
+        )
+    } else if target.get("policy_id").is_some() {
+        format!("policy {}", display_hash(str_field("policy_id"), 16, args))
+    } else if let Some(cert) = target.get("certificate").and_then(|v| v.as_u64()) {
+        format!("certificate {} ({})", cert, str_field("type"))
+    } else if target.get("reward_address").is_some() {
+        format!(
+            "withdrawal {}",
+            display_address(str_field("reward_address"), 24, args)
+        )
+    } else if let Some(voter) = target.get("voter") {
+        let role = voter.get("role").and_then(|v| v.as_str()).unwrap_or("?");
+        let hash = voter.get("hash").and_then(|v| v.as_str()).unwrap_or("?");
+        format!("{} voter {}", role, display_hash(hash, 16, args))
+    } else if let Some(proposal) = target.get("proposal").and_then(|v| v.as_u64()) {
+        format!("proposal {}", proposal)
+    } else {
+        "?".to_string()
+    }
+}
+
+/// Format total execution units, with the budget they are checked against.
+fn format_ex_units(total: &JsonValue) -> String {
+    let part = |used: &str, max: &str, unit: &str| {
+        let used = total.get(used).and_then(|v| v.as_u64()).unwrap_or(0);
+        match total.get(max).and_then(|v| v.as_u64()) {
+            Some(max) => format!(
+                "{} / {} {} ({:.1}%)",
+                format_number_with_separators(used),
+                format_number_with_separators(max),
+                unit,
+                used as f64 * 100.0 / max.max(1) as f64
+            ),
+            None => format!("{} {}", format_number_with_separators(used), unit),
+        }
+    };
+    let units = format!(
+        "{}, {}",
+        part("mem", "max_mem", "mem"),
+        part("steps", "max_steps", "steps")
+    );
+    match total.get("within_budget").and_then(|v| v.as_bool()) {
+        Some(true) => format!("{} {}", units, "within budget".green()),
+        Some(false) => format!("{} {}", units, "OVER BUDGET".red().bold()),
+        None => units,
+    }
+}
+
 /// Format auxiliary data.
 fn format_auxiliary_data(aux: &JsonValue) -> Result<String> {
     let mut output = String::new();
@@ -622,7 +1219,7 @@ fn format_single_value(value: &QueryValue, args: &Args) -> Result<String> {
                 Ok(s.clone())
             } else if s.chars().all(|c| c.is_ascii_hexdigit()) && s.len() >= 16 {
                 // Looks like a hash - show truncated
-                Ok(truncate_hash(s, 24))
+                Ok(display_hash(s, 24, args))
             } else {
                 Ok(s.clone())
             }
@@ -654,6 +1251,22 @@ fn format_multiple_values(values: &[QueryValue], args: &Args) -> Result<String>
 }
 
 /// Format lovelace amount, optionally as ADA.
+/// When a slot field's slot starts and its epoch, as ` (time, epoch N)`,
+/// if the body has them.
+fn slot_time(body: &JsonValue, slot_field: &str) -> String {
+    let time = body
+        .get(format!("{}_time", slot_field))
+        .and_then(|v| v.as_str());
+    let epoch = body
+        .get(format!("{}_epoch", slot_field))
+        .and_then(|v| v.as_u64());
+    match (time, epoch) {
+        (Some(time), Some(epoch)) => format!(" ({}, epoch {})", time, epoch),
+        (Some(time), None) => format!(" ({})", time),
+        _ => String::new(),
+    }
+}
+
 fn format_lovelace(lovelace: u64, args: &Args) -> String {
     if args.ada {
         let ada = lovelace as f64 / 1_000_000.0;
@@ -664,7 +1277,7 @@ fn format_lovelace(lovelace: u64, args: &Args) -> String {
 }
 
 /// Format a number with thousand separators.
-fn format_number_with_separators(n: u64) -> String {
+pub(crate) fn format_number_with_separators(n: u64) -> String {
     let s = n.to_string();
     let mut result = String::new();
     for (i, c) in s.chars().rev().enumerate() {
@@ -676,6 +1289,28 @@ fn format_number_with_separators(n: u64) -> String {
     result
 }
 
+/// Shorten a hash for display unless --no-truncate is set.
+pub(crate) fn display_hash(hash: &str, max_len: usize, args: &Args) -> String {
+    // Bech32 identifiers (--bech32-ids) keep their prefix visible
+    if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return display_address(hash, max_len.max(24), args);
+    }
+    if args.no_truncate {
+        hash.to_string()
+    } else {
+        truncate_hash(hash, max_len)
+    }
+}
+
+/// Shorten an address for display unless --no-truncate is set.
+pub(crate) fn display_address(addr: &str, max_len: usize, args: &Args) -> String {
+    if args.no_truncate {
+        addr.to_string()
+    } else {
+        truncate_address(addr, max_len)
+    }
+}
+
 /// Truncate a hash for display.
 fn truncate_hash(hash: &str, max_len: usize) -> String {
     if hash.len() <= max_len {
@@ -715,6 +1350,7 @@ fn truncate_address(addr: &str, max_len: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::TableStyle;
 
     #[test]
     fn test_format_number_with_separators() {
@@ -743,11 +1379,52 @@ mod tests {
             command: None,
             first: None,
             second: None,
+            queries: vec![],
+            query_file: None,
+            unique: false,
+            sort_by: None,
+            desc: false,
+            to: None,
+            head: None,
+            tail: None,
             json: false,
+            no_json: false,
             raw: false,
+            csv: false,
+            tsv: false,
+            cbor: false,
+            format: None,
             ada: true,
+            no_ada: false,
+            hex_asset_names: false,
+            utf8_asset_names: false,
+            uplc: false,
+            script_ids: false,
+            bech32_ids: false,
+            no_bech32_ids: false,
+            token_registry: None,
             check: false,
             no_color: true,
+            color: false,
+            no_truncate: false,
+            truncate: false,
+            outpoints: false,
+            no_outpoints: false,
+            table_style: TableStyle::Utf8,
+            max_width: None,
+            verbose: 0,
+            config: None,
+            network: None,
+            slot_config: None,
+            pparams: None,
+            fetch_anchors: false,
+            pool_metadata: None,
+            expect_hash: None,
+            cardano_cli_schema: false,
+            paths: false,
+            stream: false,
+            generic: false,
+            exit_status: false,
         };
         assert_eq!(format_lovelace(2_500_000, &args), "2.500000 ADA");
     }
@@ -758,11 +1435,52 @@ mod tests {
             command: None,
             first: None,
             second: None,
+            queries: vec![],
+            query_file: None,
+            unique: false,
+            sort_by: None,
+            desc: false,
+            to: None,
+            head: None,
+            tail: None,
             json: false,
+            no_json: false,
             raw: false,
+            csv: false,
+            tsv: false,
+            cbor: false,
+            format: None,
             ada: false,
+            no_ada: false,
+            hex_asset_names: false,
+            utf8_asset_names: false,
+            uplc: false,
+            script_ids: false,
+            bech32_ids: false,
+            no_bech32_ids: false,
+            token_registry: None,
             check: false,
             no_color: true,
+            color: false,
+            no_truncate: false,
+            truncate: false,
+            outpoints: false,
+            no_outpoints: false,
+            table_style: TableStyle::Utf8,
+            max_width: None,
+            verbose: 0,
+            config: None,
+            network: None,
+            slot_config: None,
+            pparams: None,
+            fetch_anchors: false,
+            pool_metadata: None,
+            expect_hash: None,
+            cardano_cli_schema: false,
+            paths: false,
+            stream: false,
+            generic: false,
+            exit_status: false,
         };
         assert_eq!(format_lovelace(2_500_000, &args), "2,500,000 lovelace");
     }
@@ -0,0 +1,19 @@
+//! Table construction shared by pretty output and reports.
+
+use crate::cli::{Args, TableStyle};
+use comfy_table::{ContentArrangement, Table, presets};
+
+/// Create a table using the configured border style and maximum width.
+pub(crate) fn new_table(args: &Args) -> Table {
+    let mut table = Table::new();
+    table.load_preset(match args.table_style {
+        TableStyle::Utf8 => presets::UTF8_FULL_CONDENSED,
+        TableStyle::Ascii => presets::ASCII_FULL_CONDENSED,
+        TableStyle::Plain => presets::NOTHING,
+    });
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    if let Some(width) = args.max_width {
+        table.set_width(width);
+    }
+    table
+}
@@ -4,4 +4,8 @@ mod detect;
 mod read;
 
 pub use detect::InputSource;
-pub use read::read_input;
+pub(crate) use read::detect_and_decode_stdin;
+pub use read::{
+    InputBytes, read_input, read_protocol_params, read_query_file, read_text_input,
+    read_token_registry, read_utxo_set,
+};
@@ -1,22 +1,52 @@
 //! Input reading implementation.
 
 use crate::cli::InputSpec;
+use crate::decode::{UtxoSet, decode_utxo_set};
 use crate::error::{Error, Result};
 use crate::input::InputSource;
+use crate::pparams::ProtocolParams;
+use crate::query::{TokenRegistry, parse_token_registry, strip_comments};
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, IsTerminal, Read};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// Files at least this large are memory-mapped instead of read into memory.
+const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// Input bytes: read into memory, or memory-mapped for large files.
+pub enum InputBytes {
+    /// Bytes held in memory.
+    Owned(Vec<u8>),
+    /// A memory-mapped file.
+    Mapped(memmap2::Mmap),
+}
+
+impl Deref for InputBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            InputBytes::Owned(bytes) => bytes,
+            InputBytes::Mapped(map) => map,
+        }
+    }
+}
+
+impl AsRef<[u8]> for InputBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
 
 /// Read input bytes from the specified source.
-pub fn read_input(spec: &InputSpec) -> Result<Vec<u8>> {
+pub fn read_input(spec: &InputSpec) -> Result<InputBytes> {
     let source = InputSource::from_spec(spec)?;
 
     match source {
-        InputSource::File(path) => fs::read(&path).map_err(|e| Error::IoError {
-            path: Some(path),
-            source: e,
-        }),
+        InputSource::File(path) => read_file(path),
 
-        InputSource::Bytes(bytes) => Ok(bytes),
+        InputSource::Bytes(bytes) => Ok(InputBytes::Owned(bytes)),
 
         InputSource::Stdin => {
             let mut buffer = Vec::new();
@@ -28,16 +58,145 @@ pub fn read_input(spec: &InputSpec) -> Result<Vec<u8>> {
                 })?;
 
             // Try to detect if stdin is hex-encoded or binary CBOR
-            detect_and_decode_stdin(buffer)
+            detect_and_decode_stdin(buffer).map(InputBytes::Owned)
+        }
+    }
+}
+
+/// Read a file, memory-mapping it if it is large.
+fn read_file(path: PathBuf) -> Result<InputBytes> {
+    let io_error = |source| Error::IoError {
+        path: Some(path.clone()),
+        source,
+    };
+    let file = fs::File::open(&path).map_err(io_error)?;
+    let len = file.metadata().map_err(io_error)?.len();
+    if len < MMAP_THRESHOLD {
+        return fs::read(&path).map(InputBytes::Owned).map_err(io_error);
+    }
+
+    log::debug!("memory-mapping {} ({} bytes)", path.display(), len);
+    // SAFETY: the map is only read, and cq reads its inputs once right after
+    // opening them. A file truncated by another process meanwhile can still
+    // fault; that is the accepted cost of mapping instead of copying.
+    let map = unsafe { memmap2::Mmap::map(&file) }.map_err(io_error)?;
+    Ok(InputBytes::Mapped(map))
+}
+
+/// Read a query from a file, dropping `#` comments and joining lines.
+pub fn read_query_file(path: &Path) -> Result<String> {
+    if !path.exists() {
+        return Err(Error::FileNotFound(path.to_path_buf()));
+    }
+    let text = fs::read_to_string(path).map_err(|e| Error::IoError {
+        path: Some(path.to_path_buf()),
+        source: e,
+    })?;
+
+    let query = strip_comments(&text);
+    if query.is_empty() {
+        return Err(Error::InvalidQuery(format!(
+            "Query file is empty: {}",
+            path.display()
+        )));
+    }
+    Ok(query)
+}
+
+/// Read token metadata from a registry JSON file, or from a directory of
+/// registry entry files (such as the token registry's `mappings/`).
+pub fn read_token_registry(path: &Path) -> Result<TokenRegistry> {
+    if !path.exists() {
+        return Err(Error::FileNotFound(path.to_path_buf()));
+    }
+    let io_error = |source| Error::IoError {
+        path: Some(path.to_path_buf()),
+        source,
+    };
+    let parse = |file: &Path| -> Result<TokenRegistry> {
+        let text = fs::read_to_string(file).map_err(io_error)?;
+        let json = serde_json::from_str(&text).map_err(|e| {
+            Error::InvalidConfig(format!("token registry {}: {}", file.display(), e))
+        })?;
+        parse_token_registry(&json)
+    };
+
+    if !path.is_dir() {
+        return parse(path);
+    }
+    let mut registry = TokenRegistry::new();
+    for entry in fs::read_dir(path).map_err(io_error)? {
+        let file = entry.map_err(io_error)?.path();
+        if file.extension().is_some_and(|ext| ext == "json") {
+            registry.extend(parse(&file)?);
+        }
+    }
+    Ok(registry)
+}
+
+/// Read protocol parameters from a JSON file.
+pub fn read_protocol_params(path: &Path) -> Result<ProtocolParams> {
+    if !path.exists() {
+        return Err(Error::FileNotFound(path.to_path_buf()));
+    }
+    let text = fs::read_to_string(path).map_err(|source| Error::IoError {
+        path: Some(path.to_path_buf()),
+        source,
+    })?;
+    let json = serde_json::from_str(&text).map_err(|e| {
+        Error::InvalidConfig(format!("protocol parameters {}: {}", path.display(), e))
+    })?;
+    ProtocolParams::from_json(&json)
+}
+
+/// Read a UTxO set from a `cardano-cli query utxo --out-file` file (JSON,
+/// or CBOR as binary or hex).
+pub fn read_utxo_set(path: &Path) -> Result<UtxoSet> {
+    if !path.exists() {
+        return Err(Error::FileNotFound(path.to_path_buf()));
+    }
+    let bytes = fs::read(path).map_err(|source| Error::IoError {
+        path: Some(path.to_path_buf()),
+        source,
+    })?;
+    decode_utxo_set(&bytes)
+}
+
+/// Read text from a file, an inline argument, or stdin.
+///
+/// An argument naming an existing file is read from disk; any other
+/// argument is used as the text itself.
+pub fn read_text_input(arg: Option<&str>) -> Result<String> {
+    match arg {
+        Some(arg) if Path::new(arg).is_file() => {
+            fs::read_to_string(arg).map_err(|e| Error::IoError {
+                path: Some(arg.into()),
+                source: e,
+            })
+        }
+        Some(arg) => Ok(arg.to_string()),
+        None => {
+            if std::io::stdin().is_terminal() {
+                return Err(Error::NoInput);
+            }
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| Error::IoError {
+                    path: None,
+                    source: e,
+                })?;
+            Ok(text)
         }
     }
 }
 
 /// Detect if stdin content is hex-encoded and decode if necessary.
-fn detect_and_decode_stdin(buffer: Vec<u8>) -> Result<Vec<u8>> {
+pub(crate) fn detect_and_decode_stdin(buffer: Vec<u8>) -> Result<Vec<u8>> {
     // Try to interpret as UTF-8 text
     let Ok(text) = String::from_utf8(buffer.clone()) else {
         // Not valid UTF-8, assume binary CBOR
+        log::debug!("stdin is binary CBOR ({} bytes)", buffer.len());
         return Ok(buffer);
     };
 
@@ -55,9 +214,14 @@ fn detect_and_decode_stdin(buffer: Vec<u8>) -> Result<Vec<u8>> {
     // Use >=4 chars (2 bytes) as minimum - reasonable for hex piped to stdin
     if hex_candidate.chars().all(|c| c.is_ascii_hexdigit()) && hex_candidate.len() >= 4 {
         // Decode as hex
+        log::debug!("stdin is hex text ({} characters)", hex_candidate.len());
         hex::decode(hex_candidate).map_err(Error::from)
     } else {
         // Assume binary CBOR (the original bytes)
+        log::debug!(
+            "stdin is not hex; treating {} bytes as binary CBOR",
+            buffer.len()
+        );
         Ok(buffer)
     }
 }
@@ -101,4 +265,22 @@ mod tests {
         let result = detect_and_decode_stdin(input);
         assert!(matches!(result, Err(Error::NoInput)));
     }
+
+    #[test]
+    fn test_large_files_are_mapped() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.cbor");
+        let large = dir.path().join("large.cbor");
+        fs::write(&small, [0x84, 0xa4]).unwrap();
+        let contents: Vec<u8> = (0..MMAP_THRESHOLD).map(|i| i as u8).collect();
+        fs::write(&large, &contents).unwrap();
+
+        assert!(matches!(
+            read_input(&InputSpec::File(small)).unwrap(),
+            InputBytes::Owned(_)
+        ));
+        let mapped = read_input(&InputSpec::File(large)).unwrap();
+        assert!(matches!(mapped, InputBytes::Mapped(_)));
+        assert_eq!(&*mapped, contents.as_slice());
+    }
 }
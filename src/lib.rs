@@ -14,81 +14,1336 @@
 //! - JSON output for piping
 //! - Validation mode with exit codes
 //! - Standalone address decoding
+//! - Compact transaction summaries
 
+pub mod anchor;
 pub mod cli;
-pub mod decode;
-pub mod error;
+pub mod config;
+pub mod edit;
+pub mod encode;
+pub mod extract;
 pub mod format;
 pub mod input;
-pub mod query;
+pub mod logging;
+pub mod node;
+pub mod plugin;
+pub mod report;
+pub mod serve;
+pub mod tui;
 pub mod update;
+pub mod watch;
 
-pub use cli::{Args, Command};
+// The decoding and query engine lives in cq-core
+pub use cq_core::{decode, error, network, pparams, query};
+
+pub use cli::{AddressCommand, Args, Command, ExtractKind, HashUtility, InputSpec};
+pub use config::Config;
 pub use error::{Error, Result};
 
-use decode::{decode_address, decode_transaction};
-use format::format_output;
-use input::read_input;
-use query::execute_query;
+use decode::{build_address, decode_address, decode_transaction};
+use format::{
+    address_to_pretty, bytes_to_diagnostic, cose_to_pretty, format_output, format_template,
+    param_updates_to_pretty,
+};
+use input::{read_input, read_query_file, read_text_input};
+use query::{QueryOptions, execute_query};
+
+/// What a run resolves once, before dispatch, and shares across every
+/// input, batch item and stream line it handles.
+struct Context {
+    /// How transactions are converted to JSON and queried.
+    options: QueryOptions,
+    /// blake2b-256 of the `--pool-metadata` document, fetched once.
+    pool_metadata_hash: Option<String>,
+}
+
+/// Run cq with the given arguments and query options.
+pub fn run(args: &Args, options: QueryOptions) -> Result<()> {
+    let ctx = Context {
+        options,
+        pool_metadata_hash: args
+            .pool_metadata
+            .as_deref()
+            .map(anchor::pool_metadata_hash)
+            .transpose()?,
+    };
 
-/// Run cq with the given arguments.
-pub fn run(args: &Args) -> Result<()> {
     // Handle subcommands first
     if let Some(ref command) = args.command {
-        return run_command(command, args);
+        return run_command(command, args, &ctx);
     }
 
     // Default behavior: transaction query mode
-    run_transaction_mode(args)
+    run_transaction_mode(args, &ctx)
+}
+
+/// Build the query options from the command line and config labels.
+pub fn query_options(
+    args: &Args,
+    labels: std::collections::BTreeMap<u64, query::LabelConfig>,
+) -> Result<QueryOptions> {
+    let asset_names = if args.hex_asset_names {
+        query::AssetNameFormat::Hex
+    } else if args.utf8_asset_names {
+        query::AssetNameFormat::Utf8
+    } else {
+        query::AssetNameFormat::Auto
+    };
+    let token_registry = match args.token_registry {
+        Some(ref path) => input::read_token_registry(path)?,
+        None => Default::default(),
+    };
+    let protocol_params = match args.pparams {
+        Some(ref path) => Some(input::read_protocol_params(path)?),
+        None => None,
+    };
+
+    Ok(QueryOptions {
+        labels,
+        asset_names,
+        token_registry,
+        protocol_params,
+        network: args.network,
+        slot_config: args.slot_config,
+        decompile_scripts: args.uplc,
+        script_ids: args.script_ids,
+        bech32_ids: args.bech32_ids,
+    })
 }
 
 /// Run a subcommand.
-fn run_command(command: &Command, args: &Args) -> Result<()> {
+fn run_command(command: &Command, args: &Args, ctx: &Context) -> Result<()> {
     use std::io::IsTerminal;
 
     match command {
-        Command::Address { address, json } => {
-            let decoded = decode_address(address)?;
+        Command::Address {
+            action:
+                Some(AddressCommand::Build {
+                    payment_keyhash,
+                    stake_keyhash,
+                    stake_address,
+                    json,
+                }),
+            ..
+        } => {
+            let Some(network) = args.network else {
+                return Err(Error::InvalidQuery(
+                    "cq addr build needs --network mainnet|preprod|preview".to_string(),
+                ));
+            };
+            let built = build_address(
+                payment_keyhash.as_deref(),
+                stake_keyhash.as_deref().or(stake_address.as_deref()),
+                network,
+            )?;
+            if *json {
+                print_json(&built.to_json())?;
+            } else {
+                println!("{}", built.bech32);
+            }
+            Ok(())
+        }
+        Command::Address {
+            address,
+            json,
+            stake,
+            hex,
+            from_hex,
+            ..
+        } => {
+            let Some(address) = address else {
+                return Err(Error::NoInput);
+            };
+            if *from_hex {
+                hex::decode(address.trim().trim_start_matches("0x"))?;
+            }
+            let decoded = decode_address(address, ctx.options.network)?;
+
+            if *stake {
+                println!("{}", decoded.stake_address()?);
+                return Ok(());
+            }
+            if *hex {
+                println!("{}", decoded.hex);
+                return Ok(());
+            }
+            if *from_hex {
+                println!("{}", decoded.bech32);
+                return Ok(());
+            }
 
             if *json {
-                let json_output = serde_json::to_string_pretty(&decoded.to_json())
-                    .map_err(|e| Error::FormatError(format!("JSON error: {}", e)))?;
-                println!("{}", json_output);
+                print_json(&decoded.to_json())?;
             } else {
                 let use_color = !args.no_color && std::io::stdout().is_terminal();
-                print!("{}", decoded.to_pretty(use_color));
+                print!("{}", address_to_pretty(&decoded, use_color));
+            }
+
+            Ok(())
+        }
+        Command::Cose { input, key, json } => {
+            let text = read_text_input(input.as_deref())?;
+            let (signature, data_key) = parse_data_signature(&text)?;
+            let mut cose = decode::decode_cose_sign1(&signature)?;
+
+            let key = match key {
+                Some(key) => Some(decode_hex(key)?),
+                None => data_key,
+            };
+            let valid = match key {
+                Some(key) => Some(cose.verify(&decode::cose_key_public_key(&key)?)?),
+                None => None,
+            };
+
+            if *json {
+                print_json(&cose.to_json())?;
+            } else {
+                let use_color = !args.no_color && std::io::stdout().is_terminal();
+                print!("{}", cose_to_pretty(&cose, use_color));
+            }
+
+            match valid {
+                Some(false) => Err(Error::InvalidSignature),
+                _ => Ok(()),
+            }
+        }
+        Command::Pparams { input, json } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()).or_any_hex())?;
+            let updates = decode::decode_param_updates(&bytes)?;
+
+            if *json {
+                print_json(&updates)?;
+            } else {
+                let use_color = !args.no_color && std::io::stdout().is_terminal();
+                print!("{}", param_updates_to_pretty(&updates, use_color));
+            }
+
+            Ok(())
+        }
+        Command::Summary { input, json } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let summary = report::summarize(&tx, &ctx.options)?;
+
+            if *json {
+                print_json(&summary.to_json())?;
+            } else {
+                print!("{}", summary.to_pretty());
+            }
+
+            Ok(())
+        }
+        Command::Stats { input, json } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let stats = report::compute_stats(&tx, &ctx.options)?;
+
+            if *json {
+                print_json(&stats.to_json())?;
+            } else {
+                print!("{}", stats.to_pretty());
+            }
+
+            Ok(())
+        }
+        Command::Size { input, json } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let report = report::compute_size(&tx, &ctx.options)?;
+
+            if *json {
+                print_json(&report.to_json())?;
+            } else {
+                print!("{}", report.to_pretty(args));
+            }
+
+            Ok(())
+        }
+        Command::Hash {
+            utility: Some(utility),
+            ..
+        } => run_hash_utility(utility),
+        // `Args::matches_from` parses the shortcut form as a `--query`
+        Command::Hash { utility: None, .. } => Err(Error::InvalidQuery(
+            "cq hash needs a utility (datum, blake2b-224, blake2b-256) or a transaction input"
+                .to_string(),
+        )),
+        Command::Script { input, json } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()).or_any_hex())?;
+            let report = report::inspect_script(&bytes)?;
+
+            if *json {
+                print_json(&report.to_json())?;
+            } else {
+                print!("{}", report.to_pretty());
+            }
+
+            Ok(())
+        }
+        Command::Grep {
+            pattern,
+            input,
+            json,
+        } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let report = report::grep(&tx, pattern, &ctx.options)?;
+
+            if *json {
+                print_json(&report.to_json())?;
+            } else {
+                print!("{}", report.to_pretty());
+            }
+
+            if report.is_empty() {
+                return Err(Error::EmptyResult);
+            }
+            Ok(())
+        }
+        Command::Flow { input, utxos, json } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let report = report::compute_flow(&tx, &input::read_utxo_set(utxos)?)?;
+
+            if *json {
+                print_json(&report.to_json())?;
+            } else {
+                print!("{}", report.to_pretty(args));
+            }
+
+            Ok(())
+        }
+        Command::Utxo { input, query, json } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()).or_any_hex())?;
+            let root = query::utxo_set_to_json(&decode::decode_utxo_set(&bytes)?, &ctx.options);
+            let result = query::execute_json_query(&root, query.as_deref().unwrap_or(""))?;
+            let result = postprocess(result, args, ctx)?;
+
+            if *json {
+                let value = serde_json::to_value(&result)
+                    .map_err(|e| Error::FormatError(format!("JSON error: {}", e)))?;
+                print_json(&value)?;
+            } else {
+                println!("{}", format_output(&result, args)?);
+            }
+
+            check_exit_status(&result, args)
+        }
+        Command::Graph {
+            input,
+            dot,
+            collateral,
+            reference_inputs,
+        } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let format = if *dot {
+                report::GraphFormat::Dot
+            } else {
+                report::GraphFormat::Mermaid
+            };
+            print!(
+                "{}",
+                report::render_graph(
+                    &tx,
+                    format,
+                    *collateral,
+                    *reference_inputs,
+                    args,
+                    &ctx.options
+                )?
+            );
+            Ok(())
+        }
+        Command::Explain { input, json } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let explanation = report::explain(&tx)?;
+
+            if *json {
+                print_json(&explanation.to_json())?;
+            } else {
+                print!("{}", explanation.to_pretty());
             }
 
             Ok(())
         }
+        Command::Canonical { input, json } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let report = report::check_canonical(&tx)?;
+
+            if *json {
+                print_json(&report.to_json())?;
+            } else {
+                print!("{}", report.to_pretty());
+            }
+
+            Ok(())
+        }
+        Command::Encode {
+            input,
+            metadata,
+            hash,
+            output,
+        } => {
+            let text = read_text_input(input.as_deref())?;
+            let bytes = if *metadata {
+                encode::encode_metadata(&text)?
+            } else {
+                encode::encode_diagnostic(&text)?
+            };
+
+            if *hash {
+                // The file (if any) is still written; the hash goes to stdout
+                if let Some(path) = output {
+                    write_cbor(&bytes, Some(path))?;
+                }
+                println!("{}", hex::encode(cml_crypto::blake2b256(&bytes)));
+                return Ok(());
+            }
+            write_cbor(&bytes, output.as_deref())
+        }
+        Command::Strip {
+            input,
+            all,
+            body,
+            output,
+        } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let mode = if *body {
+                edit::StripMode::BodyOnly
+            } else if *all {
+                edit::StripMode::AllWitnesses
+            } else {
+                edit::StripMode::Signatures
+            };
+            write_cbor(&edit::strip(&tx, mode)?, output.as_deref())
+        }
+        Command::Assemble {
+            input,
+            witnesses,
+            output,
+        } => {
+            let base = read_input(&InputSpec::from_arg(Some(input)))?;
+            let witnesses = witnesses
+                .iter()
+                .map(|w| read_input(&InputSpec::from_arg(Some(w))))
+                .collect::<Result<Vec<_>>>()?;
+            write_cbor(&edit::assemble(&base, &witnesses)?, output.as_deref())
+        }
+        Command::Edit {
+            input,
+            remove_aux,
+            drop_witness,
+            valid,
+            ttl,
+            output,
+        } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let edits = edit::Edits {
+                remove_auxiliary_data: *remove_aux,
+                drop_witnesses: drop_witness.clone(),
+                is_valid: *valid,
+                ttl: *ttl,
+            };
+            write_cbor(&edit::edit(&tx, &edits)?, output.as_deref())
+        }
+        Command::Extract {
+            what,
+            input,
+            out_dir,
+            json,
+        } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            let extraction = match what {
+                ExtractKind::Scripts => extract::extract_scripts(&tx)?,
+                ExtractKind::Datums => extract::extract_datums(&tx)?,
+            };
+            extraction.write_to(out_dir)?;
+
+            if *json {
+                print_json(&extraction.to_json())?;
+            } else {
+                print!("{}", extraction.to_pretty(out_dir));
+            }
+
+            Ok(())
+        }
+        Command::Watch {
+            path,
+            query,
+            json,
+            interval,
+            existing,
+        } => {
+            use colored::Colorize;
+
+            let mut watcher = watch::Watcher::new(path, *existing)?;
+            loop {
+                for file in watcher.poll()? {
+                    if let Err(e) =
+                        print_watched(&file, query.as_deref().unwrap_or(""), *json, args, ctx)
+                    {
+                        eprintln!("{}: {}: {}", "error".red(), file.display(), e);
+                    }
+                }
+                std::thread::sleep(std::time::Duration::from_millis(*interval));
+            }
+        }
+        Command::Mempool {
+            socket,
+            query,
+            network_magic,
+            once,
+        } => {
+            let magic = node_magic(*network_magic, args);
+            let monitor =
+                node::TxMonitor::new(node::connect(node_socket(socket.as_deref())?, magic)?);
+            let queries: Vec<&str> = query.as_deref().into_iter().collect();
+            stream_mempool(monitor, &queries, *once, args, ctx)
+        }
+        Command::Follow {
+            socket,
+            query,
+            network_magic,
+            blocks,
+        } => {
+            let magic = node_magic(*network_magic, args);
+            let sync = node::ChainSync::new(node::connect(node_socket(socket.as_deref())?, magic)?);
+            follow_chain(sync, query.as_deref().unwrap_or(""), *blocks, args, ctx)
+        }
+        Command::Serve { listen } => serve::serve(listen, &ctx.options),
+        Command::Tui { input } => {
+            let bytes = read_input(&InputSpec::from_arg(input.as_deref()))?;
+            let tx = decode_transaction(&bytes)?;
+            tui::explore(&tx, &ctx.options)
+        }
         Command::Update => update::check_for_updates(),
+        Command::Complete { prefix, input } => {
+            // Completion must stay quiet: an unreadable input just means no field paths
+            let bytes = input
+                .as_deref()
+                .and_then(|input| read_input(&InputSpec::detect(input)).ok());
+            let tx = bytes
+                .as_deref()
+                .and_then(|bytes| decode_transaction(bytes).ok());
+            for suggestion in query::complete_query(prefix, tx.as_ref(), &ctx.options) {
+                println!("{}", suggestion);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Split a `signData` result into COSE_Sign1 bytes and an optional COSE_Key.
+///
+/// Accepts bare COSE_Sign1 hex or the CIP-30 `{"signature", "key"}` JSON.
+fn parse_data_signature(text: &str) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let text = text.trim();
+    if !text.starts_with('{') {
+        return Ok((decode_hex(text)?, None));
+    }
+
+    let json: serde_json::Value = serde_json::from_str(text)
+        .map_err(|e| Error::DecodeFailed(format!("Invalid DataSignature JSON: {}", e)))?;
+    let field = |name: &str| json.get(name).and_then(|v| v.as_str()).map(decode_hex);
+    let signature = field("signature").ok_or_else(|| {
+        Error::DecodeFailed("DataSignature JSON has no \"signature\" field".to_string())
+    })??;
+    Ok((signature, field("key").transpose()?))
+}
+
+/// Decode hex with an optional `0x` prefix.
+fn decode_hex(text: &str) -> Result<Vec<u8>> {
+    let text = text.trim();
+    Ok(hex::decode(text.strip_prefix("0x").unwrap_or(text))?)
+}
+
+/// Write CBOR to a file as binary, or print it to stdout as hex.
+fn write_cbor(bytes: &[u8], output: Option<&std::path::Path>) -> Result<()> {
+    match output {
+        Some(path) => std::fs::write(path, bytes).map_err(|e| Error::IoError {
+            path: Some(path.to_path_buf()),
+            source: e,
+        }),
+        None => {
+            println!("{}", hex::encode(bytes));
+            Ok(())
+        }
+    }
+}
+
+/// Print a JSON value as pretty-printed JSON.
+fn print_json(value: &serde_json::Value) -> Result<()> {
+    let json_output = serde_json::to_string_pretty(value)
+        .map_err(|e| Error::FormatError(format!("JSON error: {}", e)))?;
+    println!("{}", json_output);
+    Ok(())
+}
+
+/// Decode a watched file and print its query result.
+fn print_watched(
+    file: &std::path::Path,
+    query: &str,
+    json: bool,
+    args: &Args,
+    ctx: &Context,
+) -> Result<()> {
+    use colored::Colorize;
+    use std::io::Write;
+
+    let bytes = read_input(&InputSpec::File(file.to_path_buf()))?;
+    let tx = decode_transaction(&bytes)?;
+    let result = execute_query(&tx, query, &ctx.options)?;
+
+    if json {
+        let line = serde_json::json!({
+            "file": file.display().to_string(),
+            "hash": tx.hash.to_hex(),
+            "result": serde_json::to_value(&result)
+                .map_err(|e| Error::FormatError(format!("JSON error: {}", e)))?
+        });
+        println!("{}", line);
+    } else {
+        println!("{}", file.display().to_string().bold());
+        println!("{}", format_output(&result, args)?);
+    }
+    // Results are consumed live, e.g. through a pipe
+    std::io::stdout().flush().map_err(|e| Error::IoError {
+        path: None,
+        source: e,
+    })
+}
+
+/// Hash a value that is not a transaction: a datum, or any payload with
+/// `blake2b-224` / `blake2b-256` (`cq hash <utility> <input>`).
+fn run_hash_utility(utility: &HashUtility) -> Result<()> {
+    let bytes = read_input(&utility.input().spec())?;
+    let json = match utility {
+        HashUtility::Datum(_) => {
+            use cml_core::serialization::Deserialize;
+
+            // Walked before CML decodes it: the walk rejects nesting too
+            // deep for CML's recursive decoder as well as trailing bytes
+            let span = decode::item_span(&bytes, 0)?;
+            if span.end != bytes.len() {
+                return Err(Error::DecodeFailed(format!(
+                    "{} trailing bytes after the datum",
+                    bytes.len() - span.end
+                )));
+            }
+            let datum = cml_chain::plutus::PlutusData::from_cbor_bytes(&bytes)
+                .map_err(|e| Error::DecodeFailed(format!("invalid Plutus data: {}", e)))?;
+            // The hash covers the bytes as given, not a re-encoding
+            let mut json = serde_json::json!({
+                "hash": hex::encode(cml_crypto::blake2b256(&bytes)),
+            });
+            if let Some(value) = query::decode_plutus_datum_to_json(&datum) {
+                json["value"] = value;
+            }
+            json
+        }
+        // Files are hashed byte for byte, a trailing newline included
+        HashUtility::Blake2b224(_) => serde_json::json!({
+            "hash": hex::encode(cml_crypto::blake2b224(&bytes)),
+            "size": bytes.len(),
+        }),
+        HashUtility::Blake2b256(_) => serde_json::json!({
+            "hash": hex::encode(cml_crypto::blake2b256(&bytes)),
+            "size": bytes.len(),
+        }),
+    };
+
+    if utility.input().json {
+        print_json(&json)
+    } else {
+        println!("{}", json["hash"].as_str().unwrap_or_default());
+        Ok(())
     }
 }
 
 /// Run transaction query mode (default).
-fn run_transaction_mode(args: &Args) -> Result<()> {
-    // Resolve query and input from positional arguments
-    let (query_opt, input_spec) = args.resolve();
+fn run_transaction_mode(args: &Args, ctx: &Context) -> Result<()> {
+    // Resolve queries and input from positional arguments, -q and -f
+    let (mut queries, input_spec) = args.resolve_queries();
+    // Hex that is not a transaction may still be another structure
+    let input_spec = input_spec.or_any_hex();
+    let file_query = args
+        .query_file
+        .as_deref()
+        .map(read_query_file)
+        .transpose()?;
+    queries.extend(file_query.as_deref());
+
+    // Each stdin line is a transaction of its own
+    if args.stream {
+        return stream_transactions(&queries, &input_spec, args, ctx);
+    }
 
     // Read input bytes
     let bytes = read_input(&input_spec)?;
 
-    // Decode the transaction
-    let tx = decode_transaction(&bytes)?;
+    // Any CBOR item rather than a transaction
+    if args.generic {
+        return run_json_mode(decode::cbor_to_json(&bytes)?, &queries, args, ctx);
+    }
+
+    // Input that is not exactly one transaction may hold several: a CBOR
+    // sequence or an array of them
+    let decoded = decode_transaction(&bytes);
+    if !decoded
+        .as_ref()
+        .is_ok_and(|tx| tx.original_bytes.len() == bytes.len())
+    {
+        if let Some(batch) = decode::decode_transaction_batch(&bytes) {
+            return run_batch(&batch?, &queries, args, ctx);
+        }
+    }
+
+    // Decode the transaction, or recognize what else the input is
+    let tx = match decoded {
+        Ok(tx) => tx,
+        Err(e @ Error::DecodeFailed(_)) => {
+            use colored::Colorize;
+            // A body before witnessing; it is not yet a valid transaction
+            if let Some(tx) = decode::decode_transaction_body(&bytes)
+                .ok()
+                .filter(|_| !args.check)
+            {
+                eprintln!(
+                    "{}: not a transaction; decoded as a transaction body without witnesses",
+                    "note".cyan()
+                );
+                tx
+            } else {
+                let detected = query::detect_input(&bytes, &ctx.options)
+                    .filter(|_| !(args.check || args.cbor || args.cardano_cli_schema));
+                let Some((kind, root)) = detected else {
+                    return Err(e);
+                };
+                eprintln!("{}: not a transaction; decoded as {}", "note".cyan(), kind);
+                return run_json_mode(root, &queries, args, ctx);
+            }
+        }
+        Err(e) => return Err(e),
+    };
+
+    warn_network_mismatch(&tx, args);
+
+    if let Some(expected) = &args.expect_hash {
+        let actual = tx.hash.to_hex();
+        if *expected != actual {
+            return Err(Error::HashMismatch {
+                expected: expected.clone(),
+                actual,
+            });
+        }
+    }
 
     // Check mode: just validate and exit
     if args.check {
-        // Transaction decoded successfully
-        return Ok(());
+        // Transaction decoded successfully; it must also fit the size limit
+        // and carry its scripts correctly encoded
+        check_transaction(&tx, &ctx.options)?;
+        return match &ctx.pool_metadata_hash {
+            Some(hash) => check_pool_metadata(&tx, hash),
+            None => Ok(()),
+        };
+    }
+
+    // Original bytes of a path rather than its decoded value
+    if args.cbor {
+        return print_cbor_slices(&tx, &queries);
+    }
+
+    // Metadata in a form cardano-cli accepts back
+    if args.cardano_cli_schema {
+        return print_metadata_schema(&tx, &queries);
+    }
+
+    // Paths that can be queried rather than values
+    if args.paths {
+        return print_paths(&tx, &queries, args, ctx);
+    }
+
+    // Several queries share the decoded transaction; results are labelled
+    if queries.len() > 1 {
+        return print_labelled_results(&tx, &queries, args, ctx);
     }
 
     // Execute query - use empty string for full transaction
-    let query = query_opt.unwrap_or("");
-    let result = execute_query(&tx, query)?;
+    let query = queries.first().copied().unwrap_or("");
+    let result = postprocess(execute_query(&tx, query, &ctx.options)?, args, ctx)?;
 
     // Format and print output
-    let output = format_output(&result, args)?;
+    let output = format_result(&tx, &result, args, ctx)?;
     println!("{}", output);
 
+    check_exit_status(&result, args)
+}
+
+/// Fail if the transaction is larger than maxTxSize, or carries a Plutus
+/// script wrapped in an extra CBOR byte string.
+fn check_transaction(tx: &decode::DecodedTransaction, options: &QueryOptions) -> Result<()> {
+    use cml_chain::Script;
+
+    let max = options.protocol_params.unwrap_or_default().tx_size_limit();
+    let size = tx.original_bytes.len();
+    if size as u64 > max {
+        return Err(Error::TxTooLarge { size, max });
+    }
+
+    // Witness and reference scripts wrapped in an extra CBOR layer
+    let witness_set = tx.witness_set();
+    let body = tx.body();
+    let witness_scripts = (witness_set.plutus_v1_scripts.iter().flatten())
+        .map(|s| Script::new_plutus_v1(s.clone()))
+        .chain(
+            (witness_set.plutus_v2_scripts.iter().flatten())
+                .map(|s| Script::new_plutus_v2(s.clone())),
+        )
+        .chain(
+            (witness_set.plutus_v3_scripts.iter().flatten())
+                .map(|s| Script::new_plutus_v3(s.clone())),
+        );
+    let reference_scripts = (body.outputs.iter().chain(&body.collateral_return))
+        .filter_map(|output| output.script_ref().cloned());
+    for script in witness_scripts.chain(reference_scripts) {
+        if let Some(unwrapped) = decode::double_wrapped_hash(&script) {
+            return Err(Error::DoubleCborScript {
+                hash: script.hash().to_hex(),
+                unwrapped_hash: unwrapped.to_hex(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Fail if a pool registration's metadata hash is not the document's hash.
+fn check_pool_metadata(tx: &decode::DecodedTransaction, document_hash: &str) -> Result<()> {
+    use cml_chain::certs::Certificate;
+
+    for cert in tx.body().certs.iter().flatten() {
+        let Certificate::PoolRegistration(registration) = cert else {
+            continue;
+        };
+        let params = &registration.pool_params;
+        if let Some(metadata) = &params.pool_metadata {
+            let expected = metadata.pool_metadata_hash.to_hex();
+            if expected != document_hash {
+                return Err(Error::PoolMetadataMismatch {
+                    pool: params.operator.to_hex(),
+                    expected,
+                    actual: document_hash.to_string(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Query each transaction of an input holding several.
+///
+/// JSON output is one `{"hash", "result"}` line per transaction, as with
+/// `--stream`; other output labels each result with its transaction.
+fn run_batch(
+    txs: &[decode::DecodedTransaction],
+    queries: &[&str],
+    args: &Args,
+    ctx: &Context,
+) -> Result<()> {
+    use colored::Colorize;
+
+    if args.expect_hash.is_some() || args.cbor || args.cardano_cli_schema || args.paths {
+        return Err(Error::InvalidQuery(
+            "--expect-hash, --cbor, --cardano-cli-schema and --paths apply to a single transaction"
+                .to_string(),
+        ));
+    }
+    for tx in txs {
+        warn_network_mismatch(tx, args);
+    }
+    if args.check {
+        return txs
+            .iter()
+            .try_for_each(|tx| check_transaction(tx, &ctx.options));
+    }
+
+    let mut results = Vec::new();
+    for (index, tx) in txs.iter().enumerate() {
+        if args.json {
+            let tx_results = query_results(tx, queries, args, ctx)?;
+            println!("{}", results_json(tx, &tx_results)?);
+            results.extend(tx_results.into_iter().map(|(_, result)| result));
+            continue;
+        }
+        let label = format!("Transaction {} ({}):", index, tx.hash.to_hex());
+        println!("{}", label.bold().cyan());
+        if queries.len() > 1 {
+            print_labelled_results(tx, queries, args, ctx)?;
+        } else {
+            let query = queries.first().copied().unwrap_or("");
+            let result = postprocess(execute_query(tx, query, &ctx.options)?, args, ctx)?;
+            println!("{}", format_result(tx, &result, args, ctx)?);
+            results.push(result);
+        }
+    }
+
+    // Every transaction must produce a usable result
+    for result in &results {
+        check_exit_status(result, args)?;
+    }
+    Ok(())
+}
+
+/// Warn when the transaction is for another network than `--network`.
+fn warn_network_mismatch(tx: &decode::DecodedTransaction, args: &Args) {
+    use colored::Colorize;
+
+    let (Some(network), Some(tx_network)) = (args.network, tx.network_id()) else {
+        return;
+    };
+    if tx_network != network.network_id() {
+        eprintln!(
+            "{}: the transaction is for network id {} ({}), not {}",
+            "warning".yellow(),
+            tx_network,
+            if tx_network == 1 {
+                "mainnet"
+            } else {
+                "a testnet"
+            },
+            network
+        );
+    }
+}
+
+/// Decode one hex transaction per stdin line, printing one JSON line each.
+///
+/// Lines are independent: a line that fails to decode or query produces an
+/// `{"error", "exit_code"}` line and the stream carries on.
+fn stream_transactions(
+    queries: &[&str],
+    input_spec: &InputSpec,
+    args: &Args,
+    ctx: &Context,
+) -> Result<()> {
+    use std::io::{BufRead, Write};
+
+    if !matches!(input_spec, InputSpec::Stdin) {
+        return Err(Error::InvalidQuery(
+            "--stream reads transactions from stdin".to_string(),
+        ));
+    }
+    let io_error = |source| Error::IoError { path: None, source };
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(io_error)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let output = match stream_line(&line, queries, args, ctx) {
+            Ok(output) => output,
+            Err(e) => error_json(&e),
+        };
+        // Flush per line so downstream consumers see results as they come
+        writeln!(stdout, "{}", output)
+            .and_then(|_| stdout.flush())
+            .map_err(io_error)?;
+    }
+    Ok(())
+}
+
+/// Network magic for a node connection: `--network-magic`, else that of
+/// `--network`, else mainnet's.
+fn node_magic(network_magic: Option<u64>, args: &Args) -> u64 {
+    network_magic
+        .or(args.network.map(network::NetworkName::magic))
+        .unwrap_or(network::NetworkName::Mainnet.magic())
+}
+
+/// Path of the node's socket: `--socket`, `CARDANO_NODE_SOCKET_PATH` or
+/// the config file's `socket`.
+fn node_socket(socket: Option<&std::path::Path>) -> Result<&std::path::Path> {
+    socket.ok_or_else(|| {
+        Error::InvalidQuery(
+            "no node socket: pass --socket, set CARDANO_NODE_SOCKET_PATH or set socket in the config defaults"
+                .to_string(),
+        )
+    })
+}
+
+/// Follow the chain from the tip, printing one JSON line per transaction
+/// of a new block whose query result is usable, and one per rollback.
+fn follow_chain<S: std::io::Read + std::io::Write>(
+    mut sync: node::ChainSync<S>,
+    query: &str,
+    blocks: Option<u64>,
+    args: &Args,
+    ctx: &Context,
+) -> Result<()> {
+    use colored::Colorize;
+    use std::io::Write;
+
+    let (_, tip) = sync.find_intersect(&[])?;
+    sync.find_intersect(&[tip])?;
+
+    let mut stdout = std::io::stdout();
+    let mut print = |line: serde_json::Value| {
+        writeln!(stdout, "{}", line)
+            .and_then(|_| stdout.flush())
+            .map_err(|source| Error::IoError { path: None, source })
+    };
+    let mut followed = 0;
+    while blocks.is_none_or(|blocks| followed < blocks) {
+        let bytes = match sync.request_next()? {
+            node::ChainEvent::RollForward(bytes) => bytes,
+            // The first step goes back to the intersection itself
+            node::ChainEvent::RollBackward(point) if followed > 0 => {
+                let (slot, hash) = point.unwrap_or_default();
+                print(serde_json::json!({
+                    "rollback": { "slot": slot, "hash": hex::encode(hash) }
+                }))?;
+                continue;
+            }
+            node::ChainEvent::RollBackward(_) => continue,
+        };
+        followed += 1;
+
+        let block = match decode::decode_block(&bytes) {
+            Ok(block) => block,
+            Err(e) => {
+                eprintln!("{}: skipping a block: {}", "warning".yellow(), e);
+                continue;
+            }
+        };
+        for tx in block.transactions() {
+            let result = match execute_query(&tx, query, &ctx.options) {
+                Ok(result) => postprocess(result, args, ctx)?,
+                // A transaction without the queried field does not match
+                Err(Error::FieldNotFound(_) | Error::IndexOutOfBounds(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            if result.is_empty() {
+                continue;
+            }
+            print(serde_json::json!({
+                "slot": block.slot(),
+                "block": block.block_number(),
+                "hash": tx.hash.to_hex(),
+                "result": serde_json::to_value(&result)
+                    .map_err(|e| Error::FormatError(format!("JSON error: {}", e)))?
+            }))?;
+        }
+    }
+    sync.done()
+}
+
+/// Print one JSON line per transaction entering the mempool, as with
+/// `--stream`. With `once`, only the transactions in the mempool now.
+fn stream_mempool<S: std::io::Read + std::io::Write>(
+    mut monitor: node::TxMonitor<S>,
+    queries: &[&str],
+    once: bool,
+    args: &Args,
+    ctx: &Context,
+) -> Result<()> {
+    use std::io::Write;
+
+    let mut stdout = std::io::stdout();
+    // Transactions of the previous snapshot, which are not printed again
+    let mut seen = std::collections::HashSet::new();
+    loop {
+        monitor.acquire()?;
+        let mut snapshot = std::collections::HashSet::new();
+        while let Some(bytes) = monitor.next_tx()? {
+            if !seen.contains(&bytes) {
+                let output = decode_transaction(&bytes)
+                    .and_then(|tx| transaction_json(&tx, queries, args, ctx))
+                    .unwrap_or_else(|e| error_json(&e));
+                writeln!(stdout, "{}", output)
+                    .and_then(|_| stdout.flush())
+                    .map_err(|source| Error::IoError { path: None, source })?;
+            }
+            snapshot.insert(bytes);
+        }
+        seen = snapshot;
+        if once {
+            return monitor.done();
+        }
+    }
+}
+
+/// An error as a JSON line: `{"error", "exit_code"}`.
+fn error_json(e: &Error) -> serde_json::Value {
+    serde_json::json!({ "error": e.to_string(), "exit_code": e.exit_code() })
+}
+
+/// Decode and query one `--stream` line.
+fn stream_line(
+    line: &str,
+    queries: &[&str],
+    args: &Args,
+    ctx: &Context,
+) -> Result<serde_json::Value> {
+    transaction_json(&decode_transaction(&decode_hex(line)?)?, queries, args, ctx)
+}
+
+/// A transaction's hash and query results as one JSON object; several
+/// queries give an object keyed by query.
+fn transaction_json(
+    tx: &decode::DecodedTransaction,
+    queries: &[&str],
+    args: &Args,
+    ctx: &Context,
+) -> Result<serde_json::Value> {
+    results_json(tx, &query_results(tx, queries, args, ctx)?)
+}
+
+/// Run each query (the whole transaction without any) on a transaction.
+fn query_results<'q>(
+    tx: &decode::DecodedTransaction,
+    queries: &[&'q str],
+    args: &Args,
+    ctx: &Context,
+) -> Result<Vec<(&'q str, query::QueryResult)>> {
+    let queries = if queries.is_empty() { &[""] } else { queries };
+    queries
+        .iter()
+        .map(|query| {
+            let result = postprocess(execute_query(tx, query, &ctx.options)?, args, ctx)?;
+            Ok((*query, result))
+        })
+        .collect()
+}
+
+/// A transaction's hash and query results as one JSON object.
+fn results_json(
+    tx: &decode::DecodedTransaction,
+    results: &[(&str, query::QueryResult)],
+) -> Result<serde_json::Value> {
+    let to_json = |result| {
+        serde_json::to_value(result).map_err(|e| Error::FormatError(format!("JSON error: {}", e)))
+    };
+    let result = match results {
+        [(_, result)] => to_json(result)?,
+        _ => {
+            let mut object = serde_json::Map::new();
+            for (query, result) in results {
+                object.insert(query.to_string(), to_json(result)?);
+            }
+            serde_json::Value::Object(object)
+        }
+    };
+    Ok(serde_json::json!({ "hash": tx.hash.to_hex(), "result": result }))
+}
+
+/// Query a JSON document: generic CBOR, or input that is not a transaction.
+fn run_json_mode(
+    root: serde_json::Value,
+    queries: &[&str],
+    args: &Args,
+    ctx: &Context,
+) -> Result<()> {
+    if args.check {
+        return Ok(());
+    }
+
+    let query = match queries {
+        [] => "",
+        [query] => query,
+        _ => {
+            return Err(Error::InvalidQuery(
+                "only a single query applies to input that is not a transaction".to_string(),
+            ));
+        }
+    };
+    let result = query::execute_json_query(&root, query)?;
+    if args.paths {
+        return print_path_list(query::leaf_paths(&result, query), args);
+    }
+
+    let result = postprocess(result, args, ctx)?;
+    println!("{}", format_output(&result, args)?);
+    check_exit_status(&result, args)
+}
+
+/// Print the original CBOR of the queried path, one hex item per line.
+fn print_cbor_slices(tx: &decode::DecodedTransaction, queries: &[&str]) -> Result<()> {
+    let query = match queries {
+        [] => "",
+        [query] => query,
+        _ => {
+            return Err(Error::InvalidQuery(
+                "--cbor takes a single query".to_string(),
+            ));
+        }
+    };
+    for span in query::locate_cbor(tx, query)? {
+        println!("{}", hex::encode(span.slice(&tx.original_bytes)));
+    }
+    Ok(())
+}
+
+/// Print the transaction metadata in cardano-cli's detailed schema.
+fn print_metadata_schema(tx: &decode::DecodedTransaction, queries: &[&str]) -> Result<()> {
+    let is_metadata = |query: &str| query::expand_shortcut(query) == "auxiliary_data.metadata";
+    match queries {
+        [] => {}
+        [query] if is_metadata(query) => {}
+        _ => {
+            return Err(Error::InvalidQuery(
+                "--cardano-cli-schema applies to the metadata query".to_string(),
+            ));
+        }
+    }
+    print_json(&format::metadata_detailed_json(tx)?)
+}
+
+/// Print the leaf paths of the transaction or query result, one per line.
+fn print_paths(
+    tx: &decode::DecodedTransaction,
+    queries: &[&str],
+    args: &Args,
+    ctx: &Context,
+) -> Result<()> {
+    let query = match queries {
+        [] => "",
+        [query] => query,
+        _ => {
+            return Err(Error::InvalidQuery(
+                "--paths takes a single query".to_string(),
+            ));
+        }
+    };
+    print_path_list(
+        query::leaf_paths(&execute_query(tx, query, &ctx.options)?, query),
+        args,
+    )
+}
+
+/// Print paths one per line, or as a JSON array with --json.
+fn print_path_list(paths: Vec<String>, args: &Args) -> Result<()> {
+    if args.json {
+        return print_json(&serde_json::json!(paths));
+    }
+    for path in paths {
+        println!("{}", path);
+    }
+    Ok(())
+}
+
+/// Format a result with the --format template, or the output flags.
+fn format_result(
+    tx: &decode::DecodedTransaction,
+    result: &query::QueryResult,
+    args: &Args,
+    ctx: &Context,
+) -> Result<String> {
+    match (&args.format, result) {
+        (Some(template), _) => format_template(tx, result, template, &ctx.options),
+        // Raw mode shows the transaction exactly as encoded
+        (None, query::QueryResult::FullTransaction(_)) if args.raw => {
+            bytes_to_diagnostic(&tx.original_bytes)
+        }
+        (None, _) => format_output(result, args),
+    }
+}
+
+/// With --exit-status, fail if a result holds nothing usable.
+fn check_exit_status(result: &query::QueryResult, args: &Args) -> Result<()> {
+    if args.exit_status && result.is_empty() {
+        return Err(Error::EmptyResult);
+    }
+    Ok(())
+}
+
+/// Apply result options (address filter, dedupe, sorting, then truncation)
+/// before formatting.
+fn postprocess(
+    result: query::QueryResult,
+    args: &Args,
+    ctx: &Context,
+) -> Result<query::QueryResult> {
+    let result = match args.to {
+        Some(ref address) => query::to_address_result(result, address)?,
+        None => result,
+    };
+    let result = if args.unique {
+        query::unique_result(result)
+    } else {
+        result
+    };
+    let mut result = match args.sort_by {
+        Some(ref key) => query::sort_result(result, key, args.desc)?,
+        None => result,
+    };
+    if let Some(n) = args.head {
+        result = query::head_result(result, n);
+    }
+    if let Some(n) = args.tail {
+        result = query::tail_result(result, n);
+    }
+    if args.fetch_anchors {
+        result = anchor::verify_anchors(result);
+    }
+    if let Some(hash) = &ctx.pool_metadata_hash {
+        result = anchor::verify_pool_metadata(result, hash);
+    }
+    if args.outpoints {
+        result = query::outpoints_result(result);
+    }
+    Ok(result)
+}
+
+/// Print the results of several queries, as one JSON object or labelled blocks.
+fn print_labelled_results(
+    tx: &decode::DecodedTransaction,
+    queries: &[&str],
+    args: &Args,
+    ctx: &Context,
+) -> Result<()> {
+    use colored::Colorize;
+
+    let mut results = Vec::new();
+    for query in queries {
+        results.push((
+            *query,
+            postprocess(execute_query(tx, query, &ctx.options)?, args, ctx)?,
+        ));
+    }
+
+    if args.json {
+        let mut object = serde_json::Map::new();
+        for (query, result) in &results {
+            let value = serde_json::to_value(result)
+                .map_err(|e| Error::FormatError(format!("JSON error: {}", e)))?;
+            object.insert(query.to_string(), value);
+        }
+        print_json(&serde_json::Value::Object(object))?;
+    } else {
+        for (query, result) in &results {
+            let output = format_result(tx, result, args, ctx)?;
+            let label = format!("{}:", query).bold().cyan();
+
+            // Multi-line results go below their label
+            if output.contains('\n') {
+                println!("{}\n{}", label, output);
+            } else {
+                println!("{} {}", label, output);
+            }
+        }
+    }
+
+    // Every query must produce a usable result
+    for (_, result) in &results {
+        check_exit_status(result, args)?;
+    }
     Ok(())
 }
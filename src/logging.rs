@@ -0,0 +1,46 @@
+//! Diagnostic logging to stderr (`-v`, `-vv`).
+//!
+//! cq logs through the `log` facade, so library users can route the same
+//! messages to their own logger instead.
+
+use colored::Colorize;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Logger printing `[level] message` lines to stderr.
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level() && metadata.target().starts_with("cq")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let level = match record.level() {
+            Level::Error => "error".red(),
+            Level::Warn => "warn".yellow(),
+            Level::Info => "info".green(),
+            Level::Debug => "debug".cyan(),
+            Level::Trace => "trace".dimmed(),
+        };
+        eprintln!("[{}] {}", level, record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+/// Install the stderr logger: `-v` shows debug messages, `-vv` also trace.
+pub fn init(verbosity: u8) {
+    let level = match verbosity {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
@@ -1,12 +1,35 @@
 //! cq - CBOR Query Tool for Cardano transactions.
 
-use clap::Parser;
+use clap::FromArgMatches;
 use colored::Colorize;
 use std::process::ExitCode;
 
 fn main() -> ExitCode {
+    // External `cq-<name>` subcommands get the raw arguments
+    let argv: Vec<String> = std::env::args().collect();
+    if let Some(plugin) = cq::plugin::find_plugin(&argv) {
+        return match cq::plugin::run_plugin(&plugin, &argv[2..]) {
+            Ok(code) => ExitCode::from(code),
+            Err(e) => report_error(e),
+        };
+    }
+
     // Parse command line arguments
-    let args = cq::Args::parse();
+    let matches = cq::Args::matches_from(std::env::args_os().collect());
+    let mut args = cq::Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    cq::logging::init(args.verbose);
+
+    // Config file defaults for options not given on the command line
+    let config = match cq::Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => return report_error(e),
+    };
+    config.defaults.apply(&mut args, &matches);
+    let options = match cq::query_options(&args, config.labels) {
+        Ok(options) => options,
+        Err(e) => return report_error(e),
+    };
 
     // Disable colors if requested
     if args.no_color {
@@ -14,14 +37,17 @@ fn main() -> ExitCode {
     }
 
     // Run the main logic
-    match cq::run(&args) {
+    match cq::run(&args, options) {
         Ok(()) => ExitCode::SUCCESS,
-        Err(e) => {
-            // Print error message
-            eprintln!("{}: {}", "error".red(), e);
-
-            // Return appropriate exit code
-            ExitCode::from(e.exit_code() as u8)
-        }
+        Err(e) => report_error(e),
     }
 }
+
+/// Print an error and return its exit code.
+fn report_error(e: cq::Error) -> ExitCode {
+    // Print error message
+    eprintln!("{}: {}", "error".red(), e);
+
+    // Return appropriate exit code
+    ExitCode::from(e.exit_code() as u8)
+}
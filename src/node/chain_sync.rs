@@ -0,0 +1,179 @@
+//! ChainSync: following a node's chain block by block.
+//!
+//! The client finds an intersection with the node's chain, then asks for
+//! the next step: a block to roll forward to, or a point to roll back to.
+//! At the tip, the node answers once a new block arrives.
+
+use super::{Connection, message_parts, protocol_error};
+use crate::error::Result;
+use ciborium::Value as CborValue;
+use std::io::{Read, Write};
+
+/// Mini-protocol number of node-to-client ChainSync.
+const CHAIN_SYNC: u16 = 5;
+
+const MSG_REQUEST_NEXT: u64 = 0;
+const MSG_AWAIT_REPLY: u64 = 1;
+const MSG_ROLL_FORWARD: u64 = 2;
+const MSG_ROLL_BACKWARD: u64 = 3;
+const MSG_FIND_INTERSECT: u64 = 4;
+const MSG_INTERSECT_FOUND: u64 = 5;
+const MSG_INTERSECT_NOT_FOUND: u64 = 6;
+const MSG_DONE: u64 = 7;
+
+/// A point on the chain: a block's slot and header hash. `None` is the
+/// origin, before the first block.
+pub type Point = Option<(u64, Vec<u8>)>;
+
+/// A step along the chain.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChainEvent {
+    /// A new block, as `[era, block]` CBOR.
+    RollForward(Vec<u8>),
+    /// Blocks after this point are no longer on the chain.
+    RollBackward(Point),
+}
+
+/// A ChainSync client.
+pub struct ChainSync<S> {
+    connection: Connection<S>,
+}
+
+impl<S: Read + Write> ChainSync<S> {
+    /// Follow the chain over a connection that completed its handshake.
+    pub fn new(connection: Connection<S>) -> Self {
+        ChainSync { connection }
+    }
+
+    /// Find the first of `points` on the node's chain, returning it (if
+    /// any) and the node's tip.
+    pub fn find_intersect(&mut self, points: &[Point]) -> Result<(Option<Point>, Point)> {
+        let points = points.iter().map(point_to_cbor).collect();
+        let message = CborValue::Array(vec![MSG_FIND_INTERSECT.into(), CborValue::Array(points)]);
+        self.connection.send(CHAIN_SYNC, &message)?;
+
+        let reply = self.connection.receive(CHAIN_SYNC)?;
+        let parsed = match message_parts(&reply) {
+            Some((MSG_INTERSECT_FOUND, [point, tip])) => {
+                point_from_cbor(point).map(Some).zip(tip_point(tip))
+            }
+            Some((MSG_INTERSECT_NOT_FOUND, [tip])) => tip_point(tip).map(|tip| (None, tip)),
+            _ => None,
+        };
+        parsed.ok_or_else(|| protocol_error("ChainSync", &reply))
+    }
+
+    /// The next step from the current point, waiting at the tip for a new
+    /// block.
+    pub fn request_next(&mut self) -> Result<ChainEvent> {
+        let message = CborValue::Array(vec![MSG_REQUEST_NEXT.into()]);
+        self.connection.send(CHAIN_SYNC, &message)?;
+        loop {
+            let reply = self.connection.receive(CHAIN_SYNC)?;
+            let event = match message_parts(&reply) {
+                Some((MSG_AWAIT_REPLY, [])) => continue,
+                // #6.24(bytes .cbor [era, block])
+                Some((MSG_ROLL_FORWARD, [CborValue::Tag(24, block), _])) => match block.as_ref() {
+                    CborValue::Bytes(bytes) => Some(ChainEvent::RollForward(bytes.clone())),
+                    _ => None,
+                },
+                Some((MSG_ROLL_BACKWARD, [point, _])) => {
+                    point_from_cbor(point).map(ChainEvent::RollBackward)
+                }
+                _ => None,
+            };
+            return event.ok_or_else(|| protocol_error("ChainSync", &reply));
+        }
+    }
+
+    /// End the protocol.
+    pub fn done(mut self) -> Result<()> {
+        let message = CborValue::Array(vec![MSG_DONE.into()]);
+        self.connection.send(CHAIN_SYNC, &message)
+    }
+}
+
+/// `[]` for the origin, `[slot, hash]` otherwise.
+fn point_to_cbor(point: &Point) -> CborValue {
+    match point {
+        None => CborValue::Array(Vec::new()),
+        Some((slot, hash)) => CborValue::Array(vec![(*slot).into(), hash.clone().into()]),
+    }
+}
+
+fn point_from_cbor(point: &CborValue) -> Option<Point> {
+    match point.as_array()?.as_slice() {
+        [] => Some(None),
+        [slot, CborValue::Bytes(hash)] => {
+            let slot = u64::try_from(slot.as_integer()?).ok()?;
+            Some(Some((slot, hash.clone())))
+        }
+        _ => None,
+    }
+}
+
+/// The point of a tip: `[point, block number]`.
+fn tip_point(tip: &CborValue) -> Option<Point> {
+    point_from_cbor(tip.as_array()?.first()?)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn test_follow_from_tip() {
+        let tip_point = CborValue::Array(vec![500.into(), vec![0xaa; 32].into()]);
+        let tip = CborValue::Array(vec![tip_point.clone(), 42.into()]);
+
+        let (client, node) = UnixStream::pair().unwrap();
+        std::thread::spawn(move || {
+            let mut node = Connection::new(node);
+            let mut reply = |expected: u64, messages: Vec<Vec<CborValue>>| {
+                let request = node.receive(CHAIN_SYNC).unwrap();
+                assert_eq!(message_parts(&request).unwrap().0, expected);
+                for message in messages {
+                    node.send(CHAIN_SYNC, &CborValue::Array(message)).unwrap();
+                }
+            };
+            reply(
+                MSG_FIND_INTERSECT,
+                vec![vec![MSG_INTERSECT_NOT_FOUND.into(), tip.clone()]],
+            );
+            reply(
+                MSG_FIND_INTERSECT,
+                vec![vec![
+                    MSG_INTERSECT_FOUND.into(),
+                    tip_point.clone(),
+                    tip.clone(),
+                ]],
+            );
+            reply(
+                MSG_REQUEST_NEXT,
+                vec![vec![MSG_ROLL_BACKWARD.into(), tip_point, tip.clone()]],
+            );
+            let block = CborValue::Tag(24, Box::new(vec![0x82, 0x06, 0x80].into()));
+            reply(
+                MSG_REQUEST_NEXT,
+                vec![
+                    vec![MSG_AWAIT_REPLY.into()],
+                    vec![MSG_ROLL_FORWARD.into(), block, tip],
+                ],
+            );
+        });
+
+        let mut sync = ChainSync::new(Connection::new(client));
+        let (intersection, tip) = sync.find_intersect(&[]).unwrap();
+        assert_eq!(intersection, None);
+        assert_eq!(tip, Some((500, vec![0xaa; 32])));
+
+        let (intersection, _) = sync.find_intersect(std::slice::from_ref(&tip)).unwrap();
+        assert_eq!(intersection, Some(tip.clone()));
+        assert_eq!(sync.request_next().unwrap(), ChainEvent::RollBackward(tip));
+        assert_eq!(
+            sync.request_next().unwrap(),
+            ChainEvent::RollForward(vec![0x82, 0x06, 0x80])
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Node-to-client connections to a local cardano-node (`--socket`).
+//!
+//! A node multiplexes its mini-protocols over one socket: each segment has
+//! a header naming the protocol and its direction, and a message may span
+//! several segments. The connection starts with the handshake, which
+//! agrees on a protocol version and checks the network magic.
+
+mod chain_sync;
+mod tx_monitor;
+
+pub use chain_sync::{ChainEvent, ChainSync, Point};
+pub use tx_monitor::TxMonitor;
+
+use crate::decode::item_span;
+use crate::error::{Error, Result};
+use ciborium::Value as CborValue;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// Largest segment payload the node accepts from clients.
+const MAX_SEGMENT: usize = 12_288;
+
+/// Largest message accepted, so a peer that never completes one fails.
+const MAX_MESSAGE: usize = 64 * 1024 * 1024;
+
+/// Mini-protocol number of the handshake.
+const HANDSHAKE: u16 = 0;
+
+/// Header bit marking a segment sent by the responder.
+const RESPONDER: u16 = 0x8000;
+
+/// Node-to-client versions proposed (V10 to V20); versions carry this bit
+/// to tell them from node-to-node versions.
+const VERSIONS: std::ops::RangeInclusive<u64> = 10..=20;
+const CLIENT_VERSION: u64 = 0x8000;
+
+/// The socket type node connections use on this platform.
+#[cfg(unix)]
+pub type Socket = std::os::unix::net::UnixStream;
+#[cfg(not(unix))]
+pub type Socket = std::net::TcpStream;
+
+/// Connect to a node's socket and run the handshake for the network with
+/// this magic.
+#[cfg(unix)]
+pub fn connect(path: &Path, magic: u64) -> Result<Connection<Socket>> {
+    let stream = Socket::connect(path)
+        .map_err(|e| Error::NetworkError(format!("cannot connect to {}: {}", path.display(), e)))?;
+    let mut connection = Connection::new(stream);
+    connection.handshake(magic)?;
+    Ok(connection)
+}
+
+/// Connect to a node's socket (Unix only).
+#[cfg(not(unix))]
+pub fn connect(path: &Path, _magic: u64) -> Result<Connection<Socket>> {
+    Err(Error::NetworkError(format!(
+        "cannot connect to {}: node sockets are only supported on Unix",
+        path.display()
+    )))
+}
+
+/// A multiplexed connection to a node.
+pub struct Connection<S> {
+    stream: S,
+    /// Received bytes that do not yet form a whole message, per protocol.
+    buffers: HashMap<u16, Vec<u8>>,
+    /// Segment timestamps count microseconds from here.
+    started: Instant,
+}
+
+impl<S: Read + Write> Connection<S> {
+    /// Wrap a stream; the handshake is not run.
+    pub fn new(stream: S) -> Self {
+        Connection {
+            stream,
+            buffers: HashMap::new(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Propose the node-to-client versions for the network with this
+    /// magic, returning the version the node accepts.
+    pub fn handshake(&mut self, magic: u64) -> Result<u64> {
+        let versions = VERSIONS.map(|version| {
+            // V15 added a query flag to the version data
+            let data = if version >= 15 {
+                CborValue::Array(vec![magic.into(), false.into()])
+            } else {
+                magic.into()
+            };
+            ((CLIENT_VERSION | version).into(), data)
+        });
+        let propose = CborValue::Array(vec![0.into(), CborValue::Map(versions.collect())]);
+        self.send(HANDSHAKE, &propose)?;
+
+        let reply = self.receive(HANDSHAKE)?;
+        match message_parts(&reply) {
+            Some((1, [version, _])) => version
+                .as_integer()
+                .and_then(|v| u64::try_from(v).ok())
+                .map(|v| v & !CLIENT_VERSION)
+                .ok_or_else(|| protocol_error("handshake", &reply)),
+            Some((2, [reason])) => Err(Error::NetworkError(format!(
+                "the node refused the connection: {}",
+                refuse_reason(reason)
+            ))),
+            _ => Err(protocol_error("handshake", &reply)),
+        }
+    }
+
+    /// Send a message, split into segments as needed.
+    pub fn send(&mut self, protocol: u16, message: &CborValue) -> Result<()> {
+        let mut payload = Vec::new();
+        ciborium::into_writer(message, &mut payload)
+            .map_err(|e| Error::NetworkError(format!("cannot encode message: {}", e)))?;
+
+        for chunk in payload.chunks(MAX_SEGMENT) {
+            let time = self.started.elapsed().as_micros() as u32;
+            let mut segment = Vec::with_capacity(8 + chunk.len());
+            segment.extend_from_slice(&time.to_be_bytes());
+            segment.extend_from_slice(&protocol.to_be_bytes());
+            segment.extend_from_slice(&(chunk.len() as u16).to_be_bytes());
+            segment.extend_from_slice(chunk);
+            self.stream.write_all(&segment).map_err(io_error)?;
+        }
+        self.stream.flush().map_err(io_error)
+    }
+
+    /// Receive the next whole message of a protocol, buffering segments of
+    /// other protocols.
+    pub fn receive(&mut self, protocol: u16) -> Result<CborValue> {
+        loop {
+            let buffer = self.buffers.entry(protocol).or_default();
+            if let Ok(span) = item_span(buffer, 0) {
+                let rest = buffer.split_off(span.end);
+                let message = std::mem::replace(buffer, rest);
+                return ciborium::from_reader(message.as_slice())
+                    .map_err(|e| Error::NetworkError(format!("invalid message: {}", e)));
+            }
+            if buffer.len() > MAX_MESSAGE {
+                return Err(Error::NetworkError(format!(
+                    "message larger than {} bytes",
+                    MAX_MESSAGE
+                )));
+            }
+
+            let mut header = [0u8; 8];
+            self.stream.read_exact(&mut header).map_err(io_error)?;
+            let id = u16::from_be_bytes([header[4], header[5]]) & !RESPONDER;
+            let len = u16::from_be_bytes([header[6], header[7]]) as usize;
+            let mut payload = vec![0u8; len];
+            self.stream.read_exact(&mut payload).map_err(io_error)?;
+            self.buffers.entry(id).or_default().extend(payload);
+        }
+    }
+}
+
+/// A message's tag and remaining fields: `[tag, fields...]`.
+pub(crate) fn message_parts(message: &CborValue) -> Option<(u64, &[CborValue])> {
+    let (tag, fields) = message.as_array()?.split_first()?;
+    let tag = u64::try_from(tag.as_integer()?).ok()?;
+    Some((tag, fields))
+}
+
+/// An unexpected message in a protocol.
+pub(crate) fn protocol_error(protocol: &str, message: &CborValue) -> Error {
+    let mut bytes = Vec::new();
+    let _ = ciborium::into_writer(message, &mut bytes);
+    Error::NetworkError(format!(
+        "unexpected {} message from the node: {}",
+        protocol,
+        hex::encode(bytes)
+    ))
+}
+
+/// Why the node refused the handshake.
+fn refuse_reason(reason: &CborValue) -> String {
+    match message_parts(reason) {
+        Some((0, [versions])) => {
+            let versions: Vec<String> = versions
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| u64::try_from(v.as_integer()?).ok())
+                .map(|v| (v & !CLIENT_VERSION).to_string())
+                .collect();
+            format!(
+                "no common protocol version (the node supports {})",
+                versions.join(", ")
+            )
+        }
+        Some((1 | 2, [_, text])) => text.as_text().unwrap_or("unknown reason").to_string(),
+        _ => "unknown reason".to_string(),
+    }
+}
+
+fn io_error(e: std::io::Error) -> Error {
+    Error::NetworkError(format!("node connection: {}", e))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn test_handshake_and_segmented_messages() {
+        let (client, node) = UnixStream::pair().unwrap();
+        let node = std::thread::spawn(move || {
+            let mut node = Connection::new(node);
+            let propose = node.receive(HANDSHAKE).unwrap();
+            let (tag, [versions]) = message_parts(&propose).unwrap() else {
+                panic!("expected a version map");
+            };
+            assert_eq!(tag, 0);
+            let (version, data) = versions.as_map().unwrap().last().unwrap().clone();
+            assert_eq!(data, CborValue::Array(vec![2.into(), false.into()]));
+            node.send(HANDSHAKE, &CborValue::Array(vec![1.into(), version, data]))
+                .unwrap();
+
+            // Larger than a segment
+            let big = CborValue::Bytes(vec![7; 30_000]);
+            node.send(9, &big).unwrap();
+        });
+
+        let mut connection = Connection::new(client);
+        assert_eq!(connection.handshake(2).unwrap(), 20);
+        let message = connection.receive(9).unwrap();
+        assert_eq!(message.as_bytes().unwrap().len(), 30_000);
+        node.join().unwrap();
+    }
+
+    #[test]
+    fn test_refused_handshake() {
+        let (client, node) = UnixStream::pair().unwrap();
+        std::thread::spawn(move || {
+            let mut node = Connection::new(node);
+            node.receive(HANDSHAKE).unwrap();
+            let reason = CborValue::Array(vec![
+                2.into(),
+                (CLIENT_VERSION | 16).into(),
+                "version data mismatch".into(),
+            ]);
+            node.send(HANDSHAKE, &CborValue::Array(vec![2.into(), reason]))
+                .unwrap();
+        });
+
+        let err = Connection::new(client).handshake(1).unwrap_err();
+        assert_eq!(err.exit_code(), 6);
+        assert!(err.to_string().contains("version data mismatch"));
+    }
+}
@@ -0,0 +1,108 @@
+//! LocalTxMonitor: the transactions in a node's mempool.
+//!
+//! The client acquires a snapshot of the mempool and reads its
+//! transactions one by one. Acquiring again while holding a snapshot waits
+//! until the mempool has changed.
+
+use super::{Connection, message_parts, protocol_error};
+use crate::error::Result;
+use ciborium::Value as CborValue;
+use std::io::{Read, Write};
+
+/// Mini-protocol number of LocalTxMonitor.
+const TX_MONITOR: u16 = 9;
+
+const MSG_DONE: u64 = 0;
+const MSG_ACQUIRE: u64 = 1;
+const MSG_ACQUIRED: u64 = 2;
+const MSG_RELEASE: u64 = 3;
+const MSG_NEXT_TX: u64 = 5;
+const MSG_REPLY_NEXT_TX: u64 = 6;
+
+/// A LocalTxMonitor client.
+pub struct TxMonitor<S> {
+    connection: Connection<S>,
+}
+
+impl<S: Read + Write> TxMonitor<S> {
+    /// Monitor the mempool over a connection that completed its handshake.
+    pub fn new(connection: Connection<S>) -> Self {
+        TxMonitor { connection }
+    }
+
+    /// Acquire a mempool snapshot, returning the slot it is for. With a
+    /// snapshot already held, this waits for the mempool to change.
+    pub fn acquire(&mut self) -> Result<u64> {
+        let reply = self.request(MSG_ACQUIRE)?;
+        match message_parts(&reply) {
+            Some((MSG_ACQUIRED, [slot])) => slot
+                .as_integer()
+                .and_then(|slot| u64::try_from(slot).ok())
+                .ok_or_else(|| protocol_error("LocalTxMonitor", &reply)),
+            _ => Err(protocol_error("LocalTxMonitor", &reply)),
+        }
+    }
+
+    /// The CBOR of the next transaction in the snapshot, or `None` once
+    /// all have been read.
+    pub fn next_tx(&mut self) -> Result<Option<Vec<u8>>> {
+        let reply = self.request(MSG_NEXT_TX)?;
+        match message_parts(&reply) {
+            Some((MSG_REPLY_NEXT_TX, [])) => Ok(None),
+            // [era, #6.24(transaction bytes)]
+            Some((MSG_REPLY_NEXT_TX, [tx])) => match tx.as_array().map(Vec::as_slice) {
+                Some([_, CborValue::Tag(24, bytes)]) => match bytes.as_ref() {
+                    CborValue::Bytes(bytes) => Ok(Some(bytes.clone())),
+                    _ => Err(protocol_error("LocalTxMonitor", &reply)),
+                },
+                _ => Err(protocol_error("LocalTxMonitor", &reply)),
+            },
+            _ => Err(protocol_error("LocalTxMonitor", &reply)),
+        }
+    }
+
+    /// Release the snapshot and end the protocol.
+    pub fn done(mut self) -> Result<()> {
+        let release = CborValue::Array(vec![MSG_RELEASE.into()]);
+        self.connection.send(TX_MONITOR, &release)?;
+        let done = CborValue::Array(vec![MSG_DONE.into()]);
+        self.connection.send(TX_MONITOR, &done)
+    }
+
+    fn request(&mut self, tag: u64) -> Result<CborValue> {
+        let message = CborValue::Array(vec![tag.into()]);
+        self.connection.send(TX_MONITOR, &message)?;
+        self.connection.receive(TX_MONITOR)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn test_read_snapshot() {
+        let (client, node) = UnixStream::pair().unwrap();
+        std::thread::spawn(move || {
+            let mut node = Connection::new(node);
+            let mut reply = |expected: u64, message: Vec<CborValue>| {
+                let request = node.receive(TX_MONITOR).unwrap();
+                assert_eq!(message_parts(&request).unwrap().0, expected);
+                node.send(TX_MONITOR, &CborValue::Array(message)).unwrap();
+            };
+            reply(MSG_ACQUIRE, vec![MSG_ACQUIRED.into(), 1234.into()]);
+            let tx = CborValue::Array(vec![
+                6.into(),
+                CborValue::Tag(24, Box::new(CborValue::Bytes(vec![0x84, 0xa0]))),
+            ]);
+            reply(MSG_NEXT_TX, vec![MSG_REPLY_NEXT_TX.into(), tx]);
+            reply(MSG_NEXT_TX, vec![MSG_REPLY_NEXT_TX.into()]);
+        });
+
+        let mut monitor = TxMonitor::new(Connection::new(client));
+        assert_eq!(monitor.acquire().unwrap(), 1234);
+        assert_eq!(monitor.next_tx().unwrap(), Some(vec![0x84, 0xa0]));
+        assert_eq!(monitor.next_tx().unwrap(), None);
+    }
+}
@@ -0,0 +1,143 @@
+//! External subcommands: `cq <name>` runs a `cq-<name>` executable from PATH.
+//!
+//! Like cargo and git plugins, the executable receives the remaining
+//! arguments. The first one, unless it is a flag, is the transaction input;
+//! the decoded transaction is written to the plugin's stdin as JSON.
+//! `--config` is cq's own: it is applied to the decoding and not passed on.
+//! The JSON carries the config's defaults, such as its network and token
+//! registry, as `cq --json` does.
+
+use crate::cli::{Args, InputSpec};
+use crate::decode::decode_transaction;
+use crate::error::{Error, Result};
+use crate::input::read_input;
+use crate::query::{shortcut_names, transaction_to_json};
+use clap::{CommandFactory, FromArgMatches};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Find the plugin for a command line, if its first argument names one.
+///
+/// Built-in subcommands, query shortcuts, flags and existing files take
+/// precedence, so a plugin never shadows `cq fee` or a file argument such
+/// as `cq tx`.
+pub fn find_plugin(argv: &[String]) -> Option<PathBuf> {
+    let name = argv.get(1)?;
+    let is_plain_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && !name.starts_with('-');
+    if !is_plain_name || is_builtin(name) || Path::new(name).exists() {
+        return None;
+    }
+
+    let file_name = format!("cq-{}{}", name, std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
+/// Check if a name is a built-in subcommand (or one of its aliases) or a
+/// query shortcut.
+fn is_builtin(name: &str) -> bool {
+    name == "help"
+        || shortcut_names().any(|shortcut| shortcut == name)
+        || Args::command()
+            .get_subcommands()
+            .any(|cmd| cmd.get_name() == name || cmd.get_all_aliases().any(|alias| alias == name))
+}
+
+/// Run a plugin with the arguments following its name, returning its exit code.
+pub fn run_plugin(plugin: &Path, args: &[String]) -> Result<u8> {
+    let (config_path, args) = take_config(args);
+    let (input, rest) = match args.split_first() {
+        Some((first, rest)) if !first.starts_with('-') => (InputSpec::detect(first), rest),
+        _ => (InputSpec::Stdin, args.as_slice()),
+    };
+
+    // The transaction is converted as `cq --json` would with this config
+    let config = crate::config::Config::load(config_path.as_deref())?;
+    let matches = Args::command().get_matches_from(["cq"]);
+    let mut cq_args = Args::from_arg_matches(&matches).expect("no arguments parse");
+    config.defaults.apply(&mut cq_args, &matches);
+    let options = crate::query_options(&cq_args, config.labels)?;
+
+    let bytes = read_input(&input)?;
+    let tx = decode_transaction(&bytes)?;
+    let json = serde_json::to_string(&transaction_to_json(&tx, &options)?)
+        .map_err(|e| Error::FormatError(e.to_string()))?;
+
+    let io_error = |source| Error::IoError {
+        path: Some(plugin.to_path_buf()),
+        source,
+    };
+    let mut child = Command::new(plugin)
+        .args(rest)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(io_error)?;
+
+    // A plugin that exits without reading its input closes the pipe early
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Err(e) = writeln!(stdin, "{}", json) {
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                return Err(io_error(e));
+            }
+        }
+    }
+
+    let status = child.wait().map_err(io_error)?;
+    Ok(status
+        .code()
+        .map_or(1, |code| u8::try_from(code).unwrap_or(1)))
+}
+
+/// Split `--config FILE` (or `--config=FILE`) off a plugin's arguments.
+fn take_config(args: &[String]) -> (Option<PathBuf>, Vec<String>) {
+    let mut config = None;
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            config = args.next().map(PathBuf::from);
+        } else if let Some(path) = arg.strip_prefix("--config=") {
+            config = Some(PathBuf::from(path));
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    (config, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_names_are_not_plugins() {
+        assert!(is_builtin("summary"));
+        assert!(is_builtin("addr"));
+        assert!(is_builtin("help"));
+        assert!(is_builtin("fee"));
+        assert!(is_builtin("outputs"));
+        assert!(!is_builtin("feecheck"));
+
+        let argv = |arg: &str| vec!["cq".to_string(), arg.to_string()];
+        assert!(find_plugin(&argv("--json")).is_none());
+        assert!(find_plugin(&argv("outputs.0")).is_none());
+    }
+
+    #[test]
+    fn test_take_config() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let (config, rest) = take_config(&args(&["tx.cbor", "--config", "cq.toml", "--all"]));
+        assert_eq!(config, Some(PathBuf::from("cq.toml")));
+        assert_eq!(rest, args(&["tx.cbor", "--all"]));
+
+        let (config, rest) = take_config(&args(&["--config=cq.toml", "tx.cbor"]));
+        assert_eq!(config, Some(PathBuf::from("cq.toml")));
+        assert_eq!(rest, args(&["tx.cbor"]));
+    }
+}
@@ -0,0 +1,198 @@
+//! Re-encoding checks: round-trip determinism and canonical CBOR.
+
+use crate::decode::{DecodedTransaction, array_items};
+use crate::error::Result;
+use cml_core::serialization::Serialize;
+use cml_crypto::RawBytesEncoding;
+use colored::Colorize;
+use serde_json::Value as JsonValue;
+
+/// How a transaction's bytes compare with CML's re-serializations.
+pub struct CanonicalReport {
+    /// Per-section results (body, witness set, auxiliary data).
+    pub sections: Vec<SectionCheck>,
+    /// Transaction id as encoded.
+    pub hash: String,
+    /// Transaction id if the body were re-encoded canonically.
+    pub canonical_hash: String,
+}
+
+/// Re-encoding results for one top-level section.
+pub struct SectionCheck {
+    /// Section name (e.g. `body`).
+    pub name: &'static str,
+    /// Whether re-serializing with preserved encoding details reproduces the bytes.
+    pub round_trip: bool,
+    /// Offset in the transaction of the first byte differing from canonical CBOR.
+    pub first_non_canonical: Option<usize>,
+}
+
+impl SectionCheck {
+    fn new(
+        name: &'static str,
+        original: (usize, &[u8]),
+        round_trip: Vec<u8>,
+        canonical: Vec<u8>,
+    ) -> Self {
+        let (start, bytes) = original;
+        SectionCheck {
+            name,
+            round_trip: round_trip == bytes,
+            first_non_canonical: first_difference(bytes, &canonical).map(|i| start + i),
+        }
+    }
+
+    /// Check if the section is already canonically encoded.
+    pub fn is_canonical(&self) -> bool {
+        self.first_non_canonical.is_none()
+    }
+}
+
+/// Compare each section's original bytes with CML's re-serializations.
+pub fn check_canonical(tx: &DecodedTransaction) -> Result<CanonicalReport> {
+    let bytes = &tx.original_bytes;
+    let parts = array_items(bytes, 0)?;
+    let section = |idx: usize| (parts[idx].start, parts[idx].slice(bytes));
+
+    let body = &tx.tx.body;
+    let witness_set = &tx.tx.witness_set;
+    let mut sections = vec![
+        SectionCheck::new(
+            "body",
+            section(0),
+            body.to_cbor_bytes(),
+            body.to_canonical_cbor_bytes(),
+        ),
+        SectionCheck::new(
+            "witness_set",
+            section(1),
+            witness_set.to_cbor_bytes(),
+            witness_set.to_canonical_cbor_bytes(),
+        ),
+    ];
+    if let Some(aux) = &tx.tx.auxiliary_data {
+        sections.push(SectionCheck::new(
+            "auxiliary_data",
+            section(parts.len() - 1),
+            aux.to_cbor_bytes(),
+            aux.to_canonical_cbor_bytes(),
+        ));
+    }
+
+    Ok(CanonicalReport {
+        sections,
+        hash: hex::encode(tx.hash.to_raw_bytes()),
+        canonical_hash: hex::encode(cml_crypto::blake2b256(&body.to_canonical_cbor_bytes())),
+    })
+}
+
+/// Offset of the first differing byte, if the slices differ.
+fn first_difference(a: &[u8], b: &[u8]) -> Option<usize> {
+    a.iter()
+        .zip(b)
+        .position(|(x, y)| x != y)
+        .or_else(|| (a.len() != b.len()).then_some(a.len().min(b.len())))
+}
+
+impl CanonicalReport {
+    /// Check if every section re-serializes to identical bytes.
+    pub fn round_trips(&self) -> bool {
+        self.sections.iter().all(|s| s.round_trip)
+    }
+
+    /// Check if every section is canonically encoded.
+    pub fn is_canonical(&self) -> bool {
+        self.sections.iter().all(SectionCheck::is_canonical)
+    }
+
+    /// Convert to JSON.
+    pub fn to_json(&self) -> JsonValue {
+        let sections: Vec<JsonValue> = self
+            .sections
+            .iter()
+            .map(|s| {
+                serde_json::json!({
+                    "name": s.name,
+                    "round_trip": s.round_trip,
+                    "canonical": s.is_canonical(),
+                    "first_non_canonical_offset": s.first_non_canonical
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "round_trip": self.round_trips(),
+            "canonical": self.is_canonical(),
+            "hash": self.hash,
+            "canonical_hash": self.canonical_hash,
+            "sections": sections
+        })
+    }
+
+    /// Format for terminal output.
+    pub fn to_pretty(&self) -> String {
+        let yes_no = |ok: bool| if ok { "yes".green() } else { "no".red() };
+
+        let mut output = format!("{}\n", "Encoding".bold().cyan());
+        output.push_str(&format!(
+            "  {} {}\n",
+            format!("{:<16}", "Round-trip:").dimmed(),
+            yes_no(self.round_trips())
+        ));
+        output.push_str(&format!(
+            "  {} {}\n",
+            format!("{:<16}", "Canonical:").dimmed(),
+            yes_no(self.is_canonical())
+        ));
+
+        output.push_str(&format!("\n{}\n", "Sections".bold().cyan()));
+        for section in &self.sections {
+            let canonical = match section.first_non_canonical {
+                None => "canonical".green().to_string(),
+                Some(offset) => format!("non-canonical from byte {}", offset)
+                    .yellow()
+                    .to_string(),
+            };
+            let round_trip = if section.round_trip {
+                "round-trips".green()
+            } else {
+                "re-encodes differently".red()
+            };
+            output.push_str(&format!(
+                "  {} {}, {}\n",
+                format!("{:<16}", section.name).dimmed(),
+                round_trip,
+                canonical
+            ));
+        }
+
+        output.push_str(&format!("\n{}\n", "Transaction ID".bold().cyan()));
+        output.push_str(&format!(
+            "  {} {}\n",
+            format!("{:<16}", "As encoded:").dimmed(),
+            self.hash
+        ));
+        if self.canonical_hash != self.hash {
+            output.push_str(&format!(
+                "  {} {} {}\n",
+                format!("{:<16}", "Canonical body:").dimmed(),
+                self.canonical_hash,
+                "(differs)".yellow()
+            ));
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_difference() {
+        assert_eq!(first_difference(&[1, 2, 3], &[1, 2, 3]), None);
+        assert_eq!(first_difference(&[1, 2, 3], &[1, 9, 3]), Some(1));
+        assert_eq!(first_difference(&[1, 2], &[1, 2, 3]), Some(2));
+    }
+}
@@ -0,0 +1,303 @@
+//! Annotated hex dump of the original transaction CBOR.
+
+use crate::decode::{CborHeader, DecodedTransaction, array_items, map_field_names, read_header};
+use crate::error::{Error, Result};
+use colored::Colorize;
+use serde_json::Value as JsonValue;
+
+/// Bytes of string content shown per line.
+const BYTES_PER_LINE: usize = 16;
+
+/// Annotated listing of every CBOR item in a transaction.
+pub struct Explanation {
+    /// One line per item header (and per chunk of string content).
+    pub lines: Vec<ExplainLine>,
+}
+
+/// A single annotated line of the dump.
+pub struct ExplainLine {
+    /// Offset of the first byte in the original CBOR.
+    pub offset: usize,
+    /// Nesting depth (for indentation).
+    pub depth: usize,
+    /// Bytes covered by this line.
+    pub bytes: Vec<u8>,
+    /// What the bytes encode (e.g. `map(3)`, `uint 171617`).
+    pub description: String,
+    /// Query-style path of the item, if it has one (e.g. `body.fee`).
+    pub path: Option<String>,
+}
+
+/// Walk the original bytes, annotating each item with its structure and path.
+pub fn explain(tx: &DecodedTransaction) -> Result<Explanation> {
+    let bytes = &tx.original_bytes;
+    let parts = array_items(bytes, 0)?;
+    let mut walker = Walker {
+        bytes,
+        lines: Vec::new(),
+    };
+
+    // The top-level array's elements get their section names
+    let header = walker.header_line(0, 0, Some("transaction".to_string()))?;
+    let mut pos = header.len;
+    for (idx, part) in parts.iter().enumerate() {
+        let name = match (idx, parts.len()) {
+            (0, _) => "body",
+            (1, _) => "witness_set",
+            (2, 4) => "is_valid",
+            _ => "auxiliary_data",
+        };
+        pos = walker.item(part.start, 1, name.to_string())?;
+    }
+    if header.arg.is_none() {
+        walker.break_line(pos, 1);
+    }
+
+    Ok(Explanation {
+        lines: walker.lines,
+    })
+}
+
+/// Recursive walker collecting annotated lines.
+struct Walker<'a> {
+    bytes: &'a [u8],
+    lines: Vec<ExplainLine>,
+}
+
+impl Walker<'_> {
+    /// Annotate the item at `offset`, returning its end offset.
+    fn item(&mut self, offset: usize, depth: usize, path: String) -> Result<usize> {
+        let header = self.header_line(offset, depth, Some(path.clone()))?;
+        let mut pos = offset + header.len;
+
+        match (header.major, header.arg) {
+            (0 | 1 | 7, _) => Ok(pos),
+            (2 | 3, Some(len)) => {
+                let end = pos
+                    .checked_add(len as usize)
+                    .filter(|end| *end <= self.bytes.len())
+                    .ok_or_else(|| malformed(offset))?;
+                while pos < end {
+                    let chunk_end = (pos + BYTES_PER_LINE).min(end);
+                    self.push(pos, depth + 1, pos..chunk_end, String::new(), None);
+                    pos = chunk_end;
+                }
+                Ok(end)
+            }
+            // Indefinite-length strings are a sequence of definite chunks
+            (2 | 3, None) => {
+                let mut idx = 0;
+                while self.more(pos, None, idx)? {
+                    pos = self.item(pos, depth + 1, path.clone())?;
+                    idx += 1;
+                }
+                Ok(self.finish(pos, depth + 1, None))
+            }
+            (4, count) => {
+                let mut idx = 0;
+                while self.more(pos, count, idx)? {
+                    pos = self.item(pos, depth + 1, format!("{}.{}", path, idx))?;
+                    idx += 1;
+                }
+                Ok(self.finish(pos, depth + 1, count))
+            }
+            (5, count) => {
+                let segments: Vec<&str> = path.split('.').collect();
+                let names = map_field_names(&segments);
+                let mut idx = 0;
+                while self.more(pos, count, idx)? {
+                    let key = read_header(self.bytes, pos)?;
+                    let name = match (key.major, key.arg) {
+                        (0, Some(k)) => names
+                            .and_then(|names| names(k))
+                            .map(str::to_string)
+                            .unwrap_or_else(|| k.to_string()),
+                        _ => format!("key{}", idx),
+                    };
+                    let child = format!("{}.{}", path, name);
+                    pos = self.key(pos, depth + 1, &child)?;
+                    pos = self.item(pos, depth + 1, child)?;
+                    idx += 1;
+                }
+                Ok(self.finish(pos, depth + 1, count))
+            }
+            (6, Some(_)) => self.item(pos, depth + 1, path),
+            _ => Err(malformed(offset)),
+        }
+    }
+
+    /// Annotate a map key, returning its end offset.
+    fn key(&mut self, offset: usize, depth: usize, path: &str) -> Result<usize> {
+        let start = self.lines.len();
+        let end = self.item(offset, depth, path.to_string())?;
+        // Keys are labelled as such rather than with the value's path
+        self.lines[start].path = Some(format!("{} (key)", path));
+        for line in &mut self.lines[start + 1..] {
+            line.path = None;
+        }
+        Ok(end)
+    }
+
+    /// Add the line for an item header and return the header.
+    fn header_line(
+        &mut self,
+        offset: usize,
+        depth: usize,
+        path: Option<String>,
+    ) -> Result<CborHeader> {
+        let header = read_header(self.bytes, offset)?;
+        let description = describe(self.bytes, offset, &header);
+        self.push(
+            offset,
+            depth,
+            offset..offset + header.len,
+            description,
+            path,
+        );
+        Ok(header)
+    }
+
+    /// Check whether a container has another element at `pos`.
+    fn more(&self, pos: usize, count: Option<u64>, idx: u64) -> Result<bool> {
+        match count {
+            Some(count) => Ok(idx < count),
+            None => match self.bytes.get(pos) {
+                Some(b) => Ok(*b != 0xff),
+                None => Err(malformed(pos)),
+            },
+        }
+    }
+
+    /// Consume the break of an indefinite-length container.
+    fn finish(&mut self, pos: usize, depth: usize, count: Option<u64>) -> usize {
+        if count.is_some() {
+            return pos;
+        }
+        self.break_line(pos, depth);
+        pos + 1
+    }
+
+    fn break_line(&mut self, pos: usize, depth: usize) {
+        self.push(pos, depth, pos..pos + 1, "break".to_string(), None);
+    }
+
+    fn push(
+        &mut self,
+        offset: usize,
+        depth: usize,
+        range: std::ops::Range<usize>,
+        description: String,
+        path: Option<String>,
+    ) {
+        self.lines.push(ExplainLine {
+            offset,
+            depth,
+            bytes: self.bytes[range].to_vec(),
+            description,
+            path,
+        });
+    }
+}
+
+/// Describe an item from its header (e.g. `array(2)`, `bytes(32)`, `uint 5`).
+fn describe(bytes: &[u8], offset: usize, header: &CborHeader) -> String {
+    let count = |name: &str| match header.arg {
+        Some(n) => format!("{}({})", name, n),
+        None => format!("{}(*)", name),
+    };
+    match (header.major, header.arg) {
+        (0, Some(n)) => format!("uint {}", n),
+        (1, Some(n)) => format!("nint {}", -1 - n as i128),
+        (2, _) => count("bytes"),
+        (3, Some(len)) => {
+            let start = offset + header.len;
+            let text = bytes
+                .get(start..start + len as usize)
+                .and_then(|b| std::str::from_utf8(b).ok())
+                .map(|s| serde_json::to_string(s).unwrap_or_default())
+                .unwrap_or_default();
+            format!("text({}) {}", len, text)
+        }
+        (3, None) => count("text"),
+        (4, _) => count("array"),
+        (5, _) => count("map"),
+        (6, Some(tag)) => format!("tag({})", tag),
+        (7, _) => match header.info {
+            20 => "false".to_string(),
+            21 => "true".to_string(),
+            22 => "null".to_string(),
+            23 => "undefined".to_string(),
+            25..=27 => "float".to_string(),
+            _ => format!("simple({})", header.arg.unwrap_or(0)),
+        },
+        _ => "?".to_string(),
+    }
+}
+
+fn malformed(offset: usize) -> Error {
+    Error::DecodeFailed(format!("Malformed CBOR at offset {}", offset))
+}
+
+impl Explanation {
+    /// Convert to JSON.
+    pub fn to_json(&self) -> JsonValue {
+        let lines: Vec<JsonValue> = self
+            .lines
+            .iter()
+            .map(|line| {
+                serde_json::json!({
+                    "offset": line.offset,
+                    "depth": line.depth,
+                    "hex": hex::encode(&line.bytes),
+                    "description": line.description,
+                    "path": line.path
+                })
+            })
+            .collect();
+        JsonValue::Array(lines)
+    }
+
+    /// Format as an annotated hex dump.
+    pub fn to_pretty(&self) -> String {
+        let offset_width = self
+            .lines
+            .last()
+            .map_or(1, |line| line.offset.to_string().len());
+
+        let mut output = String::new();
+        for line in &self.lines {
+            let annotation = format!("{}{}", "  ".repeat(line.depth), line.description);
+            let path = line
+                .path
+                .as_deref()
+                .map(|p| p.cyan().to_string())
+                .unwrap_or_default();
+            let text = format!(
+                "{}  {:<width$}  {:<40} {}",
+                format!("{:>offset_width$}", line.offset).dimmed(),
+                hex::encode(&line.bytes),
+                annotation,
+                path,
+                width = BYTES_PER_LINE * 2,
+            );
+            output.push_str(text.trim_end());
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let describe_at = |bytes: &[u8]| describe(bytes, 0, &read_header(bytes, 0).unwrap());
+        assert_eq!(describe_at(&[0x1a, 0x00, 0x02, 0x9e, 0x61]), "uint 171617");
+        assert_eq!(describe_at(&[0x20]), "nint -1");
+        assert_eq!(describe_at(&[0x9f, 0xff]), "array(*)");
+        assert_eq!(describe_at(&[0x62, 0x68, 0x69]), "text(2) \"hi\"");
+        assert_eq!(describe_at(&[0xd9, 0x01, 0x02, 0x80]), "tag(258)");
+    }
+}
@@ -0,0 +1,224 @@
+//! Net value flow per address, from the inputs a transaction spends to the
+//! outputs it creates.
+
+use crate::cli::Args;
+use crate::decode::{DecodedTransaction, UtxoSet, find_utxo};
+use crate::error::{Error, Result};
+use crate::format::{format_number_with_separators, new_table};
+use cml_chain::assets::Value;
+use cml_chain::transaction::TransactionOutput;
+use cml_crypto::RawBytesEncoding;
+use colored::Colorize;
+use comfy_table::{Cell, CellAlignment};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+/// Value moved by a transaction, per address.
+pub struct FlowReport {
+    /// Addresses in order of first appearance (spent inputs, then outputs).
+    pub addresses: Vec<AddressFlow>,
+    /// Fee paid (the collateral kept, for a failed script transaction).
+    pub fee: u64,
+    /// Whether the transaction fails phase-2 validation, so its collateral
+    /// is spent instead of its inputs.
+    pub collateral_only: bool,
+}
+
+/// Lovelace and assets an address spends and receives.
+pub struct AddressFlow {
+    pub address: String,
+    /// Lovelace in the inputs spent from this address.
+    pub lovelace_in: u64,
+    /// Lovelace in the outputs paying this address.
+    pub lovelace_out: u64,
+    /// Net change per asset (policy id hex, asset name hex), without zeros.
+    pub assets: BTreeMap<(String, String), i128>,
+}
+
+impl AddressFlow {
+    /// Lovelace received minus lovelace spent.
+    pub fn net_lovelace(&self) -> i128 {
+        i128::from(self.lovelace_out) - i128::from(self.lovelace_in)
+    }
+}
+
+/// Compute the flow of a transaction, resolving its inputs against a UTxO
+/// set. Every spent input must be in the set.
+pub fn compute_flow(tx: &DecodedTransaction, utxos: &UtxoSet) -> Result<FlowReport> {
+    let body = &tx.tx.body;
+    let collateral_only = !tx.tx.is_valid;
+    let (spent, created): (Vec<_>, Vec<&TransactionOutput>) = if collateral_only {
+        (
+            body.collateral_inputs.iter().flatten().collect(),
+            body.collateral_return.iter().collect(),
+        )
+    } else {
+        (body.inputs.iter().collect(), body.outputs.iter().collect())
+    };
+
+    let mut addresses: Vec<AddressFlow> = Vec::new();
+    let mut entry = |output: &TransactionOutput| -> usize {
+        let address = output
+            .address()
+            .to_bech32(None)
+            .unwrap_or_else(|_| hex::encode(output.address().to_raw_bytes()));
+        match addresses.iter().position(|flow| flow.address == address) {
+            Some(idx) => idx,
+            None => {
+                addresses.push(AddressFlow {
+                    address,
+                    lovelace_in: 0,
+                    lovelace_out: 0,
+                    assets: BTreeMap::new(),
+                });
+                addresses.len() - 1
+            }
+        }
+    };
+
+    let mut moves = Vec::new();
+    for input in spent {
+        let output = find_utxo(utxos, input).ok_or_else(|| {
+            Error::UnresolvedInput(format!("{}#{}", input.transaction_id.to_hex(), input.index))
+        })?;
+        moves.push((entry(output), output.amount(), -1));
+    }
+    for output in created {
+        moves.push((entry(output), output.amount(), 1));
+    }
+    for (idx, value, sign) in moves {
+        add_value(&mut addresses[idx], value, sign);
+    }
+
+    let fee = if collateral_only {
+        let total_in: u64 = addresses.iter().map(|flow| flow.lovelace_in).sum();
+        let total_out: u64 = addresses.iter().map(|flow| flow.lovelace_out).sum();
+        body.total_collateral
+            .unwrap_or(total_in.saturating_sub(total_out))
+    } else {
+        body.fee
+    };
+
+    Ok(FlowReport {
+        addresses,
+        fee,
+        collateral_only,
+    })
+}
+
+/// Add a value to an address's flow, as spent (`sign` -1) or received (1).
+fn add_value(flow: &mut AddressFlow, value: &Value, sign: i128) {
+    if sign < 0 {
+        flow.lovelace_in += value.coin;
+    } else {
+        flow.lovelace_out += value.coin;
+    }
+    for (policy_id, assets) in value.multiasset.iter() {
+        for (name, amount) in assets.iter() {
+            let key = (policy_id.to_hex(), hex::encode(name.to_raw_bytes()));
+            *flow.assets.entry(key).or_default() += sign * i128::from(*amount);
+        }
+    }
+    flow.assets.retain(|_, net| *net != 0);
+}
+
+impl FlowReport {
+    /// Convert to JSON.
+    pub fn to_json(&self) -> JsonValue {
+        let addresses: Vec<JsonValue> = self
+            .addresses
+            .iter()
+            .map(|flow| {
+                let assets: Vec<JsonValue> = flow
+                    .assets
+                    .iter()
+                    .map(|((policy_id, name_hex), net)| {
+                        serde_json::json!({
+                            "policy_id": policy_id,
+                            "name_hex": name_hex,
+                            "net": net
+                        })
+                    })
+                    .collect();
+                serde_json::json!({
+                    "address": flow.address,
+                    "lovelace_in": flow.lovelace_in,
+                    "lovelace_out": flow.lovelace_out,
+                    "net_lovelace": flow.net_lovelace(),
+                    "assets": assets
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "addresses": addresses,
+            "fee": self.fee,
+            "collateral_only": self.collateral_only
+        })
+    }
+
+    /// Format as a table with one row per address.
+    pub fn to_pretty(&self, args: &Args) -> String {
+        let mut table = new_table(args);
+        table.set_header(vec![
+            Cell::new("Address").fg(comfy_table::Color::DarkGrey),
+            Cell::new("In").fg(comfy_table::Color::DarkGrey),
+            Cell::new("Out").fg(comfy_table::Color::DarkGrey),
+            Cell::new("Net").fg(comfy_table::Color::DarkGrey),
+            Cell::new("Assets").fg(comfy_table::Color::DarkGrey),
+        ]);
+
+        for flow in &self.addresses {
+            let assets: Vec<String> = flow
+                .assets
+                .iter()
+                .map(|((policy_id, name_hex), net)| {
+                    let name = hex::decode(name_hex)
+                        .ok()
+                        .and_then(|bytes| String::from_utf8(bytes).ok())
+                        .filter(|name| !name.is_empty() && !name.chars().any(char::is_control))
+                        .unwrap_or_else(|| name_hex.clone());
+                    format!("{} {}.{}", signed(*net), &policy_id[..8], name)
+                })
+                .collect();
+            table.add_row(vec![
+                Cell::new(&flow.address),
+                Cell::new(format_number_with_separators(flow.lovelace_in))
+                    .set_alignment(CellAlignment::Right),
+                Cell::new(format_number_with_separators(flow.lovelace_out))
+                    .set_alignment(CellAlignment::Right),
+                Cell::new(signed(flow.net_lovelace())).set_alignment(CellAlignment::Right),
+                Cell::new(assets.join("\n")),
+            ]);
+        }
+
+        let mut output = format!(
+            "{} ({} addresses, lovelace)\n{}\n",
+            "Value flow".bold().cyan(),
+            self.addresses.len(),
+            table
+        );
+        if self.collateral_only {
+            output.push_str(&format!(
+                "{}\n",
+                "Script validation fails: the collateral is spent instead of the inputs".yellow()
+            ));
+        }
+        output.push_str(&format!(
+            "Fee: {} lovelace\n",
+            format_number_with_separators(self.fee)
+        ));
+        output
+    }
+}
+
+/// A signed amount with separators, e.g. `+1,000` or `-5`.
+fn signed(amount: i128) -> String {
+    let magnitude = u64::try_from(amount.unsigned_abs())
+        .map(format_number_with_separators)
+        .unwrap_or_else(|_| amount.unsigned_abs().to_string());
+    match amount.signum() {
+        -1 => format!("-{}", magnitude),
+        0 => magnitude,
+        _ => format!("+{}", magnitude),
+    }
+}
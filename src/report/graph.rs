@@ -0,0 +1,207 @@
+//! Transaction graphs: inputs → transaction → outputs, as Mermaid or
+//! Graphviz DOT.
+
+use crate::cli::Args;
+use crate::decode::DecodedTransaction;
+use crate::error::Result;
+use crate::format::{display_address, display_hash, format_number_with_separators};
+use crate::query::{QueryOptions, transaction_to_json};
+use serde_json::Value as JsonValue;
+
+/// Graph notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Mermaid,
+    Dot,
+}
+
+/// What an edge into or out of the transaction represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeKind {
+    Spend,
+    Reference,
+    Collateral,
+    Output,
+    CollateralReturn,
+}
+
+impl EdgeKind {
+    /// Label of a dashed edge; plain spends and outputs have none.
+    fn label(self) -> Option<&'static str> {
+        match self {
+            EdgeKind::Spend | EdgeKind::Output => None,
+            EdgeKind::Reference => Some("reference"),
+            EdgeKind::Collateral => Some("collateral"),
+            EdgeKind::CollateralReturn => Some("collateral return"),
+        }
+    }
+
+    fn is_incoming(self) -> bool {
+        matches!(
+            self,
+            EdgeKind::Spend | EdgeKind::Reference | EdgeKind::Collateral
+        )
+    }
+}
+
+/// A node linked to the transaction node.
+struct Node {
+    id: String,
+    /// Label lines.
+    label: Vec<String>,
+    kind: EdgeKind,
+}
+
+/// Render the transaction as a graph. Collateral and reference inputs (and
+/// the collateral return) are included on request.
+pub fn render_graph(
+    tx: &DecodedTransaction,
+    format: GraphFormat,
+    collateral: bool,
+    reference_inputs: bool,
+    args: &Args,
+    options: &QueryOptions,
+) -> Result<String> {
+    let json = transaction_to_json(tx, options)?;
+    let body = &json["body"];
+
+    let mut nodes = Vec::new();
+    let mut add_inputs = |field: &str, prefix: &str, kind: EdgeKind| {
+        for (idx, input) in body[field].as_array().into_iter().flatten().enumerate() {
+            let txid = input["transaction_id"].as_str().unwrap_or("?");
+            nodes.push(Node {
+                id: format!("{}{}", prefix, idx),
+                label: vec![format!(
+                    "{}#{}",
+                    display_hash(txid, 16, args),
+                    input["index"]
+                )],
+                kind,
+            });
+        }
+    };
+    add_inputs("inputs", "in", EdgeKind::Spend);
+    if reference_inputs {
+        add_inputs("reference_inputs", "ref", EdgeKind::Reference);
+    }
+    if collateral {
+        add_inputs("collateral_inputs", "col", EdgeKind::Collateral);
+    }
+    for (idx, output) in body["outputs"].as_array().into_iter().flatten().enumerate() {
+        nodes.push(Node {
+            id: format!("out{}", idx),
+            label: output_label(output, args),
+            kind: EdgeKind::Output,
+        });
+    }
+    if let Some(output) = body.get("collateral_return").filter(|_| collateral) {
+        nodes.push(Node {
+            id: "colret".to_string(),
+            label: output_label(output, args),
+            kind: EdgeKind::CollateralReturn,
+        });
+    }
+
+    let tx_label = vec![
+        format!("tx {}", display_hash(&tx.hash.to_hex(), 16, args)),
+        format!(
+            "fee {} lovelace",
+            format_number_with_separators(body["fee"].as_u64().unwrap_or(0))
+        ),
+    ];
+    Ok(match format {
+        GraphFormat::Mermaid => mermaid(&tx_label, &nodes),
+        GraphFormat::Dot => dot(&tx_label, &nodes),
+    })
+}
+
+/// Address, lovelace and assets of an output.
+fn output_label(output: &JsonValue, args: &Args) -> Vec<String> {
+    let value = &output["value"];
+    let mut label = vec![
+        display_address(
+            output["address"]["address"].as_str().unwrap_or("?"),
+            24,
+            args,
+        ),
+        format!(
+            "{} lovelace",
+            format_number_with_separators(value["coin"].as_u64().unwrap_or(0))
+        ),
+    ];
+    for policy in value["multi_assets"].as_array().into_iter().flatten() {
+        for asset in policy["assets"].as_array().into_iter().flatten() {
+            let name = asset["name"]
+                .as_str()
+                .or_else(|| asset["name_hex"].as_str())
+                .unwrap_or("?");
+            label.push(format!(
+                "{} {}",
+                format_number_with_separators(asset["amount"].as_u64().unwrap_or(0)),
+                name
+            ));
+        }
+    }
+    label
+}
+
+fn mermaid(tx_label: &[String], nodes: &[Node]) -> String {
+    let label = |lines: &[String]| {
+        lines
+            .iter()
+            .map(|line| line.replace('"', "#quot;"))
+            .collect::<Vec<_>>()
+            .join("<br/>")
+    };
+    let mut output = format!("flowchart LR\n    tx((\"{}\"))\n", label(tx_label));
+    for node in nodes {
+        let declaration = format!("{}[\"{}\"]", node.id, label(&node.label));
+        let arrow = match node.kind.label() {
+            Some(text) => format!("-. {} .->", text),
+            None => "-->".to_string(),
+        };
+        if node.kind.is_incoming() {
+            output.push_str(&format!("    {} {} tx\n", declaration, arrow));
+        } else {
+            output.push_str(&format!("    tx {} {}\n", arrow, declaration));
+        }
+    }
+    output
+}
+
+fn dot(tx_label: &[String], nodes: &[Node]) -> String {
+    let label = |lines: &[String]| {
+        lines
+            .iter()
+            .map(|line| line.replace('\\', "\\\\").replace('"', "\\\""))
+            .collect::<Vec<_>>()
+            .join("\\n")
+    };
+    let mut output =
+        String::from("digraph transaction {\n    rankdir=LR;\n    node [shape=box];\n");
+    output.push_str(&format!(
+        "    tx [label=\"{}\", shape=ellipse];\n",
+        label(tx_label)
+    ));
+    for node in nodes {
+        output.push_str(&format!(
+            "    {} [label=\"{}\"];\n",
+            node.id,
+            label(&node.label)
+        ));
+    }
+    for node in nodes {
+        let (from, to) = if node.kind.is_incoming() {
+            (node.id.as_str(), "tx")
+        } else {
+            ("tx", node.id.as_str())
+        };
+        let style = match node.kind.label() {
+            Some(text) => format!(" [style=dashed, label=\"{}\"]", text),
+            None => String::new(),
+        };
+        output.push_str(&format!("    {} -> {}{};\n", from, to, style));
+    }
+    output.push_str("}\n");
+    output
+}
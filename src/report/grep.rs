@@ -0,0 +1,209 @@
+//! Where a hex pattern appears in a transaction (`cq grep`).
+
+use crate::decode::DecodedTransaction;
+use crate::error::{Error, Result};
+use crate::query::{QueryOptions, transaction_to_json};
+use crate::report::explain;
+use colored::Colorize;
+use serde_json::Value as JsonValue;
+
+/// Every place a hex pattern appears.
+pub struct GrepReport {
+    /// The pattern searched for, lowercase without `0x`.
+    pub pattern: String,
+    /// Decoded values containing the pattern.
+    pub matches: Vec<GrepMatch>,
+    /// Occurrences in the original CBOR bytes.
+    pub raw: Vec<RawMatch>,
+}
+
+/// A decoded value containing the pattern.
+pub struct GrepMatch {
+    /// Query path of the value (e.g. `body.inputs.0.transaction_id`).
+    pub path: String,
+    /// The value, as shown in the transaction JSON.
+    pub value: String,
+}
+
+/// An occurrence of the pattern in the original bytes.
+pub struct RawMatch {
+    /// Byte offset of the occurrence.
+    pub offset: usize,
+    /// Path of the CBOR item the occurrence starts in, if it has one.
+    pub path: Option<String>,
+}
+
+/// Search the decoded transaction and its bytes for a hex pattern.
+///
+/// Decoded hex values (hashes, ids, script and datum bytes) match if they
+/// contain the pattern. The original bytes are searched too when the
+/// pattern is whole bytes, which also finds data the JSON does not show.
+pub fn grep(tx: &DecodedTransaction, pattern: &str, options: &QueryOptions) -> Result<GrepReport> {
+    let pattern = pattern.trim();
+    let pattern = pattern
+        .strip_prefix("0x")
+        .unwrap_or(pattern)
+        .to_ascii_lowercase();
+    if pattern.is_empty() {
+        return Err(Error::InvalidHex(hex::FromHexError::InvalidStringLength));
+    }
+    // Check the digits; an odd-length pattern is padded to whole bytes
+    hex::decode(format!(
+        "{:0<width$}",
+        pattern,
+        width = pattern.len().div_ceil(2) * 2
+    ))?;
+
+    let mut matches = Vec::new();
+    collect_matches(
+        &transaction_to_json(tx, options)?,
+        "",
+        &pattern,
+        &mut matches,
+    );
+
+    let raw = match hex::decode(&pattern) {
+        Ok(needle) => {
+            let explanation = explain(tx)?;
+            find_all(&tx.original_bytes, &needle)
+                .into_iter()
+                .map(|offset| RawMatch {
+                    offset,
+                    path: explanation
+                        .lines
+                        .iter()
+                        .take_while(|line| line.offset <= offset)
+                        .filter_map(|line| line.path.clone())
+                        .last(),
+                })
+                .collect()
+        }
+        // An odd number of digits is not whole bytes
+        Err(_) => Vec::new(),
+    };
+
+    Ok(GrepReport {
+        pattern,
+        matches,
+        raw,
+    })
+}
+
+/// Collect the string leaves of `value` that contain the pattern.
+fn collect_matches(value: &JsonValue, path: &str, pattern: &str, matches: &mut Vec<GrepMatch>) {
+    let child = |name: &str| {
+        if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", path, name)
+        }
+    };
+    match value {
+        JsonValue::Object(map) => {
+            for (key, value) in map {
+                collect_matches(value, &child(key), pattern, matches);
+            }
+        }
+        JsonValue::Array(items) => {
+            for (idx, value) in items.iter().enumerate() {
+                collect_matches(value, &child(&idx.to_string()), pattern, matches);
+            }
+        }
+        JsonValue::String(s) if s.to_ascii_lowercase().contains(pattern) => {
+            matches.push(GrepMatch {
+                path: path.to_string(),
+                value: s.clone(),
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Offsets of every (possibly overlapping) occurrence of `needle`.
+fn find_all(haystack: &[u8], needle: &[u8]) -> Vec<usize> {
+    haystack
+        .windows(needle.len())
+        .enumerate()
+        .filter(|(_, window)| *window == needle)
+        .map(|(offset, _)| offset)
+        .collect()
+}
+
+impl GrepReport {
+    /// Whether the pattern was found anywhere.
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty() && self.raw.is_empty()
+    }
+
+    /// Convert to JSON.
+    pub fn to_json(&self) -> JsonValue {
+        let matches: Vec<JsonValue> = self
+            .matches
+            .iter()
+            .map(|m| serde_json::json!({ "path": m.path, "value": m.value }))
+            .collect();
+        let raw: Vec<JsonValue> = self
+            .raw
+            .iter()
+            .map(|m| serde_json::json!({ "offset": m.offset, "path": m.path }))
+            .collect();
+        serde_json::json!({
+            "pattern": self.pattern,
+            "matches": matches,
+            "raw": raw
+        })
+    }
+
+    /// Format as one line per match, with the pattern highlighted.
+    pub fn to_pretty(&self) -> String {
+        let mut output = String::new();
+        for m in &self.matches {
+            output.push_str(&format!(
+                "{}  {}\n",
+                m.path.cyan(),
+                highlight(&m.value, &self.pattern)
+            ));
+        }
+        if !self.raw.is_empty() {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&format!("{}\n", "Raw bytes".bold().cyan()));
+            for m in &self.raw {
+                output.push_str(&format!(
+                    "  offset {}  {}\n",
+                    m.offset,
+                    m.path.as_deref().unwrap_or("-")
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// Highlight the first occurrence of the pattern in a value.
+fn highlight(value: &str, pattern: &str) -> String {
+    match value.to_ascii_lowercase().find(pattern) {
+        Some(start) => {
+            let end = start + pattern.len();
+            format!(
+                "{}{}{}",
+                &value[..start],
+                value[start..end].yellow().bold(),
+                &value[end..]
+            )
+        }
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_all() {
+        assert_eq!(find_all(&[1, 2, 1, 2, 1], &[1, 2, 1]), vec![0, 2]);
+        assert!(find_all(&[1, 2], &[3]).is_empty());
+    }
+}
@@ -0,0 +1,21 @@
+//! Transaction reports (summaries, totals and other condensed views).
+
+mod canonical;
+mod explain;
+mod flow;
+mod graph;
+mod grep;
+mod script;
+mod size;
+mod stats;
+mod summary;
+
+pub use canonical::{CanonicalReport, check_canonical};
+pub use explain::{Explanation, explain};
+pub use flow::{AddressFlow, FlowReport, compute_flow};
+pub use graph::{GraphFormat, render_graph};
+pub use grep::{GrepMatch, GrepReport, RawMatch, grep};
+pub use script::{ScriptReport, inspect_script};
+pub use size::{SizeReport, compute_size};
+pub use stats::{TransactionStats, compute_stats};
+pub use summary::{TransactionSummary, summarize};
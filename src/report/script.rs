@@ -0,0 +1,142 @@
+//! A Plutus script and its decompiled program (`cq script`).
+
+use crate::decode::{Program, decode_program, double_wrapped_hash};
+use crate::error::{Error, Result};
+use crate::format::{format_number_with_separators, format_uplc_summary};
+use cml_chain::Script;
+use cml_chain::plutus::{PlutusV1Script, PlutusV2Script, PlutusV3Script};
+use cml_core::serialization::Deserialize;
+use cml_crypto::RawBytesEncoding;
+use colored::Colorize;
+use serde_json::Value as JsonValue;
+
+/// A decompiled Plutus script.
+pub struct ScriptReport {
+    /// Plutus version, when the input says (script CBOR or a script file).
+    pub language: Option<&'static str>,
+    /// Script hash, when the language is known.
+    pub hash: Option<String>,
+    /// Hash without an extra CBOR byte string layer around the script
+    /// bytes, when there is one.
+    pub unwrapped_hash: Option<String>,
+    /// Size of the script bytes as given.
+    pub size: usize,
+    /// The decoded program.
+    pub program: Program,
+}
+
+/// Decompile a Plutus script given as a cardano-cli script file, script
+/// CBOR (`[language, bytes]`), or script bytes with or without their CBOR
+/// byte string wrapping.
+pub fn inspect_script(bytes: &[u8]) -> Result<ScriptReport> {
+    let script = match text_envelope(bytes)? {
+        Some(script) => Some(script),
+        None => Script::from_cbor_bytes(bytes).ok(),
+    };
+    let (language, script_bytes) = match &script {
+        Some(Script::Native { .. }) => {
+            return Err(Error::DecodeFailed(
+                "native scripts have no Plutus program".to_string(),
+            ));
+        }
+        Some(Script::PlutusV1 { script, .. }) => (Some("plutus_v1"), script.to_raw_bytes()),
+        Some(Script::PlutusV2 { script, .. }) => (Some("plutus_v2"), script.to_raw_bytes()),
+        Some(Script::PlutusV3 { script, .. }) => (Some("plutus_v3"), script.to_raw_bytes()),
+        None => (None, bytes),
+    };
+
+    Ok(ScriptReport {
+        language,
+        hash: script.as_ref().map(|s| s.hash().to_hex()),
+        unwrapped_hash: script
+            .as_ref()
+            .and_then(double_wrapped_hash)
+            .map(|hash| hash.to_hex()),
+        size: script_bytes.len(),
+        program: decode_program(script_bytes)?,
+    })
+}
+
+/// A cardano-cli script file: `{"type": "PlutusScriptV2", "cborHex": ...}`.
+fn text_envelope(bytes: &[u8]) -> Result<Option<Script>> {
+    let Ok(json) = serde_json::from_slice::<JsonValue>(bytes) else {
+        return Ok(None);
+    };
+    let invalid = |msg: String| Error::DecodeFailed(format!("invalid script file: {}", msg));
+    let cbor = json["cborHex"]
+        .as_str()
+        .and_then(|cbor| hex::decode(cbor).ok())
+        .ok_or_else(|| invalid("no cborHex".to_string()))?;
+    let kind = json["type"].as_str().unwrap_or_default();
+    let script = match kind {
+        "PlutusScriptV1" => PlutusV1Script::from_cbor_bytes(&cbor).map(Script::new_plutus_v1),
+        "PlutusScriptV2" => PlutusV2Script::from_cbor_bytes(&cbor).map(Script::new_plutus_v2),
+        "PlutusScriptV3" => PlutusV3Script::from_cbor_bytes(&cbor).map(Script::new_plutus_v3),
+        other => return Err(invalid(format!("not a Plutus script type: {}", other))),
+    };
+    script
+        .map(Some)
+        .map_err(|e| invalid(format!("{}: {}", kind, e)))
+}
+
+impl ScriptReport {
+    /// The script as JSON, the program under `uplc`.
+    pub fn to_json(&self) -> JsonValue {
+        let mut json = serde_json::json!({
+            "language": self.language,
+            "hash": self.hash,
+            "size": self.size,
+            "uplc": self.program.to_json(),
+        });
+        if let Some(unwrapped) = &self.unwrapped_hash {
+            json["double_cbor"] = serde_json::json!({
+                "hash": self.hash,
+                "unwrapped_hash": unwrapped,
+            });
+        }
+        json
+    }
+
+    /// The script summary followed by the program.
+    pub fn to_pretty(&self) -> String {
+        let mut output = format!("{}\n", "Script".bold().cyan());
+        let mut line = |label: &str, value: String| {
+            output.push_str(&format!(
+                "  {} {}\n",
+                format!("{:<10}", label).dimmed(),
+                value
+            ));
+        };
+        let language = match self.language {
+            Some("plutus_v1") => "Plutus V1",
+            Some("plutus_v2") => "Plutus V2",
+            Some("plutus_v3") => "Plutus V3",
+            _ => "unknown (bare script bytes)",
+        };
+        line("Language:", language.to_string());
+        if let Some(hash) = &self.hash {
+            line("Hash:", hash.yellow().to_string());
+        }
+        if let Some(unwrapped) = &self.unwrapped_hash {
+            line(
+                "",
+                format!(
+                    "{} without the extra layer, the hash is {}",
+                    "✗ double CBOR-wrapped:".red(),
+                    unwrapped.yellow()
+                ),
+            );
+        }
+        line(
+            "Size:",
+            format!("{} B", format_number_with_separators(self.size as u64)),
+        );
+        let uplc = self.program.to_json();
+        output.push_str(&format!("  {}\n", format_uplc_summary(&uplc)));
+
+        output.push_str(&format!("\n{}\n", "Program".bold().cyan()));
+        output.push_str(uplc["program"].as_str().unwrap_or_default());
+        output.push('\n');
+        output
+    }
+}
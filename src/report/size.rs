@@ -0,0 +1,258 @@
+//! Per-section byte-size breakdown of the original transaction CBOR.
+
+use crate::cli::Args;
+use crate::decode::{
+    CborSpan, DecodedTransaction, array_items, body_field_name, map_entries, output_field_name,
+    read_uint, witness_field_name,
+};
+use crate::error::{Error, Result};
+use crate::format::{format_number_with_separators, new_table};
+use crate::query::QueryOptions;
+use colored::Colorize;
+use comfy_table::{Cell, CellAlignment};
+use serde_json::Value as JsonValue;
+
+/// Byte-size breakdown of a transaction.
+pub struct SizeReport {
+    /// Total serialized size in bytes.
+    pub total: usize,
+    /// Maximum transaction size (`--pparams` maxTxSize, or mainnet's).
+    pub max: u64,
+    /// Sections in encoding order, identified by query-style paths.
+    pub sections: Vec<SizeEntry>,
+}
+
+/// Size of a single section of the transaction.
+pub struct SizeEntry {
+    /// Query-style path of the section (e.g. `body.outputs.0`).
+    pub path: String,
+    /// Encoded size in bytes.
+    pub bytes: usize,
+}
+
+/// Witness set fields broken down per element.
+const PER_ELEMENT_WITNESS_FIELDS: [&str; 4] = [
+    "plutus_v1_scripts",
+    "plutus_v2_scripts",
+    "plutus_v3_scripts",
+    "plutus_datums",
+];
+
+/// Compute the size breakdown from the transaction's original bytes.
+pub fn compute_size(tx: &DecodedTransaction, options: &QueryOptions) -> Result<SizeReport> {
+    let bytes = &tx.original_bytes;
+    let total = bytes.len();
+    let parts = array_items(bytes, 0)?;
+    if parts.len() < 3 {
+        return Err(Error::DecodeFailed(
+            "Expected a transaction array with at least 3 elements".to_string(),
+        ));
+    }
+
+    let mut sections = Vec::new();
+    let mut push = |path: String, span: CborSpan| {
+        sections.push(SizeEntry {
+            path,
+            bytes: span.len(),
+        })
+    };
+
+    // Body and its fields, with per-output detail
+    push("body".to_string(), parts[0]);
+    for (key, value) in map_entries(bytes, parts[0].start)? {
+        let name = field_label(bytes, key, body_field_name);
+        push(format!("body.{}", name), value);
+
+        if name == "outputs" {
+            for (idx, output) in array_items(bytes, value.start)?.into_iter().enumerate() {
+                push(format!("body.outputs.{}", idx), output);
+                // Post-Alonzo outputs are maps; legacy outputs are arrays
+                if let Ok(fields) = map_entries(bytes, output.start) {
+                    for (key, value) in fields {
+                        let field = field_label(bytes, key, output_field_name);
+                        if field == "datum" || field == "script_ref" {
+                            push(format!("body.outputs.{}.{}", idx, field), value);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Witness set, with per-script and per-datum detail
+    push("witness_set".to_string(), parts[1]);
+    for (key, value) in map_entries(bytes, parts[1].start)? {
+        let name = field_label(bytes, key, witness_field_name);
+        push(format!("witness_set.{}", name), value);
+
+        if PER_ELEMENT_WITNESS_FIELDS.contains(&name.as_str()) {
+            for (idx, item) in array_items(bytes, value.start)?.into_iter().enumerate() {
+                push(format!("witness_set.{}.{}", name, idx), item);
+            }
+        }
+    }
+
+    // Babbage+ transactions carry is_valid before the auxiliary data
+    let aux = if parts.len() >= 4 {
+        push("is_valid".to_string(), parts[2]);
+        parts[3]
+    } else {
+        parts[2]
+    };
+
+    // Auxiliary data (null when absent)
+    if bytes[aux.start] != 0xf6 {
+        push("auxiliary_data".to_string(), aux);
+        if let Some(metadata) = metadata_span(bytes, aux)? {
+            push("auxiliary_data.metadata".to_string(), metadata);
+        }
+    }
+
+    let max = options.protocol_params.unwrap_or_default().tx_size_limit();
+    Ok(SizeReport {
+        total,
+        max,
+        sections,
+    })
+}
+
+/// Resolve a map key to a field name, falling back to the raw key.
+fn field_label(bytes: &[u8], key: CborSpan, names: fn(u64) -> Option<&'static str>) -> String {
+    match read_uint(bytes, key.start) {
+        Ok(k) => names(k)
+            .map(str::to_string)
+            .unwrap_or_else(|| k.to_string()),
+        Err(_) => format!("key@{}", key.start),
+    }
+}
+
+/// Locate the metadata inside auxiliary data.
+///
+/// Handles the Shelley (bare metadata map), Shelley-MA (`[metadata, scripts]`)
+/// and Alonzo+ (tag 259 map with metadata at key 0) encodings.
+fn metadata_span(bytes: &[u8], aux: CborSpan) -> Result<Option<CborSpan>> {
+    // Alonzo+ format: #6.259({0: metadata, ...})
+    if bytes[aux.start..].starts_with(&[0xd9, 0x01, 0x03]) {
+        for (key, value) in map_entries(bytes, aux.start)? {
+            if read_uint(bytes, key.start).ok() == Some(0) {
+                return Ok(Some(value));
+            }
+        }
+        return Ok(None);
+    }
+
+    // Shelley-MA format: [metadata, scripts]
+    if let Ok(items) = array_items(bytes, aux.start) {
+        return Ok(items.first().copied());
+    }
+
+    // Shelley format: the map is the metadata
+    Ok(Some(aux))
+}
+
+impl SizeReport {
+    /// Percentage of the total size used by a section.
+    fn percent(&self, bytes: usize) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            bytes as f64 * 100.0 / self.total as f64
+        }
+    }
+
+    /// Convert to JSON.
+    pub fn to_json(&self) -> JsonValue {
+        let sections: Vec<JsonValue> = self
+            .sections
+            .iter()
+            .map(|s| {
+                serde_json::json!({
+                    "path": s.path,
+                    "bytes": s.bytes,
+                    "percent": (self.percent(s.bytes) * 100.0).round() / 100.0
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "total": self.total,
+            "max_size": self.max,
+            "within_limit": self.total as u64 <= self.max,
+            "sections": sections
+        })
+    }
+
+    /// Format as an indented table for terminal output.
+    pub fn to_pretty(&self, args: &Args) -> String {
+        let mut table = new_table(args);
+        table.set_header(vec![
+            Cell::new("Section").fg(comfy_table::Color::DarkGrey),
+            Cell::new("Bytes").fg(comfy_table::Color::DarkGrey),
+            Cell::new("%").fg(comfy_table::Color::DarkGrey),
+        ]);
+
+        for section in &self.sections {
+            let depth = section.path.matches('.').count();
+            let name = section.path.rsplit('.').next().unwrap_or(&section.path);
+            let name = if name.chars().all(|c| c.is_ascii_digit()) {
+                format!("[{}]", name)
+            } else {
+                name.to_string()
+            };
+
+            table.add_row(vec![
+                Cell::new(format!("{}{}", "  ".repeat(depth), name)),
+                Cell::new(format_number_with_separators(section.bytes as u64))
+                    .set_alignment(CellAlignment::Right),
+                Cell::new(format!("{:.1}", self.percent(section.bytes)))
+                    .set_alignment(CellAlignment::Right),
+            ]);
+        }
+
+        let limit = if self.total as u64 > self.max {
+            "OVER LIMIT".red().bold().to_string()
+        } else {
+            "within limit".green().to_string()
+        };
+        format!(
+            "{} {} / {} bytes ({:.1}%) {}\n{}\n",
+            "Transaction size:".bold().cyan(),
+            format_number_with_separators(self.total as u64),
+            format_number_with_separators(self.max),
+            self.total as f64 * 100.0 / self.max.max(1) as f64,
+            limit,
+            table
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_span_formats() {
+        // Shelley: {674: 1}
+        let shelley = [0xa1, 0x19, 0x02, 0xa2, 0x01];
+        let span = CborSpan { start: 0, end: 5 };
+        assert_eq!(metadata_span(&shelley, span).unwrap(), Some(span));
+
+        // Alonzo: 259({0: {1: 1}})
+        let alonzo = [0xd9, 0x01, 0x03, 0xa1, 0x00, 0xa1, 0x01, 0x01];
+        let span = CborSpan { start: 0, end: 8 };
+        assert_eq!(
+            metadata_span(&alonzo, span).unwrap(),
+            Some(CborSpan { start: 5, end: 8 })
+        );
+    }
+
+    #[test]
+    fn test_percent() {
+        let report = SizeReport {
+            total: 200,
+            max: 16_384,
+            sections: vec![],
+        };
+        assert_eq!(report.percent(50), 25.0);
+    }
+}
@@ -0,0 +1,254 @@
+//! Transaction totals for monitoring and scripting.
+
+use crate::decode::DecodedTransaction;
+use crate::error::Result;
+use crate::format::format_number_with_separators;
+use crate::query::{QueryOptions, transaction_to_json};
+use colored::Colorize;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+/// Aggregated totals for a transaction.
+pub struct TransactionStats {
+    /// Sum of lovelace across all outputs.
+    pub total_output: u64,
+    /// Per-policy asset quantities summed across all outputs.
+    pub assets: BTreeMap<String, BTreeMap<String, u64>>,
+    /// Sum of all reward withdrawals.
+    pub total_withdrawals: u64,
+    /// Sum of explicit deposits paid by certificates.
+    pub total_deposits: u64,
+    /// Sum of explicit deposits refunded by certificates.
+    pub total_refunds: u64,
+    /// Transaction fee in lovelace.
+    pub fee: u64,
+    /// Witness counts by kind.
+    pub witnesses: WitnessCounts,
+    /// Serialized transaction size in bytes.
+    pub size: usize,
+}
+
+/// Counts of each kind of witness.
+#[derive(Default)]
+pub struct WitnessCounts {
+    pub vkey: usize,
+    pub bootstrap: usize,
+    pub native_scripts: usize,
+    pub plutus_scripts: usize,
+    pub plutus_datums: usize,
+    pub redeemers: usize,
+}
+
+/// Certificate types whose `deposit` field is refunded rather than paid.
+const REFUND_CERTS: [&str; 2] = ["unreg_cert", "unreg_drep_cert"];
+
+/// Compute totals for a decoded transaction.
+pub fn compute_stats(tx: &DecodedTransaction, options: &QueryOptions) -> Result<TransactionStats> {
+    let json = transaction_to_json(tx, options)?;
+    let body = &json["body"];
+    let witness_set = tx.witness_set();
+
+    let outputs = body["outputs"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+
+    let mut total_output = 0;
+    let mut assets: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+    for output in outputs {
+        total_output += output["value"]["coin"].as_u64().unwrap_or(0);
+
+        for policy in output["value"]["multi_assets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            let policy_id = policy["policy_id"].as_str().unwrap_or("?").to_string();
+            let entry = assets.entry(policy_id).or_default();
+            for asset in policy["assets"].as_array().into_iter().flatten() {
+                let name = asset["name"].as_str().unwrap_or("").to_string();
+                *entry.entry(name).or_default() += asset["amount"].as_u64().unwrap_or(0);
+            }
+        }
+    }
+
+    let total_withdrawals = body["withdrawals"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|w| w["amount"].as_u64())
+        .sum();
+
+    let mut total_deposits = 0;
+    let mut total_refunds = 0;
+    for cert in body["certs"].as_array().into_iter().flatten() {
+        let Some(deposit) = cert["deposit"].as_u64() else {
+            continue;
+        };
+        let cert_type = cert["type"].as_str().unwrap_or("");
+        if REFUND_CERTS.contains(&cert_type) {
+            total_refunds += deposit;
+        } else {
+            total_deposits += deposit;
+        }
+    }
+
+    let witnesses = WitnessCounts {
+        vkey: witness_set.vkeywitnesses.as_ref().map_or(0, |w| w.len()),
+        bootstrap: witness_set
+            .bootstrap_witnesses
+            .as_ref()
+            .map_or(0, |w| w.len()),
+        native_scripts: witness_set.native_scripts.as_ref().map_or(0, |s| s.len()),
+        plutus_scripts: witness_set
+            .plutus_v1_scripts
+            .as_ref()
+            .map_or(0, |s| s.len())
+            + witness_set
+                .plutus_v2_scripts
+                .as_ref()
+                .map_or(0, |s| s.len())
+            + witness_set
+                .plutus_v3_scripts
+                .as_ref()
+                .map_or(0, |s| s.len()),
+        plutus_datums: witness_set.plutus_datums.as_ref().map_or(0, |d| d.len()),
+        redeemers: json["witness_set"]["redeemers"]
+            .as_array()
+            .map_or(0, Vec::len),
+    };
+
+    Ok(TransactionStats {
+        total_output,
+        assets,
+        total_withdrawals,
+        total_deposits,
+        total_refunds,
+        fee: body["fee"].as_u64().unwrap_or(0),
+        witnesses,
+        size: tx.original_bytes.len(),
+    })
+}
+
+impl TransactionStats {
+    /// Convert to JSON.
+    pub fn to_json(&self) -> JsonValue {
+        let assets: Vec<JsonValue> = self
+            .assets
+            .iter()
+            .map(|(policy_id, names)| {
+                let assets_json: Vec<JsonValue> = names
+                    .iter()
+                    .map(|(name, amount)| serde_json::json!({ "name": name, "amount": amount }))
+                    .collect();
+                serde_json::json!({
+                    "policy_id": policy_id,
+                    "total": names.values().sum::<u64>(),
+                    "assets": assets_json
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "total_output": self.total_output,
+            "assets": assets,
+            "total_withdrawals": self.total_withdrawals,
+            "total_deposits": self.total_deposits,
+            "total_refunds": self.total_refunds,
+            "fee": self.fee,
+            "witnesses": {
+                "vkey": self.witnesses.vkey,
+                "bootstrap": self.witnesses.bootstrap,
+                "native_scripts": self.witnesses.native_scripts,
+                "plutus_scripts": self.witnesses.plutus_scripts,
+                "plutus_datums": self.witnesses.plutus_datums,
+                "redeemers": self.witnesses.redeemers
+            },
+            "size": self.size
+        })
+    }
+
+    /// Format for terminal output.
+    pub fn to_pretty(&self) -> String {
+        let mut output = String::new();
+        let lovelace = |n: u64| format!("{} lovelace", format_number_with_separators(n));
+
+        output.push_str(&format!("{}\n", "Totals".bold().cyan()));
+        for (label, value) in [
+            ("Outputs:", lovelace(self.total_output)),
+            ("Fee:", lovelace(self.fee)),
+            ("Withdrawals:", lovelace(self.total_withdrawals)),
+            ("Deposits:", lovelace(self.total_deposits)),
+            ("Refunds:", lovelace(self.total_refunds)),
+            (
+                "Size:",
+                format!("{} bytes", format_number_with_separators(self.size as u64)),
+            ),
+        ] {
+            output.push_str(&format!(
+                "  {} {}\n",
+                format!("{:<13}", label).dimmed(),
+                value
+            ));
+        }
+
+        if !self.assets.is_empty() {
+            output.push_str(&format!("\n{}\n", "Assets".bold().cyan()));
+            for (policy_id, names) in &self.assets {
+                output.push_str(&format!("  {} {}\n", "Policy:".dimmed(), policy_id));
+                for (name, amount) in names {
+                    let name = if name.is_empty() { "(empty)" } else { name };
+                    output.push_str(&format!(
+                        "    {} {}\n",
+                        name,
+                        format_number_with_separators(*amount)
+                    ));
+                }
+            }
+        }
+
+        output.push_str(&format!("\n{}\n", "Witnesses".bold().cyan()));
+        for (label, count) in [
+            ("VKey:", self.witnesses.vkey),
+            ("Bootstrap:", self.witnesses.bootstrap),
+            ("Native scripts:", self.witnesses.native_scripts),
+            ("Plutus scripts:", self.witnesses.plutus_scripts),
+            ("Plutus datums:", self.witnesses.plutus_datums),
+            ("Redeemers:", self.witnesses.redeemers),
+        ] {
+            output.push_str(&format!(
+                "  {} {}\n",
+                format!("{:<16}", label).dimmed(),
+                count
+            ));
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_json_policy_totals() {
+        let mut assets = BTreeMap::new();
+        assets.insert(
+            "ab".repeat(28),
+            BTreeMap::from([("A".to_string(), 5), ("B".to_string(), 7)]),
+        );
+        let stats = TransactionStats {
+            total_output: 10,
+            assets,
+            total_withdrawals: 0,
+            total_deposits: 2_000_000,
+            total_refunds: 0,
+            fee: 1,
+            witnesses: WitnessCounts::default(),
+            size: 300,
+        };
+
+        let json = stats.to_json();
+        assert_eq!(json["assets"][0]["total"], 12);
+        assert_eq!(json["assets"][0]["assets"][1]["name"], "B");
+        assert_eq!(json["total_deposits"], 2_000_000);
+    }
+}
@@ -0,0 +1,335 @@
+//! Compact one-screen transaction summary.
+
+use crate::decode::{DecodedTransaction, Era};
+use crate::error::Result;
+use crate::format::format_number_with_separators;
+use crate::query::{QueryOptions, transaction_to_json};
+use colored::Colorize;
+use serde_json::Value as JsonValue;
+
+/// A condensed view of a transaction for quick triage.
+pub struct TransactionSummary {
+    /// Detected ledger era.
+    pub era: Era,
+    /// Transaction hash in hex.
+    pub hash: String,
+    /// Whether the transaction is marked as valid.
+    pub is_valid: bool,
+    /// Number of spent inputs.
+    pub input_count: usize,
+    /// Number of outputs.
+    pub output_count: usize,
+    /// Sum of lovelace across all outputs.
+    pub total_output: u64,
+    /// Transaction fee in lovelace.
+    pub fee: u64,
+    /// Minted (positive) and burned (negative) assets.
+    pub minted: Vec<MintedAsset>,
+    /// Certificate types in body order.
+    pub certificates: Vec<String>,
+    /// Metadata labels present in auxiliary data.
+    pub metadata_labels: Vec<u64>,
+    /// Script usage counts.
+    pub scripts: ScriptUsage,
+}
+
+/// A single minted or burned asset.
+pub struct MintedAsset {
+    pub policy_id: String,
+    pub name: String,
+    pub amount: i64,
+    /// Amount with the token's decimals and ticker, from a token registry.
+    pub formatted: Option<String>,
+}
+
+/// Counts of scripts and script-related witnesses.
+#[derive(Default)]
+pub struct ScriptUsage {
+    pub native: u64,
+    pub plutus_v1: usize,
+    pub plutus_v2: usize,
+    pub plutus_v3: usize,
+    /// Outputs carrying a reference script.
+    pub reference_scripts: usize,
+    pub redeemers: usize,
+}
+
+impl ScriptUsage {
+    /// Check if the transaction uses no scripts at all.
+    pub fn is_empty(&self) -> bool {
+        self.native == 0
+            && self.plutus_v1 == 0
+            && self.plutus_v2 == 0
+            && self.plutus_v3 == 0
+            && self.reference_scripts == 0
+            && self.redeemers == 0
+    }
+}
+
+/// Build a summary of a decoded transaction.
+pub fn summarize(tx: &DecodedTransaction, options: &QueryOptions) -> Result<TransactionSummary> {
+    let json = transaction_to_json(tx, options)?;
+    let body = &json["body"];
+    let witnesses = &json["witness_set"];
+
+    let outputs = array_or_empty(&body["outputs"]);
+    let total_output = outputs
+        .iter()
+        .filter_map(|o| o["value"]["coin"].as_u64())
+        .sum();
+
+    let minted = array_or_empty(&body["mint"])
+        .iter()
+        .flat_map(|entry| {
+            let policy_id = entry["policy_id"].as_str().unwrap_or("?").to_string();
+            array_or_empty(&entry["assets"])
+                .iter()
+                .map(move |asset| MintedAsset {
+                    policy_id: policy_id.clone(),
+                    name: asset["name"].as_str().unwrap_or("").to_string(),
+                    amount: asset["amount"].as_i64().unwrap_or(0),
+                    formatted: asset["formatted"].as_str().map(str::to_string),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let certificates = array_or_empty(&body["certs"])
+        .iter()
+        .map(|c| c["type"].as_str().unwrap_or("unknown").to_string())
+        .collect();
+
+    let metadata_labels = array_or_empty(&json["auxiliary_data"]["metadata"]["labels"])
+        .iter()
+        .filter_map(|l| l["label"].as_u64())
+        .collect();
+
+    let scripts = ScriptUsage {
+        native: witnesses["native_scripts"].as_u64().unwrap_or(0),
+        plutus_v1: array_or_empty(&witnesses["plutus_v1_scripts"]).len(),
+        plutus_v2: array_or_empty(&witnesses["plutus_v2_scripts"]).len(),
+        plutus_v3: array_or_empty(&witnesses["plutus_v3_scripts"]).len(),
+        reference_scripts: outputs
+            .iter()
+            .filter(|o| o.get("script_ref").is_some())
+            .count(),
+        redeemers: array_or_empty(&witnesses["redeemers"]).len(),
+    };
+
+    Ok(TransactionSummary {
+        era: tx.era(),
+        hash: json["hash"].as_str().unwrap_or_default().to_string(),
+        is_valid: tx.is_valid(),
+        input_count: array_or_empty(&body["inputs"]).len(),
+        output_count: outputs.len(),
+        total_output,
+        fee: body["fee"].as_u64().unwrap_or(0),
+        minted,
+        certificates,
+        metadata_labels,
+        scripts,
+    })
+}
+
+/// Borrow a JSON array, treating missing fields as empty.
+fn array_or_empty(value: &JsonValue) -> &[JsonValue] {
+    value.as_array().map(Vec::as_slice).unwrap_or(&[])
+}
+
+impl TransactionSummary {
+    /// Convert to JSON.
+    pub fn to_json(&self) -> JsonValue {
+        let minted: Vec<JsonValue> = self
+            .minted
+            .iter()
+            .map(|a| {
+                serde_json::json!({
+                    "policy_id": a.policy_id,
+                    "name": a.name,
+                    "amount": a.amount
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "era": self.era.as_str(),
+            "hash": self.hash,
+            "is_valid": self.is_valid,
+            "inputs": self.input_count,
+            "outputs": self.output_count,
+            "total_output": self.total_output,
+            "fee": self.fee,
+            "minted": minted,
+            "certificates": self.certificates,
+            "metadata_labels": self.metadata_labels,
+            "scripts": {
+                "native": self.scripts.native,
+                "plutus_v1": self.scripts.plutus_v1,
+                "plutus_v2": self.scripts.plutus_v2,
+                "plutus_v3": self.scripts.plutus_v3,
+                "reference_scripts": self.scripts.reference_scripts,
+                "redeemers": self.scripts.redeemers
+            }
+        })
+    }
+
+    /// Format as a compact block for terminal output.
+    pub fn to_pretty(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("{}\n", "Summary".bold().cyan()));
+
+        let mut line = |label: &str, value: String| {
+            output.push_str(&format!(
+                "  {} {}\n",
+                format!("{:<14}", label).dimmed(),
+                value
+            ));
+        };
+
+        line("Era:", self.era.description().to_string());
+        line("Hash:", self.hash.yellow().to_string());
+        line(
+            "Valid:",
+            if self.is_valid {
+                "true".green().to_string()
+            } else {
+                "false".red().to_string()
+            },
+        );
+        line("Inputs:", self.input_count.to_string());
+        line("Outputs:", self.output_count.to_string());
+        line(
+            "Total output:",
+            format!(
+                "{} lovelace",
+                format_number_with_separators(self.total_output)
+            ),
+        );
+        line(
+            "Fee:",
+            format!("{} lovelace", format_number_with_separators(self.fee)),
+        );
+        line("Minted:", self.minted_display());
+        line("Certificates:", self.certificates_display());
+        line("Metadata:", self.metadata_display());
+        line("Scripts:", self.scripts_display());
+
+        output
+    }
+
+    fn minted_display(&self) -> String {
+        if self.minted.is_empty() {
+            return "-".to_string();
+        }
+
+        let assets: Vec<String> = self
+            .minted
+            .iter()
+            .map(|a| {
+                let name = if a.name.is_empty() {
+                    "(empty)"
+                } else {
+                    a.name.as_str()
+                };
+                let amount = a.formatted.clone().unwrap_or_else(|| a.amount.to_string());
+                if a.amount > 0 {
+                    format!("{} +{}", name, amount)
+                } else {
+                    format!("{} {}", name, amount)
+                }
+            })
+            .collect();
+
+        format!("{} asset(s): {}", self.minted.len(), assets.join(", "))
+    }
+
+    fn certificates_display(&self) -> String {
+        if self.certificates.is_empty() {
+            "-".to_string()
+        } else {
+            format!(
+                "{} ({})",
+                self.certificates.len(),
+                self.certificates.join(", ")
+            )
+        }
+    }
+
+    fn metadata_display(&self) -> String {
+        if self.metadata_labels.is_empty() {
+            "-".to_string()
+        } else {
+            let labels: Vec<String> = self.metadata_labels.iter().map(u64::to_string).collect();
+            format!("label(s) {}", labels.join(", "))
+        }
+    }
+
+    fn scripts_display(&self) -> String {
+        if self.scripts.is_empty() {
+            return "-".to_string();
+        }
+
+        let mut parts = Vec::new();
+        for (count, label) in [
+            (self.scripts.native as usize, "native"),
+            (self.scripts.plutus_v1, "Plutus V1"),
+            (self.scripts.plutus_v2, "Plutus V2"),
+            (self.scripts.plutus_v3, "Plutus V3"),
+            (self.scripts.reference_scripts, "reference"),
+            (self.scripts.redeemers, "redeemer(s)"),
+        ] {
+            if count > 0 {
+                parts.push(format!("{} {}", count, label));
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary() -> TransactionSummary {
+        TransactionSummary {
+            era: Era::Babbage,
+            hash: "00".repeat(32),
+            is_valid: true,
+            input_count: 1,
+            output_count: 2,
+            total_output: 3_000_000,
+            fee: 170_000,
+            minted: vec![MintedAsset {
+                policy_id: "ab".repeat(28),
+                name: "TOKEN".to_string(),
+                amount: -5,
+                formatted: None,
+            }],
+            certificates: vec![],
+            metadata_labels: vec![674, 721],
+            scripts: ScriptUsage {
+                plutus_v2: 1,
+                redeemers: 2,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_summary_displays() {
+        let s = summary();
+        assert_eq!(s.minted_display(), "1 asset(s): TOKEN -5");
+        assert_eq!(s.certificates_display(), "-");
+        assert_eq!(s.metadata_display(), "label(s) 674, 721");
+        assert_eq!(s.scripts_display(), "1 Plutus V2, 2 redeemer(s)");
+    }
+
+    #[test]
+    fn test_summary_json() {
+        let json = summary().to_json();
+        assert_eq!(json["era"], "babbage");
+        assert_eq!(json["total_output"], 3_000_000);
+        assert_eq!(json["scripts"]["redeemers"], 2);
+    }
+}
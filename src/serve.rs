@@ -0,0 +1,185 @@
+//! HTTP API mode (`cq serve`).
+//!
+//! - `POST /decode`: the full transaction as JSON
+//! - `POST /query?q=<query>`: the query result as JSON
+//! - `GET /health`: liveness check
+//!
+//! Request bodies are binary CBOR or hex text. Errors are returned as
+//! `{"error": ..., "exit_code": ...}` with the exit code the CLI would use.
+
+use crate::decode::decode_transaction;
+use crate::error::{Error, Result};
+use crate::input::detect_and_decode_stdin;
+use crate::query::{QueryOptions, execute_query, transaction_to_json};
+use serde_json::Value as JsonValue;
+use std::io::Read;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Largest request body accepted (transactions are limited to 16 KB on chain).
+const MAX_BODY: u64 = 1024 * 1024;
+
+/// Serve the API on `addr` until the process is stopped.
+pub fn serve(addr: &str, options: &QueryOptions) -> Result<()> {
+    let server = Server::http(addr)
+        .map_err(|e| Error::NetworkError(format!("cannot listen on {}: {}", addr, e)))?;
+    match server.server_addr().to_ip() {
+        Some(bound) => eprintln!("Listening on http://{}", bound),
+        None => eprintln!("Listening on {}", addr),
+    }
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match read_body(&mut request) {
+            Ok(bytes) => handle(request.method(), request.url(), &bytes, options),
+            Err(e) => error_response(&e),
+        };
+        log::debug!("{} {} -> {}", request.method(), request.url(), status);
+
+        let header =
+            Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(header);
+        if let Err(e) = request.respond(response) {
+            log::debug!("failed to send response: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Read the request body, rejecting oversized ones.
+fn read_body(request: &mut Request) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_end(&mut body)
+        .map_err(|source| Error::IoError { path: None, source })?;
+    if body.len() as u64 > MAX_BODY {
+        return Err(Error::DecodeFailed(format!(
+            "request body exceeds {} bytes",
+            MAX_BODY
+        )));
+    }
+    Ok(body)
+}
+
+/// Route a request, returning the status code and JSON body.
+fn handle(method: &Method, url: &str, body: &[u8], options: &QueryOptions) -> (u16, JsonValue) {
+    let (path, query_string) = url.split_once('?').unwrap_or((url, ""));
+    let result = match (method, path) {
+        (Method::Get, "/health") => Ok(serde_json::json!({
+            "status": "ok",
+            "version": env!("CARGO_PKG_VERSION")
+        })),
+        (Method::Post, "/decode") => {
+            decode_body(body).and_then(|tx| transaction_to_json(&tx, options))
+        }
+        (Method::Post, "/query") => match query_param(query_string, "q") {
+            Some(query) => decode_body(body).and_then(|tx| {
+                let result = execute_query(&tx, &query, options)?;
+                serde_json::to_value(&result)
+                    .map_err(|e| Error::FormatError(format!("JSON error: {}", e)))
+            }),
+            None => {
+                return (
+                    400,
+                    serde_json::json!({ "error": "missing query parameter 'q'" }),
+                );
+            }
+        },
+        (_, "/health" | "/decode" | "/query") => {
+            return (405, serde_json::json!({ "error": "method not allowed" }));
+        }
+        _ => return (404, serde_json::json!({ "error": "not found" })),
+    };
+
+    match result {
+        Ok(json) => (200, json),
+        Err(e) => error_response(&e),
+    }
+}
+
+/// Decode a request body holding binary CBOR or hex text.
+fn decode_body(body: &[u8]) -> Result<crate::decode::DecodedTransaction<'static>> {
+    decode_transaction(&detect_and_decode_stdin(body.to_vec())?).map(|tx| tx.into_owned())
+}
+
+/// Status and JSON body for an error: client errors for bad input and
+/// queries, server errors otherwise.
+fn error_response(e: &Error) -> (u16, JsonValue) {
+    let status = match e.exit_code() {
+        1..=4 => 400,
+        _ => 500,
+    };
+    (
+        status,
+        serde_json::json!({ "error": e.to_string(), "exit_code": e.exit_code() }),
+    )
+}
+
+/// Value of a query string parameter, percent-decoded.
+fn query_param(query_string: &str, name: &str) -> Option<String> {
+    query_string
+        .split('&')
+        .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(value))
+}
+
+/// Decode `%XX` escapes and `+` (as a space) in a URL component.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let escaped = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match escaped {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("outputs.*.address"), "outputs.*.address");
+        assert_eq!(
+            percent_decode("outputs%5Bvalue.coin+%3E+1%5D"),
+            "outputs[value.coin > 1]"
+        );
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(query_param("a=1&q=fee", "q").as_deref(), Some("fee"));
+        assert_eq!(query_param("a=1", "q"), None);
+    }
+
+    #[test]
+    fn test_routes() {
+        let options = QueryOptions::default();
+        assert_eq!(handle(&Method::Get, "/health", b"", &options).0, 200);
+        assert_eq!(handle(&Method::Get, "/decode", b"", &options).0, 405);
+        assert_eq!(handle(&Method::Get, "/nope", b"", &options).0, 404);
+        assert_eq!(handle(&Method::Post, "/query", b"84", &options).0, 400);
+
+        let (status, body) = handle(&Method::Post, "/decode", b"not a transaction", &options);
+        assert_eq!(status, 400);
+        assert!(body["error"].is_string());
+    }
+}
@@ -0,0 +1,313 @@
+//! Interactive transaction explorer (`cq tui`).
+
+mod tree;
+
+use crate::decode::DecodedTransaction;
+use crate::error::{Error, Result};
+use crate::query::{QueryOptions, transaction_to_json};
+use base64::Engine;
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use std::io::{IsTerminal, Write};
+use tree::Node;
+
+/// Explore a transaction as a collapsible tree until the user quits.
+pub fn explore(tx: &DecodedTransaction, options: &QueryOptions) -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        return Err(Error::FormatError(
+            "cq tui needs an interactive terminal".to_string(),
+        ));
+    }
+
+    let mut app = App::new(Node::root(&transaction_to_json(tx, options)?));
+    let mut terminal = ratatui::try_init().map_err(io_error)?;
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+fn io_error(source: std::io::Error) -> Error {
+    Error::IoError { path: None, source }
+}
+
+/// What keystrokes currently do.
+#[derive(Debug, PartialEq, Eq)]
+enum Mode {
+    /// Moving around the tree.
+    Browse,
+    /// Typing a search term.
+    Search(String),
+}
+
+/// Explorer state.
+struct App {
+    tree: Node,
+    /// Index of the selected row among the visible rows.
+    selected: usize,
+    mode: Mode,
+    /// Locations matching the last search, and the current one.
+    matches: Vec<Vec<usize>>,
+    current_match: usize,
+    /// Status line message (e.g. after copying).
+    message: Option<String>,
+    /// Text to copy to the clipboard after the next draw.
+    to_copy: Option<String>,
+}
+
+impl App {
+    fn new(tree: Node) -> Self {
+        App {
+            tree,
+            selected: 0,
+            mode: Mode::Browse,
+            matches: Vec::new(),
+            current_match: 0,
+            message: None,
+            to_copy: None,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        let mut list_state = ListState::default();
+        loop {
+            list_state.select(Some(self.selected));
+            terminal
+                .draw(|frame| self.draw(frame, &mut list_state))
+                .map_err(io_error)?;
+
+            if let Some(text) = self.to_copy.take() {
+                copy_to_clipboard(&text)?;
+            }
+
+            if let Event::Key(key) = event::read().map_err(io_error)? {
+                if key.kind == KeyEventKind::Press && self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut ratatui::Frame, list_state: &mut ListState) {
+        let [tree_area, status_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(frame.area());
+
+        let items: Vec<ListItem> = self
+            .tree
+            .rows()
+            .iter()
+            .filter_map(|row| {
+                let node = self.tree.get(&row.location)?;
+                let marker = match (node.is_branch(), node.expanded) {
+                    (false, _) => "  ",
+                    (true, false) => "▸ ",
+                    (true, true) => "▾ ",
+                };
+                let mut spans = vec![
+                    Span::raw("  ".repeat(row.depth)),
+                    Span::raw(marker),
+                    Span::styled(node.key.clone(), Style::default().fg(Color::Cyan)),
+                ];
+                if !node.expanded {
+                    spans.push(Span::raw(": "));
+                    spans.push(Span::raw(node.preview()));
+                }
+                Some(ListItem::new(Line::from(spans)))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().title(" cq ").borders(Borders::ALL))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, tree_area, list_state);
+
+        let path = self
+            .selected_node()
+            .map(|node| node.path.clone())
+            .unwrap_or_default();
+        let status = match &self.mode {
+            Mode::Search(term) => format!("/{}", term),
+            Mode::Browse => self.message.clone().unwrap_or_else(|| {
+                "↑↓ move  →← expand/collapse  / search  n/N next/previous  y copy  q quit"
+                    .to_string()
+            }),
+        };
+        let lines = vec![
+            Line::from(Span::styled(path, Style::default().fg(Color::Yellow))),
+            Line::from(status),
+        ];
+        frame.render_widget(Paragraph::new(lines), status_area);
+    }
+
+    fn selected_node(&self) -> Option<&Node> {
+        let rows = self.tree.rows();
+        self.tree.get(&rows.get(self.selected)?.location)
+    }
+
+    /// Apply a key press, returning `true` to quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if let Mode::Search(term) = &mut self.mode {
+            match key.code {
+                KeyCode::Enter => {
+                    let term = std::mem::take(term);
+                    self.mode = Mode::Browse;
+                    self.search(&term);
+                }
+                KeyCode::Esc => self.mode = Mode::Browse,
+                KeyCode::Backspace => {
+                    term.pop();
+                }
+                KeyCode::Char(c) => term.push(c),
+                _ => {}
+            }
+            return false;
+        }
+
+        self.message = None;
+        let rows = self.tree.rows();
+        let last = rows.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return true,
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(last),
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::PageDown => self.selected = (self.selected + 20).min(last),
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(20),
+            KeyCode::Home | KeyCode::Char('g') => self.selected = 0,
+            KeyCode::End | KeyCode::Char('G') => self.selected = last,
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => {
+                if let Some(row) = rows.get(self.selected) {
+                    if let Some(node) = self.tree.get_mut(&row.location) {
+                        node.expanded = node.is_branch();
+                    }
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(&rows),
+            KeyCode::Char('/') => self.mode = Mode::Search(String::new()),
+            KeyCode::Char('n') => self.next_match(1),
+            KeyCode::Char('N') => self.next_match(self.matches.len().saturating_sub(1)),
+            KeyCode::Char('y') => {
+                if let Some(node) = self.selected_node() {
+                    let (text, path) = (node.copy_text(), node.path.clone());
+                    self.to_copy = Some(text);
+                    self.message = Some(format!("Copied {}", path));
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Collapse the selected node, or move to its parent if it is not expanded.
+    fn collapse(&mut self, rows: &[tree::Row]) {
+        let Some(row) = rows.get(self.selected) else {
+            return;
+        };
+        if let Some(node) = self.tree.get_mut(&row.location) {
+            if node.expanded {
+                node.expanded = false;
+                return;
+            }
+        }
+        let parent = &row.location[..row.location.len() - 1];
+        if let Some(idx) = rows.iter().position(|r| r.location == parent) {
+            self.selected = idx;
+        }
+    }
+
+    fn search(&mut self, term: &str) {
+        self.matches = if term.is_empty() {
+            Vec::new()
+        } else {
+            self.tree.search(term)
+        };
+        self.current_match = 0;
+        if self.matches.is_empty() {
+            self.message = Some(format!("No matches for '{}'", term));
+        } else {
+            self.select_match();
+        }
+    }
+
+    /// Move `step` matches forward (wrapping around).
+    fn next_match(&mut self, step: usize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        self.current_match = (self.current_match + step) % self.matches.len();
+        self.select_match();
+    }
+
+    fn select_match(&mut self) {
+        let location = self.matches[self.current_match].clone();
+        self.tree.reveal(&location);
+        if let Some(idx) = self.tree.rows().iter().position(|r| r.location == location) {
+            self.selected = idx;
+        }
+        self.message = Some(format!(
+            "Match {} of {}",
+            self.current_match + 1,
+            self.matches.len()
+        ));
+    }
+}
+
+/// Copy text with the OSC 52 escape sequence, which terminals (including
+/// over SSH and in tmux) forward to the system clipboard.
+fn copy_to_clipboard(text: &str) -> Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", encoded)
+        .and_then(|_| stdout.flush())
+        .map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(app: &mut App, code: KeyCode) -> bool {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn app() -> App {
+        App::new(Node::root(&serde_json::json!({
+            "body": { "fee": 171617, "outputs": [{ "address": "addr_test1" }] },
+            "hash": "0edb"
+        })))
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut app = app();
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.selected_node().unwrap().path, "body.fee");
+
+        // Left on a leaf moves to its parent, then collapses it
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.selected_node().unwrap().path, "body");
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.tree.rows().len(), 2);
+
+        assert!(press(&mut app, KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_search_and_copy() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('/'));
+        for c in "address".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.mode, Mode::Browse);
+        assert_eq!(app.selected_node().unwrap().path, "body.outputs.0.address");
+
+        press(&mut app, KeyCode::Char('y'));
+        assert_eq!(app.to_copy.as_deref(), Some("addr_test1"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Collapsible tree of a JSON document, flattened for display.
+
+use serde_json::Value as JsonValue;
+
+/// One node of the tree (an object field, array element or the root).
+pub struct Node {
+    /// Label shown for the node (field name or index).
+    pub key: String,
+    /// Query path of the node (e.g. `body.outputs.0.address`).
+    pub path: String,
+    /// The node's value.
+    pub value: JsonValue,
+    /// Child nodes (empty for scalars).
+    pub children: Vec<Node>,
+    /// Whether the children are shown.
+    pub expanded: bool,
+}
+
+/// A visible row: the node's position in the tree and its depth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    /// Child indices from the root to the node.
+    pub location: Vec<usize>,
+    /// Nesting depth (for indentation).
+    pub depth: usize,
+}
+
+impl Node {
+    /// Build the tree for a JSON value, with the root and its direct children expanded.
+    pub fn root(value: &JsonValue) -> Self {
+        let mut root = Node::new("transaction".to_string(), String::new(), value);
+        root.expanded = true;
+        root
+    }
+
+    fn new(key: String, path: String, value: &JsonValue) -> Self {
+        let child_path = |name: &str| {
+            if path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", path, name)
+            }
+        };
+        let children = match value {
+            JsonValue::Object(map) => map
+                .iter()
+                .map(|(k, v)| Node::new(k.clone(), child_path(k), v))
+                .collect(),
+            JsonValue::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| Node::new(i.to_string(), child_path(&i.to_string()), v))
+                .collect(),
+            _ => Vec::new(),
+        };
+        Node {
+            key,
+            path,
+            value: value.clone(),
+            children,
+            expanded: false,
+        }
+    }
+
+    /// Check if the node has children to expand.
+    pub fn is_branch(&self) -> bool {
+        !self.children.is_empty()
+    }
+
+    /// Short description of the value: the scalar itself or a child count.
+    pub fn preview(&self) -> String {
+        match &self.value {
+            JsonValue::Object(map) => format!("{{{} fields}}", map.len()),
+            JsonValue::Array(items) => format!("[{} items]", items.len()),
+            JsonValue::String(s) => format!("{:?}", s),
+            other => other.to_string(),
+        }
+    }
+
+    /// Value as copied to the clipboard: strings unquoted, others as JSON.
+    pub fn copy_text(&self) -> String {
+        match &self.value {
+            JsonValue::String(s) => s.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        }
+    }
+
+    /// Find a node by location.
+    pub fn get(&self, location: &[usize]) -> Option<&Node> {
+        location
+            .iter()
+            .try_fold(self, |node, &idx| node.children.get(idx))
+    }
+
+    /// Find a node by location, mutably.
+    pub fn get_mut(&mut self, location: &[usize]) -> Option<&mut Node> {
+        location
+            .iter()
+            .try_fold(self, |node, &idx| node.children.get_mut(idx))
+    }
+
+    /// Visible rows below the root, in display order.
+    pub fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        self.collect_rows(&mut Vec::new(), 0, &mut rows);
+        rows
+    }
+
+    fn collect_rows(&self, location: &mut Vec<usize>, depth: usize, rows: &mut Vec<Row>) {
+        if !self.expanded {
+            return;
+        }
+        for (idx, child) in self.children.iter().enumerate() {
+            location.push(idx);
+            rows.push(Row {
+                location: location.clone(),
+                depth,
+            });
+            child.collect_rows(location, depth + 1, rows);
+            location.pop();
+        }
+    }
+
+    /// Locations of every node whose key, path or scalar value contains
+    /// `needle` (case-insensitive), in display order.
+    pub fn search(&self, needle: &str) -> Vec<Vec<usize>> {
+        let needle = needle.to_lowercase();
+        let mut matches = Vec::new();
+        self.collect_matches(&needle, &mut Vec::new(), &mut matches);
+        matches
+    }
+
+    fn collect_matches(&self, needle: &str, location: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        for (idx, child) in self.children.iter().enumerate() {
+            location.push(idx);
+            let scalar = !child.is_branch() && child.preview().to_lowercase().contains(needle);
+            if scalar || child.path.to_lowercase().contains(needle) {
+                out.push(location.clone());
+            }
+            child.collect_matches(needle, location, out);
+            location.pop();
+        }
+    }
+
+    /// Expand every ancestor of a location so it becomes visible.
+    pub fn reveal(&mut self, location: &[usize]) {
+        let mut node = self;
+        node.expanded = true;
+        for &idx in &location[..location.len().saturating_sub(1)] {
+            node = &mut node.children[idx];
+            node.expanded = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Node {
+        Node::root(&serde_json::json!({
+            "body": { "fee": 171617, "outputs": [{ "address": "addr_test1" }] },
+            "hash": "0edb"
+        }))
+    }
+
+    #[test]
+    fn test_rows_follow_expansion() {
+        let mut tree = sample();
+        let keys = |tree: &Node| -> Vec<String> {
+            tree.rows()
+                .iter()
+                .map(|row| tree.get(&row.location).unwrap().key.clone())
+                .collect()
+        };
+        assert_eq!(keys(&tree), vec!["body", "hash"]);
+
+        tree.get_mut(&[0]).unwrap().expanded = true;
+        assert_eq!(keys(&tree), vec!["body", "fee", "outputs", "hash"]);
+        assert_eq!(tree.rows()[1].depth, 1);
+    }
+
+    #[test]
+    fn test_search_and_reveal() {
+        let mut tree = sample();
+        let matches = tree.search("ADDR_TEST");
+        assert_eq!(matches, vec![vec![0, 1, 0, 0]]);
+
+        tree.reveal(&matches[0]);
+        let node = tree.get(&matches[0]).unwrap();
+        assert_eq!(node.path, "body.outputs.0.address");
+        assert!(tree.rows().iter().any(|row| row.location == matches[0]));
+    }
+
+    #[test]
+    fn test_preview() {
+        let tree = sample();
+        assert_eq!(tree.get(&[0]).unwrap().preview(), "{2 fields}");
+        assert_eq!(tree.get(&[0, 0]).unwrap().preview(), "171617");
+        assert_eq!(tree.get(&[1]).unwrap().copy_text(), "0edb");
+    }
+}
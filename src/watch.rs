@@ -0,0 +1,136 @@
+//! Polling for new and changed files (`cq watch`).
+//!
+//! Polling keeps cq free of platform-specific notification APIs and works
+//! the same on network filesystems, at the cost of up to one interval of
+//! latency.
+
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// What identifies a version of a file: modification time and length.
+type Signature = (Option<SystemTime>, u64);
+
+/// Tracks the files under a path and reports those that are ready to read.
+pub struct Watcher {
+    root: PathBuf,
+    /// Signature seen on the previous poll.
+    seen: HashMap<PathBuf, Signature>,
+    /// Signature of the version last reported.
+    reported: HashMap<PathBuf, Signature>,
+}
+
+impl Watcher {
+    /// Watch a directory (its direct entries) or a single file.
+    ///
+    /// Files already present count as reported unless `include_existing`
+    /// is set, so only new and changed files are returned by [`poll`].
+    ///
+    /// [`poll`]: Watcher::poll
+    pub fn new(root: &Path, include_existing: bool) -> Result<Self> {
+        if !root.exists() {
+            return Err(Error::FileNotFound(root.to_path_buf()));
+        }
+        let mut watcher = Watcher {
+            root: root.to_path_buf(),
+            seen: HashMap::new(),
+            reported: HashMap::new(),
+        };
+        if !include_existing {
+            watcher.reported = watcher.scan()?;
+        }
+        Ok(watcher)
+    }
+
+    /// Files that changed since they were last reported, in name order.
+    ///
+    /// A file is ready once its signature is unchanged across two polls,
+    /// so files still being written are not read half-way.
+    pub fn poll(&mut self) -> Result<Vec<PathBuf>> {
+        let current = self.scan()?;
+        let mut ready: Vec<PathBuf> = current
+            .iter()
+            .filter(|(path, sig)| {
+                self.seen.get(*path) == Some(sig) && self.reported.get(*path) != Some(sig)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        ready.sort();
+
+        for path in &ready {
+            self.reported.insert(path.clone(), current[path]);
+        }
+        self.reported.retain(|path, _| current.contains_key(path));
+        self.seen = current;
+        Ok(ready)
+    }
+
+    /// Current signatures of the watched files (hidden files are skipped).
+    fn scan(&self) -> Result<HashMap<PathBuf, Signature>> {
+        let io_error = |source| Error::IoError {
+            path: Some(self.root.clone()),
+            source,
+        };
+
+        let paths: Vec<PathBuf> = if self.root.is_dir() {
+            std::fs::read_dir(&self.root)
+                .map_err(io_error)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| !name.starts_with('.'))
+                })
+                .collect()
+        } else {
+            vec![self.root.clone()]
+        };
+
+        Ok(paths
+            .into_iter()
+            .filter_map(|path| {
+                let metadata = std::fs::metadata(&path).ok()?;
+                metadata
+                    .is_file()
+                    .then(|| (path, (metadata.modified().ok(), metadata.len())))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_reports_settled_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("old.cbor"), "84").unwrap();
+
+        let mut watcher = Watcher::new(dir.path(), false).unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+
+        // New files are reported once they have settled for one poll
+        std::fs::write(dir.path().join("new.cbor"), "84a4").unwrap();
+        std::fs::write(dir.path().join(".partial"), "84").unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+        assert_eq!(watcher.poll().unwrap(), vec![dir.path().join("new.cbor")]);
+        assert!(watcher.poll().unwrap().is_empty());
+
+        // Changed files are reported again
+        std::fs::write(dir.path().join("old.cbor"), "84a400").unwrap();
+        watcher.poll().unwrap();
+        assert_eq!(watcher.poll().unwrap(), vec![dir.path().join("old.cbor")]);
+    }
+
+    #[test]
+    fn test_include_existing() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.cbor"), "84").unwrap();
+
+        let mut watcher = Watcher::new(dir.path(), true).unwrap();
+        watcher.poll().unwrap();
+        assert_eq!(watcher.poll().unwrap(), vec![dir.path().join("a.cbor")]);
+    }
+}
@@ -209,7 +209,64 @@ fn test_raw_output() {
         .args([fixture_path(), "--raw"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("\"hash\":"));
+        .stdout(predicate::str::starts_with("[\n  {\n    0: ["))
+        .stdout(predicate::str::contains("    2: 171617,\n"))
+        .stdout(predicate::str::contains("\"hash\":").not());
+}
+
+#[test]
+fn test_raw_query_value() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["inputs.0.transaction_id", fixture_path(), "--raw"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("h'852ec7f7"));
+}
+
+#[test]
+fn test_cbor_slice() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--cbor"])
+        .assert()
+        .success()
+        .stdout("1a00029e61\n");
+}
+
+#[test]
+fn test_cbor_slice_wildcard() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.*.value.coin",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--cbor",
+        ])
+        .assert()
+        .success()
+        .stdout("1a001cce24\n1a00116d86\n1a00cd7678\n");
+}
+
+#[test]
+fn test_cbor_slice_full_transaction() {
+    let bytes = fs::read(fixture_path()).unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--cbor"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", hex::encode(bytes)));
+}
+
+#[test]
+fn test_cbor_slice_rejects_pipeline() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["outputs | length", fixture_path(), "--cbor"])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("--cbor supports"));
 }
 
 #[test]
@@ -236,6 +293,32 @@ fn test_check_mode_invalid() {
         .code(1); // Validation fail exit code (DecodeFailed)
 }
 
+#[test]
+fn test_expect_hash() {
+    let hash = "0EDB4EAC0B992AC4AF71A2A52F41AB63C806E0EF4E5C5D9C7348EA03CF9A9E4E";
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--check", "--expect-hash", hash])
+        .assert()
+        .success();
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--expect-hash", &"a".repeat(64)])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "got 0edb4eac0b992ac4af71a2a52f41ab63c806e0ef4e5c5d9c7348ea03cf9a9e4e",
+        ));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--check", "--expect-hash", "0edb4e"])
+        .assert()
+        .code(2);
+}
+
 #[test]
 fn test_no_color_flag() {
     Command::cargo_bin("cq")
@@ -306,6 +389,17 @@ fn test_plutus_transaction_multi_assets() {
         .stdout(predicate::str::contains("asset(s)"));
 }
 
+#[test]
+fn test_outputs_table_totals() {
+    // NIGHT appears in two outputs but counts once
+    Command::cargo_bin("cq")
+        .unwrap()
+        .arg("tests/fixtures/preprod_plutus.cbor")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Total\s.*16,495,138 lovelace \+ 1 asset\(s\)").unwrap());
+}
+
 #[test]
 fn test_plutus_transaction_inline_datum() {
     Command::cargo_bin("cq")
@@ -406,3 +500,3191 @@ fn test_pool_registration_json_output() {
         .stdout(predicate::str::contains("\"certs\":"))
         .stdout(predicate::str::contains("pool_registration"));
 }
+
+#[test]
+fn test_summary_command() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["summary", fixture_path()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Summary"))
+        .stdout(predicate::str::contains("Era:"))
+        .stdout(predicate::str::contains("9,594,993,891 lovelace"))
+        .stdout(predicate::str::contains("stake_deregistration"));
+}
+
+#[test]
+fn test_summary_command_json() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["summary", "tests/fixtures/preprod_plutus.cbor", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"era\": \"conway\""))
+        .stdout(predicate::str::contains("\"redeemers\": 1"));
+}
+
+#[test]
+fn test_summary_command_alonzo_era() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["summary", "tests/fixtures/alonzo_datum_hash.cbor", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"era\": \"alonzo\""));
+
+    // Nothing era-specific: fits Alonzo, Babbage and Conway alike
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["summary", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"era\": \"unknown\""));
+}
+
+#[test]
+fn test_summary_command_conway_era() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["summary", "tests/fixtures/drep_registration.cbor", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"era\": \"conway\""));
+}
+
+#[test]
+fn test_stats_command() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["stats", "tests/fixtures/pool_registration.cbor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Totals"))
+        .stdout(predicate::str::contains("Assets"))
+        .stdout(predicate::str::contains("fivekryptos"));
+}
+
+#[test]
+fn test_stats_command_json() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["stats", "tests/fixtures/drep_registration.cbor", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"total_deposits\": 500000000"))
+        .stdout(predicate::str::contains("\"size\": 375"));
+}
+
+#[test]
+fn test_size_command() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["size", "tests/fixtures/preprod_plutus.cbor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Transaction size:"))
+        .stdout(predicate::str::contains("outputs"))
+        .stdout(predicate::str::contains("redeemers"));
+}
+
+#[test]
+fn test_pparams_command() {
+    // {3: 16384, 19: [577/10000, 721/10000000]}
+    let update = "a2031940001382d81e8219024119271\
+                  0d81e821902d11a00989680";
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["pparams", update])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("max_tx_size: 16384"))
+        .stdout(predicate::str::contains("mem: 577/10000"));
+
+    // A transaction without parameter-change proposals
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["pparams", fixture_path()])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("no protocol parameter changes"));
+}
+
+#[test]
+fn test_size_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    let pparams = dir.path().join("protocol.json");
+    fs::write(&pparams, r#"{"maxTxSize": 300}"#).unwrap();
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["size", fixture_path()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("332 / 16,384 bytes"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--check", "--pparams"])
+        .arg(&pparams)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("332 bytes, the limit is 300"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["size", fixture_path(), "--json", "--pparams"])
+        .arg(&pparams)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"within_limit\": false"));
+}
+
+#[test]
+fn test_size_command_json() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["size", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"total\": 332"))
+        .stdout(predicate::str::contains("\"path\": \"body.outputs.0\""));
+}
+
+#[test]
+fn test_explain_command() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["explain", fixture_path()])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?m)^ 84  1a00029e61 +uint 171617 +body\.fee$").unwrap())
+        .stdout(predicate::str::contains("body.outputs (key)"));
+}
+
+#[test]
+fn test_explain_command_json() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["explain", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"hex\": \"1a00029e61\""))
+        .stdout(predicate::str::contains("\"path\": \"is_valid\""));
+}
+
+#[test]
+fn test_grep_command() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["grep", "0x1A4899", "tests/fixtures/preprod_plutus.cbor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("body.inputs.0.transaction_id"))
+        .stdout(predicate::str::contains(
+            "witness_set.redeemers.0.target.outpoint",
+        ))
+        .stdout(predicate::str::contains("offset 10  body.inputs.0.0"));
+}
+
+#[test]
+fn test_grep_command_no_match() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "grep",
+            "deadbeef",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .code(7)
+        .stdout(predicate::str::contains("\"matches\": []"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["grep", "xyz", fixture_path()])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_flow_command() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "flow",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--utxos",
+            "tests/fixtures/preprod_plutus_utxo.json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-1,522,572"))
+        .stdout(predicate::str::contains("+22 387c0fb5.NIGHT"))
+        .stdout(predicate::str::contains("Fee: 392,642 lovelace"));
+}
+
+#[test]
+fn test_flow_command_json() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "flow",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--utxos",
+            "tests/fixtures/preprod_plutus_utxo.json",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"net_lovelace\": -12220"))
+        .stdout(predicate::str::contains("\"net\": -22"))
+        .stdout(predicate::str::contains("\"collateral_only\": false"));
+}
+
+#[test]
+fn test_flow_command_unresolved_input() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "flow",
+            fixture_path(),
+            "--utxos",
+            "tests/fixtures/preprod_plutus_utxo.json",
+        ])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("is not in the UTxO set"));
+}
+
+#[test]
+fn test_graph_command() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "graph",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--collateral",
+            "--reference-inputs",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("flowchart LR\n"))
+        .stdout(predicate::str::contains(
+            "in1[\"5ad73f...9d7698#32\"] --> tx",
+        ))
+        .stdout(predicate::str::contains(
+            "1,887,780 lovelace<br/>46,113 NIGHT",
+        ))
+        .stdout(predicate::str::contains("-. reference .-> tx"))
+        .stdout(predicate::str::contains(
+            "tx -. collateral return .-> colret",
+        ));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["graph", fixture_path(), "--dot"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("digraph transaction {"))
+        .stdout(predicate::str::contains("in0 -> tx;"))
+        .stdout(predicate::str::contains("tx -> out0;"))
+        .stdout(predicate::str::contains("collateral").not());
+}
+
+#[test]
+fn test_utxo_command() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "utxo",
+            "tests/fixtures/preprod_plutus_utxo.json",
+            "-q",
+            "*.value.coin",
+            "--sort-by",
+            ".",
+            "--desc",
+            "--head",
+            "1",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("14987780"))
+        .stdout(predicate::str::contains("1900000").not());
+
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "utxo",
+            "tests/fixtures/preprod_plutus_utxo.json",
+            "--to",
+            "addr_test1wqt7v9crzydwpwx7f4sjp7w7wsy7rr3ahkldels6tsc707q3xhdsk",
+            "--json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let utxos: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(utxos.as_array().unwrap().len(), 1);
+    assert_eq!(utxos[0]["input"]["index"], 0);
+    assert_eq!(
+        utxos[0]["value"]["multi_assets"][0]["assets"][0]["amount"],
+        46135
+    );
+}
+
+#[test]
+fn test_utxo_command_cbor() {
+    // The older CBOR form: {[txid, index] => output}, here as hex
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["outputs.2", "tests/fixtures/preprod_plutus.cbor", "--cbor"])
+        .output()
+        .unwrap();
+    let output_hex = String::from_utf8(output.stdout).unwrap();
+    let utxo_hex = format!("a1825820{}02{}", "ab".repeat(32), output_hex.trim());
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["utxo", &utxo_hex, "-q", "0.value.coin"])
+        .assert()
+        .success()
+        .stdout("13,465,208\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["utxo", "-q", "0.input.index"])
+        .write_stdin(utxo_hex)
+        .assert()
+        .success()
+        .stdout("2\n");
+}
+
+#[test]
+fn test_canonical_command() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["canonical", fixture_path()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Round-trip:      yes"))
+        .stdout(predicate::str::contains("Canonical:       yes"));
+}
+
+#[test]
+fn test_canonical_command_json_non_canonical() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["canonical", "tests/fixtures/preprod_plutus.cbor", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"round_trip\": true"))
+        .stdout(predicate::str::contains("\"canonical\": false"))
+        .stdout(predicate::str::contains(
+            "\"canonical_hash\": \"8ce221805237559b46f2663e834c990fe835032abc86c4b7cce962f87a958725\"",
+        ));
+}
+
+#[test]
+fn test_strip_preserves_hash() {
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["strip", "tests/fixtures/preprod_plutus.cbor"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stripped = String::from_utf8(output.stdout).unwrap();
+
+    // Signatures are gone but redeemers stay
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["witness_set | keys", "--json"])
+        .write_stdin(stripped.clone())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("redeemers"))
+        .stdout(predicate::str::contains("vkeywitnesses").not());
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["hash", "--no-truncate"])
+        .write_stdin(stripped)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "9581e54879a77e196fd0e078422dd154f6f51e0d167c4d0cc27a512bdcc12eb6",
+        ));
+}
+
+#[test]
+fn test_strip_all_and_body() {
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["strip", "--body", fixture_path()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let body = String::from_utf8(output.stdout).unwrap();
+    assert!(fixture_hex().starts_with(&format!("84{}", body.trim())));
+
+    // Same body, empty witness set, remaining sections unchanged
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["strip", "--all", fixture_path()])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!("84{}a0", body.trim())));
+}
+
+#[test]
+fn test_assemble_restores_signed_tx() {
+    let dir = tempfile::tempdir().unwrap();
+    let unsigned = dir.path().join("unsigned.cbor");
+    let witness = dir.path().join("witness.cbor");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["strip", "--all", fixture_path(), "-o"])
+        .arg(&unsigned)
+        .assert()
+        .success();
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["witness_set", "--cbor", fixture_path()])
+        .output()
+        .unwrap();
+    let witness_hex = String::from_utf8(output.stdout).unwrap();
+    fs::write(&witness, hex::decode(witness_hex.trim()).unwrap()).unwrap();
+
+    // Adding the same witness set twice merges the duplicates
+    Command::cargo_bin("cq")
+        .unwrap()
+        .arg("assemble")
+        .args([&unsigned, &witness, &witness])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", fixture_hex()));
+}
+
+#[test]
+fn test_assemble_invalid_witness() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["assemble", fixture_path(), fixture_path()])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Expected a witness set"));
+}
+
+#[test]
+fn test_edit_without_changes_is_identity() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["edit", fixture_path()])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", fixture_hex()));
+}
+
+#[test]
+fn test_edit_fields() {
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["edit", fixture_path(), "--ttl", "5000", "--valid", "false"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["{ttl: body.ttl, fee: fee, valid: is_valid}", "--json"])
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"ttl\": 5000"))
+        .stdout(predicate::str::contains("\"fee\": 171617"))
+        .stdout(predicate::str::contains("\"valid\": false"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "edit",
+            fixture_path(),
+            "--drop-witness",
+            "0",
+            "--drop-witness",
+            "1",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("a0f5f6\n"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["edit", fixture_path(), "--drop-witness", "3"])
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_edit_remove_aux() {
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["strip", "--body", fixture_path()])
+        .output()
+        .unwrap();
+    let body = String::from_utf8(output.stdout).unwrap();
+    let body = body.trim();
+    let tx = fixture_hex();
+    let witness_set = &tx[2 + body.len()..tx.len() - 4];
+
+    // Same body plus an auxiliary data hash (key 7), with metadata {0: 1}
+    let with_aux = format!(
+        "84a5{}075820{}{}f5a10001",
+        &body[2..],
+        "00".repeat(32),
+        witness_set
+    );
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["edit", "--remove-aux", &with_aux])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", tx));
+}
+
+/// Write the fixture with a native and a Plutus V2 script witness added.
+fn fixture_with_scripts(dir: &std::path::Path) -> std::path::PathBuf {
+    // {1: [[0, h'11..11']], 6: [h'4d01000033222220051200120011']}
+    let witness_set = format!(
+        "a20181 8200581c{} 0681 4e4d01000033222220051200120011",
+        "11".repeat(28)
+    )
+    .replace(' ', "");
+    let witness = dir.join("scripts.witness");
+    fs::write(&witness, hex::decode(witness_set).unwrap()).unwrap();
+
+    let tx = dir.join("tx.cbor");
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["assemble", fixture_path()])
+        .arg(&witness)
+        .arg("-o")
+        .arg(&tx)
+        .assert()
+        .success();
+    tx
+}
+
+#[test]
+fn test_extract_scripts() {
+    let dir = tempfile::tempdir().unwrap();
+    let tx = fixture_with_scripts(dir.path());
+    let out = dir.path().join("scripts");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["extract", "scripts"])
+        .arg(&tx)
+        .arg("--out-dir")
+        .arg(&out)
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"type\": \"native\""))
+        .stdout(predicate::str::contains("witness_set.plutus_v2_scripts.0"));
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(out.join("manifest.json")).unwrap()).unwrap();
+    let items = manifest.as_array().unwrap();
+    assert_eq!(items.len(), 2);
+
+    let plutus = &items[1];
+    assert_eq!(plutus["type"], "plutus_v2");
+    let file = out.join(plutus["file"].as_str().unwrap());
+    assert_eq!(
+        hex::encode(fs::read(file).unwrap()),
+        "4e4d01000033222220051200120011"
+    );
+}
+
+#[test]
+fn test_extract_scripts_none() {
+    let dir = tempfile::tempdir().unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["extract", "scripts", fixture_path(), "--out-dir"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote 0 item(s)"));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("manifest.json")).unwrap(),
+        "[]\n"
+    );
+}
+
+#[test]
+fn test_extract_datums() {
+    let dir = tempfile::tempdir().unwrap();
+    let hash = "02520c494a5f2e80f440e2ea9a6bde00f29f11f2bd68a01fcbf9d41d32e5950e";
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "extract",
+            "datums",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--out-dir",
+        ])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("{}.cbor", hash)))
+        .stdout(predicate::str::contains("outputs.0.datum"));
+
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.0.datum.bytes",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--no-truncate",
+        ])
+        .output()
+        .unwrap();
+    let datum = fs::read(dir.path().join(format!("{}.cbor", hash))).unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim(),
+        hex::encode(datum)
+    );
+
+    let json = fs::read_to_string(dir.path().join(format!("{}.json", hash))).unwrap();
+    assert!(json.contains("\"constructor\": 0"));
+}
+
+/// Hex of the fixture with its auxiliary data replaced by `metadata_hex`.
+fn fixture_with_metadata(metadata_hex: &str) -> String {
+    let tx = fixture_hex();
+    format!("{}{}", tx.strip_suffix("f6").unwrap(), metadata_hex)
+}
+
+#[test]
+fn test_metadata_cardano_cli_schema() {
+    // {674: {"msg": ["hello"]}, 1: [h'ab', -5]}
+    let tx = fixture_with_metadata("a21902a2a1636d7367816568656c6c6f018241ab24");
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["metadata", &tx, "--cardano-cli-schema"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "1": {"list": [{"bytes": "ab"}, {"int": -5}]},
+            "674": {"map": [{"k": {"string": "msg"}, "v": {"list": [{"string": "hello"}]}}]}
+        })
+    );
+}
+
+#[test]
+fn test_metadata_cardano_cli_schema_other_query() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["metadata", fixture_path(), "--cardano-cli-schema"])
+        .assert()
+        .success()
+        .stdout("{}\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--cardano-cli-schema"])
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_config_metadata_labels() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("cq")).unwrap();
+    fs::write(
+        dir.path().join("cq").join("config.toml"),
+        "[labels.1967]\nname = \"Acme Audit\"\nfields = { 0 = \"version\", 1 = \"auditor\" }\n",
+    )
+    .unwrap();
+
+    // {1967: {0: 1, 1: "acme"}}
+    let tx = fixture_with_metadata("a11907afa20001016461636d65");
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args(["auxiliary_data.metadata.labels.0.value", &tx, "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "standard": "Acme Audit",
+            "data": {"version": "1", "auditor": "acme"}
+        })
+    );
+
+    // Malformed config files are reported
+    fs::write(dir.path().join("cq").join("config.toml"), "[labels.x]\n").unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args(["fee", fixture_path()])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid config file"));
+}
+
+#[test]
+fn test_config_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("cq.toml");
+    fs::write(&config, "[defaults]\nada = true\ncolor = false\n").unwrap();
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--config"])
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout("0.171617 ADA\n");
+
+    // The default location is read when --config is not given
+    fs::create_dir(dir.path().join("cq")).unwrap();
+    fs::copy(&config, dir.path().join("cq").join("config.toml")).unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .args(["fee", fixture_path()])
+        .assert()
+        .success()
+        .stdout("0.171617 ADA\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--config", "missing.toml"])
+        .assert()
+        .code(3);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_plugin_subcommand() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // A plugin that echoes its arguments and the fee from its JSON input
+    let dir = tempfile::tempdir().unwrap();
+    let plugin = dir.path().join("cq-feecheck");
+    fs::write(
+        &plugin,
+        "#!/bin/sh\necho \"args: $*\"\ngrep -o '\"fee\":[0-9]*'\nexit 3\n",
+    )
+    .unwrap();
+    fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path = format!(
+        "{}:{}",
+        dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    Command::cargo_bin("cq")
+        .unwrap()
+        .env("PATH", &path)
+        .args(["feecheck", fixture_path(), "--max", "5"])
+        .assert()
+        .code(3)
+        .stdout("args: --max 5\n\"fee\":171617\n");
+
+    // Input from stdin when the first argument is a flag
+    Command::cargo_bin("cq")
+        .unwrap()
+        .env("PATH", &path)
+        .args(["feecheck", "--max", "5"])
+        .write_stdin(fixture_hex())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("\"fee\":171617"));
+
+    // Unknown names without a plugin are still queries
+    Command::cargo_bin("cq")
+        .unwrap()
+        .env("PATH", &path)
+        .args(["nosuchplugin", fixture_path()])
+        .assert()
+        .code(4);
+
+    // --config is cq's: it is loaded and not passed on
+    let config = dir.path().join("config.toml");
+    fs::write(&config, "[defaults]\n").unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .env("PATH", &path)
+        .args(["feecheck", fixture_path(), "--config"])
+        .arg(&config)
+        .assert()
+        .code(3)
+        .stdout(predicate::str::starts_with("args: \n"));
+    Command::cargo_bin("cq")
+        .unwrap()
+        .env("PATH", &path)
+        .args(["feecheck", fixture_path(), "--config", "missing.toml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("missing.toml"));
+
+    // The JSON is converted with the config's defaults, as by cq --json
+    let dump = dir.path().join("cq-dump");
+    fs::write(
+        &dump,
+        "#!/bin/sh
+cat
+",
+    )
+    .unwrap();
+    fs::set_permissions(&dump, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::write(&config, "[defaults]\nnetwork = \"preprod\"\n").unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .env("PATH", &path)
+        .args(["dump", "tests/fixtures/preprod_plutus.cbor", "--config"])
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"network\":\"preprod\""));
+
+    // A plugin never shadows a query shortcut
+    fs::copy(&plugin, dir.path().join("cq-fee")).unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .env("PATH", &path)
+        .args(["fee", fixture_path()])
+        .assert()
+        .success()
+        .stdout("171,617\n");
+}
+
+#[test]
+fn test_serve_http_api() {
+    use std::io::BufRead;
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("cq"))
+        .args(["serve", "--listen", "127.0.0.1:0"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // The first stderr line reports the bound address
+    let mut line = String::new();
+    std::io::BufReader::new(child.stderr.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let base = line.trim().trim_start_matches("Listening on ").to_string();
+
+    let json = |response: ureq::Response| -> serde_json::Value {
+        serde_json::from_str(&response.into_string().unwrap()).unwrap()
+    };
+    let fee = json(
+        ureq::post(&format!("{}/query?q=fee", base))
+            .send_bytes(&fs::read(fixture_path()).unwrap())
+            .unwrap(),
+    );
+    let decoded = json(
+        ureq::post(&format!("{}/decode", base))
+            .send_string(&fixture_hex())
+            .unwrap(),
+    );
+    let bad = ureq::post(&format!("{}/decode", base)).send_string("zz");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert_eq!(fee, 171617);
+    assert_eq!(decoded["body"]["fee"], 171617);
+    match bad {
+        Err(ureq::Error::Status(status, response)) => {
+            assert_eq!(status, 400);
+            assert_eq!(json(response)["exit_code"], 1);
+        }
+        other => panic!(
+            "expected a 400 response, got {:?}",
+            other.map(|r| r.status())
+        ),
+    }
+}
+
+#[test]
+fn test_watch_directory() {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("old.cbor"), fixture_hex()).unwrap();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("cq"))
+        .args(["watch", "--query", "fee", "--json", "--interval", "20"])
+        .arg(dir.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    fs::copy(fixture_path(), dir.path().join("new.cbor")).unwrap();
+    fs::write(dir.path().join("broken.cbor"), "not cbor").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let mut stdout = String::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut stdout)
+        .unwrap();
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+
+    // Only the new file is decoded; the broken one is reported and skipped
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1, "{}", stdout);
+    assert!(lines[0]["file"].as_str().unwrap().ends_with("new.cbor"));
+    assert_eq!(lines[0]["result"], 171617);
+    assert!(stderr.contains("broken.cbor"), "{}", stderr);
+}
+
+#[test]
+fn test_verbose_logging() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["-v", "fee", fixture_path()])
+        .assert()
+        .success()
+        .stdout("171,617\n")
+        .stderr(predicate::str::contains("input is a file path"))
+        .stderr(predicate::str::contains("(unknown era)"))
+        .stderr(predicate::str::contains(
+            "shortcut 'fee' expands to 'body.fee'",
+        ))
+        .stderr(predicate::str::contains("Field(\"fee\")"))
+        .stderr(predicate::str::contains("era detection").not());
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["-vv", "fee", fixture_path()])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("[trace] era detection"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path()])
+        .assert()
+        .success()
+        .stderr("");
+}
+
+#[test]
+fn test_section_queries_match_full_transaction() {
+    let query_json = |query: &str| -> serde_json::Value {
+        let output = Command::cargo_bin("cq")
+            .unwrap()
+            .args([query, fixture_path(), "--json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", query);
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    // Queries build only the section they need; it must match the full tree
+    let full: serde_json::Value = serde_json::from_slice(
+        &Command::cargo_bin("cq")
+            .unwrap()
+            .args([fixture_path(), "--json"])
+            .output()
+            .unwrap()
+            .stdout,
+    )
+    .unwrap();
+    for (section, value) in full.as_object().unwrap() {
+        assert_eq!(&query_json(section), value, "{}", section);
+    }
+    for (field, value) in full["body"].as_object().unwrap() {
+        assert_eq!(&query_json(&format!("body.{}", field)), value, "{}", field);
+    }
+}
+
+#[test]
+fn test_detect_non_transaction_input() {
+    // Constr 0 [42]
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fields.0.int", "d8799f182aff"])
+        .assert()
+        .success()
+        .stdout("42\n")
+        .stderr(predicate::str::contains("decoded as Plutus datum"));
+
+    let address = format!("61{}", "ab".repeat(28));
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["type", &address])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("enterprise"))
+        .stderr(predicate::str::contains("decoded as address"));
+
+    // --check still validates transactions only
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["d8799f182aff", "--check"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_addr_hex_and_byron() {
+    let raw = "ab".repeat(28);
+    for address in [format!("61{}", raw), format!("0x581d61{}", raw)] {
+        Command::cargo_bin("cq")
+            .unwrap()
+            .args(["addr", &address, "--json"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "addr1vx46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46h2c4u8cnp",
+            ))
+            .stdout(predicate::str::contains("\"enterprise\""));
+    }
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "addr",
+            "Ae2tdPwUPEZFRbyhz3cpfC2CumGzNkFBN2L42rcUc2yjQpEkxDbkPodpMAi",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"byron\""))
+        .stdout(predicate::str::contains("\"mainnet\""));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", "not-an-address"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_addr_build() {
+    let payment = "ab".repeat(28);
+    let stake = "cd".repeat(28);
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", "build", "--payment-keyhash", &payment])
+        .args(["--network", "mainnet"])
+        .assert()
+        .success()
+        .stdout("addr1vx46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46h2c4u8cnp\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", "build", "--payment-keyhash", &payment])
+        .args(["--stake-keyhash", &stake, "--network", "preprod", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"address\": \"addr_test1q"))
+        .stdout(predicate::str::contains("\"type\": \"base\""));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "addr",
+            "build",
+            "--stake-keyhash",
+            &stake,
+            "--network",
+            "mainnet",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("stake1u"));
+
+    // Key hashes must be 28 bytes
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "addr",
+            "build",
+            "--payment-keyhash",
+            "abcd",
+            "--network",
+            "mainnet",
+        ])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("28 bytes"));
+}
+
+#[test]
+fn test_addr_stake_address() {
+    let base = "addr1qx46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46h27dehxumnwdehxumnwdehxumnwdehxumnwdehxumnwdehxsmlhaw3";
+    let stake = "stake1u8xumnwdehxumnwdehxumnwdehxumnwdehxumnwdehxumngnxnspf";
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", base, "--stake"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", stake));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", base, "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "\"stake_address\": \"{}\"",
+            stake
+        )));
+
+    // Back to the base address from the payment key hash
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", "build", "--payment-keyhash", &"ab".repeat(28)])
+        .args(["--stake-address", stake, "--network", "mainnet"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", base));
+
+    // Enterprise addresses have no stake credential
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", &format!("61{}", "ab".repeat(28)), "--stake"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("no stake credential"));
+}
+
+#[test]
+fn test_addr_hex_conversion() {
+    let bech32 = "addr1vx46h2at4w46h2at4w46h2at4w46h2at4w46h2at4w46h2c4u8cnp";
+    let hex = format!("61{}", "ab".repeat(28));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", bech32, "--hex"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", hex));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", "--from-hex", &hex])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", bech32));
+
+    // A typo is located
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", &bech32.replace("c4u8", "c4u9")])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("checksum mismatch"))
+        .stderr(predicate::str::contains("position 55"));
+
+    // --from-hex insists on hex
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addr", "--from-hex", bech32])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_network_flag() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.0.address.network",
+            fixture_path(),
+            "--network",
+            "preprod",
+        ])
+        .assert()
+        .success()
+        .stdout("preprod\n")
+        .stderr("");
+
+    // The fixture is a testnet transaction
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--network", "mainnet"])
+        .assert()
+        .success()
+        .stdout("171,617\n")
+        .stderr(predicate::str::contains(
+            "warning: the transaction is for network id 0",
+        ));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--network", "testnet"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_slot_times() {
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["edit", fixture_path(), "--ttl", "70000000"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["body.ttl_time", "--network", "preprod"])
+        .write_stdin(output.stdout.clone())
+        .assert()
+        .success()
+        .stdout("2024-09-07T04:26:40Z\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["body.ttl_epoch", "--network", "preprod"])
+        .write_stdin(output.stdout.clone())
+        .assert()
+        .success()
+        .stdout("165\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["--slot-config", "1000,0,2", "--network", "preprod"])
+        .write_stdin(output.stdout.clone())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "TTL: 70000000 (1974-06-09T09:10:00Z)\n",
+        ));
+
+    // Without a network there is no time
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["body.ttl_time"])
+        .write_stdin(output.stdout)
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_ada_json() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--json", "--ada"])
+        .assert()
+        .success()
+        .stdout("\"0.171617\"\n");
+
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--json", "--ada"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["body"]["fee"], 171617);
+    assert_eq!(json["body"]["fee_ada"], "0.171617");
+    assert!(json["body"]["outputs"][0]["value"]["coin_ada"].is_string());
+}
+
+#[test]
+fn test_redeemer_targets() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .arg("tests/fixtures/preprod_plutus.cbor")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "spend #0 → input 0 (1a4899...1383e9#0)",
+        ));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "witness_set.redeemers.0.target.outpoint",
+            "tests/fixtures/preprod_plutus.cbor",
+        ])
+        .assert()
+        .success()
+        .stdout("1a48999128069edfa82776fbccb9696ac49d1b2c4a054be42ca9affb111383e9#0\n");
+}
+
+#[test]
+fn test_ex_units_budget() {
+    let dir = tempfile::tempdir().unwrap();
+    let pparams = dir.path().join("protocol.json");
+    fs::write(
+        &pparams,
+        r#"{"maxTxExecutionUnits": {"memory": 1000000, "steps": 10000000000}}"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .arg("tests/fixtures/preprod_plutus.cbor")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Ex units: 1,038,472 mem, 283,954,130 steps",
+        ));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["tests/fixtures/preprod_plutus.cbor", "--pparams"])
+        .arg(&pparams)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1,038,472 / 1,000,000 mem (103.8%)",
+        ))
+        .stdout(predicate::str::contains("OVER BUDGET"));
+
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "witness_set.ex_units_total",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+            "--pparams",
+        ])
+        .arg(&pparams)
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["steps"], 283954130);
+    assert_eq!(json["within_budget"], false);
+}
+
+#[test]
+fn test_generic_cbor_mode() {
+    // {0: h'0102', "name": [1, -2, true, null], 1: 6(3.5)}
+    let hex = "a300420102646e616d65840121f5f601c6f94300";
+    let query = |args: &[&str]| {
+        let output = Command::cargo_bin("cq")
+            .unwrap()
+            .arg("--generic")
+            .args(args)
+            .arg(hex)
+            .arg("--json")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", args);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    assert_eq!(query(&["name.1"]), -2);
+    assert_eq!(query(&["0"]), "0102");
+    assert_eq!(query(&["1.tag"]), 6);
+    assert_eq!(query(&["name | length"]), 4);
+    assert_eq!(query(&[])["name"][2], true);
+
+    // Transactions are plain CBOR too: integer keys, no shortcuts
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["--generic", "0.2", fixture_path()])
+        .assert()
+        .success()
+        .stdout("171,617\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["--generic", "8201"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("invalid CBOR"));
+}
+
+#[test]
+fn test_stream_mode() {
+    let input = format!("{hex}\n\nnot hex\n{hex}\n", hex = fixture_hex());
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["-q", "fee", "-q", "outputs | length", "--stream"])
+        .write_stdin(input)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // Blank lines are skipped; a bad line gets an error line and the stream goes on
+    let lines: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["result"]["fee"], 171617);
+    assert!(lines[0]["hash"].as_str().unwrap().starts_with("0edb4eac"));
+    assert_eq!(lines[1]["exit_code"], 2);
+    assert_eq!(lines[2], lines[0]);
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--stream"])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            "--stream reads transactions from stdin",
+        ));
+}
+
+#[test]
+fn test_transaction_batch() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let simple = fs::read(fixture_path()).unwrap();
+    let plutus = fs::read("tests/fixtures/preprod_plutus.cbor").unwrap();
+    let sequence = temp_dir.path().join("sequence.cbor");
+    fs::write(&sequence, [simple.as_slice(), &plutus].concat()).unwrap();
+    let array = temp_dir.path().join("array.cbor");
+    fs::write(&array, [&[0x82], simple.as_slice(), &plutus].concat()).unwrap();
+
+    for path in [&sequence, &array] {
+        let output = Command::cargo_bin("cq")
+            .unwrap()
+            .args(["fee", path.to_str().unwrap(), "--json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let lines: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["result"], 171617);
+        assert_eq!(lines[1]["result"], 392642);
+        assert!(lines[1]["hash"].as_str().unwrap().starts_with("9581e548"));
+    }
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", sequence.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Transaction 1 (9581e548"))
+        .stdout(predicate::str::contains("392,642"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([sequence.to_str().unwrap(), "--check"])
+        .assert()
+        .success();
+
+    // Every transaction's result counts for --exit-status, JSON lines too
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["-e", "ttl?", sequence.to_str().unwrap(), "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(7));
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_node_socket_required() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["mempool", "--config", "/dev/null"])
+        .env_remove("CARDANO_NODE_SOCKET_PATH")
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("no node socket"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_mempool_command() {
+    use ciborium::Value as CborValue;
+    use cq::node::Connection;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let socket = temp_dir.path().join("node.socket");
+    let listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+    let tx = fs::read(fixture_path()).unwrap();
+
+    // A node whose mempool holds the fixture transaction
+    let node = std::thread::spawn(move || {
+        let mut node = Connection::new(listener.accept().unwrap().0);
+        let propose = node.receive(0).unwrap();
+        let (version, data) = propose.as_array().unwrap()[1].as_map().unwrap()[0].clone();
+        assert_eq!(data, CborValue::from(1));
+        node.send(0, &CborValue::Array(vec![1.into(), version, data]))
+            .unwrap();
+
+        let tx = CborValue::Array(vec![6.into(), CborValue::Tag(24, Box::new(tx.into()))]);
+        for reply in [
+            vec![2.into(), 100.into()],
+            vec![6.into(), tx],
+            vec![6.into()],
+        ] {
+            node.receive(9).unwrap();
+            node.send(9, &CborValue::Array(reply)).unwrap();
+        }
+        // Release, then done
+        for tag in [3, 0] {
+            assert_eq!(node.receive(9).unwrap(), CborValue::Array(vec![tag.into()]));
+        }
+    });
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["mempool", "--socket", socket.to_str().unwrap()])
+        .args(["-q", "fee", "--network", "preprod", "--once"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "{\"hash\":\"0edb4eac0b992ac4af71a2a52f41ab63c806e0ef4e5c5d9c7348ea03cf9a9e4e\"",
+        ))
+        .stdout(predicate::str::contains("\"result\":171617}"));
+    node.join().unwrap();
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "mempool",
+            "--socket",
+            temp_dir.path().join("missing").to_str().unwrap(),
+        ])
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains("cannot connect to"));
+}
+
+/// A block at slot 1234 holding the fixture transaction, as `[era, block]`.
+#[cfg(unix)]
+fn fixture_block() -> Vec<u8> {
+    use cml_chain::block::{Block, Header, HeaderBody, OperationalCert, ProtocolVersion};
+    use cml_chain::crypto::{KESSignature, VRFCert};
+    use cml_chain::transaction::Transaction;
+    use cml_core::serialization::{Deserialize, Serialize};
+    use cml_crypto::{
+        BlockBodyHash, Ed25519Signature, KESVkey, PublicKey, RawBytesEncoding, VRFVkey,
+    };
+
+    let tx = Transaction::from_cbor_bytes(&fs::read(fixture_path()).unwrap()).unwrap();
+    let header_body = HeaderBody::new(
+        7,
+        1234,
+        None,
+        PublicKey::from_raw_bytes(&[1; 32]).unwrap(),
+        VRFVkey::from([2; 32]),
+        VRFCert::new(vec![3; 64], vec![4; 80]).unwrap(),
+        0,
+        BlockBodyHash::from([5; 32]),
+        OperationalCert::new(
+            KESVkey::from([6; 32]),
+            0,
+            0,
+            Ed25519Signature::from_raw_bytes(&[7; 64]).unwrap(),
+        ),
+        ProtocolVersion::new(10, 0),
+    );
+    let block = Block::new(
+        Header::new(header_body, KESSignature::new(vec![8; 448]).unwrap()),
+        vec![tx.body],
+        vec![tx.witness_set],
+        Default::default(),
+        Vec::new(),
+    );
+    [&[0x82, 0x07][..], &block.to_cbor_bytes()].concat()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_follow_command() {
+    use ciborium::Value as CborValue;
+    use cq::node::Connection;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let socket = temp_dir.path().join("node.socket");
+    let listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+    let block = fixture_block();
+
+    // A node whose next block holds the fixture transaction
+    let node = std::thread::spawn(move || {
+        let mut node = Connection::new(listener.accept().unwrap().0);
+        let propose = node.receive(0).unwrap();
+        let (version, data) = propose.as_array().unwrap()[1].as_map().unwrap()[0].clone();
+        node.send(0, &CborValue::Array(vec![1.into(), version, data]))
+            .unwrap();
+
+        let point = CborValue::Array(vec![1000.into(), vec![0xaa; 32].into()]);
+        let tip = CborValue::Array(vec![point.clone(), 6.into()]);
+        let block = CborValue::Tag(24, Box::new(block.into()));
+        for reply in [
+            vec![6.into(), tip.clone()],
+            vec![5.into(), point.clone(), tip.clone()],
+            vec![3.into(), point, tip.clone()],
+            vec![2.into(), block, tip],
+        ] {
+            node.receive(5).unwrap();
+            node.send(5, &CborValue::Array(reply)).unwrap();
+        }
+        assert_eq!(node.receive(5).unwrap(), CborValue::Array(vec![7.into()]));
+    });
+
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "follow",
+            "--socket",
+            socket.to_str().unwrap(),
+            "--blocks",
+            "1",
+        ])
+        .args(["-q", "body.outputs[address.address ~ 'addr_test1vp9']"])
+        .args(["--network", "preprod"])
+        .output()
+        .unwrap();
+    node.join().unwrap();
+    assert!(output.status.success());
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["slot"], 1234);
+    assert_eq!(lines[0]["block"], 7);
+    assert_eq!(
+        lines[0]["hash"],
+        "0edb4eac0b992ac4af71a2a52f41ab63c806e0ef4e5c5d9c7348ea03cf9a9e4e"
+    );
+    assert_eq!(lines[0]["result"][0]["value"]["coin"], 9594993891u64);
+}
+
+#[test]
+fn test_paths_flag() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--paths"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("body.fee\n"))
+        .stdout(predicate::str::contains("body.outputs.0.value.coin\n"));
+
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["outputs.*", fixture_path(), "--paths", "--json"])
+        .output()
+        .unwrap();
+    let paths: Vec<String> = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(paths.contains(&"outputs.*.value.coin".to_string()));
+
+    // Every listed path is a valid query
+    for path in paths {
+        Command::cargo_bin("cq")
+            .unwrap()
+            .args([&path, fixture_path()])
+            .assert()
+            .success();
+    }
+}
+
+#[test]
+fn test_complete_query_paths() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["__complete", "wit"])
+        .assert()
+        .success()
+        .stdout("witnesses\nwithdrawals\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["__complete", "outputs.0.", fixture_path()])
+        .assert()
+        .success()
+        .stdout("outputs.0.address\noutputs.0.value\n");
+
+    // Unreadable input still completes shortcuts
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["__complete", "fe", "missing.cbor"])
+        .assert()
+        .success()
+        .stdout("fee\n");
+}
+
+#[test]
+fn test_encode_metadata_round_trip() {
+    let metadata = "a21902a2a1636d7367816568656c6c6f018241ab24";
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "metadata",
+            &fixture_with_metadata(metadata),
+            "--cardano-cli-schema",
+        ])
+        .output()
+        .unwrap();
+
+    // Labels are emitted in numeric order
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["encode", "--metadata"])
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stdout("a2018241ab241902a2a1636d7367816568656c6c6f\n");
+}
+
+#[test]
+fn test_encode_metadata_hash() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("aux.cbor");
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "encode",
+            "--metadata",
+            r#"{"674": {"msg": ["hi"]}}"#,
+            "--hash",
+            "-o",
+        ])
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout("d172f34e1de3cd36e24bfab2fe3ee479f01225c8751789411b3c38b944941ee6\n");
+    assert_eq!(
+        hex::encode(fs::read(out).unwrap()),
+        "a11902a2a1636d736781626869"
+    );
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["encode", "--metadata", r#"{"674": null}"#])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid metadata JSON"));
+}
+
+#[test]
+fn test_encode_inline() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["encode", "258([_ 1, h'00'])"])
+        .assert()
+        .success()
+        .stdout("d901029f014100ff\n");
+}
+
+#[test]
+fn test_encode_round_trips_raw_output() {
+    let raw = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["tests/fixtures/preprod_plutus.cbor", "--raw"])
+        .output()
+        .unwrap();
+    assert!(raw.status.success());
+
+    let original = fs::read("tests/fixtures/preprod_plutus.cbor").unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .arg("encode")
+        .write_stdin(raw.stdout)
+        .assert()
+        .success()
+        .stdout(format!("{}\n", hex::encode(original)));
+}
+
+#[test]
+fn test_encode_output_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.cbor");
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["encode", "[1, 2]", "-o", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(fs::read(out).unwrap(), vec![0x82, 0x01, 0x02]);
+}
+
+#[test]
+fn test_encode_invalid() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["encode", "[1,"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid diagnostic notation"));
+}
+
+/// COSE_Sign1 over "hello cq" from an enterprise testnet address, and its COSE_Key.
+const COSE_SIGN1: &str = "84582aa201276761646472657373581d601dfb74a8cbcda254c65b5dd5d95df89f60b28b11de4da2ded3bc1f9ba166686173686564f44868656c6c6f20637158402ee240d35bc054707611c094d476222b5a1fd2e477238d190e8ebb9f4e05b01245fcd77dae9eb4ae3237689d3ec82f49657d5355c7197fd8ab63103569db5f0d";
+const COSE_KEY: &str =
+    "a4010103272006215820197f6b23e16c8532c6abc838facd5ea789be0c76b2920334039bfa8b3d368d61";
+
+#[test]
+fn test_cose_decode() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["cose", COSE_SIGN1])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("EdDSA"))
+        .stdout(predicate::str::contains(
+            "addr_test1vqwlka9ge0x6y4xxtdwatk2alz0kpv5tz80ymgk76w7plxcke422n",
+        ))
+        .stdout(predicate::str::contains("\"hello cq\""));
+}
+
+#[test]
+fn test_cose_verify_data_signature() {
+    let data_signature = format!(
+        r#"{{"signature": "{}", "key": "{}"}}"#,
+        COSE_SIGN1, COSE_KEY
+    );
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["cose", "--json"])
+        .write_stdin(data_signature)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"valid\": true"))
+        .stdout(predicate::str::contains("\"key_matches_address\": true"));
+}
+
+#[test]
+fn test_cose_verify_wrong_key() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["cose", COSE_SIGN1, "--key", &"11".repeat(32)])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("INVALID"))
+        .stderr(predicate::str::contains("Signature verification failed"));
+}
+
+#[test]
+fn test_query_addresses() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addresses", "tests/fixtures/preprod_plutus.cbor", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"collateral_return\""))
+        .stdout(predicate::str::contains(
+            "addr_test1wqt7v9crzydwpwx7f4sjp7w7wsy7rr3ahkldels6tsc707q3xhdsk",
+        ));
+}
+
+#[test]
+fn test_query_addresses_includes_certificates() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["addresses.1.address", fixture_path()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("stake_test1"));
+}
+
+#[test]
+fn test_query_assets() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["assets", "tests/fixtures/preprod_plutus.cbor", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\": \"NIGHT\""))
+        .stdout(predicate::str::contains("\"net\": 46135"));
+}
+
+#[test]
+fn test_asset_name_flags() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["assets.0.name", "tests/fixtures/preprod_plutus.cbor"])
+        .arg("--hex-asset-names")
+        .assert()
+        .success()
+        .stdout("4e49474854\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["assets.0", "tests/fixtures/preprod_plutus.cbor", "--json"])
+        .arg("--utf8-asset-names")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\": \"NIGHT\""))
+        .stdout(predicate::str::contains("\"name_hex\": \"4e49474854\""))
+        .stdout(predicate::str::contains("\"name_utf8\": \"NIGHT\""));
+}
+
+#[test]
+fn test_token_registry() {
+    let dir = tempfile::tempdir().unwrap();
+    let registry = dir.path().join("night.json");
+    std::fs::write(
+        &registry,
+        r#"{
+            "subject": "387c0fb50b54d3e1edca3eb80c7efafd72c1e1a9f3362e7d88f8ccf84e49474854",
+            "ticker": { "value": "NIGHT" },
+            "decimals": { "value": 1 }
+        }"#,
+    )
+    .unwrap();
+
+    // A registry file, or a directory of entries
+    for path in [&registry, &dir.path().to_path_buf()] {
+        Command::cargo_bin("cq")
+            .unwrap()
+            .args(["outputs.1.value.multi_assets.0.assets.0.formatted"])
+            .arg("tests/fixtures/preprod_plutus.cbor")
+            .arg("--token-registry")
+            .arg(path)
+            .assert()
+            .success()
+            .stdout("2.2 NIGHT\n");
+    }
+
+    std::fs::write(&registry, "[1]").unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--token-registry"])
+        .arg(&registry)
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_query_assets_filter() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "assets[name==fivekryptos].output",
+            "tests/fixtures/pool_registration.cbor",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5,000"));
+}
+
+#[test]
+fn test_query_aggregate_sum() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.*.value.coin | sum",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout("16495138\n");
+}
+
+#[test]
+fn test_query_aggregate_count() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["outputs | count", "tests/fixtures/preprod_plutus.cbor"])
+        .assert()
+        .success()
+        .stdout("3\n");
+}
+
+#[test]
+fn test_query_aggregate_non_numeric() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["outputs.*.address | sum", fixture_path()])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("expected numbers"));
+}
+
+#[test]
+fn test_query_group_by() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.* | group_by(address.network) | {network: key, total: values.*.value.coin | sum, count: values | length}",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"network\": \"testnet\""))
+        .stdout(predicate::str::contains("\"total\": 16495138"))
+        .stdout(predicate::str::contains("\"count\": 3"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee | group_by(.)", fixture_path()])
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("group_by needs multiple values"));
+}
+
+#[test]
+fn test_query_pipe_filter_then_path() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs[value.coin > 2000000] | *.value.coin",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("13465208"))
+        .stdout(predicate::str::contains("1887780").not());
+}
+
+#[test]
+fn test_query_pipe_chained_stages() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.* | .value | coin | sum",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout("16495138\n");
+}
+
+#[test]
+fn test_query_length() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["outputs | length", "tests/fixtures/preprod_plutus.cbor"])
+        .assert()
+        .success()
+        .stdout("3\n");
+}
+
+#[test]
+fn test_query_keys() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["body | keys", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"fee\""))
+        .stdout(predicate::str::contains("\"outputs\""));
+}
+
+#[test]
+fn test_query_slice() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.0:2.value.coin",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1887780"))
+        .stdout(predicate::str::contains("1142150"))
+        .stdout(predicate::str::contains("13465208").not());
+}
+
+#[test]
+fn test_query_slice_brackets_open_end() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["outputs[1:] | count", "tests/fixtures/preprod_plutus.cbor"])
+        .assert()
+        .success()
+        .stdout("2\n");
+}
+
+#[test]
+fn test_query_recursive_descent() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "..policy_id",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "387c0fb50b54d3e1edca3eb80c7efafd72c1e1a9f3362e7d88f8ccf8",
+        ));
+}
+
+#[test]
+fn test_query_recursive_descent_in_pipe() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs | ..coin | sum",
+            "tests/fixtures/preprod_plutus.cbor",
+        ])
+        .assert()
+        .success()
+        .stdout("16,495,138\n");
+}
+
+#[test]
+fn test_query_optional_missing_field() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["ttl?", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout("null\n");
+}
+
+#[test]
+fn test_query_optional_present_field() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee?", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout("171617\n");
+}
+
+#[test]
+fn test_query_default_literal() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["ttl // 0", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout("0\n");
+}
+
+#[test]
+fn test_query_default_chain() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["ttl // validity_start // fee", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout("171617\n");
+}
+
+#[test]
+fn test_query_default_not_used_when_present() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee // 0", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout("171617\n");
+}
+
+#[test]
+fn test_query_object_construction() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "{fee: fee, hash: hash, outs: outputs | length}",
+            fixture_path(),
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"fee\": 171617"))
+        .stdout(predicate::str::contains("\"hash\": \"0edb4eac"))
+        .stdout(predicate::str::contains("\"outs\": 1"));
+}
+
+#[test]
+fn test_query_object_construction_per_value() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.* | {coin: value.coin}",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"coin\": 1887780"))
+        .stdout(predicate::str::contains("\"coin\": 13465208"));
+}
+
+#[test]
+fn test_multiple_queries_pretty() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["-q", "fee", "-q", "outputs | length", fixture_path()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("fee: 171,617"))
+        .stdout(predicate::str::contains("outputs | length: 1"));
+}
+
+#[test]
+fn test_multiple_queries_json() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["-q", "fee", "-q", "hash", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"fee\": 171617"))
+        .stdout(predicate::str::contains("\"hash\": \"0edb4eac"));
+}
+
+#[test]
+fn test_single_query_flag_is_unlabelled() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["-q", "fee", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout("171617\n");
+}
+
+#[test]
+fn test_query_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let query_path = temp_dir.path().join("big.cq");
+    fs::write(
+        &query_path,
+        "# outputs over 2 ADA\noutputs[value.coin > 2000000]  # filter\n  | *.value.coin\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "-f",
+            query_path.to_str().unwrap(),
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("13465208"))
+        .stdout(predicate::str::contains("1887780").not());
+}
+
+#[test]
+fn test_query_file_not_found() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["-f", "/nonexistent/query.cq", fixture_path()])
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn test_filter_in_list() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs[address.address in (\"addr_test1vrcmpfvcpjasdr8ak5yqvcl8pgyrm7tdzddek5ew6ea4wtgl8u9wx\", \"addr1xyz\")] | *.value.coin",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("13465208"))
+        .stdout(predicate::str::contains("1887780").not());
+}
+
+#[test]
+fn test_filter_in_numbers() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs[value.coin in (1887780, 1142150)] | count",
+            "tests/fixtures/preprod_plutus.cbor",
+        ])
+        .assert()
+        .success()
+        .stdout("2\n");
+}
+
+#[test]
+fn test_filter_starts_with() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs[address.address ^= addr_test1v] | count",
+            "tests/fixtures/preprod_plutus.cbor",
+        ])
+        .assert()
+        .success()
+        .stdout("1\n");
+}
+
+#[test]
+fn test_filter_case_insensitive() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "assets[name ^=* FIVE] | *.name",
+            "tests/fixtures/pool_registration.cbor",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("fivekryptos"))
+        .stdout(predicate::str::contains("fiftykryptos").not());
+}
+
+#[test]
+fn test_sort_by_desc() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.*",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--sort-by",
+            "value.coin",
+            "--desc",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?s)13465208.*1887780.*1142150").unwrap());
+}
+
+#[test]
+fn test_sort_by_value() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.*.value.coin",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--sort-by",
+            ".",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?s)1142150.*1887780.*13465208").unwrap());
+}
+
+#[test]
+fn test_sort_then_head() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.*.value.coin",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--sort-by",
+            ".",
+            "--desc",
+            "--limit",
+            "1",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("13465208"))
+        .stdout(predicate::str::contains("1887780").not());
+}
+
+#[test]
+fn test_to_address_filter() {
+    let address = "addr_test1qzptz2u4vs2ypqpr0ulyl2ferwayfw5lcfv4d38na9rd96g0vg662c3cpstwzltpsmdn25erk02aa2lrgr8pc99ye99sseznpf";
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--to",
+            address,
+        ])
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(address))
+        .stdout(predicate::str::contains("\"network\"").count(1));
+
+    // Values that are not outputs
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.*.value.coin",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--to",
+            "82b12b9564144080237f3e4fa9391bba44ba9fc25956c4f3e946d2e9",
+            "--json",
+        ])
+        .assert()
+        .code(4);
+
+    // By payment credential hash
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.*",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--to",
+            "82b12b9564144080237f3e4fa9391bba44ba9fc25956c4f3e946d2e9",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(address));
+}
+
+#[test]
+fn test_tail() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.*.value.coin",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--tail",
+            "2",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1142150"))
+        .stdout(predicate::str::contains("13465208"))
+        .stdout(predicate::str::contains("1887780").not());
+}
+
+#[test]
+fn test_unique_flag() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "..name",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--unique",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout("[\n  \"NIGHT\"\n]\n");
+}
+
+#[test]
+fn test_unique_function() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "..name | unique | count",
+            "tests/fixtures/preprod_plutus.cbor",
+        ])
+        .assert()
+        .success()
+        .stdout("1\n");
+}
+
+#[test]
+fn test_exit_status_empty_result() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "-e",
+            "outputs[address.address ~ \"addr1xyz\"]",
+            "tests/fixtures/preprod_plutus.cbor",
+        ])
+        .assert()
+        .code(7);
+}
+
+#[test]
+fn test_exit_status_non_empty_result() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["--exit-status", "fee", fixture_path()])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_exit_status_null_result() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["-e", "ttl?", fixture_path(), "--json"])
+        .assert()
+        .code(7)
+        .stdout("null\n");
+}
+
+#[test]
+fn test_csv_output() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.* | {addr: address.address, coin: value.coin}",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--csv",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("addr,coin\n"))
+        .stdout(predicate::str::contains(
+            "addr_test1vrcmpfvcpjasdr8ak5yqvcl8pgyrm7tdzddek5ew6ea4wtgl8u9wx,13465208",
+        ));
+}
+
+#[test]
+fn test_tsv_output_flattens_columns() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["outputs.*", fixture_path(), "--tsv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("address.address\t"))
+        .stdout(predicate::str::contains("value.coin"));
+}
+
+#[test]
+fn test_csv_requires_query() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--csv"])
+        .assert()
+        .code(5);
+}
+
+#[test]
+fn test_csv_conflicts_with_json() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "--csv", "--json"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_format_template_per_value() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.*",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--format",
+            "{address.address}: {value.coin}",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "addr_test1vrcmpfvcpjasdr8ak5yqvcl8pgyrm7tdzddek5ew6ea4wtgl8u9wx: 13465208\n",
+        ));
+}
+
+#[test]
+fn test_format_template_root() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            fixture_path(),
+            "--format",
+            "fee={fee} outputs={outputs | length} {{done}}",
+        ])
+        .assert()
+        .success()
+        .stdout("fee=171617 outputs=1 {done}\n");
+}
+
+#[test]
+fn test_format_template_missing_field() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--format", "{ttl}"])
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_pretty_truncates_by_default() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["tests/fixtures/preprod_plutus.cbor", "--no-color"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1a4899...1383e9"))
+        .stdout(
+            predicate::str::contains(
+                "addr_test1vrcmpfvcpjasdr8ak5yqvcl8pgyrm7tdzddek5ew6ea4wtgl8u9wx",
+            )
+            .not(),
+        );
+}
+
+#[test]
+fn test_no_truncate() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "tests/fixtures/preprod_plutus.cbor",
+            "--no-color",
+            "--no-truncate",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1a48999128069edfa82776fbccb9696ac49d1b2c4a054be42ca9affb111383e9",
+        ))
+        .stdout(predicate::str::contains(
+            "addr_test1vrcmpfvcpjasdr8ak5yqvcl8pgyrm7tdzddek5ew6ea4wtgl8u9wx",
+        ));
+}
+
+#[test]
+fn test_table_style_ascii() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--table-style", "ascii"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("| # | Transaction ID"))
+        .stdout(predicate::str::contains("┌").not());
+}
+
+#[test]
+fn test_table_style_plain_size() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["--table-style", "plain", "size", fixture_path()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("│").not())
+        .stdout(predicate::str::contains("|").not());
+}
+
+#[test]
+fn test_max_width_wraps_tables() {
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "tests/fixtures/preprod_plutus.cbor",
+            "--no-truncate",
+            "--max-width",
+            "60",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout
+            .lines()
+            .filter(|line| line.starts_with(['│', '┌', '└', '╞']))
+            .all(|line| line.chars().count() <= 60)
+    );
+}
+
+#[test]
+fn test_transaction_body() {
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["body", fixture_path(), "--cbor"])
+        .output()
+        .unwrap();
+    let body = String::from_utf8(output.stdout).unwrap();
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["hash", body.trim(), "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "0edb4eac0b992ac4af71a2a52f41ab63c806e0ef4e5c5d9c7348ea03cf9a9e4e",
+        ))
+        .stderr(predicate::str::contains("decoded as a transaction body"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", body.trim(), "--json"])
+        .assert()
+        .success()
+        .stdout("171617\n");
+
+    // Not submittable without witnesses
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([body.trim(), "--check"])
+        .assert()
+        .failure()
+        .code(1);
+}
+
+#[test]
+fn test_outpoints() {
+    let outpoint = "852ec7f7da4556214f45b166c346802dbe644bdbf16cd8245d431ccdd573fa31#0";
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["inputs.0.outpoint", fixture_path(), "--json"])
+        .assert()
+        .success()
+        .stdout(format!("\"{}\"\n", outpoint));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["inputs", fixture_path(), "--outpoints", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "[\n  \"{}\"\n]",
+            outpoint
+        )));
+
+    // Pretty tables show the whole outpoint
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([fixture_path(), "--outpoints", "--no-color"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(outpoint));
+}
+
+/// The PlutusV1 "always succeeds" script, as a cardano-cli `cborHex`.
+const ALWAYS_SUCCEEDS: &str = "4e4d01000033222220051200120011";
+
+#[test]
+fn test_script_command() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let script_file = temp_dir.path().join("always.plutus");
+    fs::write(
+        &script_file,
+        format!(
+            r#"{{"type": "PlutusScriptV1", "description": "", "cborHex": "{}"}}"#,
+            ALWAYS_SUCCEEDS
+        ),
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["script", script_file.to_str().unwrap(), "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["language"], "plutus_v1");
+    assert_eq!(
+        json["hash"],
+        "67f33146617a5e61936081db3b2117cbf59bd2123748f58ac9678656"
+    );
+    assert_eq!(json["uplc"]["version"], "1.0.0");
+    assert_eq!(json["uplc"]["terms"], 13);
+
+    // Bare script bytes: no language, so no hash
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["script", ALWAYS_SUCCEEDS])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("unknown (bare script bytes)"))
+        .stdout(predicate::str::contains("(delay (lam i_0 i_0))"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["script", "8200820181"])
+        .assert()
+        .failure()
+        .code(1);
+}
+
+#[test]
+fn test_uplc_witness_scripts() {
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["body", fixture_path(), "--cbor"])
+        .output()
+        .unwrap();
+    let body = String::from_utf8(output.stdout).unwrap();
+    // [body, {3: [script]}, true, null]
+    let tx = format!("84{}a10381{}f5f6", body.trim(), ALWAYS_SUCCEEDS);
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "witness_set.plutus_v1_scripts.0.uplc.lambdas",
+            &tx,
+            "--uplc",
+        ])
+        .assert()
+        .success()
+        .stdout("7\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([&tx, "--uplc", "--no-color"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPLC 1.0.0, 13 B flat, 13 terms"));
+
+    // Only on request
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["witness_set.plutus_v1_scripts.0.uplc", &tx])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_double_cbor_script() {
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["body", fixture_path(), "--cbor"])
+        .output()
+        .unwrap();
+    let body = String::from_utf8(output.stdout).unwrap();
+    // The script bytes wrapped once more: 4f 4e 4d <flat>
+    let tx = format!("84{}a103814f{}f5f6", body.trim(), ALWAYS_SUCCEEDS);
+    let intended = "67f33146617a5e61936081db3b2117cbf59bd2123748f58ac9678656";
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "witness_set.plutus_v1_scripts.0.double_cbor.unwrapped_hash",
+            &tx,
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(format!("\"{}\"\n", intended));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([&tx, "--check"])
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("extra CBOR byte string"))
+        .stderr(predicate::str::contains(intended));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["script", &format!("82014f{}", ALWAYS_SUCCEEDS), "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("double_cbor"))
+        .stdout(predicate::str::contains(intended));
+}
+
+#[test]
+fn test_datum_hash() {
+    // Constr 0 [], the unit datum
+    let unit = "923918e403bf43c34b4ef6b48eb2ee04babed17320d8d1b9ff9ad086e86f44ec";
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["hash", "datum", "d87980"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", unit));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["hash", "datum", "-", "--json"])
+        .write_stdin("d87980")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(unit))
+        .stdout(predicate::str::contains("\"constructor\": 0"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["hash", "datum", "d87980ff"])
+        .assert()
+        .failure()
+        .code(1);
+
+    // The fixture's outputs reference no datum, so a witness datum is an orphan
+    let output = Command::cargo_bin("cq")
+        .unwrap()
+        .args(["body", fixture_path(), "--cbor"])
+        .output()
+        .unwrap();
+    let body = String::from_utf8(output.stdout).unwrap();
+    let tx = format!("84{}a10481d87980f5f6", body.trim());
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["witness_set.datum_coverage.orphans", &tx, "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(unit));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .arg(&tx)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("? orphan"));
+}
+
+#[test]
+fn test_blake2b_hash() {
+    // "hello", as hex and as raw stdin bytes
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["hash", "blake2b-256", "68656c6c6f"])
+        .assert()
+        .success()
+        .stdout("324dcf027dd4a30a932c441f365a25e86b173defa4b8e58948253471b81b72cf\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["hash", "blake2b-224", "-", "--json"])
+        .write_stdin("hello")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "a4963e4ea2aa9b4120672abfc4c4299ba365368fa5a3910d5c559fc5",
+        ))
+        .stdout(predicate::str::contains("\"size\": 5"));
+
+    // A file is hashed as it is, not decoded
+    let bytes = fs::read(fixture_path()).unwrap();
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["hash", "blake2b-256", fixture_path()])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", hex::encode(cml_crypto::blake2b256(&bytes))));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["fee", fixture_path(), "extra"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("'extra'"));
+}
+
+#[test]
+fn test_pool_metadata_check() {
+    let dir = tempfile::tempdir().unwrap();
+    let metadata = dir.path().join("poolMetadata.json");
+    fs::write(&metadata, r#"{"name": "Other pool", "ticker": "OTHR"}"#).unwrap();
+    let metadata = metadata.to_str().unwrap();
+    let document_hash = hex::encode(cml_crypto::blake2b256(&fs::read(metadata).unwrap()));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "certs.0",
+            "tests/fixtures/pool_registration.cbor",
+            "--pool-metadata",
+            metadata,
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"hash_matches\": false"))
+        .stdout(predicate::str::contains(&document_hash));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "tests/fixtures/pool_registration.cbor",
+            "--check",
+            "--pool-metadata",
+            metadata,
+        ])
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("Pool metadata hash mismatch"))
+        .stderr(predicate::str::contains(&document_hash));
+}
+
+#[test]
+fn test_pool_metadata_fetched_once() {
+    // The server answers a single request: every query must share it
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/pool.json", server.server_addr().to_ip().unwrap());
+    std::thread::spawn(move || {
+        if let Ok(request) = server.recv() {
+            let _ = request.respond(tiny_http::Response::from_string("{}"));
+        }
+    });
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "tests/fixtures/pool_registration.cbor",
+            "-q",
+            "certs.0",
+            "-q",
+            "certs",
+            "--pool-metadata",
+            &url,
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"hash_matches\": false").count(2));
+}
+
+#[test]
+fn test_pool_ids() {
+    let pool_id = "pool1vvkurfxhajtj4f7x8wjkeet7rg8amz34duy5nux76per5sn3npx";
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["certs.0", "tests/fixtures/pool_registration.cbor", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(pool_id))
+        .stdout(predicate::str::contains(
+            "632dc1a4d7ec972aa7c63ba56ce57e1a0fdd8a356f0949f0ded0723a",
+        ));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "certs",
+            "tests/fixtures/pool_registration.cbor",
+            "--no-truncate",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(pool_id));
+}
+
+#[test]
+fn test_drep_ids() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["certs.0", "tests/fixtures/drep_registration.cbor", "--json"])
+        .assert()
+        .success()
+        // CIP-129, with the key header byte 0x22, and CIP-105
+        .stdout(predicate::str::contains(
+            "drep1ygpuetneftlmufa97hm5mf3xvqpdkyw656hyg6h20qaewtg3csnkc",
+        ))
+        .stdout(predicate::str::contains(
+            "drep1q0x2u722l7lz0f047ax6vfnqqtd3rk4x4ezx46nc8wtj6f92vtc",
+        ))
+        .stdout(predicate::str::contains(
+            "03ccae794affbe27a5f5f74da6266002db11daa6ae446aea783b972d",
+        ));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args(["tests/fixtures/drep_registration.cbor", "--no-truncate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "drep: drep1ygpuetneftlmufa97hm5mf3xvqpdkyw656hyg6h20qaewtg3csnkc",
+        ));
+}
+
+#[test]
+fn test_script_ids() {
+    use cml_crypto::ScriptHash;
+
+    let hash = "17e61703111ae0b8de4d6120f9de7409e18e3dbdbedcfe1a5c31e7f8";
+    let script_id = ScriptHash::from_hex(hash)
+        .unwrap()
+        .to_bech32("script")
+        .unwrap();
+    let query = "outputs.0.address.payment_credential";
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([query, "tests/fixtures/preprod_plutus.cbor", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("script_id").not());
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            query,
+            "tests/fixtures/preprod_plutus.cbor",
+            "--script-ids",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(script_id));
+
+    // Policy IDs of multi-asset values
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "..script_id",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--script-ids",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "script18p7qldgt2nf7rmw286uqclh6l4evrcdf7vmzulvglrx0sgvw47r",
+        ));
+}
+
+#[test]
+fn test_bech32_ids() {
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "certs.0",
+            "tests/fixtures/pool_registration.cbor",
+            "--bech32-ids",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"pool_keyhash\": \"pool1vvkurfxhajtj4f7x8wjkeet7rg8amz34duy5nux76per5sn3npx\"",
+        ))
+        .stdout(predicate::str::contains("\"vrf_keyhash\": \"vrf_vkh1"));
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "certs.0.drep_credential.hash",
+            "tests/fixtures/drep_registration.cbor",
+            "--bech32-ids",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout("\"drep1ygpuetneftlmufa97hm5mf3xvqpdkyw656hyg6h20qaewtg3csnkc\"\n");
+
+    Command::cargo_bin("cq")
+        .unwrap()
+        .args([
+            "outputs.0.address.payment_credential.hash",
+            "tests/fixtures/preprod_plutus.cbor",
+            "--bech32-ids",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("\"script1"));
+}